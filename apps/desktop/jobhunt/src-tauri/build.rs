@@ -1,3 +1,32 @@
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+
+// Embeds the SHA-256 of the engine sidecar so the app can refuse to spawn a
+// binary that was swapped or corrupted after the build. Only debug builds may
+// go without one.
+fn embed_engine_digest() {
+    let target = std::env::var("TARGET").unwrap_or_default();
+    let ext = if target.contains("windows") { ".exe" } else { "" };
+    let path = PathBuf::from("bin").join(format!("engine-{}{}", target, ext));
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let digest = match std::fs::read(&path) {
+        Ok(bytes) => format!("{:x}", Sha256::digest(&bytes)),
+        Err(e) if std::env::var("PROFILE").as_deref() == Ok("release") => {
+            panic!("a release build needs the engine binary {}: {}", path.display(), e)
+        }
+        Err(e) => {
+            println!("cargo:warning=engine integrity check disabled: {}: {}", path.display(), e);
+            String::new()
+        }
+    };
+    println!("cargo:rustc-env=ENGINE_SHA256={}", digest);
+    // Lets the engine updater pick the matching build from its release manifest.
+    println!("cargo:rustc-env=ENGINE_TARGET={}", target);
+}
+
 fn main() {
+    embed_engine_digest();
     tauri_build::build()
 }
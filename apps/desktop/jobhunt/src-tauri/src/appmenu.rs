@@ -0,0 +1,421 @@
+use tauri::menu::{
+  AboutMetadata, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu, HELP_SUBMENU_ID,
+  WINDOW_SUBMENU_ID,
+};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::{archiveimport, backup, engine, export, fileopen, i18n, startup, support};
+
+const MAIN_WINDOW: &str = "main";
+const DOCS_URL: &str = "https://github.com/stevenmed26/JobHunt#readme";
+const ISSUES_URL: &str = "https://github.com/stevenmed26/JobHunt/issues/new";
+// Extensions the import dialog offers; the same set as file associations.
+const IMPORT_EXTENSIONS: &[&str] = &["pdf", "eml", "html", "htm"];
+
+// Every id starts with "menu:" so tray clicks, which reach the same
+// app-wide handler, are never mistaken for menu bar items.
+const IMPORT: &str = "menu:import";
+const IMPORT_ARCHIVE: &str = "menu:import-archive";
+const EXPORT_XLSX: &str = "menu:export-xlsx";
+const EXPORT_CSV: &str = "menu:export-csv";
+const BACKUP: &str = "menu:backup";
+const DATA: &str = "menu:data";
+const RESTART: &str = "menu:restart";
+const STATUS: &str = "menu:status";
+const LOGS: &str = "menu:logs";
+const RELOAD: &str = "menu:reload";
+const SETTINGS: &str = "menu:settings";
+const DOCS: &str = "menu:docs";
+const REPORT: &str = "menu:report";
+const SUPPORT: &str = "menu:support";
+
+/// Asks the frontend to perform an action that lives in the UI, like opening settings.
+#[derive(Clone, serde::Serialize)]
+struct MenuAction {
+  action: &'static str,
+}
+
+fn item(
+  app: &AppHandle,
+  id: &str,
+  key: &str,
+  accelerator: Option<&str>,
+) -> tauri::Result<MenuItem<Wry>> {
+  MenuItem::with_id(app, id, i18n::t(key), true, accelerator)
+}
+
+fn about(app: &AppHandle) -> tauri::Result<PredefinedMenuItem<Wry>> {
+  let info = app.package_info();
+  let metadata = AboutMetadata {
+    name: Some(info.name.clone()),
+    version: Some(info.version.to_string()),
+    website: Some(DOCS_URL.into()),
+    ..Default::default()
+  };
+  PredefinedMenuItem::about(app, Some(&i18n::t("menu.about")), Some(metadata))
+}
+
+fn build(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+  let t = |key: &str| i18n::t(key);
+  let sep = || PredefinedMenuItem::separator(app);
+
+  let file = Submenu::with_items(
+    app,
+    t("menu.file"),
+    true,
+    &[
+      &item(app, IMPORT, "menu.import", Some("CmdOrCtrl+O"))?,
+      &item(app, IMPORT_ARCHIVE, "menu.import_archive", None)?,
+      &sep()?,
+      &item(app, EXPORT_XLSX, "menu.export_xlsx", Some("CmdOrCtrl+E"))?,
+      &item(
+        app,
+        EXPORT_CSV,
+        "menu.export_csv",
+        Some("CmdOrCtrl+Shift+E"),
+      )?,
+      &sep()?,
+      &item(app, BACKUP, "menu.backup", None)?,
+      &item(app, DATA, "menu.data", None)?,
+      &sep()?,
+      // macOS keeps Settings and Quit in the app menu.
+      #[cfg(not(target_os = "macos"))]
+      &item(app, SETTINGS, "menu.settings", Some("CmdOrCtrl+,"))?,
+      #[cfg(not(target_os = "macos"))]
+      &sep()?,
+      &PredefinedMenuItem::close_window(app, Some(&t("menu.close")))?,
+      #[cfg(not(target_os = "macos"))]
+      &PredefinedMenuItem::quit(app, Some(&t("menu.quit")))?,
+    ],
+  )?;
+
+  let edit = Submenu::with_items(
+    app,
+    t("menu.edit"),
+    true,
+    &[
+      &PredefinedMenuItem::undo(app, Some(&t("menu.undo")))?,
+      &PredefinedMenuItem::redo(app, Some(&t("menu.redo")))?,
+      &sep()?,
+      &PredefinedMenuItem::cut(app, Some(&t("menu.cut")))?,
+      &PredefinedMenuItem::copy(app, Some(&t("menu.copy")))?,
+      &PredefinedMenuItem::paste(app, Some(&t("menu.paste")))?,
+      &PredefinedMenuItem::select_all(app, Some(&t("menu.select_all")))?,
+    ],
+  )?;
+
+  let view = Submenu::with_items(
+    app,
+    t("menu.view"),
+    true,
+    &[
+      &item(app, RELOAD, "menu.reload", Some("CmdOrCtrl+R"))?,
+      &sep()?,
+      &PredefinedMenuItem::fullscreen(app, Some(&t("menu.fullscreen")))?,
+    ],
+  )?;
+
+  let engine = Submenu::with_items(
+    app,
+    t("menu.engine"),
+    true,
+    &[
+      &item(app, RESTART, "menu.restart", Some("CmdOrCtrl+Shift+R"))?,
+      &item(app, STATUS, "menu.status", None)?,
+      &sep()?,
+      &item(app, LOGS, "menu.logs", None)?,
+    ],
+  )?;
+
+  // The well-known ids let macOS add its own Window and Help menu entries.
+  let window = Submenu::with_id_and_items(
+    app,
+    WINDOW_SUBMENU_ID,
+    t("menu.window"),
+    true,
+    &[
+      &PredefinedMenuItem::minimize(app, Some(&t("menu.minimize")))?,
+      &PredefinedMenuItem::maximize(app, Some(&t("menu.zoom")))?,
+    ],
+  )?;
+
+  let docs_accel = if cfg!(target_os = "macos") {
+    None
+  } else {
+    Some("F1")
+  };
+  let help = Submenu::with_id_and_items(
+    app,
+    HELP_SUBMENU_ID,
+    t("menu.help"),
+    true,
+    &[
+      &item(app, DOCS, "menu.docs", docs_accel)?,
+      &item(app, REPORT, "menu.report", None)?,
+      &item(app, SUPPORT, "menu.support", None)?,
+      #[cfg(not(target_os = "macos"))]
+      &sep()?,
+      #[cfg(not(target_os = "macos"))]
+      &about(app)?,
+    ],
+  )?;
+
+  #[cfg(target_os = "macos")]
+  {
+    let app_menu = Submenu::with_items(
+      app,
+      app.package_info().name.clone(),
+      true,
+      &[
+        &about(app)?,
+        &sep()?,
+        &item(app, SETTINGS, "menu.settings", Some("CmdOrCtrl+,"))?,
+        &sep()?,
+        &PredefinedMenuItem::services(app, Some(&t("menu.services")))?,
+        &sep()?,
+        &PredefinedMenuItem::hide(app, Some(&t("menu.hide")))?,
+        &PredefinedMenuItem::hide_others(app, Some(&t("menu.hide_others")))?,
+        &PredefinedMenuItem::show_all(app, Some(&t("menu.show_all")))?,
+        &sep()?,
+        &PredefinedMenuItem::quit(app, Some(&t("menu.quit")))?,
+      ],
+    )?;
+    Menu::with_items(
+      app,
+      &[&app_menu, &file, &edit, &view, &engine, &window, &help],
+    )
+  }
+  #[cfg(not(target_os = "macos"))]
+  {
+    Menu::with_items(app, &[&file, &edit, &view, &engine, &window, &help])
+  }
+}
+
+/// macOS has one menu bar for the whole app; elsewhere it belongs to the
+/// main window only, so the splash, print, and capture windows stay bare.
+fn attach(app: &AppHandle) -> tauri::Result<()> {
+  let menu = build(app)?;
+  #[cfg(target_os = "macos")]
+  app.set_menu(menu)?;
+  #[cfg(not(target_os = "macos"))]
+  if let Some(main) = app.get_webview_window(MAIN_WINDOW) {
+    main.set_menu(menu)?;
+  }
+  Ok(())
+}
+
+fn show_main(app: &AppHandle) {
+  if let Some(main) = app.get_webview_window(MAIN_WINDOW) {
+    let _ = main.unminimize();
+    let _ = main.show();
+    let _ = main.set_focus();
+  }
+}
+
+fn message(app: &AppHandle, title: String, text: String, kind: MessageDialogKind) {
+  app
+    .dialog()
+    .message(text)
+    .title(title)
+    .kind(kind)
+    .show(|_| {});
+}
+
+/// Shows a failed action's error.
+fn report_error(app: &AppHandle, action: &str, err: String) {
+  eprintln!("[appmenu] {} failed: {}", action, err);
+  message(app, i18n::t("menu.failed"), err, MessageDialogKind::Error);
+}
+
+fn pick_imports(app: &AppHandle) {
+  let handle = app.clone();
+  app
+    .dialog()
+    .file()
+    .set_title(i18n::t("dialog.import"))
+    .add_filter("JobHunt", IMPORT_EXTENSIONS)
+    .pick_files(move |files| {
+      let paths = files
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|f| f.into_path().ok())
+        .collect();
+      fileopen::open(&handle, paths);
+    });
+}
+
+fn import_archive(app: &AppHandle) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    match archiveimport::pick_and_import(&app).await {
+      Ok(Some(report)) => message(
+        &app,
+        i18n::t("menu.import_archive"),
+        i18n::t_with(
+          "menu.import_archive_done",
+          &[
+            ("created", &report.created.to_string()),
+            ("duplicates", &report.duplicates.to_string()),
+            ("errors", &report.errors.to_string()),
+          ],
+        ),
+        if report.errors > 0 {
+          MessageDialogKind::Warning
+        } else {
+          MessageDialogKind::Info
+        },
+      ),
+      Ok(None) => {}
+      Err(e) => report_error(&app, "archive import", e),
+    }
+  });
+}
+
+fn export(app: &AppHandle, format: &'static str) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    match export::export_applications(&app, format, None).await {
+      Ok(Some(summary)) => message(
+        &app,
+        i18n::t("dialog.export"),
+        i18n::t_with(
+          "menu.export_done",
+          &[("rows", &summary.rows.to_string()), ("path", &summary.path)],
+        ),
+        MessageDialogKind::Info,
+      ),
+      Ok(None) => {}
+      Err(e) => report_error(&app, "export", e),
+    }
+  });
+}
+
+fn back_up(app: &AppHandle) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let result = match backup::default_backup_dir(&app) {
+      Ok(dir) => backup::create_backup(&app, dir).await,
+      Err(e) => Err(e),
+    };
+    match result {
+      Ok(path) => message(
+        &app,
+        i18n::t("menu.backup"),
+        i18n::t_with("menu.backup_done", &[("path", &path.to_string_lossy())]),
+        MessageDialogKind::Info,
+      ),
+      Err(e) => report_error(&app, "backup", e),
+    }
+  });
+}
+
+fn show_status(app: &AppHandle) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let status = engine::status(&app, true).await;
+    let mut lines = Vec::new();
+    match (&status.port, &status.socket) {
+      _ if !status.running => lines.push(i18n::t("status.stopped")),
+      (Some(port), _) => lines.push(i18n::t_with("status.port", &[("port", &port.to_string())])),
+      (None, Some(socket)) => lines.push(i18n::t_with("status.socket", &[("socket", socket)])),
+      (None, None) => {}
+    }
+    if let Some(version) = &status.version {
+      lines.push(i18n::t_with("status.version", &[("version", version)]));
+    }
+    if let Some(pid) = status.pid {
+      lines.push(i18n::t_with("status.pid", &[("pid", &pid.to_string())]));
+    }
+    if let Some(secs) = status.uptime_secs {
+      let minutes = i18n::number((secs / 60) as f64, 0);
+      lines.push(i18n::t_with("status.uptime", &[("minutes", &minutes)]));
+    }
+    match status.healthy {
+      Some(true) => lines.push(i18n::t("status.healthy")),
+      Some(false) => lines.push(i18n::t("status.unhealthy")),
+      None => {}
+    }
+    if let Some(error) = &status.last_error {
+      lines.push(i18n::t_with("status.last_error", &[("error", error)]));
+    }
+    let kind = if status.healthy == Some(true) {
+      MessageDialogKind::Info
+    } else {
+      MessageDialogKind::Warning
+    };
+    message(&app, i18n::t("status.title"), lines.join("\n"), kind);
+  });
+}
+
+fn open_url(app: &AppHandle, url: &str) {
+  if let Err(e) = app.opener().open_url(url, None::<&str>) {
+    eprintln!("[appmenu] open {} failed: {}", url, e);
+  }
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+  match event.id().as_ref() {
+    IMPORT => pick_imports(app),
+    IMPORT_ARCHIVE => import_archive(app),
+    EXPORT_XLSX => export(app, "xlsx"),
+    EXPORT_CSV => export(app, "csv"),
+    BACKUP => back_up(app),
+    DATA => match engine::data_dir(app) {
+      Ok(dir) => {
+        if let Err(e) = app.opener().open_path(dir.to_string_lossy(), None::<&str>) {
+          eprintln!("[appmenu] open data folder failed: {}", e);
+        }
+      }
+      Err(e) => eprintln!("[appmenu] {}", e),
+    },
+    RESTART => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = engine::restart_engine(&app).await {
+          report_error(&app, "restart", e.to_string());
+        }
+      });
+    }
+    STATUS => show_status(app),
+    LOGS => startup::open_logs(app),
+    RELOAD => {
+      if let Some(main) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = main.reload();
+      }
+    }
+    SETTINGS => {
+      show_main(app);
+      let _ = app.emit_to(
+        MAIN_WINDOW,
+        "menu-action",
+        MenuAction { action: "settings" },
+      );
+    }
+    DOCS => open_url(app, DOCS_URL),
+    REPORT => open_url(app, ISSUES_URL),
+    SUPPORT => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = support::create_support_bundle(&app).await {
+          report_error(&app, "support bundle", e);
+        }
+      });
+    }
+    _ => {}
+  }
+}
+
+/// Builds the menu bar and routes its clicks to the matching commands.
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+  app.on_menu_event(on_menu_event);
+  attach(app)
+}
+
+/// Rebuilds the menu bar in the current language.
+pub fn rebuild(app: &AppHandle) {
+  if let Err(e) = attach(app) {
+    eprintln!("[appmenu] rebuild failed: {}", e);
+  }
+}
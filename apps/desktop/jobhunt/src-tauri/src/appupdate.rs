@@ -0,0 +1,309 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_updater::{Updater, UpdaterExt};
+
+use crate::startup::StartupError;
+use crate::{backup, engine, netproxy, proxy, sidecar, writeguard};
+
+// Left in the data dir from shutting down for an update until the next engine launch.
+const MARKER: &str = "update-pending.json";
+const DB_FILE: &str = "jobhunt.db";
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+// How long an update waits for in-flight writes before stopping the engine anyway.
+const WRITES_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize)]
+struct Marker {
+  from_version: String,
+  to_version: String,
+  backup: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize)]
+struct Progress {
+  downloaded: u64,
+  total: Option<u64>,
+}
+
+/// The app updater, sent through the user's proxy like other outbound traffic.
+pub fn updater(app: &AppHandle) -> Result<Updater, String> {
+  let mut builder = app.updater_builder();
+  if let Some(url) = netproxy::https_url(app).and_then(|u| u.parse().ok()) {
+    builder = builder.proxy(url);
+  }
+  builder.build().map_err(|e| e.to_string())
+}
+
+/// Takes a backup, flushes the WAL into the database, and stops every
+/// sidecar, engine last, so the installer never kills it mid-transaction.
+/// Returns the backup's path, if one could be made.
+async fn shut_down_engine(app: &AppHandle) -> Option<PathBuf> {
+  if !writeguard::wait_idle(app, WRITES_TIMEOUT).await {
+    eprintln!(
+      "[appupdate] writes still running after {} s; stopping the engine anyway",
+      WRITES_TIMEOUT.as_secs()
+    );
+  }
+
+  let backup = match backup::default_backup_dir(app) {
+    Ok(dir) => backup::create_backup(app, dir).await,
+    Err(e) => Err(e),
+  };
+  let backup = backup
+    .map_err(|e| eprintln!("[appupdate] pre-update backup failed: {}", e))
+    .ok();
+
+  if engine::is_running(app) {
+    let req = proxy::engine_request(app, reqwest::Method::POST, "/db/checkpoint");
+    match proxy::send(app, req).await {
+      Ok(resp) if resp.status().is_success() => {}
+      Ok(resp) => eprintln!("[appupdate] checkpoint returned HTTP {}", resp.status()),
+      Err(e) => eprintln!("[appupdate] checkpoint failed: {}", e),
+    }
+  }
+  sidecar::stop_all(app).await;
+  backup
+}
+
+/// Downloads and installs a newer app version, shutting the engine down
+/// gracefully first. Restarts the app on success, so it only returns when
+/// there is nothing to install or installing failed.
+pub async fn install(app: &AppHandle) -> Result<(), String> {
+  let Some(update) = updater(app)?.check().await.map_err(|e| e.to_string())? else {
+    return Ok(());
+  };
+  println!("[appupdate] downloading {}", update.version);
+
+  let handle = app.clone();
+  let mut downloaded = 0u64;
+  let bytes = update
+    .download(
+      move |chunk, total| {
+        downloaded += chunk as u64;
+        let _ = handle.emit("app-update-progress", Progress { downloaded, total });
+      },
+      || {},
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+  let data_dir = engine::data_dir(app)?;
+  let backup = shut_down_engine(app).await;
+  let marker = Marker {
+    from_version: update.current_version.clone(),
+    to_version: update.version.clone(),
+    backup,
+  };
+  let marker_path = data_dir.join(MARKER);
+  let json = serde_json::to_vec_pretty(&marker).map_err(|e| e.to_string())?;
+  if let Err(e) = std::fs::write(&marker_path, json) {
+    eprintln!(
+      "[appupdate] could not write {}: {}",
+      marker_path.display(),
+      e
+    );
+  }
+
+  // On Windows this hands over to the installer and exits.
+  if let Err(e) = update.install(bytes) {
+    let _ = std::fs::remove_file(&marker_path);
+    if let Err(e) = engine::spawn_engine(app).await {
+      eprintln!("[appupdate] engine restart failed: {}", e);
+    }
+    return Err(format!("installing {} failed: {}", update.version, e));
+  }
+  println!("[appupdate] installed {}; restarting", update.version);
+  app.restart()
+}
+
+/// Sanity-checks the SQLite header: the magic string, a valid page size, and
+/// a file that holds every page the header says it has.
+fn check_db(path: &Path) -> Result<(), String> {
+  let mut file = match std::fs::File::open(path) {
+    Ok(file) => file,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+    Err(e) => return Err(e.to_string()),
+  };
+  let len = file.metadata().map_err(|e| e.to_string())?.len();
+  if len == 0 {
+    return Ok(());
+  }
+  let mut header = [0u8; 100];
+  file
+    .read_exact(&mut header)
+    .map_err(|_| format!("the file is only {} bytes", len))?;
+  if &header[..16] != SQLITE_MAGIC {
+    return Err("the file is not a SQLite database".into());
+  }
+
+  let be32 = |at: usize| u32::from_be_bytes(header[at..at + 4].try_into().unwrap()) as u64;
+  let page_size = match u16::from_be_bytes([header[16], header[17]]) {
+    1 => 65536,
+    n => n as u64,
+  };
+  if page_size < 512 || !page_size.is_power_of_two() {
+    return Err(format!(
+      "the header has an invalid page size of {}",
+      page_size
+    ));
+  }
+  if len % page_size != 0 {
+    return Err(format!(
+      "the file is {} bytes, not a whole number of pages",
+      len
+    ));
+  }
+  // The page count is only trustworthy when its version matches the change counter.
+  let pages = be32(28);
+  if be32(92) == be32(24) && pages * page_size > len {
+    return Err(format!(
+      "the file is truncated: {} of {} pages",
+      len / page_size,
+      pages
+    ));
+  }
+  Ok(())
+}
+
+/// After an update, checks the database the previous version left before a
+/// new engine opens it. Does nothing on an ordinary launch. A failed check
+/// keeps the marker until the user has chosen what to do.
+pub fn verify_data_dir(data_dir: &Path) -> Result<(), StartupError> {
+  let marker_path = data_dir.join(MARKER);
+  let Ok(raw) = std::fs::read(&marker_path) else {
+    return Ok(());
+  };
+  let marker: Option<Marker> = serde_json::from_slice(&raw).ok();
+
+  if let Err(e) = check_db(&data_dir.join(DB_FILE)) {
+    let backup = marker.and_then(|m| m.backup);
+    let message = match &backup {
+      Some(backup) => format!(
+        "The JobHunt database did not pass its post-update check: {}. A backup taken before \
+         the update is at {}.",
+        e,
+        backup.display()
+      ),
+      None => format!(
+        "The JobHunt database did not pass its post-update check: {}.",
+        e
+      ),
+    };
+    return Err(StartupError::UpdateCheck(message, backup));
+  }
+  let wal = data_dir.join(format!("{}-wal", DB_FILE));
+  if std::fs::metadata(&wal).is_ok_and(|m| m.len() > 0) {
+    // Not damage: the engine replays it on open. But the checkpoint didn't run.
+    eprintln!(
+      "[appupdate] {} was not empty after the update",
+      wal.display()
+    );
+  }
+
+  let _ = std::fs::remove_file(&marker_path);
+  match marker {
+    Some(m) => println!(
+      "[appupdate] data dir verified after update from {} to {}",
+      m.from_version, m.to_version
+    ),
+    None => println!("[appupdate] data dir verified after update"),
+  }
+  Ok(())
+}
+
+/// Forgets a failed post-update check once the user has restored the backup
+/// or chosen to start anyway.
+pub fn dismiss_check(data_dir: &Path) {
+  match std::fs::remove_file(data_dir.join(MARKER)) {
+    Ok(()) => println!("[appupdate] post-update check dismissed"),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+    Err(e) => eprintln!("[appupdate] could not remove {}: {}", MARKER, e),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A file with a SQLite header: `page_size` as stored, `pages` in the
+  /// header count, which is trusted when `current` is set, and `len` bytes.
+  fn db(name: &str, page_size: u16, pages: u32, current: bool, len: usize) -> PathBuf {
+    let mut bytes = vec![0u8; len.max(100)];
+    bytes[..16].copy_from_slice(SQLITE_MAGIC);
+    bytes[16..18].copy_from_slice(&page_size.to_be_bytes());
+    bytes[24..28].copy_from_slice(&7u32.to_be_bytes());
+    bytes[28..32].copy_from_slice(&pages.to_be_bytes());
+    let version: u32 = if current { 7 } else { 6 };
+    bytes[92..96].copy_from_slice(&version.to_be_bytes());
+    bytes.truncate(len);
+    write(name, &bytes)
+  }
+
+  fn write(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+      "jobhunt-check-db-{}-{}",
+      std::process::id(),
+      name
+    ));
+    std::fs::write(&path, bytes).unwrap();
+    path
+  }
+
+  fn check(path: PathBuf) -> Result<(), String> {
+    let result = check_db(&path);
+    let _ = std::fs::remove_file(&path);
+    result
+  }
+
+  #[test]
+  fn check_db_accepts_a_missing_or_empty_file() {
+    assert!(check_db(&std::env::temp_dir().join("jobhunt-check-db-missing")).is_ok());
+    assert!(check(write("empty", b"")).is_ok());
+  }
+
+  #[test]
+  fn check_db_accepts_a_whole_database() {
+    assert!(check(db("whole", 4096, 3, true, 3 * 4096)).is_ok());
+    // 1 stands for 65536.
+    assert!(check(db("large-pages", 1, 1, true, 65536)).is_ok());
+  }
+
+  #[test]
+  fn check_db_rejects_a_short_header() {
+    let err = check(write("short", &SQLITE_MAGIC[..10])).unwrap_err();
+    assert!(err.contains("only 10 bytes"), "{}", err);
+  }
+
+  #[test]
+  fn check_db_rejects_other_files() {
+    let err = check(write("other", &[b'x'; 4096])).unwrap_err();
+    assert!(err.contains("not a SQLite database"), "{}", err);
+  }
+
+  #[test]
+  fn check_db_rejects_an_invalid_page_size() {
+    let err = check(db("bad-page-size", 1000, 1, true, 1000)).unwrap_err();
+    assert!(err.contains("invalid page size"), "{}", err);
+  }
+
+  #[test]
+  fn check_db_rejects_a_partial_page() {
+    let err = check(db("partial", 4096, 2, true, 4096 + 100)).unwrap_err();
+    assert!(err.contains("whole number of pages"), "{}", err);
+  }
+
+  #[test]
+  fn check_db_rejects_a_truncated_file() {
+    let err = check(db("truncated", 4096, 3, true, 2 * 4096)).unwrap_err();
+    assert!(err.contains("truncated: 2 of 3 pages"), "{}", err);
+  }
+
+  #[test]
+  fn check_db_ignores_a_stale_page_count() {
+    assert!(check(db("stale", 4096, 3, false, 2 * 4096)).is_ok());
+  }
+}
@@ -0,0 +1,623 @@
+//! Imports saved jobs and applications from another site's "download your
+//! data" archive. LinkedIn's export has `Jobs/Saved Jobs.csv` and
+//! `Jobs/Job Applications.csv`, the latter split into numbered parts when
+//! long; Indeed's has applied and saved jobs as CSV or JSON. Columns are
+//! matched by name, since both sites have reordered them between exports.
+//!
+//! Records go to the engine in batches, and each comes back as created,
+//! duplicate, or failed, so the UI can show what happened row by row.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::{engine, i18n, proxy};
+
+const BATCH: usize = 100;
+// Reading stops here, so a compressed bomb can't fill memory.
+const MAX_ENTRY_BYTES: u64 = 50 * 1024 * 1024;
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+const TITLE: &[&str] = &["jobtitle", "title", "position", "positiontitle"];
+const COMPANY: &[&str] = &["companyname", "company", "employer", "employername"];
+const LOCATION: &[&str] = &["location", "joblocation", "city"];
+const URL: &[&str] = &["joburl", "url", "joblink", "link", "viewjoburl"];
+const DATE: &[&str] = &[
+  "applicationdate",
+  "saveddate",
+  "dateapplied",
+  "appliedon",
+  "appliedat",
+  "appliedtime",
+  "datesaved",
+  "savedat",
+  "date",
+];
+const STATUS: &[&str] = &["status", "applicationstatus"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+  Linkedin,
+  Indeed,
+}
+
+/// A job as the engine's `/api/import/jobs` takes it.
+#[derive(Clone, Debug, Serialize)]
+struct Record {
+  title: String,
+  company: String,
+  location: String,
+  url: String,
+  /// RFC 3339, or empty when the export had no date the engine could use.
+  date: String,
+  status: String,
+  source: Source,
+}
+
+/// Where a record came from in the archive, for reporting.
+struct Parsed {
+  file: String,
+  /// 1-based, counting only data rows.
+  row: usize,
+  record: Record,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordStatus {
+  Created,
+  Duplicate,
+  Error,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordResult {
+  pub file: String,
+  pub row: usize,
+  pub source: Source,
+  pub title: String,
+  pub company: String,
+  pub status: RecordStatus,
+  pub error: Option<String>,
+}
+
+/// Sent as `archive-import-progress` after each batch.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgress {
+  pub done: usize,
+  pub total: usize,
+  pub created: usize,
+  pub duplicates: usize,
+  pub errors: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+  pub archive: String,
+  pub created: usize,
+  pub duplicates: usize,
+  pub errors: usize,
+  pub results: Vec<RecordResult>,
+}
+
+#[derive(Deserialize)]
+struct BatchResult {
+  index: usize,
+  status: RecordStatus,
+  #[serde(default)]
+  error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+  results: Vec<BatchResult>,
+}
+
+enum Format {
+  Csv,
+  Json,
+}
+
+/// Which known export file `name` is, if any: its site, format, and the
+/// status its records get when they don't carry their own.
+fn layout(name: &str) -> Option<(Source, Format, &'static str)> {
+  let path = Path::new(name);
+  let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+  let format = match path.extension()?.to_string_lossy().to_lowercase().as_str() {
+    "csv" => Format::Csv,
+    "json" => Format::Json,
+    _ => return None,
+  };
+  if matches!(format, Format::Csv) {
+    if stem == "saved jobs" {
+      return Some((Source::Linkedin, format, "saved"));
+    }
+    if stem.starts_with("job applications") {
+      return Some((Source::Linkedin, format, "applied"));
+    }
+  }
+  match normalize_key(&stem).as_str() {
+    "savedjobs" | "myjobssaved" => Some((Source::Indeed, format, "saved")),
+    "appliedjobs" | "myjobsapplied" | "applications" | "jobapplications" => {
+      Some((Source::Indeed, format, "applied"))
+    }
+    _ => None,
+  }
+}
+
+/// Lowercase ASCII letters and digits only, so "Job Title", "job_title",
+/// and "jobTitle" all match.
+fn normalize_key(s: &str) -> String {
+  s.chars()
+    .filter(|c| c.is_ascii_alphanumeric())
+    .map(|c| c.to_ascii_lowercase())
+    .collect()
+}
+
+/// Trims and collapses runs of whitespace, including line breaks inside
+/// quoted CSV fields.
+fn clean(s: &str) -> String {
+  s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// RFC 4180 rows. Tolerates a BOM, bare LF line ends, and a missing final
+/// line break.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+  let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+  let mut rows = Vec::new();
+  let mut row = Vec::new();
+  let mut field = String::new();
+  let mut quoted = false;
+  let mut chars = text.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '"' if quoted && chars.peek() == Some(&'"') => {
+        field.push('"');
+        chars.next();
+      }
+      '"' if quoted => quoted = false,
+      '"' if field.is_empty() => quoted = true,
+      ',' if !quoted => row.push(std::mem::take(&mut field)),
+      '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+      '\n' if !quoted => {
+        row.push(std::mem::take(&mut field));
+        rows.push(std::mem::take(&mut row));
+      }
+      c => field.push(c),
+    }
+  }
+  if !field.is_empty() || !row.is_empty() {
+    row.push(field);
+    rows.push(row);
+  }
+  rows
+}
+
+/// A row as normalized column name -> value.
+type Fields = Vec<(String, String)>;
+
+fn field(fields: &Fields, names: &[&str]) -> String {
+  names
+    .iter()
+    .find_map(|name| {
+      fields
+        .iter()
+        .find(|(k, v)| k == name && !v.trim().is_empty())
+        .map(|(_, v)| clean(v))
+    })
+    .unwrap_or_default()
+}
+
+/// CSV rows under the first row that names a title or URL column. LinkedIn
+/// puts a "Notes:" paragraph above the header in some exports.
+fn csv_fields(text: &str) -> Vec<Fields> {
+  let rows = parse_csv(text);
+  let Some(header_at) = rows.iter().position(|row| {
+    row
+      .iter()
+      .map(|h| normalize_key(h))
+      .any(|h| TITLE.contains(&h.as_str()) || URL.contains(&h.as_str()))
+  }) else {
+    return Vec::new();
+  };
+  let header: Vec<String> = rows[header_at].iter().map(|h| normalize_key(h)).collect();
+  rows[header_at + 1..]
+    .iter()
+    .map(|row| header.iter().cloned().zip(row.iter().cloned()).collect())
+    .collect()
+}
+
+/// Adds `value`'s leaves to `out`. A nested object's fields are added both
+/// under their own name and prefixed with the parent's, so `{"job": {"title":
+/// ...}}` matches `title` and `jobtitle`.
+fn flatten(prefix: &str, value: &Value, out: &mut Fields) {
+  match value {
+    Value::Object(map) => {
+      for (k, v) in map {
+        let key = normalize_key(k);
+        if !prefix.is_empty() {
+          flatten(&format!("{}{}", prefix, key), v, out);
+        }
+        flatten(&key, v, out);
+      }
+    }
+    Value::String(s) => out.push((prefix.to_string(), s.clone())),
+    Value::Number(n) => out.push((prefix.to_string(), n.to_string())),
+    _ => {}
+  }
+}
+
+/// An array of objects, either at the top level or as the first array-valued
+/// field of a top-level object.
+fn json_fields(text: &str) -> Result<Vec<Fields>, String> {
+  let value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+  let items = match &value {
+    Value::Array(items) => items,
+    Value::Object(map) => match map.values().find_map(|v| v.as_array()) {
+      Some(items) => items,
+      None => return Ok(Vec::new()),
+    },
+    _ => return Ok(Vec::new()),
+  };
+  Ok(
+    items
+      .iter()
+      .filter(|item| item.is_object())
+      .map(|item| {
+        let mut fields = Vec::new();
+        flatten("", item, &mut fields);
+        fields
+      })
+      .collect(),
+  )
+}
+
+/// The export's date as RFC 3339. LinkedIn writes "9/19/23, 6:35 PM" in local
+/// time; Indeed has ISO dates or epoch milliseconds.
+fn normalize_date(s: &str) -> String {
+  let s = s.trim();
+  if s.is_empty() {
+    return String::new();
+  }
+  if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+    return t.with_timezone(&Utc).to_rfc3339();
+  }
+  if let Ok(n) = s.parse::<i64>() {
+    // Seconds until the year 5138, milliseconds after.
+    let t = if n > 100_000_000_000 {
+      DateTime::from_timestamp_millis(n)
+    } else {
+      DateTime::from_timestamp(n, 0)
+    };
+    return t.map(|t| t.to_rfc3339()).unwrap_or_default();
+  }
+  let local = |naive: NaiveDateTime| {
+    Local
+      .from_local_datetime(&naive)
+      .earliest()
+      .map(|t| t.with_timezone(&Utc).to_rfc3339())
+      .unwrap_or_default()
+  };
+  for format in [
+    "%m/%d/%y, %I:%M %p",
+    "%m/%d/%Y, %I:%M %p",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+  ] {
+    if let Ok(naive) = NaiveDateTime::parse_from_str(s, format) {
+      return local(naive);
+    }
+  }
+  for format in ["%Y-%m-%d", "%m/%d/%Y", "%m/%d/%y", "%b %d, %Y"] {
+    if let Ok(date) = NaiveDate::parse_from_str(s, format) {
+      return date.and_hms_opt(0, 0, 0).map(local).unwrap_or_default();
+    }
+  }
+  String::new()
+}
+
+fn record(fields: &Fields, source: Source, default_status: &str) -> Option<Record> {
+  let record = Record {
+    title: field(fields, TITLE),
+    company: field(fields, COMPANY),
+    location: field(fields, LOCATION),
+    url: field(fields, URL),
+    date: normalize_date(&field(fields, DATE)),
+    status: match field(fields, STATUS) {
+      s if s.is_empty() => default_status.to_string(),
+      s => s.to_lowercase(),
+    },
+    source,
+  };
+  // Blank lines and footers aren't records.
+  if record.title.is_empty() && record.company.is_empty() && record.url.is_empty() {
+    return None;
+  }
+  Some(record)
+}
+
+/// Every record in the archive's known export files, in archive order.
+fn read_archive(path: &Path) -> Result<Vec<Parsed>, String> {
+  let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+  let mut archive = zip::ZipArchive::new(file)
+    .map_err(|e| format!("{} is not a readable ZIP archive: {}", path.display(), e))?;
+
+  let mut parsed = Vec::new();
+  for i in 0..archive.len() {
+    let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+    if !entry.is_file() {
+      continue;
+    }
+    let name = entry.name().to_string();
+    let Some((source, format, status)) = layout(&name) else {
+      continue;
+    };
+    let mut bytes = Vec::new();
+    (&mut entry)
+      .take(MAX_ENTRY_BYTES)
+      .read_to_end(&mut bytes)
+      .map_err(|e| format!("{}: {}", name, e))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let rows = match format {
+      Format::Csv => csv_fields(&text),
+      Format::Json => match json_fields(&text) {
+        Ok(rows) => rows,
+        Err(e) => {
+          eprintln!("[archiveimport] skipping {}: {}", name, e);
+          continue;
+        }
+      },
+    };
+    let before = parsed.len();
+    for (row, fields) in rows.iter().enumerate() {
+      if let Some(record) = record(fields, source, status) {
+        parsed.push(Parsed {
+          file: name.clone(),
+          row: row + 1,
+          record,
+        });
+      }
+    }
+    println!(
+      "[archiveimport] {}: {} record(s) from {:?}",
+      name,
+      parsed.len() - before,
+      source
+    );
+  }
+  Ok(parsed)
+}
+
+async fn send_batch(app: &AppHandle, batch: &[Parsed]) -> Result<Vec<BatchResult>, String> {
+  let records: Vec<&Record> = batch.iter().map(|p| &p.record).collect();
+  let body = serde_json::json!({ "records": records });
+  let resp = proxy::engine_fetch(app, "POST", "/api/import/jobs", Some(body)).await?;
+  if resp.status >= 400 {
+    return Err(format!(
+      "engine returned HTTP {}: {}",
+      resp.status,
+      resp.body.trim()
+    ));
+  }
+  let parsed: BatchResponse = serde_json::from_str(&resp.body).map_err(|e| e.to_string())?;
+  Ok(parsed.results)
+}
+
+fn result(parsed: &Parsed, status: RecordStatus, error: Option<String>) -> RecordResult {
+  RecordResult {
+    file: parsed.file.clone(),
+    row: parsed.row,
+    source: parsed.record.source,
+    title: parsed.record.title.clone(),
+    company: parsed.record.company.clone(),
+    status,
+    error,
+  }
+}
+
+/// Imports every saved job and application in the archive at `path`. A
+/// batch the engine rejects marks its records failed and the rest go on.
+pub async fn import(app: &AppHandle, path: &Path) -> Result<ImportReport, String> {
+  let archive = path
+    .file_name()
+    .map(|n| n.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let owned = path.to_path_buf();
+  let parsed = tauri::async_runtime::spawn_blocking(move || read_archive(&owned))
+    .await
+    .map_err(|e| e.to_string())??;
+  if parsed.is_empty() {
+    return Err(format!("no LinkedIn or Indeed jobs found in {}", archive));
+  }
+  engine::wait_for_ready(app, READY_TIMEOUT).await?;
+
+  let mut results: Vec<RecordResult> = Vec::with_capacity(parsed.len());
+  for batch in parsed.chunks(BATCH) {
+    match send_batch(app, batch).await {
+      Ok(batch_results) => {
+        let mut reported = vec![None; batch.len()];
+        for r in batch_results {
+          if let Some(slot) = reported.get_mut(r.index) {
+            *slot = Some((r.status, r.error));
+          }
+        }
+        for (p, outcome) in batch.iter().zip(reported) {
+          let (status, error) = outcome.unwrap_or((
+            RecordStatus::Error,
+            Some("no result from the engine".into()),
+          ));
+          results.push(result(p, status, error));
+        }
+      }
+      Err(e) => {
+        eprintln!("[archiveimport] batch failed: {}", e);
+        results.extend(
+          batch
+            .iter()
+            .map(|p| result(p, RecordStatus::Error, Some(e.clone()))),
+        );
+      }
+    }
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let _ = app.emit(
+      "archive-import-progress",
+      ImportProgress {
+        done: results.len(),
+        total: parsed.len(),
+        created: count(RecordStatus::Created),
+        duplicates: count(RecordStatus::Duplicate),
+        errors: count(RecordStatus::Error),
+      },
+    );
+  }
+
+  let count = |status| results.iter().filter(|r| r.status == status).count();
+  let report = ImportReport {
+    archive,
+    created: count(RecordStatus::Created),
+    duplicates: count(RecordStatus::Duplicate),
+    errors: count(RecordStatus::Error),
+    results,
+  };
+  println!(
+    "[archiveimport] {}: {} created, {} duplicate, {} failed",
+    report.archive, report.created, report.duplicates, report.errors
+  );
+  Ok(report)
+}
+
+/// Asks for an export archive and imports it. `None` if the user cancelled.
+pub async fn pick_and_import(app: &AppHandle) -> Result<Option<ImportReport>, String> {
+  let Some(file) = app
+    .dialog()
+    .file()
+    .set_title(i18n::t("dialog.import_archive"))
+    .add_filter("ZIP", &["zip"])
+    .blocking_pick_file()
+  else {
+    return Ok(None);
+  };
+  let path: PathBuf = file.into_path().map_err(|e| e.to_string())?;
+  import(app, &path).await.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn row(fields: &[&str]) -> Vec<String> {
+    fields.iter().map(|f| f.to_string()).collect()
+  }
+
+  #[test]
+  fn parse_csv_keeps_line_breaks_inside_quotes() {
+    let rows = parse_csv("title,notes\r\nEngineer,\"first line\r\nsecond line\"\r\n");
+    assert_eq!(
+      rows,
+      vec![row(&["title", "notes"]), row(&["Engineer", "first line\r\nsecond line"])]
+    );
+  }
+
+  #[test]
+  fn parse_csv_unescapes_doubled_quotes() {
+    let rows = parse_csv("name\n\"The \"\"Best\"\" Co, Inc\"\n");
+    assert_eq!(rows, vec![row(&["name"]), row(&["The \"Best\" Co, Inc"])]);
+  }
+
+  #[test]
+  fn parse_csv_strips_a_bom_and_reads_a_last_line_without_a_break() {
+    let rows = parse_csv("\u{feff}title,url\nEngineer,https://example.com/1");
+    assert_eq!(rows[0], row(&["title", "url"]));
+    assert_eq!(rows[1], row(&["Engineer", "https://example.com/1"]));
+  }
+
+  #[test]
+  fn parse_csv_keeps_empty_fields() {
+    assert_eq!(parse_csv("a,,c\n"), vec![row(&["a", "", "c"])]);
+  }
+
+  #[test]
+  fn csv_fields_skip_the_linkedin_notes_preamble() {
+    let text = "Notes:\n\"This file lists the jobs you saved, \nincluding closed ones.\"\n\n\
+                Job Title,Company Name,Job Url\n\
+                Engineer,Acme,https://www.linkedin.com/jobs/view/1\n";
+    let records = csv_fields(text);
+    assert_eq!(records.len(), 1);
+    assert_eq!(field(&records[0], TITLE), "Engineer");
+    assert_eq!(field(&records[0], URL), "https://www.linkedin.com/jobs/view/1");
+  }
+
+  #[test]
+  fn csv_fields_are_empty_without_a_header() {
+    assert!(csv_fields("just,some\nvalues,here\n").is_empty());
+  }
+
+  #[test]
+  fn normalize_date_reads_linkedin_local_times() {
+    let naive = NaiveDate::from_ymd_opt(2023, 9, 19)
+      .and_then(|d| d.and_hms_opt(18, 35, 0))
+      .unwrap();
+    let expected = Local
+      .from_local_datetime(&naive)
+      .earliest()
+      .unwrap()
+      .with_timezone(&Utc)
+      .to_rfc3339();
+    assert_eq!(normalize_date("9/19/23, 6:35 PM"), expected);
+    assert_eq!(normalize_date(" 09/19/2023, 06:35 PM "), expected);
+  }
+
+  #[test]
+  fn normalize_date_converts_offsets_and_epochs_to_utc() {
+    assert_eq!(normalize_date("2023-09-19T18:35:00+02:00"), "2023-09-19T16:35:00+00:00");
+    assert_eq!(normalize_date("1695148500"), "2023-09-19T18:35:00+00:00");
+    assert_eq!(normalize_date("1695148500000"), "2023-09-19T18:35:00+00:00");
+  }
+
+  #[test]
+  fn normalize_date_gives_up_on_anything_else() {
+    assert_eq!(normalize_date(""), "");
+    assert_eq!(normalize_date("last Tuesday"), "");
+  }
+
+  #[test]
+  fn layout_recognizes_linkedin_exports() {
+    assert!(matches!(
+      layout("Saved Jobs.csv"),
+      Some((Source::Linkedin, Format::Csv, "saved"))
+    ));
+    assert!(matches!(
+      layout("Jobs/Job Applications_1.csv"),
+      Some((Source::Linkedin, Format::Csv, "applied"))
+    ));
+  }
+
+  #[test]
+  fn layout_recognizes_indeed_exports() {
+    assert!(matches!(
+      layout("my_jobs_saved.json"),
+      Some((Source::Indeed, Format::Json, "saved"))
+    ));
+    assert!(matches!(
+      layout("applied-jobs.CSV"),
+      Some((Source::Indeed, Format::Csv, "applied"))
+    ));
+  }
+
+  #[test]
+  fn layout_ignores_other_files() {
+    assert!(layout("Connections.csv").is_none());
+    assert!(layout("Saved Jobs.txt").is_none());
+    assert!(layout("README").is_none());
+  }
+}
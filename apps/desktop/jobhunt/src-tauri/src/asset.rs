@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use reqwest::Method;
+use tauri::http::{header, HeaderValue, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, UriSchemeContext, UriSchemeResponder};
+
+use crate::{engine, proxy};
+
+/// `jobhunt-asset://localhost/<kind>/<id>`; Windows webviews see it as
+/// `http://jobhunt-asset.localhost/<kind>/<id>`.
+pub const SCHEME: &str = "jobhunt-asset";
+const READY_TIMEOUT: Duration = Duration::from_secs(5);
+// Origins the bundled frontend is served from, per platform.
+const APP_ORIGINS: &[&str] = &[
+  "tauri://localhost",
+  "http://tauri.localhost",
+  "https://tauri.localhost",
+];
+// Request headers passed through so the engine can answer ranges and revalidations.
+const FORWARDED_REQUEST: &[header::HeaderName] = &[
+  header::RANGE,
+  header::IF_RANGE,
+  header::IF_NONE_MATCH,
+  header::IF_MODIFIED_SINCE,
+];
+const FORWARDED_RESPONSE: &[header::HeaderName] = &[
+  header::CONTENT_TYPE,
+  header::CONTENT_RANGE,
+  header::ACCEPT_RANGES,
+  header::CACHE_CONTROL,
+  header::ETAG,
+  header::LAST_MODIFIED,
+];
+
+/// Engine path for an asset URL path, e.g. `/logo/acme` -> `/logo/acme`.
+fn engine_path(path: &str) -> Option<String> {
+  let (kind, id) = path.trim_start_matches('/').split_once('/')?;
+  let valid = !id.is_empty()
+    && id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    && !id.starts_with('.');
+  if !valid {
+    return None;
+  }
+  match kind {
+    "logo" => Some(format!("/logo/{}", id)),
+    "attachment" => Some(format!("/api/attachments/{}", id)),
+    _ => None,
+  }
+}
+
+/// The origin of the page in `label` if it is one of the app's own pages.
+/// Anything else (a webview that navigated away, say) gets nothing.
+fn app_origin(app: &AppHandle, label: &str) -> Option<String> {
+  let page = app.get_webview_window(label)?.url().ok()?;
+  let origin = page.origin().ascii_serialization();
+  let dev_origin = app
+    .config()
+    .build
+    .dev_url
+    .as_ref()
+    .filter(|_| cfg!(debug_assertions))
+    .map(|u| u.origin().ascii_serialization());
+  let trusted = APP_ORIGINS.contains(&origin.as_str()) || dev_origin.as_deref() == Some(&origin);
+  trusted.then_some(origin)
+}
+
+fn error(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+  Response::builder()
+    .status(status)
+    .header(header::CONTENT_TYPE, "text/plain")
+    .body(message.as_bytes().to_vec())
+    .unwrap_or_default()
+}
+
+async fn fetch(app: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+  if request.method() != Method::GET {
+    return error(StatusCode::METHOD_NOT_ALLOWED, "method not allowed");
+  }
+  let Some(path) = engine_path(request.uri().path()) else {
+    return error(StatusCode::NOT_FOUND, "unknown asset");
+  };
+  if let Err(e) = engine::wait_for_ready(app, READY_TIMEOUT).await {
+    return error(StatusCode::SERVICE_UNAVAILABLE, &e);
+  }
+
+  let mut req = proxy::engine_request(app, Method::GET, &path);
+  for name in FORWARDED_REQUEST {
+    if let Some(value) = request.headers().get(name) {
+      req = req.header(name, value);
+    }
+  }
+  let resp = match proxy::send(app, req).await {
+    Ok(resp) => resp,
+    Err(e) => return error(StatusCode::BAD_GATEWAY, &e.to_string()),
+  };
+
+  let mut builder = Response::builder().status(resp.status());
+  for name in FORWARDED_RESPONSE {
+    if let Some(value) = resp.headers().get(name) {
+      builder = builder.header(name, value);
+    }
+  }
+  match resp.bytes().await {
+    Ok(body) => builder
+      .body(body.to_vec())
+      .unwrap_or_else(|e| error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())),
+    Err(e) => error(StatusCode::BAD_GATEWAY, &e.to_string()),
+  }
+}
+
+/// Serves résumés and logos from the engine to the app's own pages, adding
+/// the engine's auth so the webview never talks to its port directly.
+pub fn handle(
+  ctx: UriSchemeContext<'_, tauri::Wry>,
+  request: Request<Vec<u8>>,
+  responder: UriSchemeResponder,
+) {
+  let app = ctx.app_handle().clone();
+  let Some(origin) = app_origin(&app, ctx.webview_label()) else {
+    eprintln!("[asset] refused {} from {:?}", request.uri(), ctx.webview_label());
+    responder.respond(error(StatusCode::FORBIDDEN, "forbidden"));
+    return;
+  };
+
+  tauri::async_runtime::spawn(async move {
+    let mut response = fetch(&app, &request).await;
+    let headers = response.headers_mut();
+    // Lets pdf.js and fetch() read the bytes cross-origin, but only for our pages.
+    if let Ok(value) = HeaderValue::from_str(&origin) {
+      headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+    }
+    headers.insert(header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    responder.respond(response);
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn engine_path_maps_known_kinds() {
+    assert_eq!(engine_path("/logo/acme").as_deref(), Some("/logo/acme"));
+    assert_eq!(
+      engine_path("/attachment/3f2a_b-1.pdf").as_deref(),
+      Some("/api/attachments/3f2a_b-1.pdf")
+    );
+  }
+
+  #[test]
+  fn engine_path_rejects_anything_that_could_leave_the_kind() {
+    for path in [
+      "/logo/../jobs",
+      "/logo/..",
+      "/logo/.hidden",
+      "/attachment/a/b",
+      "/attachment/a%2Fb",
+      "/attachment/a?b",
+      "/attachment/",
+      "/attachment",
+      "/jobs/1",
+    ] {
+      assert_eq!(engine_path(path), None, "{}", path);
+    }
+  }
+}
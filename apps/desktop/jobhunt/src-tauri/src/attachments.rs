@@ -0,0 +1,176 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::{engine, i18n, integrity, previews, proxy};
+
+const STORE_DIR: &str = "attachments";
+// Older versions kept a plaintext copy of the engine's registry here.
+const LEGACY_INDEX_FILE: &str = "index.json";
+const EXTENSIONS: &[&str] = &["pdf", "doc", "docx", "odt", "rtf", "txt", "md"];
+
+/// A file in the content-addressed store. `id` is the SHA-256 of its contents,
+/// so importing the same file twice yields the same attachment.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+  pub id: String,
+  pub name: String,
+  pub kind: String,
+  pub path: String,
+  pub size: u64,
+  pub added_at: u64,
+  /// The job this was captured for, if any.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub job_id: Option<i64>,
+}
+
+fn store_dir(app: &AppHandle) -> Result<PathBuf, String> {
+  Ok(engine::data_dir(app)?.join(STORE_DIR))
+}
+
+async fn register(app: &AppHandle, attachment: &Attachment) -> Result<(), String> {
+  let body = serde_json::to_value(attachment).map_err(|e| e.to_string())?;
+  let resp = proxy::engine_fetch(app, "POST", "/api/attachments", Some(body)).await?;
+  if resp.status >= 400 {
+    return Err(format!("engine rejected attachment: HTTP {} {}", resp.status, resp.body));
+  }
+  Ok(())
+}
+
+/// Copies `src` into the store (unless identical content is already there)
+/// and registers it with the engine, linked to `job_id` if given. The engine
+/// seals the stored copy when a passphrase is set.
+pub async fn import(
+  app: &AppHandle,
+  src: &Path,
+  kind: &str,
+  job_id: Option<i64>,
+) -> Result<Attachment, String> {
+  let dir = store_dir(app)?;
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+  let id = integrity::sha256_file(src).map_err(|e| format!("cannot read {}: {}", src.display(), e))?;
+  if let Some(existing) = list(app).await?.into_iter().find(|a| a.id == id) {
+    return Ok(existing);
+  }
+
+  let name = src
+    .file_name()
+    .map(|n| n.to_string_lossy().to_string())
+    .unwrap_or_else(|| id.clone());
+  let stored = match src.extension() {
+    Some(ext) => dir.join(format!("{}.{}", id, ext.to_string_lossy().to_lowercase())),
+    None => dir.join(&id),
+  };
+  let size = std::fs::copy(src, &stored).map_err(|e| e.to_string())?;
+
+  let attachment = Attachment {
+    id,
+    name,
+    kind: kind.to_string(),
+    path: stored.to_string_lossy().to_string(),
+    size,
+    added_at: SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs())
+      .unwrap_or_default(),
+    job_id,
+  };
+  if let Err(e) = register(app, &attachment).await {
+    let _ = std::fs::remove_file(&stored);
+    return Err(e);
+  }
+
+  println!("[attachments] stored {} as {}", attachment.name, attachment.id);
+  Ok(attachment)
+}
+
+/// Opens a file picker and imports every selected file as `kind`.
+pub async fn pick_and_import(app: &AppHandle, kind: &str) -> Result<Vec<Attachment>, String> {
+  let Some(files) = app
+    .dialog()
+    .file()
+    .set_title(i18n::t("dialog.attachments"))
+    .add_filter(i18n::t("filter.documents"), EXTENSIONS)
+    .blocking_pick_files()
+  else {
+    return Ok(Vec::new());
+  };
+
+  let mut added = Vec::new();
+  for file in files {
+    let path = file.into_path().map_err(|e| e.to_string())?;
+    added.push(import(app, &path, kind, None).await?);
+  }
+  Ok(added)
+}
+
+/// The engine's registry, which is sealed along with everything else.
+pub async fn list(app: &AppHandle) -> Result<Vec<Attachment>, String> {
+  if let Ok(dir) = store_dir(app) {
+    let _ = std::fs::remove_file(dir.join(LEGACY_INDEX_FILE));
+  }
+  let resp = proxy::engine_fetch(app, "GET", "/api/attachments", None).await?;
+  if resp.status >= 400 {
+    return Err(format!("engine returned HTTP {} {}", resp.status, resp.body));
+  }
+  serde_json::from_str(&resp.body).map_err(|e| e.to_string())
+}
+
+pub async fn find(app: &AppHandle, id: &str) -> Result<Attachment, String> {
+  list(app)
+    .await?
+    .into_iter()
+    .find(|a| a.id == id)
+    .ok_or_else(|| format!("no attachment {}", id))
+}
+
+/// The file's contents, opened by the engine since the stored copy may be sealed.
+pub async fn contents(app: &AppHandle, attachment: &Attachment) -> Result<Vec<u8>, String> {
+  let req = proxy::engine_request(
+    app,
+    reqwest::Method::GET,
+    &format!("/api/attachments/{}", attachment.id),
+  );
+  let resp = proxy::send(app, req).await.map_err(|e| e.to_string())?;
+  if !resp.status().is_success() {
+    return Err(format!(
+      "engine returned HTTP {} for {}",
+      resp.status(),
+      attachment.name
+    ));
+  }
+  let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+  Ok(bytes.to_vec())
+}
+
+/// Removes an attachment from the engine and disk.
+pub async fn delete(app: &AppHandle, id: &str) -> Result<(), String> {
+  let attachment = find(app, id).await?;
+  let path = format!("/api/attachments/{}", attachment.id);
+  let resp = proxy::engine_fetch(app, "DELETE", &path, None).await?;
+  if resp.status >= 400 && resp.status != 404 {
+    return Err(format!("engine refused delete: HTTP {} {}", resp.status, resp.body));
+  }
+
+  if let Err(e) = std::fs::remove_file(&attachment.path) {
+    eprintln!("[attachments] failed to remove {}: {}", attachment.path, e);
+  }
+  Ok(())
+}
+
+/// Shows a readable copy of the file in the OS file manager; the stored one
+/// may be sealed.
+pub async fn reveal(app: &AppHandle, id: &str) -> Result<(), String> {
+  let attachment = find(app, id).await?;
+  let copy = previews::private_copy(app, &attachment).await?;
+  app
+    .opener()
+    .reveal_item_in_dir(&copy)
+    .map_err(|e| e.to_string())
+}
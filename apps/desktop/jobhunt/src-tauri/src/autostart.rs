@@ -0,0 +1,37 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+
+use crate::splash::SPLASH_WINDOW;
+
+/// Passed by the login item so the app starts in the tray with no window.
+pub const HIDDEN_ARG: &str = "--hidden";
+
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+  tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(vec![HIDDEN_ARG]))
+}
+
+pub fn launched_hidden() -> bool {
+  std::env::args().any(|a| a == HIDDEN_ARG)
+}
+
+/// For a login launch: drop the splash and leave the main window hidden; the
+/// engine still starts as usual.
+pub fn start_hidden(app: &AppHandle) {
+  if let Some(splash) = app.get_webview_window(SPLASH_WINDOW) {
+    let _ = splash.destroy();
+  }
+}
+
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+  let launcher = app.autolaunch();
+  let res = if enabled {
+    launcher.enable()
+  } else {
+    launcher.disable()
+  };
+  res.map_err(|e| e.to_string())
+}
+
+pub fn is_enabled(app: &AppHandle) -> Result<bool, String> {
+  app.autolaunch().is_enabled().map_err(|e| e.to_string())
+}
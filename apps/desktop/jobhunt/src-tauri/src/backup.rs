@@ -0,0 +1,265 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::engine::{self, EngineState};
+use crate::{monitor, pidfile, proxy, settings, transport};
+
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "jobhunt-backup-";
+// Scratch dirs used while a restore swaps the data dir contents.
+const STAGING_DIR: &str = ".restore";
+const PREVIOUS_DIR: &str = ".pre-restore";
+const DEFAULT_RETENTION: usize = 7;
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DAILY: Duration = Duration::from_secs(24 * 60 * 60);
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where scheduled backups go unless `backup_dir` is set.
+pub fn default_backup_dir(app: &AppHandle) -> Result<PathBuf, String> {
+  Ok(engine::data_dir(app)?.join(BACKUP_DIR))
+}
+
+/// Entries that belong to the shell, or to one engine session, rather than
+/// the engine's data. Heap profiles are diagnostics, not data.
+fn skipped(app: &AppHandle, path: &Path) -> bool {
+  let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+  [
+    BACKUP_DIR,
+    STAGING_DIR,
+    PREVIOUS_DIR,
+    monitor::DUMPS_DIR,
+    pidfile::PID_FILE,
+    pidfile::LOCK_FILE,
+    transport::SOCKET_DIR,
+  ]
+  .contains(&name)
+    || settings::settings_path(app).is_ok_and(|p| p == path)
+}
+
+fn add_entry(
+  zip: &mut ZipWriter<File>,
+  path: &Path,
+  name: &str,
+  options: SimpleFileOptions,
+) -> Result<(), String> {
+  if path.is_dir() {
+    zip.add_directory(name, options).map_err(|e| e.to_string())?;
+    for entry in std::fs::read_dir(path).map_err(|e| e.to_string())? {
+      let entry = entry.map_err(|e| e.to_string())?;
+      let child = format!("{}/{}", name, entry.file_name().to_string_lossy());
+      add_entry(zip, &entry.path(), &child, options)?;
+    }
+  } else if path.is_file() {
+    // Anything else, like the engine's socket, can't be read and is left out.
+    zip.start_file(name, options).map_err(|e| e.to_string())?;
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    std::io::copy(&mut file, zip).map_err(|e| e.to_string())?;
+  }
+  Ok(())
+}
+
+fn write_zip(app: &AppHandle, data_dir: &Path, dest: &Path) -> Result<(), String> {
+  let file = File::create(dest).map_err(|e| e.to_string())?;
+  let mut zip = ZipWriter::new(file);
+  let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+  for entry in std::fs::read_dir(data_dir).map_err(|e| e.to_string())? {
+    let entry = entry.map_err(|e| e.to_string())?;
+    if skipped(app, &entry.path()) {
+      continue;
+    }
+    let name = entry.file_name().to_string_lossy().to_string();
+    add_entry(&mut zip, &entry.path(), &name, options)?;
+  }
+  zip.finish().map_err(|e| e.to_string())?;
+  Ok(())
+}
+
+/// Snapshots the data dir into `dest/jobhunt-backup-<timestamp>.zip`.
+///
+/// The engine is asked to checkpoint its WAL first so the DB file on disk is
+/// complete; the zip is written under a temporary name and renamed at the end.
+pub async fn create_backup(app: &AppHandle, dest: PathBuf) -> Result<PathBuf, String> {
+  let endpoint = app.state::<EngineState>().info.lock().unwrap().endpoint.clone();
+  if let Some(endpoint) = endpoint {
+    let req = transport::client(&endpoint).post(endpoint.url("/db/checkpoint"));
+    if let Err(e) = proxy::send(app, req).await {
+      eprintln!("[backup] checkpoint failed: {}", e);
+    }
+  }
+
+  let data_dir = engine::data_dir(app)?;
+  std::fs::create_dir_all(&dest).map_err(|e| e.to_string())?;
+  let ts = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default();
+  let path = dest.join(format!("{}{}.zip", BACKUP_PREFIX, ts));
+  let partial = path.with_extension("zip.partial");
+
+  let handle = app.clone();
+  let tmp = partial.clone();
+  let written = tauri::async_runtime::spawn_blocking(move || write_zip(&handle, &data_dir, &tmp))
+    .await
+    .map_err(|e| e.to_string())?;
+  if let Err(e) = written {
+    let _ = std::fs::remove_file(&partial);
+    return Err(format!("backup failed: {}", e));
+  }
+  std::fs::rename(&partial, &path).map_err(|e| e.to_string())?;
+
+  println!("[backup] wrote {}", path.display());
+  Ok(path)
+}
+
+fn move_entries(from: &Path, to: &Path, skip: impl Fn(&Path) -> bool) -> Result<(), String> {
+  std::fs::create_dir_all(to).map_err(|e| e.to_string())?;
+  for entry in std::fs::read_dir(from).map_err(|e| e.to_string())? {
+    let entry = entry.map_err(|e| e.to_string())?;
+    if skip(&entry.path()) {
+      continue;
+    }
+    std::fs::rename(entry.path(), to.join(entry.file_name())).map_err(|e| e.to_string())?;
+  }
+  Ok(())
+}
+
+fn clear_entries(dir: &Path, skip: impl Fn(&Path) -> bool) {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let path = entry.path();
+    if skip(&path) {
+      continue;
+    }
+    let res = if path.is_dir() {
+      std::fs::remove_dir_all(&path)
+    } else {
+      std::fs::remove_file(&path)
+    };
+    if let Err(e) = res {
+      eprintln!("[backup] failed to remove {}: {}", path.display(), e);
+    }
+  }
+}
+
+/// Replaces the data dir contents with the backup at `src` and restarts the
+/// engine. The previous contents are kept aside until the engine comes up on
+/// the restored data, and put back if it doesn't.
+pub async fn restore_backup(app: &AppHandle, src: PathBuf) -> Result<(), String> {
+  if *app.state::<EngineState>().attached.lock().unwrap() {
+    return Err("attached to an external engine; restore its data yourself".into());
+  }
+  let mut archive = File::open(&src)
+    .map_err(|e| e.to_string())
+    .and_then(|f| ZipArchive::new(f).map_err(|e| e.to_string()))
+    .map_err(|e| format!("{} is not a readable backup: {}", src.display(), e))?;
+
+  let data_dir = engine::data_dir(app)?;
+  let staging = data_dir.join(STAGING_DIR);
+  let previous = data_dir.join(PREVIOUS_DIR);
+  let _ = std::fs::remove_dir_all(&staging);
+  let _ = std::fs::remove_dir_all(&previous);
+
+  engine::stop_engine(app).await;
+
+  if let Err(e) = archive.extract(&staging) {
+    let _ = std::fs::remove_dir_all(&staging);
+    engine::spawn_engine(app).await?;
+    return Err(format!("could not extract {}: {}", src.display(), e));
+  }
+
+  let swapped = move_entries(&data_dir, &previous, |p| skipped(app, p))
+    .and_then(|_| move_entries(&staging, &data_dir, |p| skipped(app, p)));
+  let _ = std::fs::remove_dir_all(&staging);
+
+  let started = match swapped {
+    Ok(()) => match engine::spawn_engine(app).await {
+      Ok(()) => engine::wait_for_ready(app, READY_TIMEOUT).await.map(|_| ()),
+      Err(e) => Err(e.into()),
+    },
+    Err(e) => Err(e),
+  };
+  if let Err(e) = started {
+    engine::stop_engine(app).await;
+    clear_entries(&data_dir, |p| skipped(app, p));
+    move_entries(&previous, &data_dir, |_| false)?;
+    let _ = std::fs::remove_dir_all(&previous);
+    engine::spawn_engine(app).await?;
+    return Err(format!("restore from {} failed: {}", src.display(), e));
+  }
+
+  let _ = std::fs::remove_dir_all(&previous);
+  println!("[backup] restored {}", src.display());
+  Ok(())
+}
+
+/// Backups in `dir`, newest first.
+fn list_backups(dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return Vec::new();
+  };
+  let mut backups: Vec<(PathBuf, SystemTime)> = entries
+    .flatten()
+    .filter(|e| {
+      let name = e.file_name().to_string_lossy().to_string();
+      name.starts_with(BACKUP_PREFIX) && name.ends_with(".zip")
+    })
+    .filter_map(|e| Some((e.path(), e.metadata().ok()?.modified().ok()?)))
+    .collect();
+  backups.sort_by_key(|b| std::cmp::Reverse(b.1));
+  backups
+}
+
+fn prune(dir: &Path, keep: usize) {
+  for (path, _) in list_backups(dir).into_iter().skip(keep) {
+    match std::fs::remove_file(&path) {
+      Ok(()) => println!("[backup] pruned {}", path.display()),
+      Err(e) => eprintln!("[backup] failed to prune {}: {}", path.display(), e),
+    }
+  }
+}
+
+/// Takes a backup once a day while `backup_daily` is on, keeping the newest
+/// `backup_retention` of them.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(CHECK_INTERVAL).await;
+
+      let settings = settings::current(&app);
+      if !settings.backup_daily {
+        continue;
+      }
+      let dir = match settings.backup_dir {
+        Some(dir) => dir,
+        None => match default_backup_dir(&app) {
+          Ok(dir) => dir,
+          Err(e) => {
+            eprintln!("[backup] {}", e);
+            continue;
+          }
+        },
+      };
+
+      let due = list_backups(&dir)
+        .first()
+        .and_then(|(_, t)| t.elapsed().ok())
+        .is_none_or(|age| age >= DAILY);
+      if !due {
+        continue;
+      }
+      if let Err(e) = create_backup(&app, dir.clone()).await {
+        eprintln!("[backup] scheduled backup failed: {}", e);
+        continue;
+      }
+      prune(&dir, settings.backup_retention.unwrap_or(DEFAULT_RETENTION).max(1));
+    }
+  });
+}
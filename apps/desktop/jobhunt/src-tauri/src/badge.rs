@@ -0,0 +1,136 @@
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+
+const MAIN_WINDOW: &str = "main";
+
+/// New matches seen since the main window last had focus.
+#[derive(Default)]
+pub struct BadgeState(Mutex<u32>);
+
+fn main_focused(app: &AppHandle) -> bool {
+  app
+    .get_webview_window(MAIN_WINDOW)
+    .is_some_and(|w| w.is_visible().unwrap_or(false) && w.is_focused().unwrap_or(false))
+}
+
+/// Shows `count` on the app's dock/taskbar entry; 0 clears it.
+pub fn update_badge(app: &AppHandle, count: u32) -> Result<(), String> {
+  *app.state::<BadgeState>().0.lock().unwrap() = count;
+  let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+    return Ok(());
+  };
+  imp::apply(&window, count).map_err(|e| e.to_string())
+}
+
+/// Counts new matches from engine events while the user is looking elsewhere.
+pub fn handle(app: &AppHandle, event: &serde_json::Value) {
+  if event.get("type").and_then(|t| t.as_str()) != Some("job_created") || main_focused(app) {
+    return;
+  }
+  let count = {
+    let state = app.state::<BadgeState>();
+    let mut count = state.0.lock().unwrap();
+    *count += 1;
+    *count
+  };
+  if let Err(e) = update_badge(app, count) {
+    eprintln!("[badge] {}", e);
+  }
+}
+
+/// Called when the main window gains focus: the new matches have been seen.
+pub fn on_focus(app: &AppHandle) {
+  if *app.state::<BadgeState>().0.lock().unwrap() == 0 {
+    return;
+  }
+  if let Err(e) = update_badge(app, 0) {
+    eprintln!("[badge] {}", e);
+  }
+}
+
+#[cfg(windows)]
+mod imp {
+  use tauri::image::Image;
+  use tauri::WebviewWindow;
+
+  const SIZE: usize = 16;
+  const SCALE: usize = 2;
+  const RED: [u8; 4] = [220, 38, 38, 255];
+  const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+  // 3x5 glyphs for 0-9 and '+', one row per u8, high bit on the left.
+  const GLYPHS: [[u8; 5]; 11] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b000, 0b010, 0b111, 0b010, 0b000],
+  ];
+  const PLUS: usize = 10;
+
+  fn put(rgba: &mut [u8], x: usize, y: usize, color: [u8; 4]) {
+    if x < SIZE && y < SIZE {
+      let i = (y * SIZE + x) * 4;
+      rgba[i..i + 4].copy_from_slice(&color);
+    }
+  }
+
+  /// A red dot with the count in white; anything past 9 reads "9+".
+  fn render(count: u32) -> Image<'static> {
+    let mut rgba = vec![0u8; SIZE * SIZE * 4];
+    let centre = (SIZE as f32 - 1.0) / 2.0;
+    for y in 0..SIZE {
+      for x in 0..SIZE {
+        let (dx, dy) = (x as f32 - centre, y as f32 - centre);
+        if dx * dx + dy * dy <= (SIZE as f32 / 2.0).powi(2) {
+          put(&mut rgba, x, y, RED);
+        }
+      }
+    }
+
+    let glyphs: Vec<usize> = if count > 9 { vec![9, PLUS] } else { vec![count as usize] };
+    let glyph_w = 3 * SCALE;
+    let width = glyphs.len() * glyph_w + (glyphs.len() - 1) * SCALE;
+    let left = (SIZE - width) / 2;
+    let top = (SIZE - 5 * SCALE) / 2;
+    for (n, glyph) in glyphs.iter().enumerate() {
+      let x0 = left + n * (glyph_w + SCALE);
+      for (row, bits) in GLYPHS[*glyph].iter().enumerate() {
+        for col in 0..3 {
+          if bits & (0b100 >> col) == 0 {
+            continue;
+          }
+          for sy in 0..SCALE {
+            for sx in 0..SCALE {
+              put(&mut rgba, x0 + col * SCALE + sx, top + row * SCALE + sy, WHITE);
+            }
+          }
+        }
+      }
+    }
+    Image::new_owned(rgba, SIZE as u32, SIZE as u32)
+  }
+
+  /// Windows has no badge count; draw the number as a taskbar overlay icon.
+  pub fn apply(window: &WebviewWindow, count: u32) -> tauri::Result<()> {
+    window.set_overlay_icon((count > 0).then(|| render(count)))
+  }
+}
+
+#[cfg(not(windows))]
+mod imp {
+  use tauri::WebviewWindow;
+
+  /// The dock badge on macOS; the launcher count on Linux desktops that
+  /// support the Unity LauncherEntry API.
+  pub fn apply(window: &WebviewWindow, count: u32) -> tauri::Result<()> {
+    window.set_badge_count((count > 0).then_some(count as i64))
+  }
+}
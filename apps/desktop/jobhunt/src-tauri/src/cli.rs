@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+pub const USAGE: &str = "\
+Usage: jobhunt [options]
+
+Options:
+  --headless         Run only the engine, restarted if it exits, without the desktop UI
+  --port <port>      Run the engine on this loopback port for this session
+  --data-dir <dir>   Use this data directory for this session
+  --profile <name>   Use this profile for this session
+  --hidden           Start in the tray without showing a window
+  --attach <url>     Use an already running engine instead of spawning one
+  -h, --help         Show this help";
+
+/// Command-line overrides for this run. None of them are saved to settings.
+#[derive(Debug, Default)]
+pub struct CliArgs {
+  pub headless: bool,
+  pub port: Option<u16>,
+  pub data_dir: Option<PathBuf>,
+  pub profile: Option<String>,
+  pub help: bool,
+}
+
+static ARGS: OnceLock<CliArgs> = OnceLock::new();
+
+/// Parses our flags out of `args` (program name first). Other arguments, like
+/// deep links or plugin flags, are left for whoever consumes them.
+pub fn parse(args: &[String]) -> Result<CliArgs, String> {
+  let mut cli = CliArgs::default();
+  let mut iter = args.iter().skip(1);
+  while let Some(arg) = iter.next() {
+    let (flag, inline) = match arg.split_once('=') {
+      Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+      _ => (arg.as_str(), None),
+    };
+    let mut value = |name: &str| {
+      inline
+        .clone()
+        .or_else(|| iter.next().cloned())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| format!("{} needs a value", name))
+    };
+    match flag {
+      "--headless" => cli.headless = true,
+      "-h" | "--help" => cli.help = true,
+      "--port" => {
+        let port = value(flag)?;
+        cli.port = Some(
+          port
+            .parse()
+            .ok()
+            .filter(|p| *p != 0)
+            .ok_or_else(|| format!("invalid port {:?}", port))?,
+        );
+      }
+      "--data-dir" => cli.data_dir = Some(PathBuf::from(value(flag)?)),
+      "--profile" => cli.profile = Some(value(flag)?),
+      _ => {}
+    }
+  }
+  Ok(cli)
+}
+
+/// Parses the process arguments once; later calls return the same result.
+pub fn init() -> Result<&'static CliArgs, String> {
+  if let Some(cli) = ARGS.get() {
+    return Ok(cli);
+  }
+  let args: Vec<String> = std::env::args().collect();
+  let cli = parse(&args)?;
+  Ok(ARGS.get_or_init(|| cli))
+}
+
+/// The flags this process was started with. Empty until `init` has run.
+pub fn args() -> &'static CliArgs {
+  ARGS.get_or_init(CliArgs::default)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn args(list: &[&str]) -> Vec<String> {
+    std::iter::once("jobhunt")
+      .chain(list.iter().copied())
+      .map(String::from)
+      .collect()
+  }
+
+  #[test]
+  fn parse_reads_flags_with_separate_and_inline_values() {
+    let cli = parse(&args(&[
+      "--headless",
+      "--port",
+      "4000",
+      "--data-dir=/tmp/jh",
+      "--profile",
+      "work",
+    ]))
+    .unwrap();
+    assert!(cli.headless);
+    assert_eq!(cli.port, Some(4000));
+    assert_eq!(cli.data_dir, Some(PathBuf::from("/tmp/jh")));
+    assert_eq!(cli.profile.as_deref(), Some("work"));
+    assert!(!cli.help);
+  }
+
+  #[test]
+  fn parse_ignores_the_program_name_and_unknown_arguments() {
+    let cli = parse(&args(&["jobhunt://job/12", "--some-plugin-flag", "-h"])).unwrap();
+    assert!(cli.help);
+    assert!(!cli.headless);
+    assert_eq!(cli.port, None);
+    assert!(parse(&["--headless".to_string()]).is_ok_and(|cli| !cli.headless));
+  }
+
+  #[test]
+  fn parse_rejects_missing_values() {
+    assert!(parse(&args(&["--port"])).is_err());
+    assert!(parse(&args(&["--profile="])).is_err());
+    assert!(parse(&args(&["--data-dir", ""])).is_err());
+  }
+
+  #[test]
+  fn parse_rejects_invalid_ports() {
+    for port in ["0", "65536", "-1", "http"] {
+      assert!(parse(&args(&["--port", port])).is_err(), "{:?} accepted", port);
+    }
+  }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Url};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::notifications::{self, ClickAction};
+use crate::{i18n, proxy, settings};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+// The same posting copied again within this window is not offered twice.
+const OFFER_COOLDOWN: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone, Serialize)]
+struct JobUrlAdded {
+  url: String,
+  added: bool,
+}
+
+/// Whether `url` points at a single posting on a board we know.
+fn is_job_posting(url: &Url) -> bool {
+  let host = url.host_str().unwrap_or_default().to_lowercase();
+  let path = url.path();
+  let has_query = |key: &str| url.query_pairs().any(|(k, _)| k == key);
+
+  if host == "linkedin.com" || host.ends_with(".linkedin.com") {
+    return path.starts_with("/jobs/view/") || has_query("currentJobId");
+  }
+  if host == "indeed.com" || host.ends_with(".indeed.com") {
+    return path.starts_with("/viewjob") || has_query("jk") || has_query("vjk");
+  }
+  if host.ends_with("greenhouse.io") {
+    return path.contains("/jobs/");
+  }
+  if host == "jobs.lever.co" {
+    return path.trim_matches('/').split('/').count() >= 2;
+  }
+  false
+}
+
+fn job_url(text: &str) -> Option<Url> {
+  let text = text.trim();
+  if text.contains(char::is_whitespace) {
+    return None;
+  }
+  let url = Url::parse(text).ok()?;
+  (matches!(url.scheme(), "http" | "https") && is_job_posting(&url)).then_some(url)
+}
+
+/// Posts `url` to the engine's ingest endpoint and reports the outcome as
+/// `clipboard-job-added`.
+pub fn ingest(app: &AppHandle, url: String) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let body = serde_json::json!({ "url": url, "source": "clipboard" });
+    let result = proxy::engine_fetch(&app, "POST", "/api/ingest", Some(body)).await;
+    match result {
+      Ok(resp) if resp.status < 400 => {
+        let added = serde_json::from_str::<serde_json::Value>(&resp.body)
+          .ok()
+          .and_then(|v| v.get("added")?.as_bool())
+          .unwrap_or(false);
+        println!("[clipboard] ingested {} (new: {})", url, added);
+        let _ = app.emit("clipboard-job-added", JobUrlAdded { url, added });
+      }
+      Ok(resp) => eprintln!("[clipboard] ingest returned HTTP {}: {}", resp.status, resp.body.trim()),
+      Err(e) => eprintln!("[clipboard] ingest failed: {}", e),
+    }
+  });
+}
+
+/// Watches the clipboard while `watch_clipboard` is on and offers to add
+/// copied job posting URLs.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let mut last_text = String::new();
+    let mut offered: HashMap<String, Instant> = HashMap::new();
+
+    loop {
+      tokio::time::sleep(POLL_INTERVAL).await;
+      if !settings::current(&app).watch_clipboard {
+        continue;
+      }
+      let Ok(text) = app.clipboard().read_text() else {
+        continue;
+      };
+      if text == last_text {
+        continue;
+      }
+      last_text = text;
+
+      let Some(url) = job_url(&last_text) else {
+        continue;
+      };
+      let url = url.to_string();
+      offered.retain(|_, t| t.elapsed() < OFFER_COOLDOWN);
+      if offered.contains_key(&url) {
+        continue;
+      }
+      offered.insert(url.clone(), Instant::now());
+
+      notifications::show(
+        &app,
+        &i18n::t("notify.clipboard.title"),
+        &i18n::t_with("notify.clipboard.body", &[("url", &url)]),
+        ClickAction::Ingest(url),
+      );
+    }
+  });
+}
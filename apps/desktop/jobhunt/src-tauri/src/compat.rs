@@ -0,0 +1,62 @@
+use semver::{Version, VersionReq};
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::{appupdate, engine, i18n, tray};
+
+// Engine versions this app knows how to talk to. Bump the floor whenever the
+// frontend starts relying on a new engine endpoint.
+const COMPATIBLE_ENGINE: &str = ">=1.1.2, <2.0.0";
+const RELEASES_URL: &str = "https://github.com/stevenmed26/JobHunt/releases/latest";
+
+/// Checks the version the engine reports from /health against `COMPATIBLE_ENGINE`.
+pub fn check(version: &str) -> Result<(), String> {
+  let req = VersionReq::parse(COMPATIBLE_ENGINE).expect("valid engine version requirement");
+  let v = Version::parse(version)
+    .map_err(|e| format!("engine reported an unreadable version {:?}: {}", version, e))?;
+  if req.matches(&v) {
+    Ok(())
+  } else {
+    Err(format!(
+      "The engine is version {}, but this version of JobHunt needs {}.",
+      v, COMPATIBLE_ENGINE
+    ))
+  }
+}
+
+/// Reinstalls via the updater, or sends the user to the releases page if
+/// there's nothing newer to install.
+async fn repair(app: &AppHandle) {
+  if let Err(e) = appupdate::install(app).await {
+    eprintln!("[compat] update failed: {}", e);
+  }
+  if let Err(e) = app.opener().open_url(RELEASES_URL, None::<&str>) {
+    eprintln!("[compat] open releases page failed: {}", e);
+  }
+}
+
+/// Blocks the app behind a dialog offering Repair or Quit.
+pub fn report_mismatch(app: &AppHandle, err: String) {
+  eprintln!("[compat] {}", err);
+  engine::record_error(app, err.clone());
+
+  let handle = app.clone();
+  app
+    .dialog()
+    .message(i18n::t_with("compat.message", &[("error", &err)]))
+    .title(i18n::t("compat.title"))
+    .kind(MessageDialogKind::Error)
+    .buttons(MessageDialogButtons::OkCancelCustom(
+      i18n::t("compat.repair"),
+      i18n::t("compat.quit"),
+    ))
+    .show(move |repair_chosen| {
+      tauri::async_runtime::spawn(async move {
+        if repair_chosen {
+          repair(&handle).await;
+        }
+        tray::quit(&handle).await;
+      });
+    });
+}
@@ -0,0 +1,142 @@
+//! Watches internet connectivity so the UI can show an offline banner and the
+//! engine can hold outgoing requests until the network is back.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{netproxy, proxy};
+
+// Answers 204 with an empty body; anything else means a portal rewrote it.
+const PROBE_URL: &str = "http://connectivitycheck.gstatic.com/generate_204";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const ONLINE_INTERVAL: Duration = Duration::from_secs(30);
+// Check more often while offline so the banner clears soon after the network returns.
+const OFFLINE_INTERVAL: Duration = Duration::from_secs(10);
+// Consecutive failed probes before going offline, so one dropped request doesn't flap.
+const OFFLINE_AFTER: u32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Connectivity {
+  Online,
+  Offline,
+  /// Connected to a network that intercepts traffic until you sign in.
+  CaptivePortal,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ConnectivityStatus {
+  pub status: Connectivity,
+  /// Where the portal redirected the probe, if it said.
+  pub portal_url: Option<String>,
+}
+
+#[derive(Default)]
+pub struct ConnectivityState(Mutex<Option<ConnectivityStatus>>);
+
+async fn probe(client: &reqwest::Client) -> ConnectivityStatus {
+  let offline = ConnectivityStatus {
+    status: Connectivity::Offline,
+    portal_url: None,
+  };
+  let resp = match client.get(PROBE_URL).timeout(PROBE_TIMEOUT).send().await {
+    Ok(resp) => resp,
+    Err(_) => return offline,
+  };
+  let status = resp.status();
+  let portal_url = resp
+    .headers()
+    .get(reqwest::header::LOCATION)
+    .and_then(|v| v.to_str().ok())
+    .map(|s| s.to_string());
+  let empty = match resp.bytes().await {
+    Ok(body) => body.is_empty(),
+    Err(_) => return offline,
+  };
+  if status == reqwest::StatusCode::NO_CONTENT && empty {
+    ConnectivityStatus {
+      status: Connectivity::Online,
+      portal_url: None,
+    }
+  } else {
+    ConnectivityStatus {
+      status: Connectivity::CaptivePortal,
+      portal_url,
+    }
+  }
+}
+
+/// Tells the engine whether it can reach the internet. A captive portal counts
+/// as offline: requests would only get the sign-in page.
+async fn notify_engine(app: &AppHandle, online: bool) {
+  let body = serde_json::json!({ "online": online });
+  match proxy::engine_fetch(app, "POST", "/control/network", Some(body)).await {
+    Ok(resp) if resp.status < 400 => {}
+    Ok(resp) => eprintln!(
+      "[connectivity] engine returned HTTP {}: {}",
+      resp.status,
+      resp.body.trim()
+    ),
+    Err(e) => eprintln!("[connectivity] could not notify engine: {}", e),
+  }
+}
+
+/// The last observed connectivity, or `None` before the first check.
+pub fn current(app: &AppHandle) -> Option<ConnectivityStatus> {
+  app.state::<ConnectivityState>().0.lock().unwrap().clone()
+}
+
+/// Probes connectivity for the life of the app and reports transitions.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let mut failures = 0u32;
+    loop {
+      // Rebuilt every time so a proxy change in settings or the OS is picked up.
+      let client = match netproxy::outbound(&app).and_then(|b| {
+        b.redirect(reqwest::redirect::Policy::none())
+          .build()
+          .map_err(|e| e.to_string())
+      }) {
+        Ok(client) => client,
+        Err(e) => {
+          eprintln!("[connectivity] cannot build HTTP client: {}", e);
+          tokio::time::sleep(OFFLINE_INTERVAL).await;
+          continue;
+        }
+      };
+      let next = probe(&client).await;
+      if next.status == Connectivity::Offline {
+        failures += 1;
+        let known = current(&app).is_some();
+        if known && failures < OFFLINE_AFTER {
+          tokio::time::sleep(OFFLINE_INTERVAL).await;
+          continue;
+        }
+      } else {
+        failures = 0;
+      }
+
+      let previous = {
+        let state = app.state::<ConnectivityState>();
+        let mut slot = state.0.lock().unwrap();
+        let previous = slot.as_ref().map(|s| s.status);
+        *slot = Some(next.clone());
+        previous
+      };
+      let online = next.status == Connectivity::Online;
+      if previous != Some(next.status) {
+        println!("[connectivity] {:?}", next.status);
+        let _ = app.emit("connectivity-changed", next.clone());
+      }
+      // Re-sent on every check while offline: a restarted engine assumes it is online.
+      if !online || previous.is_some_and(|p| p != Connectivity::Online) {
+        notify_engine(&app, online).await;
+      }
+
+      tokio::time::sleep(if online { ONLINE_INTERVAL } else { OFFLINE_INTERVAL }).await;
+    }
+  });
+}
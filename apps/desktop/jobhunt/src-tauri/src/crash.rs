@@ -0,0 +1,91 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::engine::{self, EngineState};
+use crate::telemetry;
+
+const STDERR_TAIL: usize = 500;
+
+#[derive(Clone, Serialize)]
+struct EngineCrashed {
+  path: String,
+  exit_code: Option<i32>,
+}
+
+/// Keeps the last `STDERR_TAIL` stderr lines of the running engine. The
+/// buffer is cleared whenever a new engine starts, so a report never mixes in
+/// output from the one before.
+pub fn record_stderr(app: &AppHandle, line: &str) {
+  let state = app.state::<EngineState>();
+  let mut tail = state.stderr_tail.lock().unwrap();
+  if tail.len() == STDERR_TAIL {
+    tail.pop_front();
+  }
+  tail.push_back(line.to_string());
+}
+
+fn stderr_tail(app: &AppHandle) -> Vec<String> {
+  let state = app.state::<EngineState>();
+  let tail = state.stderr_tail.lock().unwrap();
+  tail.iter().cloned().collect()
+}
+
+fn write_report(
+  app: &AppHandle,
+  exit_code: Option<i32>,
+  signal: Option<i32>,
+) -> Result<PathBuf, String> {
+  let ts = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default();
+
+  let mut report = String::new();
+  let _ = writeln!(report, "JobHunt engine crash report");
+  let _ = writeln!(report, "time (unix): {}", ts);
+  let _ = writeln!(report, "app version: {}", app.package_info().version);
+  let engine_version = app.state::<EngineState>().info.lock().unwrap().version.clone();
+  let _ = writeln!(report, "engine version: {}", engine_version.as_deref().unwrap_or("unknown"));
+  let _ = writeln!(
+    report,
+    "os: {} ({} {})",
+    sysinfo::System::long_os_version().unwrap_or_else(|| "unknown".into()),
+    std::env::consts::OS,
+    std::env::consts::ARCH
+  );
+  let _ = writeln!(report, "exit code: {:?}", exit_code);
+  let _ = writeln!(report, "signal: {:?}", signal);
+  let _ = writeln!(report, "\n--- last {} stderr lines ---", STDERR_TAIL);
+  for line in stderr_tail(app) {
+    let _ = writeln!(report, "{}", line);
+  }
+
+  let dir = engine::data_dir(app)?.join("crashes");
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let path = dir.join(format!("crash-{}.txt", ts));
+  std::fs::write(&path, report).map_err(|e| e.to_string())?;
+  Ok(path)
+}
+
+/// Writes `crashes/crash-<timestamp>.txt` for an engine that died on its own
+/// and emits `engine-crashed` so the UI can offer to show it.
+pub fn capture(app: &AppHandle, exit_code: Option<i32>, signal: Option<i32>) {
+  telemetry::engine_crashed(app);
+  match write_report(app, exit_code, signal) {
+    Ok(path) => {
+      println!("[crash] report written to {}", path.display());
+      let _ = app.emit(
+        "engine-crashed",
+        EngineCrashed {
+          path: path.to_string_lossy().to_string(),
+          exit_code,
+        },
+      );
+    }
+    Err(e) => eprintln!("[crash] could not write report: {}", e),
+  }
+}
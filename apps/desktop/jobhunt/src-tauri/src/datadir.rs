@@ -0,0 +1,244 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use sysinfo::Disks;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::cli;
+use crate::engine::{self, EngineState};
+use crate::settings;
+use crate::vault;
+
+// The engine runs migrations on a freshly moved DB, so give it longer than a plain restart.
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn dir_size(path: &Path) -> std::io::Result<u64> {
+  let mut total = 0;
+  for entry in std::fs::read_dir(path)? {
+    let entry = entry?;
+    let meta = entry.metadata()?;
+    if meta.is_dir() {
+      total += dir_size(&entry.path())?;
+    } else {
+      total += meta.len();
+    }
+  }
+  Ok(total)
+}
+
+pub fn available_space(path: &Path) -> Option<u64> {
+  // The target may not exist yet; measure the disk of its nearest existing ancestor.
+  let existing = path.ancestors().find(|p| p.exists())?.canonicalize().ok()?;
+  let disks = Disks::new_with_refreshed_list();
+  disks
+    .list()
+    .iter()
+    .filter(|d| existing.starts_with(d.mount_point()))
+    .max_by_key(|d| d.mount_point().as_os_str().len())
+    .map(|d| d.available_space())
+}
+
+#[derive(Clone, Serialize)]
+struct MoveProgress {
+  copied_bytes: u64,
+  total_bytes: u64,
+  file: String,
+}
+
+/// Emits `data-dir-progress` as files are copied.
+struct Progress {
+  app: AppHandle,
+  copied: u64,
+  total: u64,
+}
+
+impl Progress {
+  fn advance(&mut self, bytes: u64, file: &Path) {
+    self.copied += bytes;
+    let _ = self.app.emit(
+      "data-dir-progress",
+      MoveProgress {
+        copied_bytes: self.copied,
+        total_bytes: self.total,
+        file: file.to_string_lossy().to_string(),
+      },
+    );
+  }
+}
+
+fn copy_dir_all(src: &Path, dst: &Path, progress: &mut Progress) -> std::io::Result<()> {
+  std::fs::create_dir_all(dst)?;
+  for entry in std::fs::read_dir(src)? {
+    let entry = entry?;
+    let target = dst.join(entry.file_name());
+    if entry.file_type()?.is_dir() {
+      copy_dir_all(&entry.path(), &target, progress)?;
+    } else if entry.path().is_file() {
+      let n = std::fs::copy(entry.path(), &target)?;
+      progress.advance(n, &entry.path());
+    }
+  }
+  Ok(())
+}
+
+/// Copies the top-level entries of `src` into `dst`, recording each name in
+/// `copied` as it goes so a partial copy can be undone.
+fn copy_entries(
+  src: &Path,
+  dst: &Path,
+  skip: &Path,
+  copied: &mut Vec<OsString>,
+  progress: &mut Progress,
+) -> std::io::Result<()> {
+  std::fs::create_dir_all(dst)?;
+  for entry in std::fs::read_dir(src)? {
+    let entry = entry?;
+    // Sockets and pipes, like the one a killed engine leaves behind, can't be copied.
+    let path = entry.path();
+    if path == skip || !(path.is_dir() || path.is_file()) {
+      continue;
+    }
+    copied.push(entry.file_name());
+    let target = dst.join(entry.file_name());
+    if entry.file_type()?.is_dir() {
+      copy_dir_all(&entry.path(), &target, progress)?;
+    } else {
+      let n = std::fs::copy(entry.path(), &target)?;
+      progress.advance(n, &entry.path());
+    }
+  }
+  Ok(())
+}
+
+fn remove_entries(dir: &Path, names: &[OsString]) {
+  for name in names {
+    let path = dir.join(name);
+    let res = if path.is_dir() {
+      std::fs::remove_dir_all(&path)
+    } else {
+      std::fs::remove_file(&path)
+    };
+    if let Err(e) = res {
+      if path.exists() {
+        eprintln!("[datadir] failed to remove {}: {}", path.display(), e);
+      }
+    }
+  }
+}
+
+/// Undoes a copy into `new_dir`, removing the directory itself if we created it.
+fn discard_copy(new_dir: &Path, copied: &[OsString], created: bool) {
+  remove_entries(new_dir, copied);
+  if created {
+    let _ = std::fs::remove_dir(new_dir);
+  }
+}
+
+/// Runs a file system walk off the async runtime, as a large data dir can
+/// take minutes to measure or copy.
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+  tauri::async_runtime::spawn_blocking(f)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn discard_copy_blocking(new_dir: &Path, copied: Vec<OsString>, created: bool) {
+  let new_dir = new_dir.to_path_buf();
+  let _ = blocking(move || discard_copy(&new_dir, &copied, created)).await;
+}
+
+/// Moves the engine's data to `new_dir` and restarts the engine there.
+///
+/// The old directory is only cleaned up once the engine is serving from the
+/// new one; any earlier failure puts the settings and engine back as they were.
+pub async fn move_data_dir(app: &AppHandle, new_dir: PathBuf) -> Result<(), String> {
+  if *app.state::<EngineState>().attached.lock().unwrap() {
+    return Err("attached to an external engine; move its data dir yourself".into());
+  }
+  if cli::args().data_dir.is_some() {
+    return Err("the data directory is pinned by --data-dir for this session".into());
+  }
+  let old_dir = engine::data_dir(app)?;
+  if new_dir.starts_with(&old_dir) || old_dir.starts_with(&new_dir) {
+    return Err("new data directory must not overlap the current one".into());
+  }
+  let created = !new_dir.exists();
+  if !created {
+    let mut entries = std::fs::read_dir(&new_dir).map_err(|e| e.to_string())?;
+    if entries.next().is_some() {
+      return Err(format!("{} is not empty", new_dir.display()));
+    }
+  }
+
+  let measured = old_dir.clone();
+  let needed = blocking(move || dir_size(&measured))
+    .await?
+    .map_err(|e| e.to_string())?;
+  if let Some(free) = available_space(&new_dir) {
+    if free < needed {
+      return Err(format!(
+        "not enough free space at {}: need {} MB, {} MB available",
+        new_dir.display(),
+        needed / (1024 * 1024),
+        free / (1024 * 1024)
+      ));
+    }
+  }
+
+  // Settings may live inside the default data dir; they stay where they are.
+  let settings_file = settings::settings_path(app)?;
+
+  engine::stop_engine(app).await;
+
+  let mut progress = Progress {
+    app: app.clone(),
+    copied: 0,
+    total: needed,
+  };
+  let (src, dst) = (old_dir.clone(), new_dir.clone());
+  let (copied, result) = blocking(move || {
+    let mut copied = Vec::new();
+    let result = copy_entries(&src, &dst, &settings_file, &mut copied, &mut progress);
+    (copied, result)
+  })
+  .await?;
+  if let Err(e) = result {
+    discard_copy_blocking(&new_dir, copied, created).await;
+    engine::spawn_engine(app).await?;
+    return Err(format!("copy to {} failed: {}", new_dir.display(), e));
+  }
+
+  let previous = settings::current(app);
+  let mut next = previous.clone();
+  next.set_data_dir(new_dir.clone());
+  if let Err(e) = settings::replace(app, next) {
+    discard_copy_blocking(&new_dir, copied, created).await;
+    engine::spawn_engine(app).await?;
+    return Err(e);
+  }
+  vault::rebind(app, &old_dir, &new_dir);
+
+  let started = match engine::spawn_engine(app).await {
+    Ok(()) => engine::wait_for_ready(app, READY_TIMEOUT).await.map(|_| ()),
+    Err(e) => Err(e.into()),
+  };
+  if let Err(e) = started {
+    engine::stop_engine(app).await;
+    settings::replace(app, previous)?;
+    vault::rebind(app, &new_dir, &old_dir);
+    discard_copy_blocking(&new_dir, copied, created).await;
+    engine::spawn_engine(app).await?;
+    return Err(format!("engine failed to start from {}: {}", new_dir.display(), e));
+  }
+
+  let cleaned = old_dir.clone();
+  blocking(move || remove_entries(&cleaned, &copied)).await?;
+  println!(
+    "[datadir] moved {} -> {}",
+    old_dir.display(),
+    new_dir.display()
+  );
+  Ok(())
+}
@@ -0,0 +1,118 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::{engine, nativehost};
+
+const SCHEME: &str = "jobhunt";
+// Links that arrive while the engine is still booting wait at most this long.
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A parsed `jobhunt://` link, e.g. `jobhunt://job/12345` -> `["job", "12345"]`.
+#[derive(Clone, Debug, Serialize)]
+pub struct DeepLinkRoute {
+  pub url: String,
+  pub segments: Vec<String>,
+}
+
+#[derive(Default)]
+struct DeepLinkQueue {
+  pending: Vec<DeepLinkRoute>,
+  // Set once the frontend has drained the queue and is listening for events.
+  frontend_ready: bool,
+}
+
+#[derive(Default)]
+pub struct DeepLinkState(Mutex<DeepLinkQueue>);
+
+pub fn parse(url: &Url) -> Option<DeepLinkRoute> {
+  if url.scheme() != SCHEME {
+    return None;
+  }
+  let segments: Vec<String> = url
+    .host_str()
+    .into_iter()
+    .chain(url.path().split('/'))
+    .filter(|s| !s.is_empty())
+    .map(|s| s.to_string())
+    .collect();
+  if segments.is_empty() {
+    return None;
+  }
+  Some(DeepLinkRoute {
+    url: url.to_string(),
+    segments,
+  })
+}
+
+/// Routes an incoming link: queued until the frontend has picked up the
+/// queue, otherwise emitted as `deep-link` once the engine is ready.
+pub fn handle(app: &AppHandle, url: &Url) {
+  let Some(route) = parse(url) else {
+    eprintln!("[deeplink] ignoring {}", url);
+    return;
+  };
+  println!("[deeplink] {:?}", route.segments);
+
+  // Captures go straight to the engine; the UI doesn't need to be involved.
+  if nativehost::is_capture(&route.segments) {
+    nativehost::handle_capture(app, url);
+    return;
+  }
+
+  {
+    let state = app.state::<DeepLinkState>();
+    let mut queue = state.0.lock().unwrap();
+    if !queue.frontend_ready {
+      queue.pending.push(route);
+      return;
+    }
+  }
+
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    if let Err(e) = engine::wait_for_ready(&app, READY_TIMEOUT).await {
+      eprintln!("[deeplink] {}", e);
+    }
+    if let Some(w) = app.get_webview_window("main") {
+      let _ = w.show();
+      let _ = w.set_focus();
+    }
+    let _ = app.emit("deep-link", route);
+  });
+}
+
+/// Hands queued links to the frontend; later links arrive as `deep-link` events.
+pub fn take_pending(app: &AppHandle) -> Vec<DeepLinkRoute> {
+  let state = app.state::<DeepLinkState>();
+  let mut queue = state.0.lock().unwrap();
+  queue.frontend_ready = true;
+  std::mem::take(&mut queue.pending)
+}
+
+pub fn init(app: &AppHandle) {
+  let deep_link = app.deep_link();
+
+  // Installed builds register the scheme at install time; dev builds need it at runtime.
+  #[cfg(all(debug_assertions, any(target_os = "linux", windows)))]
+  if let Err(e) = deep_link.register_all() {
+    eprintln!("[deeplink] register failed: {}", e);
+  }
+
+  // Cold start: the link that launched the app.
+  if let Ok(Some(urls)) = deep_link.get_current() {
+    for url in &urls {
+      handle(app, url);
+    }
+  }
+
+  let handle_app = app.clone();
+  deep_link.on_open_url(move |event| {
+    for url in &event.urls() {
+      handle(&handle_app, url);
+    }
+  });
+}
@@ -0,0 +1,351 @@
+//! A troubleshooting checklist: is the engine binary there, is the engine
+//! answering and how fast, can the data dir be written, is there disk space,
+//! is the clock right, and can the proxy be reached. Every check runs even
+//! when an earlier one fails, so the report shows everything that's wrong.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::engine::{self, EngineState};
+use crate::transport::{self, Endpoint};
+use crate::{datadir, engineupdate, i18n, integrity, netproxy};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+const HEALTH_SLOW: Duration = Duration::from_millis(250);
+const HEALTH_VERY_SLOW: Duration = Duration::from_secs(2);
+const LOW_DISK: u64 = 1024 * 1024 * 1024;
+const CRITICAL_DISK: u64 = 100 * 1024 * 1024;
+// Request signatures and TLS both start failing once the clock is this far off.
+const SKEW_WARN: i64 = 2 * 60;
+const SKEW_FAIL: i64 = 10 * 60;
+// Any server with a Date header would do; this one the updater talks to anyway.
+const CLOCK_URL: &str = "https://github.com";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+  Pass,
+  Warn,
+  Fail,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Check {
+  pub id: &'static str,
+  pub title: String,
+  pub outcome: Outcome,
+  pub detail: String,
+  pub duration_ms: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+  /// The worst outcome of any check.
+  pub outcome: Outcome,
+  pub checks: Vec<Check>,
+  pub ran_at: DateTime<Local>,
+}
+
+type CheckResult = (Outcome, String);
+
+fn pass(detail: impl Into<String>) -> CheckResult {
+  (Outcome::Pass, detail.into())
+}
+
+fn warn(detail: impl Into<String>) -> CheckResult {
+  (Outcome::Warn, detail.into())
+}
+
+fn fail(detail: impl Into<String>) -> CheckResult {
+  (Outcome::Fail, detail.into())
+}
+
+fn mb(bytes: u64) -> u64 {
+  bytes / (1024 * 1024)
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+  use std::os::unix::fs::PermissionsExt;
+  meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(windows)]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+  true
+}
+
+/// The engine the next launch would run: a downloaded update, or the
+/// bundled sidecar, which must match the digest built into the app.
+fn engine_binary(app: &AppHandle) -> CheckResult {
+  let (path, downloaded) = match engineupdate::active_binary(app) {
+    Ok(Some(path)) => (path, true),
+    Ok(None) => match integrity::engine_path() {
+      Ok(path) => (path, false),
+      Err(e) => return fail(e),
+    },
+    Err(e) => return fail(e),
+  };
+  let meta = match std::fs::metadata(&path) {
+    Ok(meta) => meta,
+    Err(e) => return fail(format!("{}: {}", path.display(), e)),
+  };
+  if !meta.is_file() {
+    return fail(format!("{} is not a file", path.display()));
+  }
+  if !is_executable(&meta) {
+    return fail(format!("{} is not executable", path.display()));
+  }
+  if !downloaded {
+    if let Err(e) = integrity::verify_engine() {
+      return fail(e);
+    }
+  }
+  pass(format!("{} ({} MB)", path.display(), mb(meta.len())))
+}
+
+fn current_endpoint(app: &AppHandle) -> Option<Endpoint> {
+  app
+    .state::<EngineState>()
+    .info
+    .lock()
+    .unwrap()
+    .endpoint
+    .clone()
+}
+
+fn not_running(app: &AppHandle) -> CheckResult {
+  match app
+    .state::<EngineState>()
+    .info
+    .lock()
+    .unwrap()
+    .last_error
+    .clone()
+  {
+    Some(e) => fail(format!("the engine is not running; last error: {}", e)),
+    None => fail("the engine is not running"),
+  }
+}
+
+/// Whether anything accepts connections where the engine should be serving.
+async fn engine_endpoint(app: &AppHandle) -> CheckResult {
+  let Some(endpoint) = current_endpoint(app) else {
+    return not_running(app);
+  };
+  match &endpoint {
+    Endpoint::Tcp(port) => {
+      let connect = tokio::net::TcpStream::connect(("127.0.0.1", *port));
+      match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+        Ok(Ok(_)) => pass(format!("listening on {}", endpoint)),
+        Ok(Err(e)) => fail(format!("could not connect to {}: {}", endpoint, e)),
+        Err(_) => fail(format!("connecting to {} timed out", endpoint)),
+      }
+    }
+    Endpoint::Socket(path) if path.exists() => pass(format!("listening on {}", endpoint)),
+    Endpoint::Socket(_) => fail(format!("{} does not exist", endpoint)),
+  }
+}
+
+async fn health(app: &AppHandle) -> CheckResult {
+  let Some(endpoint) = current_endpoint(app) else {
+    return not_running(app);
+  };
+  let started = Instant::now();
+  let req = transport::client(&endpoint)
+    .get(endpoint.url("/health"))
+    .timeout(HEALTH_TIMEOUT);
+  let resp = match req.send().await {
+    Ok(resp) => resp,
+    Err(e) if e.is_timeout() => {
+      return fail(format!("no answer within {} s", HEALTH_TIMEOUT.as_secs()))
+    }
+    Err(e) => return fail(e.to_string()),
+  };
+  let latency = started.elapsed();
+  let ms = latency.as_millis();
+  if !resp.status().is_success() {
+    return fail(format!("HTTP {} after {} ms", resp.status(), ms));
+  }
+  if latency >= HEALTH_VERY_SLOW {
+    return fail(format!("answered in {} ms", ms));
+  }
+  if latency >= HEALTH_SLOW {
+    return warn(format!("answered in {} ms", ms));
+  }
+  pass(format!("answered in {} ms", ms))
+}
+
+/// Writes, syncs, reads back, and removes a scratch file.
+fn data_dir_writable(app: &AppHandle) -> CheckResult {
+  let dir = match engine::data_dir(app) {
+    Ok(dir) => dir,
+    Err(e) => return fail(e),
+  };
+  let mut suffix = [0u8; 4];
+  let _ = getrandom::fill(&mut suffix);
+  let name: String = suffix.iter().map(|b| format!("{:02x}", b)).collect();
+  let probe = dir.join(format!(".diagnostics-{}", name));
+  let payload = b"jobhunt diagnostics";
+
+  let result = (|| -> std::io::Result<bool> {
+    use std::io::Write;
+    std::fs::create_dir_all(&dir)?;
+    let mut file = std::fs::File::create(&probe)?;
+    file.write_all(payload)?;
+    file.sync_all()?;
+    Ok(std::fs::read(&probe)? == payload)
+  })();
+  let _ = std::fs::remove_file(&probe);
+  match result {
+    Ok(true) => pass(dir.display().to_string()),
+    Ok(false) => fail(format!(
+      "{}: a file read back different from what was written",
+      dir.display()
+    )),
+    Err(e) => fail(format!("{}: {}", dir.display(), e)),
+  }
+}
+
+fn disk_space(app: &AppHandle) -> CheckResult {
+  let dir = match engine::data_dir(app) {
+    Ok(dir) => dir,
+    Err(e) => return fail(e),
+  };
+  let Some(free) = datadir::available_space(&dir) else {
+    return warn(format!("could not read free space for {}", dir.display()));
+  };
+  let detail = format!("{} MB free", mb(free));
+  if free < CRITICAL_DISK {
+    fail(detail)
+  } else if free < LOW_DISK {
+    warn(detail)
+  } else {
+    pass(detail)
+  }
+}
+
+/// Compares the local clock with a web server's `Date` header, through the
+/// proxy like other outbound traffic. The header has whole seconds, which is
+/// plenty at these thresholds.
+async fn clock_skew(app: &AppHandle) -> CheckResult {
+  let client = match netproxy::outbound(app).and_then(|b| b.build().map_err(|e| e.to_string())) {
+    Ok(client) => client,
+    Err(e) => return warn(format!("could not check: {}", e)),
+  };
+  let sent = Utc::now();
+  let resp = match client.head(CLOCK_URL).timeout(HEALTH_TIMEOUT).send().await {
+    Ok(resp) => resp,
+    Err(e) => return warn(format!("could not reach {}: {}", CLOCK_URL, e)),
+  };
+  let received = Utc::now();
+  let Some(date) = resp
+    .headers()
+    .get(reqwest::header::DATE)
+    .and_then(|v| v.to_str().ok())
+    .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+  else {
+    return warn(format!("{} sent no usable Date header", CLOCK_URL));
+  };
+  let local = sent + (received - sent) / 2;
+  let skew = (local - date.with_timezone(&Utc)).num_seconds();
+  let detail = match skew {
+    0 => "in sync".to_string(),
+    s if s > 0 => format!("{} s ahead", s),
+    s => format!("{} s behind", -s),
+  };
+  match skew.abs() {
+    s if s >= SKEW_FAIL => fail(detail),
+    s if s >= SKEW_WARN => warn(detail),
+    _ => pass(detail),
+  }
+}
+
+/// Connects to the configured proxy. With no proxy there's nothing to reach.
+async fn proxy(app: &AppHandle) -> CheckResult {
+  let Some(config) = netproxy::resolve(app) else {
+    return pass("no proxy; connecting directly");
+  };
+  let Some(url) = config.https.or(config.http) else {
+    return pass("no proxy; connecting directly");
+  };
+  let parsed = match reqwest::Url::parse(&url) {
+    Ok(parsed) => parsed,
+    Err(e) => return fail(format!("invalid proxy URL {}: {}", url, e)),
+  };
+  let (Some(host), Some(port)) = (parsed.host_str(), parsed.port_or_known_default()) else {
+    return fail(format!("invalid proxy URL {}", url));
+  };
+  let target = format!("{}:{}", host, port);
+  let connect = tokio::net::TcpStream::connect(target.as_str());
+  match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+    Ok(Ok(_)) => pass(format!("{} (from {})", target, config.source)),
+    Ok(Err(e)) => fail(format!("could not connect to {}: {}", target, e)),
+    Err(_) => fail(format!("connecting to {} timed out", target)),
+  }
+}
+
+fn check(id: &'static str, started: Instant, (outcome, detail): CheckResult) -> Check {
+  Check {
+    id,
+    title: i18n::t(&format!("diagnostics.{}", id)),
+    outcome,
+    detail,
+    duration_ms: started.elapsed().as_millis() as u64,
+  }
+}
+
+/// Runs the whole checklist, in order.
+pub async fn run(app: &AppHandle) -> DiagnosticsReport {
+  let mut checks = Vec::new();
+
+  let started = Instant::now();
+  let handle = app.clone();
+  let result = tauri::async_runtime::spawn_blocking(move || engine_binary(&handle))
+    .await
+    .unwrap_or_else(|e| fail(e.to_string()));
+  checks.push(check("engine_binary", started, result));
+
+  let started = Instant::now();
+  checks.push(check(
+    "engine_endpoint",
+    started,
+    engine_endpoint(app).await,
+  ));
+
+  let started = Instant::now();
+  checks.push(check("health", started, health(app).await));
+
+  let started = Instant::now();
+  checks.push(check("data_dir", started, data_dir_writable(app)));
+
+  let started = Instant::now();
+  checks.push(check("disk_space", started, disk_space(app)));
+
+  let started = Instant::now();
+  checks.push(check("clock", started, clock_skew(app).await));
+
+  let started = Instant::now();
+  checks.push(check("proxy", started, proxy(app).await));
+
+  let outcome = checks
+    .iter()
+    .map(|c| c.outcome)
+    .max()
+    .unwrap_or(Outcome::Pass);
+  for c in checks.iter().filter(|c| c.outcome != Outcome::Pass) {
+    eprintln!("[diagnostics] {} {:?}: {}", c.id, c.outcome, c.detail);
+  }
+  println!("[diagnostics] finished: {:?}", outcome);
+  DiagnosticsReport {
+    outcome,
+    checks,
+    ran_at: Local::now(),
+  }
+}
@@ -0,0 +1,300 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+
+use crate::{dropimport, i18n};
+use crate::engine::EngineState;
+use crate::notifications::{self, ClickAction};
+use crate::settings::{self, DropFolder};
+
+const PROCESSED_DIR: &str = "processed";
+const FAILED_DIR: &str = "failed";
+const EXTENSIONS: &[&str] = &["pdf", "html", "htm", "eml"];
+// A file counts as fully written once its size has held still this long.
+const SETTLE: Duration = Duration::from_secs(2);
+const TICK: Duration = Duration::from_millis(500);
+// Settings are re-read this often to pick up added, removed, or toggled folders.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Serialize)]
+struct DropFolderImport {
+  folder: String,
+  name: String,
+  ok: bool,
+  error: Option<String>,
+}
+
+/// A file seen in a watched folder that hasn't settled yet.
+struct Candidate {
+  size: u64,
+  changed: Instant,
+}
+
+fn supported(path: &Path) -> bool {
+  path
+    .extension()
+    .map(|e| e.to_string_lossy().to_lowercase())
+    .is_some_and(|e| EXTENSIONS.contains(&e.as_str()))
+}
+
+fn enabled_folders(app: &AppHandle) -> BTreeSet<PathBuf> {
+  settings::current(app)
+    .drop_folders
+    .into_iter()
+    .filter(|f| f.enabled)
+    .map(|f| f.path)
+    .collect()
+}
+
+/// Watches `folders` (not their subfolders, so processed/ and failed/ are ignored)
+/// and forwards every touched path to `tx`.
+fn watch(
+  folders: &BTreeSet<PathBuf>,
+  tx: mpsc::UnboundedSender<PathBuf>,
+) -> Option<RecommendedWatcher> {
+  if folders.is_empty() {
+    return None;
+  }
+  let handler = move |res: notify::Result<Event>| match res {
+    Ok(event) if !matches!(event.kind, EventKind::Remove(_) | EventKind::Access(_)) => {
+      for path in event.paths {
+        let _ = tx.send(path);
+      }
+    }
+    Ok(_) => {}
+    Err(e) => eprintln!("[dropfolder] watch error: {}", e),
+  };
+  let mut watcher = match notify::recommended_watcher(handler) {
+    Ok(w) => w,
+    Err(e) => {
+      eprintln!("[dropfolder] cannot start watcher: {}", e);
+      return None;
+    }
+  };
+  for folder in folders {
+    match watcher.watch(folder, RecursiveMode::NonRecursive) {
+      Ok(()) => println!("[dropfolder] watching {}", folder.display()),
+      Err(e) => eprintln!("[dropfolder] cannot watch {}: {}", folder.display(), e),
+    }
+  }
+  Some(watcher)
+}
+
+/// Files already sitting in `folder`, e.g. dropped while the app was closed.
+fn existing(folder: &Path) -> Vec<PathBuf> {
+  std::fs::read_dir(folder)
+    .map(|entries| entries.flatten().map(|e| e.path()).collect())
+    .unwrap_or_default()
+}
+
+/// Moves `path` into `folder/sub/`, adding a counter if the name is taken.
+fn move_into(folder: &Path, sub: &str, path: &Path) -> Result<PathBuf, String> {
+  let dir = folder.join(sub);
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let name = path.file_name().ok_or("no file name")?;
+  let mut dest = dir.join(name);
+  let stem = path
+    .file_stem()
+    .unwrap_or(name)
+    .to_string_lossy()
+    .to_string();
+  let ext = path.extension().map(|e| e.to_string_lossy().to_string());
+  let mut n = 1;
+  while dest.exists() {
+    let file = match &ext {
+      Some(ext) => format!("{} ({}).{}", stem, n, ext),
+      None => format!("{} ({})", stem, n),
+    };
+    dest = dir.join(file);
+    n += 1;
+  }
+  std::fs::rename(path, &dest).map_err(|e| e.to_string())?;
+  Ok(dest)
+}
+
+/// Whether another process still has `path` open for writing. Windows
+/// refuses to share a file that is being written; elsewhere the settle
+/// period is the only signal.
+#[cfg(windows)]
+fn still_locked(path: &Path) -> bool {
+  std::fs::OpenOptions::new().append(true).open(path).is_err()
+}
+
+#[cfg(not(windows))]
+fn still_locked(_path: &Path) -> bool {
+  false
+}
+
+async fn import(app: &AppHandle, folder: &Path, path: &Path) {
+  let name = path
+    .file_name()
+    .map(|n| n.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let result = dropimport::upload(app, path).await;
+  let (sub, error) = match &result {
+    Ok(_) => (PROCESSED_DIR, None),
+    Err(e) => (FAILED_DIR, Some(e.clone())),
+  };
+  if let Err(e) = move_into(folder, sub, path) {
+    eprintln!("[dropfolder] could not move {} to {}/: {}", name, sub, e);
+  }
+
+  match &error {
+    None => println!("[dropfolder] imported {}", name),
+    Some(e) => eprintln!("[dropfolder] {}: {}", name, e),
+  }
+  if notifications::enabled(app, "imports") {
+    let (title, body) = match &error {
+      None => (
+        i18n::t("notify.drop.imported.title"),
+        i18n::t_with("notify.drop.imported.body", &[("name", &name)]),
+      ),
+      Some(e) => (
+        i18n::t("notify.drop.failed.title"),
+        i18n::t_with("notify.drop.failed.body", &[("name", &name), ("error", e)]),
+      ),
+    };
+    notifications::show(
+      app,
+      &title,
+      &body,
+      ClickAction::Open("jobhunt://imports".into()),
+    );
+  }
+  let _ = app.emit(
+    "drop-folder-import",
+    DropFolderImport {
+      folder: folder.to_string_lossy().to_string(),
+      name,
+      ok: error.is_none(),
+      error,
+    },
+  );
+}
+
+/// The watched folder `path` lives directly in, if any.
+fn owning_folder<'a>(folders: &'a BTreeSet<PathBuf>, path: &Path) -> Option<&'a PathBuf> {
+  let parent = path.parent()?;
+  folders.iter().find(|f| f.as_path() == parent)
+}
+
+/// Watches the enabled drop folders and imports each supported file once it
+/// has finished being written, then files it under processed/ (or failed/).
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+    let mut folders = BTreeSet::new();
+    // Dropping the watcher stops it, so it lives as long as the loop.
+    let mut _watcher: Option<RecommendedWatcher> = None;
+    let mut pending: HashMap<PathBuf, Candidate> = HashMap::new();
+    let mut last_reload: Option<Instant> = None;
+    let mut tick = tokio::time::interval(TICK);
+
+    loop {
+      tokio::select! {
+        Some(path) = rx.recv() => {
+          if owning_folder(&folders, &path).is_some() && supported(&path) && path.is_file() {
+            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            pending.insert(path, Candidate { size, changed: Instant::now() });
+          }
+          continue;
+        }
+        _ = tick.tick() => {}
+      }
+
+      if last_reload.is_none_or(|t| t.elapsed() >= RELOAD_INTERVAL) {
+        last_reload = Some(Instant::now());
+        let wanted = enabled_folders(&app);
+        if wanted != folders {
+          _watcher = watch(&wanted, tx.clone());
+          pending.retain(|p, _| owning_folder(&wanted, p).is_some());
+          for folder in wanted.difference(&folders) {
+            for path in existing(folder) {
+              let _ = tx.send(path);
+            }
+          }
+          folders = wanted;
+        }
+      }
+
+      // Imports wait for the engine rather than failing while it restarts.
+      let serving = app.state::<EngineState>().info.lock().unwrap().endpoint.is_some();
+      if pending.is_empty() || !serving {
+        continue;
+      }
+      let mut settled = Vec::new();
+      for (path, candidate) in pending.iter_mut() {
+        if candidate.changed.elapsed() < SETTLE {
+          continue;
+        }
+        let Ok(meta) = std::fs::metadata(path) else {
+          // Gone again, e.g. a browser renaming its temp download.
+          settled.push((path.clone(), false));
+          continue;
+        };
+        if meta.len() != candidate.size || still_locked(path) {
+          candidate.size = meta.len();
+          candidate.changed = Instant::now();
+          continue;
+        }
+        settled.push((path.clone(), true));
+      }
+      for (path, ready) in settled {
+        pending.remove(&path);
+        if !ready {
+          continue;
+        }
+        if let Some(folder) = owning_folder(&folders, &path).cloned() {
+          import(&app, &folder, &path).await;
+        }
+      }
+    }
+  });
+}
+
+pub fn list(app: &AppHandle) -> Vec<DropFolder> {
+  settings::current(app).drop_folders
+}
+
+/// Adds `path` as an enabled drop folder, creating it if needed.
+pub fn add(app: &AppHandle, path: PathBuf) -> Result<Vec<DropFolder>, String> {
+  std::fs::create_dir_all(&path).map_err(|e| e.to_string())?;
+  let path = path.canonicalize().map_err(|e| e.to_string())?;
+  let mut s = settings::current(app);
+  if !s.drop_folders.iter().any(|f| f.path == path) {
+    s.drop_folders.push(DropFolder {
+      path,
+      enabled: true,
+    });
+  }
+  let folders = s.drop_folders.clone();
+  settings::replace(app, s)?;
+  Ok(folders)
+}
+
+pub fn set_enabled(app: &AppHandle, path: &Path, enabled: bool) -> Result<Vec<DropFolder>, String> {
+  let mut s = settings::current(app);
+  let folder = s
+    .drop_folders
+    .iter_mut()
+    .find(|f| f.path == path)
+    .ok_or_else(|| format!("{} is not a drop folder", path.display()))?;
+  folder.enabled = enabled;
+  let folders = s.drop_folders.clone();
+  settings::replace(app, s)?;
+  Ok(folders)
+}
+
+/// Stops watching `path`. The folder and its contents are left alone.
+pub fn remove(app: &AppHandle, path: &Path) -> Result<Vec<DropFolder>, String> {
+  let mut s = settings::current(app);
+  s.drop_folders.retain(|f| f.path != path);
+  let folders = s.drop_folders.clone();
+  settings::replace(app, s)?;
+  Ok(folders)
+}
@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use reqwest::Method;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+use tokio::io::AsyncReadExt;
+
+use crate::{engine, proxy};
+
+const MAX_BYTES: u64 = 20 * 1024 * 1024;
+const EXTENSIONS: &[&str] = &["pdf", "doc", "docx", "txt", "md", "html", "htm", "rtf", "eml"];
+const CHUNK: usize = 64 * 1024;
+const READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Serialize)]
+struct ImportProgress {
+  name: String,
+  sent: u64,
+  total: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct ImportDone {
+  name: String,
+  result: serde_json::Value,
+}
+
+#[derive(Clone, Serialize)]
+struct ImportFailed {
+  name: String,
+  error: String,
+}
+
+fn file_name(path: &Path) -> String {
+  path
+    .file_name()
+    .map(|n| n.to_string_lossy().to_string())
+    .unwrap_or_default()
+}
+
+fn validate(path: &Path) -> Result<u64, String> {
+  let ext = path
+    .extension()
+    .map(|e| e.to_string_lossy().to_lowercase())
+    .unwrap_or_default();
+  if !EXTENSIONS.contains(&ext.as_str()) {
+    return Err(format!("unsupported file type .{}", ext));
+  }
+  let meta = std::fs::metadata(path).map_err(|e| e.to_string())?;
+  if !meta.is_file() {
+    return Err("not a file".into());
+  }
+  if meta.len() > MAX_BYTES {
+    return Err(format!(
+      "file is {} MB; the limit is {} MB",
+      meta.len() / (1024 * 1024),
+      MAX_BYTES / (1024 * 1024)
+    ));
+  }
+  Ok(meta.len())
+}
+
+/// The hex SHA-256 of the file at `path`, which the upload is signed with.
+async fn digest(path: &Path) -> Result<String, String> {
+  let mut file = tokio::fs::File::open(path).await.map_err(|e| e.to_string())?;
+  let mut hasher = Sha256::new();
+  let mut buf = vec![0u8; CHUNK];
+  loop {
+    let n = file.read(&mut buf).await.map_err(|e| e.to_string())?;
+    if n == 0 {
+      return Ok(format!("{:x}", hasher.finalize()));
+    }
+    hasher.update(&buf[..n]);
+  }
+}
+
+/// Streams one file to the engine's `/api/import`, emitting
+/// `file-import-progress` as chunks go out.
+pub async fn upload(app: &AppHandle, path: &Path) -> Result<serde_json::Value, String> {
+  let total = validate(path)?;
+  engine::wait_for_ready(app, READY_TIMEOUT).await?;
+
+  let name = file_name(path);
+  // A file changed after this fails the engine's signature check.
+  let sha256 = digest(path).await?;
+  let file = tokio::fs::File::open(path).await.map_err(|e| e.to_string())?;
+  let progress_app = app.clone();
+  let progress_name = name.clone();
+  let stream = futures_util::stream::unfold((file, 0u64), move |(mut file, sent)| {
+    let app = progress_app.clone();
+    let name = progress_name.clone();
+    async move {
+      let mut buf = vec![0u8; CHUNK];
+      match file.read(&mut buf).await {
+        Ok(0) => None,
+        Ok(n) => {
+          buf.truncate(n);
+          let sent = sent + n as u64;
+          let _ = app.emit("file-import-progress", ImportProgress { name, sent, total });
+          Some((Ok::<_, std::io::Error>(buf), (file, sent)))
+        }
+        Err(e) => Some((Err(e), (file, sent))),
+      }
+    }
+  });
+
+  let req = proxy::engine_request(app, Method::POST, "/api/import")
+    .query(&[("name", name.as_str())])
+    .header(reqwest::header::CONTENT_LENGTH, total)
+    .body(reqwest::Body::wrap_stream(stream));
+  let resp = proxy::send_streamed(app, req, &sha256)
+    .await
+    .map_err(|e| e.to_string())?;
+  if !resp.status().is_success() {
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    return Err(format!("engine returned HTTP {}: {}", status, body.trim()));
+  }
+  resp.json().await.map_err(|e| e.to_string())
+}
+
+/// Imports files dropped onto the main window, one at a time. Each ends in
+/// either `file-import-done` or `file-import-failed`.
+pub fn handle_drop(app: &AppHandle, paths: Vec<PathBuf>) {
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    for path in paths {
+      let name = file_name(&path);
+      match upload(&app, &path).await {
+        Ok(result) => {
+          println!("[import] {} imported", name);
+          let _ = app.emit("file-import-done", ImportDone { name, result });
+        }
+        Err(error) => {
+          eprintln!("[import] {}: {}", name, error);
+          let _ = app.emit("file-import-failed", ImportFailed { name, error });
+        }
+      }
+    }
+  });
+}
@@ -0,0 +1,286 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use base64::Engine as _;
+use serde::Deserialize;
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::attachments::{self, Attachment};
+use crate::{engine, proxy};
+
+const DRAFT_DIR: &str = "drafts";
+// RFC 2045 caps encoded lines at 76 characters.
+const LINE_LEN: usize = 76;
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Delivery {
+  /// A mailto: link opened in the default mail client.
+  Mailto,
+  /// An unsent .eml message with the attachments, opened in the mail client.
+  Eml,
+}
+
+#[derive(Deserialize)]
+struct Draft {
+  subject: String,
+  body: String,
+  #[serde(default)]
+  attachments: Vec<Attachment>,
+}
+
+async fn fetch_draft(app: &AppHandle, job_id: i64, kind: &str) -> Result<Draft, String> {
+  let path = format!("/jobs/{}/email?kind={}", job_id, encode_component(kind));
+  let resp = proxy::engine_fetch(app, "GET", &path, None).await?;
+  if resp.status >= 400 {
+    return Err(format!(
+      "engine returned HTTP {}: {}",
+      resp.status,
+      resp.body.trim()
+    ));
+  }
+  serde_json::from_str(&resp.body).map_err(|e| e.to_string())
+}
+
+/// Percent-encodes everything but RFC 3986's unreserved characters, which
+/// is what RFC 6068 asks of mailto: header values.
+fn encode_component(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  for b in s.bytes() {
+    match b {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => out.push(b as char),
+      _ => {
+        let _ = write!(out, "%{:02X}", b);
+      }
+    }
+  }
+  out
+}
+
+fn mailto_url(to: &str, draft: &Draft) -> String {
+  // Line breaks in a mailto: body must be CRLF.
+  let body = draft.body.replace("\r\n", "\n").replace('\n', "\r\n");
+  format!(
+    "mailto:{}?subject={}&body={}",
+    // Some clients don't decode the address itself, so its @ stays literal.
+    encode_component(to).replace("%40", "@"),
+    encode_component(&draft.subject),
+    encode_component(&body)
+  )
+}
+
+fn wrap_base64(bytes: &[u8]) -> String {
+  let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+  let mut out = String::with_capacity(encoded.len() + encoded.len() / LINE_LEN * 2 + 2);
+  for line in encoded.as_bytes().chunks(LINE_LEN) {
+    out.push_str(std::str::from_utf8(line).unwrap_or_default());
+    out.push_str("\r\n");
+  }
+  out
+}
+
+/// An RFC 2047 encoded word when `value` isn't plain ASCII.
+fn header_value(value: &str) -> String {
+  if value.is_ascii() {
+    return value.to_string();
+  }
+  format!(
+    "=?UTF-8?B?{}?=",
+    base64::engine::general_purpose::STANDARD.encode(value)
+  )
+}
+
+/// `filename="..."`, plus an RFC 2231 `filename*` for non-ASCII names.
+fn filename_param(name: &str) -> String {
+  let ascii: String = name
+    .chars()
+    .map(|c| {
+      if c.is_ascii() && c != '"' && c != '\\' && !c.is_ascii_control() {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect();
+  if name.is_ascii() {
+    return format!("filename=\"{}\"", ascii);
+  }
+  format!(
+    "filename=\"{}\"; filename*=UTF-8''{}",
+    ascii,
+    encode_component(name)
+  )
+}
+
+fn content_type(name: &str) -> &'static str {
+  let ext = Path::new(name)
+    .extension()
+    .map(|e| e.to_string_lossy().to_ascii_lowercase())
+    .unwrap_or_default();
+  match ext.as_str() {
+    "pdf" => "application/pdf",
+    "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "doc" => "application/msword",
+    "txt" => "text/plain",
+    "png" => "image/png",
+    "jpg" | "jpeg" => "image/jpeg",
+    _ => "application/octet-stream",
+  }
+}
+
+fn boundary() -> Result<String, String> {
+  let mut bytes = [0u8; 12];
+  getrandom::fill(&mut bytes).map_err(|e| e.to_string())?;
+  let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+  Ok(format!("----=_JobHunt_{}", hex))
+}
+
+/// A multipart/mixed message marked `X-Unsent`, so mail clients open it as
+/// a draft ready to send rather than as received mail.
+async fn build_eml(app: &AppHandle, to: &str, draft: &Draft) -> Result<Vec<u8>, String> {
+  let boundary = boundary()?;
+  let mut out = String::new();
+  // A line break in the address would start a header of its own.
+  let to: String = to.chars().filter(|c| !c.is_control()).collect();
+  if !to.is_empty() {
+    let _ = write!(out, "To: {}\r\n", to);
+  }
+  let _ = write!(out, "Subject: {}\r\n", header_value(&draft.subject));
+  let _ = write!(out, "Date: {}\r\n", chrono::Local::now().to_rfc2822());
+  out.push_str("MIME-Version: 1.0\r\nX-Unsent: 1\r\n");
+  let _ = write!(
+    out,
+    "Content-Type: multipart/mixed; boundary=\"{}\"\r\n\r\n",
+    boundary
+  );
+
+  let _ = write!(out, "--{}\r\n", boundary);
+  out.push_str("Content-Type: text/plain; charset=utf-8\r\n");
+  out.push_str("Content-Transfer-Encoding: base64\r\n\r\n");
+  out.push_str(&wrap_base64(
+    draft
+      .body
+      .replace("\r\n", "\n")
+      .replace('\n', "\r\n")
+      .as_bytes(),
+  ));
+
+  for attachment in &draft.attachments {
+    let local = attachments::find(app, &attachment.id).await?;
+    let bytes = attachments::contents(app, &local)
+      .await
+      .map_err(|e| format!("{}: {}", local.name, e))?;
+    let _ = write!(out, "--{}\r\n", boundary);
+    let _ = write!(
+      out,
+      "Content-Type: {}\r\nContent-Transfer-Encoding: base64\r\n",
+      content_type(&local.name)
+    );
+    let _ = write!(
+      out,
+      "Content-Disposition: attachment; {}\r\n\r\n",
+      filename_param(&local.name)
+    );
+    out.push_str(&wrap_base64(&bytes));
+  }
+  let _ = write!(out, "--{}--\r\n", boundary);
+  Ok(out.into_bytes())
+}
+
+/// A file name from the subject, with characters no file system allows removed.
+fn draft_path(app: &AppHandle, subject: &str) -> Result<PathBuf, String> {
+  let dir = engine::data_dir(app)?.join(DRAFT_DIR);
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let stem: String = subject
+    .chars()
+    .map(|c| match c {
+      '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+      c if c.is_control() => '_',
+      c => c,
+    })
+    .take(80)
+    .collect();
+  let stem = stem.trim().trim_end_matches('.');
+  let stem = if stem.is_empty() { "Draft" } else { stem };
+  let stamp = chrono::Local::now().format("%Y-%m-%d %H.%M.%S");
+  Ok(dir.join(format!("{} ({}).eml", stem, stamp)))
+}
+
+/// Drafts the `kind` email (`application` or `follow_up`) for job `job_id`
+/// from the engine's templates and hands it to the default mail client.
+///
+/// A mailto: link can't carry attachments, so the résumé is revealed in the
+/// file manager for dragging in. An .eml draft includes them and is kept in
+/// the data dir; its path is returned.
+pub async fn draft(
+  app: &AppHandle,
+  job_id: i64,
+  kind: &str,
+  to: &str,
+  delivery: Delivery,
+) -> Result<Option<PathBuf>, String> {
+  let draft = fetch_draft(app, job_id, kind).await?;
+  match delivery {
+    Delivery::Mailto => {
+      app
+        .opener()
+        .open_url(mailto_url(to, &draft), None::<&str>)
+        .map_err(|e| e.to_string())?;
+      if let Some(first) = draft.attachments.first() {
+        if let Err(e) = attachments::reveal(app, &first.id).await {
+          eprintln!("[emaildraft] could not reveal {}: {}", first.name, e);
+        }
+      }
+      println!("[emaildraft] opened {} mailto: for job {}", kind, job_id);
+      Ok(None)
+    }
+    Delivery::Eml => {
+      let bytes = build_eml(app, to, &draft).await?;
+      let path = draft_path(app, &draft.subject)?;
+      std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+      println!(
+        "[emaildraft] wrote {} with {} attachment(s)",
+        path.display(),
+        draft.attachments.len()
+      );
+      app
+        .opener()
+        .open_path(path.to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())?;
+      Ok(Some(path))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn encode_component_keeps_only_unreserved_characters() {
+    assert_eq!(encode_component("AZaz09-._~"), "AZaz09-._~");
+    assert_eq!(encode_component("a b&c=d?e/f"), "a%20b%26c%3Dd%3Fe%2Ff");
+    assert_eq!(encode_component("line\r\nbreak"), "line%0D%0Abreak");
+  }
+
+  #[test]
+  fn encode_component_encodes_utf8_bytes() {
+    assert_eq!(encode_component("é"), "%C3%A9");
+    assert_eq!(encode_component("50%"), "50%25");
+  }
+
+  #[test]
+  fn filename_param_quotes_ascii_names() {
+    assert_eq!(filename_param("resume.pdf"), "filename=\"resume.pdf\"");
+    assert_eq!(filename_param("a \"b\"\\c.pdf"), "filename=\"a _b__c.pdf\"");
+  }
+
+  #[test]
+  fn filename_param_adds_an_rfc_2231_name_for_non_ascii() {
+    assert_eq!(
+      filename_param("résumé.pdf"),
+      "filename=\"r_sum_.pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf"
+    );
+  }
+}
@@ -0,0 +1,516 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::Notify;
+
+use crate::logs::{self, LogLine};
+use crate::sidecar::{self, Exit, Launch, RestartPolicy, Sidecar};
+use crate::startup::StartupError;
+use crate::{
+  appupdate, cli, compat, crash, engineupdate, integrity, nativehost, netaudit, netproxy, pidfile,
+  procguard, profiles, proxy, sandbox, secrets, settings, telemetry, transport, tray, vault,
+};
+use crate::transport::Endpoint;
+
+// How long stop_engine waits for a graceful exit before killing the engine.
+const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 5;
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+const READY_TIMEOUT: Duration = Duration::from_secs(15);
+const READY_POLL: Duration = Duration::from_millis(100);
+pub const ENGINE: &str = "engine";
+
+#[derive(Default)]
+pub struct EngineInfo {
+  // Set once the engine is serving; `None` while it is starting or stopped.
+  pub endpoint: Option<Endpoint>,
+  // Per-session HMAC key for signing requests; handed to the engine via env.
+  pub auth_key: Option<String>,
+  pub version: Option<String>,
+  pub pid: Option<u32>,
+  pub started_at: Option<Instant>,
+  // Survives respawns so the UI can still explain why the engine restarted.
+  pub last_error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct EngineState {
+  pub info: Mutex<EngineInfo>,
+  pub allow_close: Mutex<bool>,
+  // Developer mode: talking to an engine we didn't spawn and must not stop.
+  pub attached: Mutex<bool>,
+  // Most recent engine output lines, oldest first, for the debug console.
+  pub recent_logs: Mutex<VecDeque<LogLine>>,
+  // Stderr of the current engine process only, for its crash report.
+  pub stderr_tail: Mutex<VecDeque<String>>,
+  // Signalled once the engine first answers /health.
+  pub ready: Notify,
+}
+
+async fn request_engine_shutdown(app: &AppHandle) -> Result<(), String> {
+  let req = proxy::engine_request(app, reqwest::Method::POST, "/shutdown");
+  let resp = proxy::send(app, req).await.map_err(|e| e.to_string())?;
+  if resp.status().is_success() {
+    Ok(())
+  } else {
+    Err(format!("shutdown returned HTTP {}", resp.status()))
+  }
+}
+
+/// What `/health` reports once the engine is serving.
+#[derive(Deserialize)]
+pub struct Health {
+  pub version: String,
+  pub pid: u32,
+}
+
+pub async fn fetch_health(endpoint: &Endpoint) -> Option<Health> {
+  let resp = transport::client(endpoint)
+    .get(endpoint.url("/health"))
+    .timeout(HEALTH_TIMEOUT)
+    .send()
+    .await
+    .ok()?;
+  if !resp.status().is_success() {
+    return None;
+  }
+  resp.json().await.ok()
+}
+
+/// Polls `/health` on the endpoint we gave the engine until it answers, then
+/// publishes the connection details and wakes anyone waiting on readiness.
+/// `rekeyed` is the data dir when this engine was handed a vault rekey.
+async fn await_health(
+  app: AppHandle,
+  generation: u64,
+  endpoint: Endpoint,
+  rekeyed: Option<PathBuf>,
+) -> Result<Option<u16>, String> {
+  let deadline = Instant::now() + READY_TIMEOUT;
+  let Some(pid) = app.state::<EngineState>().info.lock().unwrap().pid else {
+    return Ok(None);
+  };
+
+  // Whoever answers must be the process we spawned, not something else on the port.
+  let mut impostor = None;
+  let health = loop {
+    // Superseded by a newer spawn, or the engine already exited.
+    if !sidecar::is_current(&app, ENGINE, generation) {
+      return Ok(None);
+    }
+    match fetch_health(&endpoint).await {
+      Some(health) if health.pid == pid => break health,
+      Some(health) => impostor = Some(health.pid),
+      None => {}
+    }
+    if Instant::now() >= deadline {
+      return Err(match impostor {
+        Some(other) => format!(
+          "process {} answered on {} instead of the engine (pid {})",
+          other, endpoint, pid
+        ),
+        None => format!(
+          "engine did not answer on {} within {} s",
+          endpoint,
+          READY_TIMEOUT.as_secs()
+        ),
+      });
+    }
+    tokio::time::sleep(READY_POLL).await;
+  };
+
+  // An incompatible engine would fail later with confusing 404s; stop here instead.
+  if let Err(e) = compat::check(&health.version) {
+    let msg = e.clone();
+    compat::report_mismatch(&app, e);
+    return Err(msg);
+  }
+
+  // The engine is told to bind 127.0.0.1; make sure nothing widened that.
+  if let Err(e) = netaudit::verify_loopback(pid).await {
+    let msg = e.to_string();
+    netaudit::report_exposure(&app, e);
+    return Err(msg);
+  }
+
+  // The engine reseals before it serves, so answering means the rekey is done.
+  if let Some(dir) = rekeyed {
+    if let Err(e) = vault::confirm_rekey(&app, &dir) {
+      eprintln!("[engine] could not record the finished reseal: {}", e);
+    }
+  }
+
+  let state = app.state::<EngineState>();
+  {
+    let mut info = state.info.lock().unwrap();
+    if !sidecar::is_current(&app, ENGINE, generation) {
+      return Ok(None);
+    }
+    info.endpoint = Some(endpoint.clone());
+    info.version = Some(health.version);
+    if let Some(key) = &info.auth_key {
+      if let Err(e) = nativehost::publish(&app, &endpoint, key) {
+        eprintln!("[engine] browser extension can't reach the engine: {}", e);
+      }
+    }
+  }
+  println!("[engine] healthy on {}", endpoint);
+  state.ready.notify_waiters();
+  telemetry::engine_ready(&app);
+  tray::refresh_status(&app);
+  Ok(endpoint.port())
+}
+
+/// The port to hand the engine: `requested` if it is free, or else one the OS
+/// picks, so nothing already listening on a well-known port can answer for it.
+pub fn choose_port(requested: Option<u16>) -> Result<u16, StartupError> {
+  if let Some(port) = requested {
+    preflight_port(port)?;
+    return Ok(port);
+  }
+  let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+    .map_err(|e| StartupError::from_io(format!("no free port on 127.0.0.1: {}", e), &e))?;
+  Ok(listener.local_addr().map(|a| a.port()).map_err(|e| e.to_string())?)
+}
+
+/// Checks that `port` is free on loopback, naming whoever holds it if not,
+/// so a conflict surfaces as a clear error instead of a silent engine exit.
+fn preflight_port(port: u16) -> Result<(), StartupError> {
+  match std::net::TcpListener::bind(("127.0.0.1", port)) {
+    Ok(_) => Ok(()),
+    Err(e) => {
+      let holder = listeners::get_process_by_port(port, listeners::Protocol::TCP)
+        .map(|p| format!("{} (PID {})", p.name, p.pid))
+        .unwrap_or_else(|_| "another process".to_string());
+      Err(StartupError::PortBusy(format!(
+        "Port {} is in use by {} ({})",
+        port, holder, e
+      )))
+    }
+  }
+}
+
+/// A fresh random 256-bit secret, hex-encoded.
+pub fn generate_token() -> Result<String, String> {
+  let mut buf = [0u8; 32];
+  getrandom::fill(&mut buf).map_err(|e| e.to_string())?;
+  Ok(buf.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Remembers the most recent engine failure for `engine_status`.
+pub fn record_error(app: &AppHandle, err: String) {
+  app.state::<EngineState>().info.lock().unwrap().last_error = Some(err);
+}
+
+/// Where to find an externally run engine, from `--attach <url>` or
+/// `JOBHUNT_ENGINE_URL`. Used when hacking on the engine outside the app.
+pub fn attach_url_from_env() -> Option<String> {
+  let mut args = std::env::args();
+  while let Some(arg) = args.next() {
+    if arg == "--attach" {
+      return args.next();
+    }
+  }
+  std::env::var("JOBHUNT_ENGINE_URL").ok().filter(|u| !u.is_empty())
+}
+
+/// Uses the engine at `url` instead of spawning the sidecar. Shutdown-on-close
+/// is skipped in this mode since there is no child to stop.
+///
+/// The engine refuses unsigned requests, so both sides need the same signing
+/// key: start the engine and the app with the same `JOBHUNT_AUTH_KEY`.
+pub fn attach_engine(app: &AppHandle, url: &str) -> Result<(), String> {
+  let auth_key = std::env::var("JOBHUNT_AUTH_KEY")
+    .ok()
+    .filter(|k| !k.is_empty())
+    .ok_or("attaching needs JOBHUNT_AUTH_KEY set to the key the engine was started with")?;
+  let parsed = tauri::Url::parse(url).map_err(|e| format!("invalid engine url {}: {}", url, e))?;
+  if !matches!(parsed.host_str(), Some("127.0.0.1" | "localhost")) {
+    return Err(format!("engine url must be on 127.0.0.1: {}", url));
+  }
+  let port = parsed
+    .port_or_known_default()
+    .ok_or_else(|| format!("engine url has no port: {}", url))?;
+
+  let state = app.state::<EngineState>();
+  *state.attached.lock().unwrap() = true;
+  {
+    let mut info = state.info.lock().unwrap();
+    info.endpoint = Some(Endpoint::Tcp(port));
+    info.auth_key = Some(auth_key);
+    info.started_at = Some(Instant::now());
+  }
+  state.ready.notify_waiters();
+
+  println!("[engine] attached to {}", url);
+  Ok(())
+}
+
+/// The directory the engine keeps its DB and config in: `--data-dir` if
+/// given, the user's chosen location if they moved it, otherwise the app data dir.
+pub fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+  if let Some(dir) = &cli::args().data_dir {
+    return Ok(dir.clone());
+  }
+  profiles::data_dir(app, &settings::current(app), &profiles::active(app))
+}
+
+/// The engine as a sidecar: app-chosen port, per-session auth key, vault key
+/// over stdin, crash reports, and supervised restarts.
+#[derive(Default)]
+pub struct EngineSidecar {
+  // The key generated by launch(), published to EngineInfo once the process is up.
+  pending_key: Mutex<Option<String>>,
+  // The data dir of the current instance, where its pidfile lives.
+  data_dir: Mutex<Option<PathBuf>>,
+}
+
+impl Sidecar for EngineSidecar {
+  fn name(&self) -> &'static str {
+    ENGINE
+  }
+
+  fn stop_order(&self) -> u8 {
+    // Last: other sidecars may still be talking to it while they shut down.
+    u8::MAX
+  }
+
+  fn restart_policy(&self) -> RestartPolicy {
+    RestartPolicy::Backoff
+  }
+
+  fn launch(&self, app: &AppHandle) -> Result<Launch, StartupError> {
+    let data_dir = data_dir(app)?;
+    std::fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+    logs::set_dir(app, data_dir.join("logs"));
+    appupdate::verify_data_dir(&data_dir)?;
+
+    // We pick the endpoint rather than learning it from the engine, so
+    // readiness is just a matter of /health answering there.
+    let endpoint = if settings::current(app).engine_socket && cli::args().port.is_none() {
+      let endpoint = Endpoint::Socket(transport::socket_path(&data_dir)?);
+      transport::remove_stale(&endpoint);
+      endpoint
+    } else {
+      let requested = cli::args().port.or(settings::current(app).engine_port);
+      Endpoint::Tcp(choose_port(requested)?)
+    };
+
+    // Passed via env rather than read back from logs, so it never hits the log stream.
+    let auth_key = generate_token()?;
+
+    // A newer engine downloaded by the engine updater takes precedence over the bundled one.
+    let cmd = match engineupdate::active_binary(app)? {
+      Some(path) => app.shell().command(path),
+      None => {
+        integrity::verify_engine()?;
+        app.shell().sidecar("engine").map_err(|e| e.to_string())?
+      }
+    };
+
+    let (parent_key, parent_pid) = procguard::parent_pid_env();
+    let mut cmd = cmd
+      .current_dir(&data_dir)
+      .env("JOBHUNT_DATA_DIR", data_dir.to_string_lossy().to_string())
+      .env("JOBHUNT_AUTH_KEY", auth_key.clone())
+      .env(parent_key, parent_pid);
+    cmd = match &endpoint {
+      Endpoint::Tcp(port) => cmd.env("JOBHUNT_PORT", port.to_string()),
+      Endpoint::Socket(path) => cmd.env("JOBHUNT_SOCKET", path.to_string_lossy().to_string()),
+    };
+    for (key, value) in secrets::engine_env() {
+      cmd = cmd.env(key, value);
+    }
+    for (key, value) in netproxy::engine_env(app) {
+      cmd = cmd.env(key, value);
+    }
+    for (key, value) in sandbox::engine_env(app) {
+      cmd = cmd.env(key, value);
+    }
+    // The vault key goes over stdin so it never appears in the engine's argv or env.
+    let vault_line = vault::handoff(app, &data_dir)?;
+    if vault_line.is_some() {
+      cmd = cmd.env("JOBHUNT_VAULT", "stdin");
+    }
+    let rekeyed = vault::rekey_pending(app, &data_dir).then(|| data_dir.clone());
+
+    *self.pending_key.lock().unwrap() = Some(auth_key);
+    *self.data_dir.lock().unwrap() = Some(data_dir);
+    Ok(Launch {
+      command: cmd,
+      stdin: vault_line,
+      handshake: Some(Box::new(move |app, generation| {
+        Box::pin(await_health(app, generation, endpoint, rekeyed))
+      })),
+    })
+  }
+
+  fn started(&self, app: &AppHandle, pid: u32) {
+    if let Some(dir) = &*self.data_dir.lock().unwrap() {
+      pidfile::write(dir, pid);
+    }
+    sandbox::restrict(app, pid);
+    let state = app.state::<EngineState>();
+    state.stderr_tail.lock().unwrap().clear();
+    let mut info = state.info.lock().unwrap();
+    *info = EngineInfo {
+      auth_key: self.pending_key.lock().unwrap().take(),
+      pid: Some(pid),
+      started_at: Some(Instant::now()),
+      last_error: info.last_error.take(),
+      ..EngineInfo::default()
+    };
+  }
+
+  fn output(&self, app: &AppHandle, stream: &str, text: &str) {
+    if stream == "stderr" {
+      eprint!("[engine stderr] {}", text);
+    } else {
+      print!("[engine stdout] {}", text);
+    }
+    logs::engine_output(app, stream, text);
+  }
+
+  fn exited(&self, app: &AppHandle, exit: &Exit) {
+    if let Some(dir) = self.data_dir.lock().unwrap().take() {
+      pidfile::remove(&dir);
+    }
+    {
+      let state = app.state::<EngineState>();
+      let mut info = state.info.lock().unwrap();
+      info.endpoint = None;
+      info.pid = None;
+      info.started_at = None;
+    }
+    nativehost::retract(app);
+    tray::refresh_status(app);
+    if exit.crashed {
+      record_error(app, format!("engine exited unexpectedly (code {:?})", exit.code));
+      crash::capture(app, exit.code, exit.signal);
+    }
+  }
+
+  fn request_stop(&self, app: &AppHandle) -> BoxFuture<'static, Result<(), String>> {
+    let app = app.clone();
+    Box::pin(async move { request_engine_shutdown(&app).await })
+  }
+
+  fn shutdown_timeout(&self, app: &AppHandle) -> Duration {
+    Duration::from_secs(
+      settings::current(app)
+        .shutdown_timeout_secs
+        .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS),
+    )
+  }
+
+  fn record_error(&self, app: &AppHandle, err: String) {
+    record_error(app, err);
+  }
+}
+
+/// Spawns the engine sidecar against the data dir and starts forwarding its
+/// output, after stopping any engine an earlier session left holding the dir.
+pub async fn spawn_engine(app: &AppHandle) -> Result<(), StartupError> {
+  let dir = data_dir(app)?;
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  pidfile::reap_stale(&dir).await?;
+  sidecar::spawn(app, ENGINE)
+}
+
+/// Asks the running engine to shut down and waits for it to exit, killing it
+/// if the request fails or it outlives the shutdown timeout.
+/// Returns false when there was no engine to stop.
+pub async fn stop_engine(app: &AppHandle) -> bool {
+  sidecar::stop(app, ENGINE).await
+}
+
+/// Whether this app spawned the engine and it is still running.
+pub fn is_running(app: &AppHandle) -> bool {
+  sidecar::is_running(app, ENGINE)
+}
+
+/// Stops the engine, waits for it to exit, and spawns a fresh one.
+pub async fn restart_engine(app: &AppHandle) -> Result<(), StartupError> {
+  if *app.state::<EngineState>().attached.lock().unwrap() {
+    return Err(String::from("attached to an external engine; restart it yourself").into());
+  }
+  stop_engine(app).await;
+  spawn_engine(app).await
+}
+
+/// Resolves with the engine's endpoint once it is serving, or errors after `timeout`.
+pub async fn wait_for_ready(app: &AppHandle, timeout: Duration) -> Result<Endpoint, String> {
+  let state = app.state::<EngineState>();
+
+  let wait = async {
+    loop {
+      // Register before checking so a notify between the check and the await isn't lost.
+      let notified = state.ready.notified();
+      if let Some(endpoint) = state.info.lock().unwrap().endpoint.clone() {
+        return endpoint;
+      }
+      notified.await;
+    }
+  };
+
+  tokio::time::timeout(timeout, wait)
+    .await
+    .map_err(|_| format!("engine did not become ready within {} ms", timeout.as_millis()))
+}
+
+#[derive(Serialize)]
+pub struct EngineStatus {
+  pub running: bool,
+  pub port: Option<u16>,
+  /// The socket or pipe the engine serves on instead of a port.
+  pub socket: Option<String>,
+  pub pid: Option<u32>,
+  pub version: Option<String>,
+  pub uptime_secs: Option<u64>,
+  pub last_error: Option<String>,
+  // Only set when the caller asked for a /health ping.
+  pub healthy: Option<bool>,
+}
+
+pub async fn ping_health(endpoint: &Endpoint) -> bool {
+  let req = transport::client(endpoint).get(endpoint.url("/health"));
+  match req.timeout(HEALTH_TIMEOUT).send().await {
+    Ok(resp) => resp.status().is_success(),
+    Err(_) => false,
+  }
+}
+
+pub async fn status(app: &AppHandle, ping: bool) -> EngineStatus {
+  let state = app.state::<EngineState>();
+  let running = is_running(app) || *state.attached.lock().unwrap();
+  let (mut status, endpoint) = {
+    let info = state.info.lock().unwrap();
+    let status = EngineStatus {
+      running,
+      port: info.endpoint.as_ref().and_then(Endpoint::port),
+      socket: match &info.endpoint {
+        Some(Endpoint::Socket(path)) => Some(path.to_string_lossy().to_string()),
+        _ => None,
+      },
+      pid: info.pid,
+      version: info.version.clone(),
+      uptime_secs: info.started_at.map(|t| t.elapsed().as_secs()),
+      last_error: info.last_error.clone(),
+      healthy: None,
+    };
+    (status, info.endpoint.clone())
+  };
+
+  if ping {
+    status.healthy = Some(match &endpoint {
+      Some(endpoint) => ping_health(endpoint).await,
+      None => false,
+    });
+  }
+  status
+}
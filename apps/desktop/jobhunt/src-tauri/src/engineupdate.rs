@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use base64::Engine as _;
+use minisign_verify::{PublicKey, Signature};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::engine::{self, EngineState};
+use crate::startup::StartupError;
+use crate::{compat, integrity, netproxy, settings};
+
+const MANIFEST_URL: &str =
+  "https://github.com/stevenmed26/JobHunt/releases/latest/download/engine-latest.json";
+// Rust target triple this app was built for, as keyed in the manifest.
+const TARGET: &str = env!("ENGINE_TARGET");
+const ENGINES_DIR: &str = "engines";
+const STATE_FILE: &str = "engines.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `engine-latest.json`: the newest engine build per target, signed with the
+/// same minisign key as app updates.
+#[derive(Deserialize)]
+struct Manifest {
+  version: String,
+  platforms: HashMap<String, PlatformBuild>,
+}
+
+#[derive(Deserialize)]
+struct PlatformBuild {
+  url: String,
+  signature: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct InstalledEngine {
+  version: String,
+  sha256: String,
+}
+
+/// Which downloaded engine to run. `None` means the sidecar bundled with the app.
+#[derive(Default, Serialize, Deserialize)]
+struct EngineSelection {
+  current: Option<InstalledEngine>,
+  previous: Option<InstalledEngine>,
+}
+
+#[derive(Clone, Serialize)]
+struct EngineUpdated {
+  version: String,
+}
+
+fn engines_dir(app: &AppHandle) -> Result<PathBuf, String> {
+  Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join(ENGINES_DIR))
+}
+
+fn binary_path(dir: &Path, version: &str) -> PathBuf {
+  dir
+    .join(version)
+    .join(format!("engine{}", std::env::consts::EXE_SUFFIX))
+}
+
+fn load_selection(dir: &Path) -> EngineSelection {
+  std::fs::read_to_string(dir.join(STATE_FILE))
+    .ok()
+    .and_then(|s| serde_json::from_str(&s).ok())
+    .unwrap_or_default()
+}
+
+fn save_selection(dir: &Path, selection: &EngineSelection) -> Result<(), String> {
+  let json = serde_json::to_string_pretty(selection).map_err(|e| e.to_string())?;
+  let tmp = dir.join(format!("{}.tmp", STATE_FILE));
+  std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+  std::fs::rename(&tmp, dir.join(STATE_FILE)).map_err(|e| e.to_string())
+}
+
+/// The downloaded engine to spawn instead of the bundled sidecar, if there is
+/// one newer than the app's own. Its hash is checked like the sidecar's.
+pub fn active_binary(app: &AppHandle) -> Result<Option<PathBuf>, StartupError> {
+  let base = app.path().app_data_dir().map_err(|e| e.to_string())?;
+  active_binary_in(&base)
+}
+
+/// [`active_binary`] with the app data dir given as `base`.
+pub fn active_binary_in(base: &Path) -> Result<Option<PathBuf>, StartupError> {
+  let dir = base.join(ENGINES_DIR);
+  let Some(current) = load_selection(&dir).current else {
+    return Ok(None);
+  };
+  // A desktop update ships a newer engine than the one we downloaded earlier.
+  let bundled = Version::parse(env!("CARGO_PKG_VERSION")).map_err(|e| e.to_string())?;
+  if Version::parse(&current.version).map_or(true, |v| v <= bundled) {
+    return Ok(None);
+  }
+
+  let path = binary_path(&dir, &current.version);
+  let actual = integrity::sha256_file(&path).map_err(|e| {
+    StartupError::from_io(format!("cannot read engine binary {}: {}", path.display(), e), &e)
+  })?;
+  if actual != current.sha256 {
+    return Err(StartupError::Damaged(format!(
+      "The downloaded engine at {} has been modified (expected SHA-256 {}, found {}).",
+      path.display(),
+      current.sha256,
+      actual
+    )));
+  }
+  Ok(Some(path))
+}
+
+fn running_version(app: &AppHandle) -> Option<Version> {
+  let version = app.state::<EngineState>().info.lock().unwrap().version.clone();
+  version.and_then(|v| Version::parse(&v).ok())
+}
+
+fn updater_pubkey(app: &AppHandle) -> Result<String, String> {
+  app
+    .config()
+    .plugins
+    .0
+    .get("updater")
+    .and_then(|u| u.get("pubkey"))
+    .and_then(|k| k.as_str())
+    .map(|k| k.to_string())
+    .ok_or_else(|| "no updater public key configured".to_string())
+}
+
+fn decode_base64(value: &str) -> Result<String, String> {
+  let bytes = base64::engine::general_purpose::STANDARD
+    .decode(value.trim())
+    .map_err(|e| e.to_string())?;
+  String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+fn verify_signature(app: &AppHandle, data: &[u8], signature: &str) -> Result<(), String> {
+  let key = PublicKey::decode(&decode_base64(&updater_pubkey(app)?)?)
+    .map_err(|e| format!("invalid updater public key: {}", e))?;
+  let sig = Signature::decode(&decode_base64(signature)?)
+    .map_err(|e| format!("invalid engine signature: {}", e))?;
+  key
+    .verify(data, &sig, true)
+    .map_err(|e| format!("engine signature does not verify: {}", e))
+}
+
+async fn fetch_manifest(app: &AppHandle) -> Result<Manifest, String> {
+  let resp = netproxy::outbound(app)?
+    .build()
+    .map_err(|e| e.to_string())?
+    .get(MANIFEST_URL)
+    .send()
+    .await
+    .map_err(|e| e.to_string())?;
+  if !resp.status().is_success() {
+    return Err(format!("engine manifest returned HTTP {}", resp.status()));
+  }
+  resp.json().await.map_err(|e| format!("invalid engine manifest: {}", e))
+}
+
+/// Downloads and verifies `build` into `engines/<version>/`, returning its record.
+async fn install(
+  app: &AppHandle,
+  dir: &Path,
+  version: &str,
+  build: &PlatformBuild,
+) -> Result<InstalledEngine, String> {
+  let resp = netproxy::outbound(app)?
+    .build()
+    .map_err(|e| e.to_string())?
+    .get(&build.url)
+    .timeout(DOWNLOAD_TIMEOUT)
+    .send()
+    .await
+    .map_err(|e| e.to_string())?;
+  if !resp.status().is_success() {
+    return Err(format!("engine download returned HTTP {}", resp.status()));
+  }
+  let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+  verify_signature(app, &bytes, &build.signature)?;
+
+  let path = binary_path(dir, version);
+  let version_dir = path.parent().ok_or("engine path has no parent directory")?;
+  std::fs::create_dir_all(version_dir).map_err(|e| e.to_string())?;
+  let partial = path.with_extension("partial");
+  std::fs::write(&partial, &bytes).map_err(|e| e.to_string())?;
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(&partial, std::fs::Permissions::from_mode(0o755))
+      .map_err(|e| e.to_string())?;
+  }
+  std::fs::rename(&partial, &path).map_err(|e| e.to_string())?;
+
+  let sha256 = integrity::sha256_file(&path).map_err(|e| e.to_string())?;
+  Ok(InstalledEngine {
+    version: version.to_string(),
+    sha256,
+  })
+}
+
+/// Removes downloaded engines other than the current and previous ones.
+fn prune(dir: &Path, selection: &EngineSelection) {
+  let keep: Vec<&str> = [&selection.current, &selection.previous]
+    .into_iter()
+    .flatten()
+    .map(|e| e.version.as_str())
+    .collect();
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let name = entry.file_name().to_string_lossy().to_string();
+    if !entry.path().is_dir() || keep.contains(&name.as_str()) {
+      continue;
+    }
+    if let Err(e) = std::fs::remove_dir_all(entry.path()) {
+      eprintln!("[engineupdate] failed to remove {}: {}", entry.path().display(), e);
+    }
+  }
+}
+
+async fn restart_and_check(app: &AppHandle) -> Result<(), String> {
+  engine::stop_engine(app).await;
+  engine::spawn_engine(app).await?;
+  let endpoint = engine::wait_for_ready(app, READY_TIMEOUT).await?;
+  if !engine::ping_health(&endpoint).await {
+    return Err("new engine failed its health check".into());
+  }
+  Ok(())
+}
+
+/// Installs the newest engine from the release manifest and restarts onto it.
+/// If the new engine doesn't come up healthy, the previous one is restored.
+/// Returns the new version, or `None` if the running engine is already current.
+pub async fn check_and_apply(app: &AppHandle) -> Result<Option<String>, String> {
+  if *app.state::<EngineState>().attached.lock().unwrap() {
+    return Err("attached to an external engine; update it yourself".into());
+  }
+  let manifest = fetch_manifest(app).await?;
+  let latest = Version::parse(manifest.version.trim_start_matches('v'))
+    .map_err(|e| format!("engine manifest has an unreadable version: {}", e))?;
+  if running_version(app).is_some_and(|v| v >= latest) {
+    return Ok(None);
+  }
+  compat::check(&latest.to_string())?;
+  let build = manifest
+    .platforms
+    .get(TARGET)
+    .ok_or_else(|| format!("no engine build for {}", TARGET))?;
+
+  let dir = engines_dir(app)?;
+  let installed = install(app, &dir, &latest.to_string(), build).await?;
+
+  let mut selection = load_selection(&dir);
+  let rollback = selection.current.clone();
+  selection.previous = selection.current.replace(installed);
+  save_selection(&dir, &selection)?;
+
+  if let Err(e) = restart_and_check(app).await {
+    eprintln!("[engineupdate] {}; rolling back", e);
+    selection.current = rollback;
+    save_selection(&dir, &selection)?;
+    prune(&dir, &selection);
+    engine::stop_engine(app).await;
+    engine::spawn_engine(app).await?;
+    return Err(format!("engine {} failed to start and was rolled back: {}", latest, e));
+  }
+
+  prune(&dir, &selection);
+  println!("[engineupdate] now running engine {}", latest);
+  let _ = app.emit(
+    "engine-updated",
+    EngineUpdated {
+      version: latest.to_string(),
+    },
+  );
+  Ok(Some(latest.to_string()))
+}
+
+/// Checks for a newer engine every few hours while `engine_auto_update` is on.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(CHECK_INTERVAL).await;
+      if !settings::current(&app).engine_auto_update {
+        continue;
+      }
+      if let Err(e) = check_and_apply(&app).await {
+        eprintln!("[engineupdate] {}", e);
+      }
+    }
+  });
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::engine::{self, EngineState};
+use crate::transport::{self, Endpoint};
+use crate::{badge, jobwindow, notifications, proxy};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Tauri event names only allow a limited charset; anything else becomes `_`.
+fn topic(event: &serde_json::Value) -> Option<String> {
+  let kind = event.get("type")?.as_str()?;
+  Some(
+    kind
+      .chars()
+      .map(|c| {
+        if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':') {
+          c
+        } else {
+          '_'
+        }
+      })
+      .collect(),
+  )
+}
+
+fn dispatch(app: &AppHandle, data: &str) {
+  let Ok(event) = serde_json::from_str::<serde_json::Value>(data) else {
+    eprintln!("[events] ignoring non-JSON event: {}", data);
+    return;
+  };
+  let Some(topic) = topic(&event) else {
+    return;
+  };
+  if topic == "ping" {
+    return;
+  }
+  jobwindow::route(app, &format!("engine://event/{}", topic), &event);
+  notifications::handle(app, &event);
+  badge::handle(app, &event);
+}
+
+/// Reads the engine's `/events` SSE stream until it ends.
+async fn follow(app: &AppHandle, endpoint: &Endpoint) -> Result<(), String> {
+  // No timeout: the stream stays open for as long as the engine runs.
+  let req = transport::client(endpoint).get(endpoint.url("/events"));
+  let mut resp = proxy::send(app, req).await.map_err(|e| e.to_string())?;
+  if !resp.status().is_success() {
+    return Err(format!("events returned HTTP {}", resp.status()));
+  }
+  println!("[events] connected to engine on {}", endpoint);
+
+  let mut buf = String::new();
+  while let Some(bytes) = resp.chunk().await.map_err(|e| e.to_string())? {
+    buf.push_str(&String::from_utf8_lossy(&bytes));
+    while let Some(pos) = buf.find('\n') {
+      let line: String = buf.drain(..=pos).collect();
+      if let Some(data) = line.trim_end().strip_prefix("data:") {
+        dispatch(app, data.trim());
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Re-emits every engine event as `engine://event/<type>` for as long as the
+/// app runs, reconnecting whenever the engine restarts.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      let Ok(endpoint) = engine::wait_for_ready(&app, READY_TIMEOUT).await else {
+        continue;
+      };
+      if *app.state::<EngineState>().allow_close.lock().unwrap() {
+        return;
+      }
+      if let Err(e) = follow(&app, &endpoint).await {
+        eprintln!("[events] stream: {}", e);
+      }
+      tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+  });
+}
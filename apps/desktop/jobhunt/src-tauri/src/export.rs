@@ -0,0 +1,248 @@
+use std::path::{Path, PathBuf};
+
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::{i18n, proxy};
+
+/// Column header catalog keys, in column order.
+const HEADERS: &[&str] = &[
+  "export.id",
+  "export.company",
+  "export.title",
+  "export.location",
+  "export.work_mode",
+  "export.score",
+  "export.tags",
+  "export.date",
+  "export.source",
+  "export.url",
+];
+
+fn headers() -> Vec<String> {
+  HEADERS.iter().map(|key| i18n::t(key)).collect()
+}
+
+/// A job as returned by the engine's `/jobs`.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct JobRow {
+  id: i64,
+  company: String,
+  title: String,
+  location: String,
+  work_mode: String,
+  score: i64,
+  tags: Option<Vec<String>>,
+  date: String,
+  seen_from_source: String,
+  url: String,
+}
+
+impl JobRow {
+  fn tags(&self) -> String {
+    self.tags.as_deref().unwrap_or_default().join(", ")
+  }
+
+  /// The engine formats dates as `YYYY-MM-DD HH:MM:SS`, and unparseable ones as year 1.
+  fn date(&self) -> &str {
+    if self.date.starts_with("0001-") {
+      ""
+    } else {
+      &self.date
+    }
+  }
+}
+
+#[derive(Serialize)]
+pub struct ExportSummary {
+  pub path: String,
+  pub rows: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ExportFormat {
+  Csv,
+  Xlsx,
+}
+
+impl ExportFormat {
+  fn parse(s: &str) -> Result<Self, String> {
+    match s.to_lowercase().as_str() {
+      "csv" => Ok(ExportFormat::Csv),
+      "xlsx" => Ok(ExportFormat::Xlsx),
+      other => Err(format!("unknown export format {:?}; use csv or xlsx", other)),
+    }
+  }
+
+  fn extension(self) -> &'static str {
+    match self {
+      ExportFormat::Csv => "csv",
+      ExportFormat::Xlsx => "xlsx",
+    }
+  }
+}
+
+async fn fetch_jobs(app: &AppHandle) -> Result<Vec<JobRow>, String> {
+  let resp = proxy::engine_fetch(app, "GET", "/jobs?sort=date&window=all", None).await?;
+  if resp.status >= 400 {
+    return Err(format!("engine returned HTTP {}: {}", resp.status, resp.body.trim()));
+  }
+  // The engine encodes an empty list as `null`.
+  let rows: Option<Vec<JobRow>> = serde_json::from_str(&resp.body).map_err(|e| e.to_string())?;
+  Ok(rows.unwrap_or_default())
+}
+
+/// RFC 4180 quoting, plus a leading `'` on cells a spreadsheet would
+/// otherwise evaluate as a formula.
+fn csv_field(value: &str) -> String {
+  let value = if value.starts_with(['=', '+', '-', '@']) {
+    format!("'{}", value)
+  } else {
+    value.to_string()
+  };
+  if value.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", value.replace('"', "\"\""))
+  } else {
+    value
+  }
+}
+
+fn write_csv(rows: &[JobRow], dest: &Path) -> Result<(), String> {
+  let mut out = String::new();
+  let headers: Vec<String> = headers().iter().map(|h| csv_field(h)).collect();
+  out.push_str(&headers.join(","));
+  out.push_str("\r\n");
+  for r in rows {
+    let fields = [
+      r.id.to_string(),
+      csv_field(&r.company),
+      csv_field(&r.title),
+      csv_field(&r.location),
+      csv_field(&r.work_mode),
+      r.score.to_string(),
+      csv_field(&r.tags()),
+      csv_field(r.date()),
+      csv_field(&r.seen_from_source),
+      csv_field(&r.url),
+    ];
+    out.push_str(&fields.join(","));
+    out.push_str("\r\n");
+  }
+  // BOM so Excel opens the file as UTF-8.
+  std::fs::write(dest, format!("\u{feff}{}", out)).map_err(|e| e.to_string())
+}
+
+fn write_xlsx(rows: &[JobRow], dest: &Path) -> Result<(), String> {
+  let mut workbook = Workbook::new();
+  let sheet = workbook.add_worksheet();
+  sheet
+    .set_name(i18n::t("export.sheet"))
+    .map_err(|e| e.to_string())?;
+  let bold = Format::new().set_bold();
+  let date_format = Format::new().set_num_format("yyyy-mm-dd hh:mm");
+
+  for (col, header) in headers().iter().enumerate() {
+    sheet
+      .write_string_with_format(0, col as u16, header, &bold)
+      .map_err(|e| e.to_string())?;
+  }
+  for (i, r) in rows.iter().enumerate() {
+    let row = i as u32 + 1;
+    let cells: Result<(), rust_xlsxwriter::XlsxError> = (|| {
+      sheet.write_number(row, 0, r.id as f64)?;
+      sheet.write_string(row, 1, &r.company)?;
+      sheet.write_string(row, 2, &r.title)?;
+      sheet.write_string(row, 3, &r.location)?;
+      sheet.write_string(row, 4, &r.work_mode)?;
+      sheet.write_number(row, 5, r.score as f64)?;
+      sheet.write_string(row, 6, r.tags())?;
+      match ExcelDateTime::parse_from_str(r.date()) {
+        Ok(dt) => sheet.write_datetime_with_format(row, 7, &dt, &date_format)?,
+        Err(_) => sheet.write_string(row, 7, r.date())?,
+      };
+      sheet.write_string(row, 8, &r.seen_from_source)?;
+      // Malformed or over-long (Excel caps links at 2079 chars) URLs go in as text.
+      if sheet.write_url(row, 9, r.url.as_str()).is_err() {
+        sheet.write_string(row, 9, &r.url)?;
+      }
+      Ok(())
+    })();
+    cells.map_err(|e| format!("row {}: {}", row, e))?;
+  }
+  sheet.autofit();
+  sheet.set_freeze_panes(1, 0).map_err(|e| e.to_string())?;
+
+  workbook.save(dest).map_err(|e| e.to_string())
+}
+
+/// Writes the engine's job list to CSV or XLSX at `dest`, asking with a save
+/// dialog when no destination is given. `None` if the user cancelled.
+pub async fn export_applications(
+  app: &AppHandle,
+  format: &str,
+  dest: Option<PathBuf>,
+) -> Result<Option<ExportSummary>, String> {
+  let format = ExportFormat::parse(format)?;
+  let dest = match dest {
+    Some(d) => d,
+    None => {
+      let Some(file) = app
+        .dialog()
+        .file()
+        .set_title(i18n::t("dialog.export"))
+        .set_file_name(format!("jobhunt-applications.{}", format.extension()))
+        .add_filter(format.extension().to_uppercase(), &[format.extension()])
+        .blocking_save_file()
+      else {
+        return Ok(None);
+      };
+      file.into_path().map_err(|e| e.to_string())?
+    }
+  };
+
+  let rows = fetch_jobs(app).await?;
+  match format {
+    ExportFormat::Csv => write_csv(&rows, &dest)?,
+    ExportFormat::Xlsx => write_xlsx(&rows, &dest)?,
+  }
+
+  println!("[export] wrote {} rows to {}", rows.len(), dest.display());
+  Ok(Some(ExportSummary {
+    path: dest.to_string_lossy().to_string(),
+    rows: rows.len(),
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn csv_field_leaves_plain_values_alone() {
+    assert_eq!(csv_field("Software Engineer"), "Software Engineer");
+    assert_eq!(csv_field(""), "");
+  }
+
+  #[test]
+  fn csv_field_quotes_separators_and_line_breaks() {
+    assert_eq!(csv_field("Acme, Inc"), "\"Acme, Inc\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    assert_eq!(csv_field("cr\rhere"), "\"cr\rhere\"");
+  }
+
+  #[test]
+  fn csv_field_doubles_quotes() {
+    assert_eq!(csv_field("the \"best\" job"), "\"the \"\"best\"\" job\"");
+  }
+
+  #[test]
+  fn csv_field_defuses_formulas() {
+    assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+    assert_eq!(csv_field("+1 555"), "'+1 555");
+    assert_eq!(csv_field("-5"), "'-5");
+    assert_eq!(csv_field("@handle"), "'@handle");
+  }
+}
@@ -0,0 +1,228 @@
+use std::io::Read;
+use std::path::Path;
+
+use pdf_extract::encryption::DecryptionError;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Serialize;
+
+// Office files saved with a password are OLE compound documents, not zips.
+const OLE_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+const MAX_HEADING_WORDS: usize = 4;
+const HEADINGS: &[&str] = &[
+  "summary",
+  "professional summary",
+  "profile",
+  "objective",
+  "experience",
+  "work experience",
+  "professional experience",
+  "employment history",
+  "education",
+  "skills",
+  "technical skills",
+  "projects",
+  "certifications",
+  "awards",
+  "publications",
+  "languages",
+  "interests",
+  "volunteer experience",
+  "references",
+];
+
+/// Why a document's text could not be extracted. Serialized as
+/// `{ "kind": "...", "message": "..." }` so the UI can react to each case.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum ExtractError {
+  Unsupported(String),
+  Encrypted(String),
+  Corrupt(String),
+  Io(String),
+}
+
+impl From<std::io::Error> for ExtractError {
+  fn from(e: std::io::Error) -> Self {
+    ExtractError::Io(e.to_string())
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Section {
+  pub title: String,
+  pub content: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExtractedText {
+  pub text: String,
+  /// Text before the first recognised heading lands in an untitled section.
+  pub sections: Vec<Section>,
+}
+
+fn extract_pdf(bytes: &[u8]) -> Result<String, ExtractError> {
+  // The parser can panic on malformed input; treat that as a corrupt file.
+  let result = std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(bytes))
+    .map_err(|_| ExtractError::Corrupt("the PDF could not be parsed".into()))?;
+  match result {
+    Ok(text) => Ok(text),
+    Err(pdf_extract::OutputError::PdfError(pdf_extract::Error::Decryption(
+      DecryptionError::IncorrectPassword,
+    ))) => Err(ExtractError::Encrypted(
+      "the PDF is password protected".into(),
+    )),
+    Err(pdf_extract::OutputError::IoError(e)) => Err(e.into()),
+    Err(e) => Err(ExtractError::Corrupt(e.to_string())),
+  }
+}
+
+fn extract_docx(bytes: &[u8]) -> Result<String, ExtractError> {
+  if bytes.starts_with(OLE_MAGIC) {
+    return Err(ExtractError::Encrypted(
+      "the document is password protected".into(),
+    ));
+  }
+  let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+    .map_err(|e| ExtractError::Corrupt(e.to_string()))?;
+  let mut xml = String::new();
+  archive
+    .by_name("word/document.xml")
+    .map_err(|e| ExtractError::Corrupt(e.to_string()))?
+    .read_to_string(&mut xml)?;
+
+  let mut reader = Reader::from_str(&xml);
+  let mut text = String::new();
+  let mut in_text = false;
+  loop {
+    match reader.read_event() {
+      Ok(Event::Start(e)) if e.name().as_ref() == b"w:t" => in_text = true,
+      Ok(Event::End(e)) => match e.name().as_ref() {
+        b"w:t" => in_text = false,
+        b"w:p" => text.push('\n'),
+        _ => {}
+      },
+      Ok(Event::Empty(e)) => match e.name().as_ref() {
+        b"w:tab" => text.push('\t'),
+        b"w:br" | b"w:cr" => text.push('\n'),
+        _ => {}
+      },
+      Ok(Event::Text(t)) if in_text => {
+        let s = t
+          .decode()
+          .map_err(|e| ExtractError::Corrupt(e.to_string()))?;
+        text.push_str(&s);
+      }
+      Ok(Event::GeneralRef(r)) if in_text => {
+        let name = r.decode().map_err(|e| ExtractError::Corrupt(e.to_string()))?;
+        let resolved = match name.as_ref() {
+          "amp" => Some('&'),
+          "lt" => Some('<'),
+          "gt" => Some('>'),
+          "quot" => Some('"'),
+          "apos" => Some('\''),
+          _ => r.resolve_char_ref().ok().flatten(),
+        };
+        text.extend(resolved);
+      }
+      Ok(Event::Eof) => break,
+      Ok(_) => {}
+      Err(e) => return Err(ExtractError::Corrupt(e.to_string())),
+    }
+  }
+  Ok(text)
+}
+
+/// Trims each line, collapses runs of spaces, drops control characters and
+/// squeezes blank lines down to one.
+fn normalize(raw: &str) -> String {
+  let mut out = Vec::new();
+  let mut blank = false;
+  for line in raw.replace("\r\n", "\n").replace('\r', "\n").lines() {
+    let line: String = line
+      .chars()
+      .map(|c| if c == '\t' || c == '\u{a0}' { ' ' } else { c })
+      .filter(|c| !c.is_control())
+      .collect();
+    let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.is_empty() {
+      if !blank && !out.is_empty() {
+        out.push(String::new());
+      }
+      blank = true;
+    } else {
+      out.push(line);
+      blank = false;
+    }
+  }
+  while out.last().is_some_and(|l| l.is_empty()) {
+    out.pop();
+  }
+  out.join("\n")
+}
+
+fn heading(line: &str) -> Option<String> {
+  let candidate = line.trim().trim_end_matches(':').trim();
+  if candidate.split_whitespace().count() > MAX_HEADING_WORDS {
+    return None;
+  }
+  let lower = candidate.to_lowercase();
+  HEADINGS.contains(&lower.as_str()).then(|| candidate.to_string())
+}
+
+fn sections(text: &str) -> Vec<Section> {
+  let mut sections = vec![Section {
+    title: String::new(),
+    content: String::new(),
+  }];
+  for line in text.lines() {
+    if let Some(title) = heading(line) {
+      sections.push(Section {
+        title,
+        content: String::new(),
+      });
+      continue;
+    }
+    let current = sections.last_mut().expect("sections is never empty");
+    if !current.content.is_empty() {
+      current.content.push('\n');
+    }
+    current.content.push_str(line);
+  }
+  for s in &mut sections {
+    s.content = s.content.trim().to_string();
+  }
+  sections.retain(|s| !s.title.is_empty() || !s.content.is_empty());
+  sections
+}
+
+/// Pulls plain text out of a PDF or DOCX résumé for the engine's matcher.
+pub fn extract_text(path: &Path) -> Result<ExtractedText, ExtractError> {
+  let ext = path
+    .extension()
+    .map(|e| e.to_string_lossy().to_lowercase())
+    .unwrap_or_default();
+  let bytes = std::fs::read(path)?;
+  let raw = match ext.as_str() {
+    "pdf" => extract_pdf(&bytes)?,
+    "docx" => extract_docx(&bytes)?,
+    "txt" | "md" => String::from_utf8_lossy(&bytes).to_string(),
+    _ => {
+      return Err(ExtractError::Unsupported(format!(
+        "cannot extract text from .{} files",
+        ext
+      )))
+    }
+  };
+
+  let text = normalize(&raw);
+  if text.is_empty() {
+    return Err(ExtractError::Corrupt(
+      "no text found; the file may be a scanned image".into(),
+    ));
+  }
+  Ok(ExtractedText {
+    sections: sections(&text),
+    text,
+  })
+}
@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{dropimport, engine};
+
+/// The file types registered under `bundle.fileAssociations`.
+const EXTENSIONS: &[&str] = &["pdf", "eml", "html", "htm"];
+// Our flags that take a value, so the value isn't mistaken for a file.
+const VALUE_FLAGS: &[&str] = &["--port", "--data-dir", "--profile", "--attach"];
+// A cold launch has to wait for the engine before anything can be imported.
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Serialize)]
+struct FilesOpened {
+  names: Vec<String>,
+}
+
+fn supported(path: &Path) -> bool {
+  path
+    .extension()
+    .map(|e| e.to_string_lossy().to_lowercase())
+    .is_some_and(|e| EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Files passed on the command line by "Open with JobHunt" (Windows and
+/// Linux pass them as arguments, program name first). Relative paths are
+/// resolved against `cwd`, which for a second instance is that instance's.
+pub fn paths_from_args(args: &[String], cwd: &Path) -> Vec<PathBuf> {
+  let mut paths = Vec::new();
+  let mut iter = args.iter().skip(1);
+  while let Some(arg) = iter.next() {
+    if VALUE_FLAGS.contains(&arg.as_str()) {
+      iter.next();
+      continue;
+    }
+    // Flags, deep links, and the browser origin of a native messaging launch.
+    if arg.starts_with('-') || arg.contains("://") {
+      continue;
+    }
+    let path = cwd.join(arg);
+    if supported(&path) && path.is_file() {
+      paths.push(path);
+    }
+  }
+  paths
+}
+
+/// Imports files the OS asked us to open and brings the main window up to
+/// show their progress. At launch the splash reveals the window itself, so
+/// it is only raised here when the app was already running.
+pub fn open(app: &AppHandle, paths: Vec<PathBuf>) {
+  let paths: Vec<PathBuf> = paths.into_iter().filter(|p| supported(p)).collect();
+  if paths.is_empty() {
+    return;
+  }
+  println!("[fileopen] opening {} file(s)", paths.len());
+
+  if let Some(main) = app.get_webview_window("main") {
+    if main.is_visible().unwrap_or(false) {
+      let _ = main.unminimize();
+      let _ = main.set_focus();
+    }
+  }
+  let names = paths
+    .iter()
+    .filter_map(|p| p.file_name())
+    .map(|n| n.to_string_lossy().to_string())
+    .collect();
+  let _ = app.emit_to("main", "files-opened", FilesOpened { names });
+
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    if let Err(e) = engine::wait_for_ready(&app, READY_TIMEOUT).await {
+      eprintln!("[fileopen] {}", e);
+    }
+    // Progress and the outcome arrive as file-import-* events, as for a drop.
+    dropimport::handle_drop(&app, paths);
+  });
+}
+
+/// macOS delivers "Open with" as an open-documents event rather than argv;
+/// `jobhunt://` links in the same event belong to the deep-link plugin.
+#[cfg(target_os = "macos")]
+pub fn open_urls(app: &AppHandle, urls: &[tauri::Url]) {
+  let paths = urls
+    .iter()
+    .filter(|u| u.scheme() == "file")
+    .filter_map(|u| u.to_file_path().ok())
+    .collect();
+  open(app, paths);
+}
@@ -0,0 +1,265 @@
+//! `--headless`: the engine under a small supervisor, without Tauri. No
+//! window system or event loop is started, so this runs on a machine with
+//! no display. Settings are read once at launch; nothing is written back.
+
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+use reqwest::Method;
+use tokio::process::{Child, Command};
+
+use crate::cli::CliArgs;
+use crate::engine;
+use crate::settings::{self, Settings};
+use crate::transport::{self, Endpoint};
+use crate::{
+  appupdate, engineupdate, integrity, netaudit, netproxy, pidfile, procguard, profiles, proxy,
+  sandbox, secrets, vault,
+};
+
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// An engine that stayed up this long resets the backoff.
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const READY_POLL: Duration = Duration::from_millis(250);
+
+#[cfg(unix)]
+async fn shutdown_signal() {
+  use tokio::signal::unix::{signal, SignalKind};
+
+  let mut term = match signal(SignalKind::terminate()) {
+    Ok(term) => term,
+    Err(e) => {
+      eprintln!("[headless] cannot listen for SIGTERM: {}", e);
+      let _ = tokio::signal::ctrl_c().await;
+      return;
+    }
+  };
+  tokio::select! {
+    _ = term.recv() => println!("[headless] SIGTERM received"),
+    _ = tokio::signal::ctrl_c() => println!("[headless] interrupt received"),
+  }
+}
+
+#[cfg(not(unix))]
+async fn shutdown_signal() {
+  if tokio::signal::ctrl_c().await.is_ok() {
+    println!("[headless] interrupt received");
+  }
+}
+
+/// A running engine and how to reach it.
+struct Running {
+  child: Child,
+  endpoint: Endpoint,
+  auth_key: String,
+}
+
+/// Where Tauri would put the app's config and data for `identifier`.
+fn app_dirs(identifier: &str) -> Result<(PathBuf, PathBuf), String> {
+  let config = dirs::config_dir().ok_or("no config directory for this user")?;
+  let data = dirs::data_dir().ok_or("no data directory for this user")?;
+  Ok((config.join(identifier), data.join(identifier)))
+}
+
+async fn launch(
+  cli: &CliArgs,
+  settings: &Settings,
+  app_data: &Path,
+  data_dir: &Path,
+) -> Result<Running, String> {
+  std::fs::create_dir_all(data_dir).map_err(|e| e.to_string())?;
+  pidfile::reap_stale(data_dir).await?;
+  appupdate::verify_data_dir(data_dir)
+    .map_err(|e| format!("{} Open JobHunt to restore the backup or start anyway.", e))?;
+
+  let endpoint = if settings.engine_socket && cli.port.is_none() {
+    let endpoint = Endpoint::Socket(transport::socket_path(data_dir)?);
+    transport::remove_stale(&endpoint);
+    endpoint
+  } else {
+    Endpoint::Tcp(engine::choose_port(cli.port.or(settings.engine_port))?)
+  };
+  let auth_key = engine::generate_token()?;
+
+  let binary = match engineupdate::active_binary_in(app_data)? {
+    Some(path) => path,
+    None => {
+      integrity::verify_engine()?;
+      integrity::engine_path()?
+    }
+  };
+  let (parent_key, parent_pid) = procguard::parent_pid_env();
+  let mut cmd = Command::new(&binary);
+  cmd
+    .current_dir(data_dir)
+    .env("JOBHUNT_DATA_DIR", data_dir)
+    .env("JOBHUNT_AUTH_KEY", &auth_key)
+    .env(parent_key, parent_pid)
+    .stdin(Stdio::null())
+    .kill_on_drop(true);
+  match &endpoint {
+    Endpoint::Tcp(port) => cmd.env("JOBHUNT_PORT", port.to_string()),
+    Endpoint::Socket(path) => cmd.env("JOBHUNT_SOCKET", path),
+  };
+  cmd.envs(secrets::engine_env());
+  cmd.envs(netproxy::engine_env_for(settings));
+  cmd.envs(sandbox::engine_env_for(settings));
+
+  let child = cmd
+    .spawn()
+    .map_err(|e| format!("could not start {}: {}", binary.display(), e))?;
+  if let Some(pid) = child.id() {
+    procguard::adopt(pid);
+    sandbox::restrict_for(settings, pid);
+    pidfile::write(data_dir, pid);
+    println!(
+      "[headless] engine {} serving {} on {}",
+      pid,
+      data_dir.display(),
+      endpoint
+    );
+  }
+  Ok(Running {
+    child,
+    endpoint,
+    auth_key,
+  })
+}
+
+/// Waits for the engine we spawned to answer /health; an answer from any
+/// other pid doesn't count. False if the engine exited or never answered.
+async fn ready(engine: &mut Running) -> bool {
+  let Some(pid) = engine.child.id() else {
+    return false;
+  };
+  let deadline = Instant::now() + READY_TIMEOUT;
+  while Instant::now() < deadline {
+    if !matches!(engine.child.try_wait(), Ok(None)) {
+      return false;
+    }
+    match engine::fetch_health(&engine.endpoint).await {
+      Some(health) if health.pid == pid => return true,
+      Some(health) => eprintln!(
+        "[headless] process {} answered on {} instead of the engine",
+        health.pid, engine.endpoint
+      ),
+      None => {}
+    }
+    tokio::time::sleep(READY_POLL).await;
+  }
+  eprintln!(
+    "[headless] engine did not answer on {} within {}s",
+    engine.endpoint,
+    READY_TIMEOUT.as_secs()
+  );
+  false
+}
+
+/// Asks the engine to shut down, and kills it if it hasn't within STOP_TIMEOUT.
+async fn stop(mut engine: Running, data_dir: &Path) {
+  let request = transport::client(&engine.endpoint)
+    .request(Method::POST, engine.endpoint.url("/shutdown"))
+    .timeout(STOP_TIMEOUT)
+    .build();
+  let sent = match request {
+    Ok(mut req) => match proxy::sign(&engine.auth_key, &mut req) {
+      Ok(()) => transport::client(&engine.endpoint)
+        .execute(req)
+        .await
+        .map_err(|e| e.to_string()),
+      Err(e) => Err(e),
+    },
+    Err(e) => Err(e.to_string()),
+  };
+  if let Err(e) = sent {
+    eprintln!("[headless] shutdown request failed: {}", e);
+  }
+  if tokio::time::timeout(STOP_TIMEOUT, engine.child.wait())
+    .await
+    .is_err()
+  {
+    eprintln!("[headless] engine did not stop in time; killing it");
+    let _ = engine.child.kill().await;
+  }
+  pidfile::remove(data_dir);
+}
+
+fn describe(status: std::io::Result<ExitStatus>) -> String {
+  match status {
+    Ok(status) => status.to_string(),
+    Err(e) => e.to_string(),
+  }
+}
+
+async fn supervise(cli: &CliArgs, identifier: &str) -> Result<(), String> {
+  let (config_dir, app_data) = app_dirs(identifier)?;
+  let settings = settings::load_from(&config_dir.join(settings::SETTINGS_FILE));
+  let data_dir = match &cli.data_dir {
+    Some(dir) => dir.clone(),
+    None => {
+      let profile = cli
+        .profile
+        .as_deref()
+        .unwrap_or_else(|| profiles::active_name(&settings));
+      profiles::data_dir_in(&app_data, &settings, profile)?
+    }
+  };
+  if vault::configured(&data_dir) {
+    return Err("this profile is encrypted; headless mode can't prompt for the passphrase".into());
+  }
+
+  println!("[headless] running without windows; stop with Ctrl+C or SIGTERM");
+  let shutdown = shutdown_signal();
+  tokio::pin!(shutdown);
+  let mut backoff = MIN_BACKOFF;
+  loop {
+    let mut engine = launch(cli, &settings, &app_data, &data_dir).await?;
+    let started = Instant::now();
+    if ready(&mut engine).await {
+      if let Some(pid) = engine.child.id() {
+        if let Err(e) = netaudit::verify_loopback(pid).await {
+          stop(engine, &data_dir).await;
+          return Err(e.to_string());
+        }
+      }
+    }
+    tokio::select! {
+      status = engine.child.wait() => {
+        pidfile::remove(&data_dir);
+        eprintln!("[headless] engine exited: {}", describe(status));
+      }
+      _ = &mut shutdown => {
+        stop(engine, &data_dir).await;
+        return Ok(());
+      }
+    }
+
+    if started.elapsed() >= STABLE_AFTER {
+      backoff = MIN_BACKOFF;
+    }
+    println!("[headless] restarting in {}s", backoff.as_secs());
+    tokio::select! {
+      _ = tokio::time::sleep(backoff) => {}
+      _ = &mut shutdown => return Ok(()),
+    }
+    backoff = (backoff * 2).min(MAX_BACKOFF);
+  }
+}
+
+/// Runs the engine until SIGTERM or Ctrl+C, restarting it with backoff if it
+/// exits. Returns the process exit code.
+pub fn run(cli: &CliArgs, identifier: &str) -> i32 {
+  tauri::async_runtime::block_on(async {
+    match supervise(cli, identifier).await {
+      Ok(()) => 0,
+      Err(e) => {
+        eprintln!("[headless] {}", e);
+        1
+      }
+    }
+  })
+}
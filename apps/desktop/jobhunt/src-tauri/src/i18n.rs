@@ -0,0 +1,211 @@
+//! Translations for everything the shell renders natively: dialogs, the tray,
+//! notifications, window titles, and exports. The webview's language picker
+//! calls `set_locale` and follows `locale-changed`, so both sides agree.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use chrono::{Datelike, NaiveDate};
+use tauri::{AppHandle, Emitter};
+
+use crate::{appmenu, settings, tray};
+
+const FALLBACK: &str = "en";
+
+/// Supported languages and their catalogs, which are flat key -> text maps
+/// with `{name}` placeholders. Every catalog has the same keys as `en`.
+const CATALOGS: &[(&str, &str)] = &[
+  ("en", include_str!("../locales/en.json")),
+  ("es", include_str!("../locales/es.json")),
+  ("fr", include_str!("../locales/fr.json")),
+  ("de", include_str!("../locales/de.json")),
+];
+
+static CURRENT: RwLock<&str> = RwLock::new(FALLBACK);
+
+fn catalogs() -> &'static HashMap<&'static str, HashMap<String, String>> {
+  static PARSED: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+  PARSED.get_or_init(|| {
+    CATALOGS
+      .iter()
+      .map(|(lang, json)| {
+        let catalog = serde_json::from_str(json).expect("locale catalog is valid JSON");
+        (*lang, catalog)
+      })
+      .collect()
+  })
+}
+
+/// The supported language closest to `tag`, e.g. `de` for `de-AT` or
+/// `de_DE.UTF-8`, falling back to English.
+pub fn negotiate(tag: &str) -> &'static str {
+  let lang = tag
+    .split(['-', '_', '.'])
+    .next()
+    .unwrap_or_default()
+    .to_lowercase();
+  CATALOGS
+    .iter()
+    .map(|(l, _)| *l)
+    .find(|l| *l == lang)
+    .unwrap_or(FALLBACK)
+}
+
+pub fn locale() -> &'static str {
+  *CURRENT.read().unwrap()
+}
+
+/// The text for `key` in the current language, or in English if it's missing.
+pub fn t(key: &str) -> String {
+  let catalogs = catalogs();
+  catalogs
+    .get(locale())
+    .and_then(|c| c.get(key))
+    .or_else(|| catalogs.get(FALLBACK).and_then(|c| c.get(key)))
+    .cloned()
+    .unwrap_or_else(|| key.to_string())
+}
+
+/// [`t`] with each `{name}` placeholder replaced by its value in `args`.
+pub fn t_with(key: &str, args: &[(&str, &str)]) -> String {
+  args.iter().fold(t(key), |text, (name, value)| {
+    text.replace(&format!("{{{}}}", name), value)
+  })
+}
+
+/// `n` with the current language's digit grouping and decimal separator.
+pub fn number(n: f64, decimals: usize) -> String {
+  let fixed = format!("{:.*}", decimals, n.abs());
+  let (int, frac) = fixed.split_once('.').unwrap_or((&fixed, ""));
+  let group = t("format.group");
+  let mut out = String::new();
+  for (i, digit) in int.chars().enumerate() {
+    if i > 0 && (int.len() - i) % 3 == 0 {
+      out.push_str(&group);
+    }
+    out.push(digit);
+  }
+  if !frac.is_empty() {
+    out.push_str(&t("format.decimal"));
+    out.push_str(frac);
+  }
+  if n < 0.0 && fixed.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+    out.insert(0, '-');
+  }
+  out
+}
+
+/// `date` in the current language's short numeric form.
+pub fn date(date: NaiveDate) -> String {
+  let month = format!("{:02}", date.month());
+  let day = format!("{:02}", date.day());
+  t_with(
+    "format.date",
+    &[
+      ("year", &date.year().to_string()),
+      ("month", &month),
+      ("day", &day),
+    ],
+  )
+}
+
+/// Reformats an engine timestamp (`YYYY-MM-DD HH:MM:SS`) as a local date,
+/// leaving anything unparseable as it was.
+pub fn engine_date(value: &str) -> String {
+  match value.get(..10).map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d")) {
+    Some(Ok(d)) => date(d),
+    _ => value.to_string(),
+  }
+}
+
+/// Picks the language at startup: the saved choice, else the OS's.
+pub fn init(app: &AppHandle) {
+  let tag = settings::current(app)
+    .locale
+    .or_else(sys_locale::get_locale)
+    .unwrap_or_else(|| FALLBACK.to_string());
+  *CURRENT.write().unwrap() = negotiate(&tag);
+  println!("[i18n] using {}", locale());
+}
+
+/// Switches the native UI to the language closest to `lang` and remembers
+/// it. Returns the language actually used.
+pub fn set_locale(app: &AppHandle, lang: &str) -> Result<String, String> {
+  let chosen = negotiate(lang);
+  let mut s = settings::current(app);
+  s.locale = Some(chosen.to_string());
+  settings::replace(app, s)?;
+  *CURRENT.write().unwrap() = chosen;
+
+  tray::refresh_labels(app);
+  appmenu::rebuild(app);
+  let _ = app.emit("locale-changed", chosen);
+  println!("[i18n] switched to {}", chosen);
+  Ok(chosen.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::sync::Mutex;
+
+  // `number` reads the process-wide language, so tests that set it take turns.
+  static LANGUAGE: Mutex<()> = Mutex::new(());
+
+  fn in_locale<R>(lang: &'static str, f: impl FnOnce() -> R) -> R {
+    let _turn = LANGUAGE.lock().unwrap_or_else(|e| e.into_inner());
+    *CURRENT.write().unwrap() = lang;
+    f()
+  }
+
+  #[test]
+  fn negotiate_matches_the_language_part_of_a_tag() {
+    assert_eq!(negotiate("de-AT"), "de");
+    assert_eq!(negotiate("de_DE.UTF-8"), "de");
+    assert_eq!(negotiate("FR"), "fr");
+    assert_eq!(negotiate("es"), "es");
+  }
+
+  #[test]
+  fn negotiate_falls_back_to_english() {
+    assert_eq!(negotiate("pt-BR"), "en");
+    assert_eq!(negotiate("C"), "en");
+    assert_eq!(negotiate(""), "en");
+  }
+
+  #[test]
+  fn number_groups_thousands() {
+    in_locale("en", || {
+      assert_eq!(number(1234567.891, 2), "1,234,567.89");
+      assert_eq!(number(999.0, 0), "999");
+      assert_eq!(number(1000.0, 0), "1,000");
+    });
+    in_locale("de", || assert_eq!(number(1234567.891, 2), "1.234.567,89"));
+    in_locale("fr", || assert_eq!(number(1234.5, 1), "1\u{a0}234,5"));
+  }
+
+  #[test]
+  fn number_keeps_the_sign_of_negatives() {
+    in_locale("en", || {
+      assert_eq!(number(-1234.5, 1), "-1,234.5");
+      assert_eq!(number(-7.0, 0), "-7");
+    });
+  }
+
+  #[test]
+  fn number_never_shows_negative_zero() {
+    in_locale("en", || {
+      assert_eq!(number(-0.004, 2), "0.00");
+      assert_eq!(number(-0.4, 0), "0");
+    });
+  }
+
+  #[test]
+  fn number_with_zero_decimals_has_no_separator() {
+    in_locale("en", || {
+      assert_eq!(number(0.0, 0), "0");
+      assert_eq!(number(2.6, 0), "3");
+    });
+    in_locale("de", || assert_eq!(number(1234.0, 0), "1.234"));
+  }
+}
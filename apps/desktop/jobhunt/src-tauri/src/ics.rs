@@ -0,0 +1,163 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use serde::Deserialize;
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::{engine, i18n};
+
+const DEFAULT_DURATION_MINUTES: i64 = 60;
+const DEFAULT_REMINDER_MINUTES: i64 = 30;
+// RFC 5545 §3.1: lines longer than 75 octets are folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Interview {
+  /// Stable ID so re-exporting updates the calendar entry instead of duplicating it.
+  pub id: Option<String>,
+  pub title: String,
+  /// RFC 3339, e.g. `2025-03-04T15:00:00-05:00`.
+  pub start: String,
+  pub duration_minutes: Option<i64>,
+  pub location: Option<String>,
+  pub url: Option<String>,
+  pub description: Option<String>,
+  /// Minutes before the start to remind; 0 disables the alarm.
+  pub reminder_minutes: Option<i64>,
+}
+
+fn utc_stamp<Tz: TimeZone>(t: DateTime<Tz>) -> String {
+  t.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace(';', "\\;")
+    .replace(',', "\\,")
+    .replace("\r\n", "\\n")
+    .replace('\n', "\\n")
+}
+
+/// Folds a content line at 75 octets without splitting a UTF-8 character.
+fn fold(line: &str) -> String {
+  let mut out = String::new();
+  let mut len = 0;
+  for c in line.chars() {
+    if len + c.len_utf8() > MAX_LINE_OCTETS {
+      out.push_str("\r\n ");
+      len = 1;
+    }
+    out.push(c);
+    len += c.len_utf8();
+  }
+  out.push_str("\r\n");
+  out
+}
+
+/// Renders `interview` as an RFC 5545 calendar with a single event.
+pub fn render(interview: &Interview) -> Result<String, String> {
+  let start = DateTime::parse_from_rfc3339(&interview.start)
+    .map_err(|e| format!("invalid start time {:?}: {}", interview.start, e))?;
+  let end = start
+    + TimeDelta::minutes(
+      interview
+        .duration_minutes
+        .unwrap_or(DEFAULT_DURATION_MINUTES)
+        .max(1),
+    );
+  let uid = match &interview.id {
+    Some(id) => format!("interview-{}@jobhunt", id),
+    None => format!("interview-{}@jobhunt", engine::generate_token()?),
+  };
+
+  let mut lines = vec![
+    "BEGIN:VCALENDAR".to_string(),
+    "VERSION:2.0".to_string(),
+    "PRODID:-//JobHunt//Interview//EN".to_string(),
+    "CALSCALE:GREGORIAN".to_string(),
+    "METHOD:PUBLISH".to_string(),
+    "BEGIN:VEVENT".to_string(),
+    format!("UID:{}", uid),
+    format!("DTSTAMP:{}", utc_stamp(Utc::now())),
+    format!("DTSTART:{}", utc_stamp(start)),
+    format!("DTEND:{}", utc_stamp(end)),
+    format!("SUMMARY:{}", escape(&interview.title)),
+  ];
+  if let Some(location) = interview.location.as_deref().filter(|s| !s.is_empty()) {
+    lines.push(format!("LOCATION:{}", escape(location)));
+  }
+  if let Some(url) = interview.url.as_deref().filter(|s| !s.is_empty()) {
+    lines.push(format!("URL:{}", url));
+  }
+  let mut description = interview.description.clone().unwrap_or_default();
+  if let Some(url) = interview.url.as_deref().filter(|s| !s.is_empty()) {
+    // Many calendar apps ignore URL; repeat it where it will be seen.
+    if !description.is_empty() {
+      description.push_str("\n\n");
+    }
+    description.push_str(url);
+  }
+  if !description.is_empty() {
+    lines.push(format!("DESCRIPTION:{}", escape(&description)));
+  }
+
+  let reminder = interview.reminder_minutes.unwrap_or(DEFAULT_REMINDER_MINUTES);
+  if reminder > 0 {
+    lines.extend([
+      "BEGIN:VALARM".to_string(),
+      "ACTION:DISPLAY".to_string(),
+      format!("DESCRIPTION:{}", escape(&interview.title)),
+      format!("TRIGGER:-PT{}M", reminder),
+      "END:VALARM".to_string(),
+    ]);
+  }
+  lines.extend(["END:VEVENT".to_string(), "END:VCALENDAR".to_string()]);
+
+  Ok(lines.iter().map(|l| fold(l)).collect())
+}
+
+/// Writes the interview's `.ics` and either opens it in the default calendar
+/// app (`save == false`) or saves it where the user picks.
+pub fn export(
+  app: &AppHandle,
+  interview: &Interview,
+  save: bool,
+) -> Result<Option<PathBuf>, String> {
+  let ics = render(interview)?;
+  let file_name = format!(
+    "interview-{}.ics",
+    interview
+      .title
+      .chars()
+      .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+      .collect::<String>()
+  );
+
+  if save {
+    let Some(path) = app
+      .dialog()
+      .file()
+      .set_title(i18n::t("dialog.ics"))
+      .set_file_name(&file_name)
+      .add_filter(i18n::t("filter.calendar"), &["ics"])
+      .blocking_save_file()
+    else {
+      return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    std::fs::write(&path, ics).map_err(|e| e.to_string())?;
+    return Ok(Some(path));
+  }
+
+  let path = std::env::temp_dir().join(file_name);
+  std::fs::write(&path, ics).map_err(|e| e.to_string())?;
+  app
+    .opener()
+    .open_path(path.to_string_lossy(), None::<&str>)
+    .map_err(|e| e.to_string())?;
+  Ok(Some(path))
+}
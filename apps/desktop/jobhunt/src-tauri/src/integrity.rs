@@ -0,0 +1,60 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::startup::StartupError;
+
+// Computed by build.rs from bin/engine-<target>; empty when a debug build had no engine binary.
+const EXPECTED_SHA256: &str = env!("ENGINE_SHA256");
+
+/// Where Tauri places the sidecar: next to our own executable, triple stripped.
+pub fn engine_path() -> Result<PathBuf, String> {
+  let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+  let dir = exe.parent().ok_or("executable has no parent directory")?;
+  Ok(dir.join(format!("engine{}", std::env::consts::EXE_SUFFIX)))
+}
+
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+  let mut file = std::fs::File::open(path)?;
+  let mut hasher = Sha256::new();
+  let mut buf = [0u8; 64 * 1024];
+  loop {
+    let n = file.read(&mut buf)?;
+    if n == 0 {
+      break;
+    }
+    hasher.update(&buf[..n]);
+  }
+  Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Refuses to run an engine binary whose hash doesn't match the one built into the app.
+pub fn verify_engine() -> Result<(), StartupError> {
+  if EXPECTED_SHA256.is_empty() {
+    if cfg!(debug_assertions) {
+      eprintln!("[integrity] no embedded engine digest; skipping check in this debug build");
+      return Ok(());
+    }
+    return Err(StartupError::Damaged(
+      "This build of JobHunt has no engine digest to check the engine binary against; \
+       please reinstall JobHunt."
+        .into(),
+    ));
+  }
+
+  let path = engine_path()?;
+  let actual = sha256_file(&path).map_err(|e| {
+    StartupError::from_io(format!("cannot read engine binary {}: {}", path.display(), e), &e)
+  })?;
+  if actual != EXPECTED_SHA256 {
+    return Err(StartupError::Damaged(format!(
+      "The engine binary at {} does not match this build of JobHunt (expected SHA-256 {}, found {}). \
+       It may have been modified or corrupted; please reinstall JobHunt.",
+      path.display(),
+      EXPECTED_SHA256,
+      actual
+    )));
+  }
+  Ok(())
+}
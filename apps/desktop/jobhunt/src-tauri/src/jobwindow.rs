@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{
+  AppHandle, Emitter, EventTarget, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent,
+};
+
+use crate::i18n;
+
+const LABEL_PREFIX: &str = "job-";
+// Cascade new windows so a second one doesn't land exactly on the first.
+const CASCADE_STEP: f64 = 28.0;
+const CASCADE_MAX: usize = 8;
+
+/// Open job windows by label, so engine events about a job reach its window.
+#[derive(Default)]
+pub struct JobWindowState(Mutex<HashMap<String, i64>>);
+
+#[derive(Clone, Serialize)]
+pub struct JobWindow {
+  pub label: String,
+  pub job_id: i64,
+}
+
+fn label(job_id: i64) -> String {
+  format!("{}{}", LABEL_PREFIX, job_id)
+}
+
+/// Opens a window showing one job, or focuses it if it's already open.
+pub fn open(app: &AppHandle, job_id: i64) -> Result<String, String> {
+  if job_id <= 0 {
+    return Err(format!("invalid job id {}", job_id));
+  }
+  let label = label(job_id);
+  if let Some(window) = app.get_webview_window(&label) {
+    let _ = window.unminimize();
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+    return Ok(label);
+  }
+
+  let open_count = app.state::<JobWindowState>().0.lock().unwrap().len();
+  let offset = (open_count % CASCADE_MAX) as f64 * CASCADE_STEP;
+  let window = WebviewWindowBuilder::new(
+    app,
+    &label,
+    WebviewUrl::App(format!("job.html?id={}", job_id).into()),
+  )
+  .title(i18n::t_with("window.job", &[("id", &job_id.to_string())]))
+  .inner_size(640.0, 720.0)
+  .min_inner_size(420.0, 360.0)
+  .position(120.0 + offset, 80.0 + offset)
+  .focused(true)
+  .build()
+  .map_err(|e| e.to_string())?;
+
+  app
+    .state::<JobWindowState>()
+    .0
+    .lock()
+    .unwrap()
+    .insert(label.clone(), job_id);
+  let handle = app.clone();
+  let closed = label.clone();
+  window.on_window_event(move |event| {
+    if let WindowEvent::Destroyed = event {
+      handle.state::<JobWindowState>().0.lock().unwrap().remove(&closed);
+    }
+  });
+  println!("[jobwindow] opened {}", label);
+  Ok(label)
+}
+
+pub fn list(app: &AppHandle) -> Vec<JobWindow> {
+  let mut windows: Vec<JobWindow> = app
+    .state::<JobWindowState>()
+    .0
+    .lock()
+    .unwrap()
+    .iter()
+    .map(|(label, job_id)| JobWindow {
+      label: label.clone(),
+      job_id: *job_id,
+    })
+    .collect();
+  windows.sort_by_key(|w| w.job_id);
+  windows
+}
+
+/// Emits an engine event. Events about a specific job go to the main windows
+/// and that job's window only; everything else goes to every window.
+pub fn route(app: &AppHandle, name: &str, event: &serde_json::Value) {
+  let Some(job_id) = event.get("id").and_then(|v| v.as_i64()) else {
+    let _ = app.emit(name, event);
+    return;
+  };
+  let own = label(job_id);
+  let _ = app.emit_filter(name, event, |target| match target {
+    EventTarget::WebviewWindow { label }
+    | EventTarget::Webview { label }
+    | EventTarget::Window { label } => !label.starts_with(LABEL_PREFIX) || *label == own,
+    _ => true,
+  });
+}
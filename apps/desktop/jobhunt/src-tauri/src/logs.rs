@@ -0,0 +1,289 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::engine::EngineState;
+use crate::{crash, proxy};
+
+const LOG_FILE: &str = "engine.log";
+// Rotate once the live file passes this size, keeping MAX_ROTATED older files.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED: u32 = 5;
+// Records kept in memory for the debug console and query_logs.
+const RECENT_CAPACITY: usize = 5000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+  Debug,
+  Info,
+  Warn,
+  Error,
+}
+
+impl LogLevel {
+  pub fn parse(s: &str) -> Result<Self, String> {
+    match s.to_lowercase().as_str() {
+      "debug" => Ok(LogLevel::Debug),
+      "info" => Ok(LogLevel::Info),
+      "warn" | "warning" => Ok(LogLevel::Warn),
+      "error" => Ok(LogLevel::Error),
+      other => Err(format!("unknown log level {:?}; use debug, info, warn or error", other)),
+    }
+  }
+}
+
+/// One line of engine output. The engine writes JSON records
+/// (`{level, ts, target, msg, fields}`); anything else, such as a Go panic,
+/// is kept as an unstructured record with `msg` set to the raw line.
+#[derive(Clone, Serialize)]
+pub struct LogLine {
+  pub stream: String,
+  pub level: LogLevel,
+  pub ts: String,
+  pub target: String,
+  pub msg: String,
+  pub fields: serde_json::Map<String, serde_json::Value>,
+  /// The line exactly as the engine wrote it.
+  pub line: String,
+  #[serde(skip)]
+  at: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Deserialize)]
+struct EngineRecord {
+  level: LogLevel,
+  ts: String,
+  #[serde(default)]
+  target: String,
+  msg: String,
+  #[serde(default)]
+  fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl LogLine {
+  fn parse(stream: &str, line: &str) -> Self {
+    if let Ok(r) = serde_json::from_str::<EngineRecord>(line) {
+      let at = DateTime::parse_from_rfc3339(&r.ts).ok();
+      return LogLine {
+        stream: stream.to_string(),
+        level: r.level,
+        ts: r.ts,
+        target: r.target,
+        msg: r.msg,
+        fields: r.fields,
+        line: line.to_string(),
+        at,
+      };
+    }
+    let now = Utc::now().fixed_offset();
+    LogLine {
+      stream: stream.to_string(),
+      // Unstructured stderr is almost always a panic or runtime fault.
+      level: if stream == "stderr" { LogLevel::Error } else { LogLevel::Info },
+      ts: now.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+      target: "engine".into(),
+      msg: line.to_string(),
+      fields: serde_json::Map::new(),
+      line: line.to_string(),
+      at: Some(now),
+    }
+  }
+}
+
+#[derive(Default)]
+struct LogFile {
+  dir: Option<PathBuf>,
+  file: Option<File>,
+  size: u64,
+}
+
+/// Engine output mirrored to `<data dir>/logs/engine.log` so release builds,
+/// which have no console, still leave something to look at.
+#[derive(Default)]
+pub struct LogState(Mutex<LogFile>);
+
+fn rotated_path(dir: &Path, n: u32) -> PathBuf {
+  dir.join(format!("engine.{}.log", n))
+}
+
+fn open_log(dir: &Path) -> std::io::Result<(File, u64)> {
+  std::fs::create_dir_all(dir)?;
+  let file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(dir.join(LOG_FILE))?;
+  let size = file.metadata()?.len();
+  Ok((file, size))
+}
+
+/// engine.log -> engine.1.log -> ... -> engine.N.log, dropping the oldest.
+fn rotate(dir: &Path) -> std::io::Result<()> {
+  let _ = std::fs::remove_file(rotated_path(dir, MAX_ROTATED));
+  for n in (1..MAX_ROTATED).rev() {
+    let from = rotated_path(dir, n);
+    if from.exists() {
+      std::fs::rename(&from, rotated_path(dir, n + 1))?;
+    }
+  }
+  std::fs::rename(dir.join(LOG_FILE), rotated_path(dir, 1))
+}
+
+/// Points the log at `dir`, e.g. after the data dir has moved.
+pub fn set_dir(app: &AppHandle, dir: PathBuf) {
+  let state = app.state::<LogState>();
+  let mut log = state.0.lock().unwrap();
+  if log.dir.as_deref() == Some(dir.as_path()) {
+    return;
+  }
+  *log = LogFile {
+    dir: Some(dir),
+    ..LogFile::default()
+  };
+}
+
+/// Appends a chunk of engine output, rotating first if the file is full.
+fn write(app: &AppHandle, stream: &str, text: &str) {
+  let state = app.state::<LogState>();
+  let mut guard = state.0.lock().unwrap();
+  let log = &mut *guard;
+  let Some(dir) = log.dir.clone() else {
+    return;
+  };
+
+  if log.file.is_some() && log.size >= MAX_LOG_BYTES {
+    log.file = None;
+    if let Err(e) = rotate(&dir) {
+      eprintln!("[logs] rotate failed: {}", e);
+    }
+  }
+  if log.file.is_none() {
+    match open_log(&dir) {
+      Ok((file, size)) => {
+        log.file = Some(file);
+        log.size = size;
+      }
+      Err(e) => {
+        eprintln!("[logs] open failed: {}", e);
+        return;
+      }
+    }
+  }
+
+  let line = format!("[{}] {}", stream, text);
+  if let Some(file) = log.file.as_mut() {
+    if file.write_all(line.as_bytes()).is_ok() {
+      log.size += line.len() as u64;
+    }
+  }
+}
+
+pub fn log_path(app: &AppHandle) -> Option<PathBuf> {
+  let state = app.state::<LogState>();
+  let log = state.0.lock().unwrap();
+  log.dir.as_ref().map(|d| d.join(LOG_FILE))
+}
+
+/// The engine log files that exist on disk, newest first.
+pub fn log_files(app: &AppHandle) -> Vec<PathBuf> {
+  let Some(dir) = app.state::<LogState>().0.lock().unwrap().dir.clone() else {
+    return Vec::new();
+  };
+  std::iter::once(dir.join(LOG_FILE))
+    .chain((1..=MAX_ROTATED).map(|n| rotated_path(&dir, n)))
+    .filter(|p| p.is_file())
+    .collect()
+}
+
+/// Records a chunk of engine output: appends it to the log file, keeps its
+/// lines as parsed records in the recent buffer, and emits each one as `engine://log`.
+pub fn engine_output(app: &AppHandle, stream: &str, text: &str) {
+  write(app, stream, text);
+
+  let state = app.state::<EngineState>();
+  for line in text.lines().filter(|l| !l.trim().is_empty()) {
+    let entry = LogLine::parse(stream, line);
+    {
+      let mut recent = state.recent_logs.lock().unwrap();
+      if recent.len() == RECENT_CAPACITY {
+        recent.pop_front();
+      }
+      recent.push_back(entry.clone());
+    }
+    if stream == "stderr" {
+      crash::record_stderr(app, line);
+    }
+    let _ = app.emit("engine://log", entry);
+  }
+}
+
+/// The last `n` engine output lines, oldest first.
+pub fn recent(app: &AppHandle, n: usize) -> Vec<LogLine> {
+  let state = app.state::<EngineState>();
+  let recent = state.recent_logs.lock().unwrap();
+  let skip = recent.len().saturating_sub(n);
+  recent.iter().skip(skip).cloned().collect()
+}
+
+fn matches(line: &LogLine, needle: &str) -> bool {
+  line.target.to_lowercase().contains(needle)
+    || line.msg.to_lowercase().contains(needle)
+    || line.fields.values().any(|v| match v {
+      serde_json::Value::String(s) => s.to_lowercase().contains(needle),
+      other => other.to_string().contains(needle),
+    })
+}
+
+/// The buffered engine records at `level` or above, at or after `since`
+/// (RFC 3339), and containing `contains` (case-insensitive) in the target,
+/// message or a field value. Oldest first.
+pub fn query(
+  app: &AppHandle,
+  level: Option<&str>,
+  since: Option<&str>,
+  contains: Option<&str>,
+) -> Result<Vec<LogLine>, String> {
+  let level = level.map(LogLevel::parse).transpose()?;
+  let since = match since {
+    Some(s) => Some(
+      DateTime::parse_from_rfc3339(s).map_err(|e| format!("invalid since {:?}: {}", s, e))?,
+    ),
+    None => None,
+  };
+  let needle = contains.map(str::to_lowercase);
+
+  let state = app.state::<EngineState>();
+  let recent = state.recent_logs.lock().unwrap();
+  Ok(
+    recent
+      .iter()
+      .filter(|l| level.is_none_or(|min| l.level >= min))
+      .filter(|l| since.is_none_or(|t| l.at.is_some_and(|at| at >= t)))
+      .filter(|l| needle.as_deref().is_none_or(|n| matches(l, n)))
+      .cloned()
+      .collect(),
+  )
+}
+
+/// Asks the engine to only log at `level` ("debug", "info", "warn", "error") and above.
+pub async fn set_engine_level(app: &AppHandle, level: &str) -> Result<(), String> {
+  let level = LogLevel::parse(level)?;
+  if app.state::<EngineState>().info.lock().unwrap().endpoint.is_none() {
+    return Err("engine is not running".into());
+  }
+
+  let req = proxy::engine_request(app, reqwest::Method::POST, "/log/level")
+    .json(&serde_json::json!({ "level": level }));
+  let resp = proxy::send(app, req).await.map_err(|e| e.to_string())?;
+
+  if resp.status().is_success() {
+    Ok(())
+  } else {
+    Err(format!("log level returned HTTP {}", resp.status()))
+  }
+}
@@ -1,135 +1,360 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::sync::Mutex;
+mod appmenu;
+mod appupdate;
+mod archiveimport;
+mod asset;
+mod attachments;
+mod autostart;
+mod backup;
+mod badge;
+mod cli;
+mod clipboard;
+mod compat;
+mod connectivity;
+mod crash;
+mod datadir;
+mod deeplink;
+mod diagnostics;
+mod dropfolder;
+mod dropimport;
+mod emaildraft;
+mod engine;
+mod engineupdate;
+mod events;
+mod export;
+mod extract;
+mod fileopen;
+mod headless;
+mod i18n;
+mod ics;
+mod integrity;
+mod jobwindow;
+mod logs;
+mod maintenance;
+mod monitor;
+mod nativehost;
+mod netaudit;
+mod netproxy;
+mod notifications;
+mod pidfile;
+mod power;
+mod previews;
+mod printing;
+mod procguard;
+mod profiles;
+mod proxy;
+mod quickcapture;
+mod sandbox;
+mod scheduler;
+mod screenshot;
+mod secrets;
+mod settings;
+mod sidecar;
+mod splash;
+mod startup;
+mod supervisor;
+mod support;
+mod telemetry;
+mod transport;
+mod tray;
+mod vault;
+mod windowstate;
+mod writeguard;
 
-use tauri::{Manager, WindowEvent};
-use tauri_plugin_shell::process::{CommandChild, CommandEvent};
-use tauri_plugin_shell::ShellExt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tauri::{DragDropEvent, Emitter, Manager, RunEvent, WindowEvent};
 use tauri_plugin_dialog::DialogExt;
 
-#[derive(Default)]
-struct EngineInfo {
-  port: Option<u16>,
-  shutdown_token: Option<String>,
-}
+use archiveimport::ImportReport;
+use attachments::Attachment;
+use badge::BadgeState;
+use connectivity::{ConnectivityState, ConnectivityStatus};
+use deeplink::{DeepLinkRoute, DeepLinkState};
+use diagnostics::DiagnosticsReport;
+use engine::{EngineState, EngineStatus};
+use jobwindow::{JobWindow, JobWindowState};
+use logs::{LogLine, LogState};
+use maintenance::{MaintenanceState, TaskProgress};
+use monitor::{EngineMetrics, MonitorState};
+use netaudit::NetworkAudit;
+use netproxy::ProxyConfig;
+use notifications::NotificationState;
+use power::PowerState;
+use profiles::{LaunchProfiles, ProfileInfo, ProfileState};
+use proxy::{FetchResponse, PoolStats, ProxyState};
+use scheduler::{Schedule, SchedulerState};
+use screenshot::CaptureMode;
+use settings::{DropFolder, Settings, SettingsState};
+use sidecar::SidecarManager;
+use startup::StartupError;
+use supervisor::SupervisorState;
+use telemetry::{TelemetryBatch, TelemetryState};
+use vault::{VaultState, VaultStatus};
+use windowstate::WindowState;
+use writeguard::WriteGuardState;
 
-struct EngineState {
-  child: Mutex<Option<CommandChild>>,
-  info: Mutex<EngineInfo>,
-  allow_close: Mutex<bool>,
-}
-
-fn parse_port_from_line(line: &str) -> Option<u16> {
-  let needle = "http://127.0.0.1:";
-  let idx = line.find(needle)? + needle.len();
-  let rest = &line[idx..];
-  let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
-  if digits.is_empty() {
-    return None;
-  }
-  digits.parse::<u16>().ok()
-}
+// Default for commands that wait on engine readiness when the caller doesn't pass one.
+const DEFAULT_READY_TIMEOUT_MS: u64 = 15_000;
 
-fn parse_shutdown_token_from_line(line: &str) -> Option<String> {
-  let needle = "shutdown_token=";
-  let idx = line.find(needle)? + needle.len();
-  Some(line[idx..].trim().to_string())
+#[derive(Clone, serde::Serialize)]
+struct SecondInstance {
+  args: Vec<String>,
+  cwd: String,
 }
 
-async fn request_engine_shutdown(port: u16, token: &str) -> Result<(), String> {
-  let url = format!("http://127.0.0.1:{}/shutdown", port);
+fn main() {
+  let context = tauri::generate_context!();
+  // Started by a browser for the extension: speak native messaging and exit.
+  if nativehost::launched_as_host() {
+    nativehost::run(&context.config().identifier);
+    return;
+  }
 
-  let client = reqwest::Client::new();
-  let resp = client
-    .post(url)
-    .header("X-Shutdown-Token", token)
-    .send()
-    .await
-    .map_err(|e| e.to_string())?;
+  let cli = match cli::init() {
+    Ok(cli) => cli,
+    Err(e) => {
+      eprintln!("jobhunt: {}\n\n{}", e, cli::USAGE);
+      std::process::exit(2);
+    }
+  };
+  if cli.help {
+    println!("{}", cli::USAGE);
+    return;
+  }
 
-  if resp.status().is_success() {
-    Ok(())
-  } else {
-    Err(format!("shutdown returned HTTP {}", resp.status()))
+  // Headless runs supervise the engine without starting Tauri at all.
+  if cli.headless {
+    std::process::exit(headless::run(cli, &context.config().identifier));
   }
-}
 
-fn main() {
   tauri::Builder::default()
+    // Must be registered first: a second launch exits here, before it can spawn another engine.
+    .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+      // Extension captures are handled in the background.
+      if !nativehost::is_capture_launch(&args) {
+        if let Some(w) = app.get_webview_window("main") {
+          let _ = w.show();
+          let _ = w.unminimize();
+          let _ = w.set_focus();
+        }
+      }
+      if let Some(name) = cli::parse(&args).ok().and_then(|a| a.profile) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+          if let Err(e) = profiles::switch_for_launch(&app, &name).await {
+            eprintln!("[profiles] {}", e);
+          }
+        });
+      }
+      fileopen::open(app, fileopen::paths_from_args(&args, std::path::Path::new(&cwd)));
+      let _ = app.emit("second-instance", SecondInstance { args, cwd });
+    }))
+    .plugin(tauri_plugin_deep_link::init())
     .plugin(tauri_plugin_updater::Builder::new().build())
     .plugin(tauri_plugin_shell::init())
     .plugin(tauri_plugin_dialog::init())
-    .invoke_handler(tauri::generate_handler![export_db])
-    .manage(EngineState {
-      child: Mutex::new(None),
-      info: Mutex::new(EngineInfo::default()),
-      allow_close: Mutex::new(false),
-    })
+    .plugin(tauri_plugin_opener::init())
+    .plugin(tauri_plugin_notification::init())
+    .plugin(autostart::plugin())
+    .plugin(tauri_plugin_clipboard_manager::init())
+    .plugin(quickcapture::plugin())
+    .register_asynchronous_uri_scheme_protocol(asset::SCHEME, asset::handle)
+    .invoke_handler(tauri::generate_handler![
+      export_db,
+      restart_engine,
+      wait_for_engine_ready,
+      restart_engine_and_wait,
+      update_engine,
+      capture_engine_dump,
+      engine_metrics,
+      get_settings,
+      update_settings,
+      get_locale,
+      set_locale,
+      move_data_dir,
+      get_data_dir,
+      set_data_dir,
+      create_backup,
+      restore_backup,
+      create_support_bundle,
+      list_drop_folders,
+      add_drop_folder,
+      set_drop_folder_enabled,
+      remove_drop_folder,
+      secret_set,
+      secret_get,
+      secret_delete,
+      set_autostart,
+      get_autostart,
+      list_profiles,
+      create_profile,
+      delete_profile,
+      switch_profile,
+      get_launch_profiles,
+      select_launch_profile,
+      add_attachments,
+      list_attachments,
+      delete_attachment,
+      reveal_attachment,
+      open_attachment_preview,
+      capture_screenshot,
+      draft_email,
+      extract_text,
+      export_applications,
+      export_interview_ics,
+      open_job_window,
+      list_job_windows,
+      print_application,
+      print_window,
+      open_quick_capture,
+      get_quick_capture_prefill,
+      submit_quick_capture,
+      set_quick_capture_shortcut,
+      install_native_host,
+      uninstall_native_host,
+      engine_status,
+      connectivity_status,
+      get_proxy_status,
+      set_proxy_password,
+      network_audit,
+      vault_status,
+      unlock_vault,
+      lock_vault,
+      set_vault_passphrase,
+      get_log_path,
+      get_recent_logs,
+      query_logs,
+      update_badge,
+      set_telemetry,
+      export_telemetry,
+      list_schedules,
+      add_schedule,
+      remove_schedule,
+      set_log_level,
+      take_pending_deep_links,
+      engine_fetch,
+      cancel_request,
+      get_request_pool_stats,
+      install_app_update,
+      get_activity,
+      run_maintenance,
+      run_diagnostics,
+      import_archive
+    ])
+    .manage(EngineState::default())
+    .manage(SupervisorState::default())
+    .manage(SidecarManager::default())
+    .manage(WriteGuardState::default())
+    .manage(LogState::default())
+    .manage(TelemetryState::default())
+    .manage(SchedulerState::default())
+    .manage(DeepLinkState::default())
+    .manage(NotificationState::default())
+    .manage(BadgeState::default())
+    .manage(MonitorState::default())
+    .manage(ProfileState::default())
+    .manage(PowerState::default())
+    .manage(MaintenanceState::default())
+    .manage(ConnectivityState::default())
+    .manage(VaultState::default())
+    .manage(WindowState::default())
+    .manage(JobWindowState::default())
+    .manage(ProxyState::default())
     .setup(|app| {
-      let data_dir = app.path().app_data_dir().expect("app_data_dir");
-      std::fs::create_dir_all(&data_dir).expect("create data dir");
-
-      let mut cmd = app
-        .shell()
-        .sidecar("engine")
-        .expect("failed to create sidecar");
-
-      cmd = cmd
-        .current_dir(&data_dir)
-        .env("JOBHUNT_DATA_DIR", data_dir.to_string_lossy().to_string());
-
-      let (mut rx, child) = cmd.spawn().expect("failed to spawn engine");
-
-      // store child
-      app.state::<EngineState>()
-        .child
-        .lock()
-        .unwrap()
-        .replace(child);
-
-      let app_handle = app.handle().clone();
-      tauri::async_runtime::spawn(async move {
-        while let Some(event) = rx.recv().await {
-          match event {
-            CommandEvent::Stdout(bytes) => {
-              let s = String::from_utf8_lossy(&bytes).to_string();
-              print!("[engine stdout] {}", s);
-
-              let state = app_handle.state::<EngineState>();
-              if let Some(port) = parse_port_from_line(&s) {
-                state.info.lock().unwrap().port = Some(port);
-              }
-              if let Some(tok) = parse_shutdown_token_from_line(&s) {
-                state.info.lock().unwrap().shutdown_token = Some(tok);
-              }
-            }
-            CommandEvent::Stderr(bytes) => {
-              let s = String::from_utf8_lossy(&bytes).to_string();
-              eprint!("[engine stderr] {}", s);
-
-              let state = app_handle.state::<EngineState>();
-              if let Some(port) = parse_port_from_line(&s) {
-                state.info.lock().unwrap().port = Some(port);
-              }
-              if let Some(tok) = parse_shutdown_token_from_line(&s) {
-                state.info.lock().unwrap().shutdown_token = Some(tok);
-              }
-            }
-            other => {
-              println!("[engine] {:?}", other);
-            }
-          }
-        }
-      });
+      app.manage(SettingsState(std::sync::Mutex::new(settings::load(app.handle()))));
+      i18n::init(app.handle());
+      sidecar::register(app.handle(), engine::EngineSidecar::default());
 
-      println!("[engine] started");
+      let args: Vec<String> = std::env::args().collect();
+      let hidden = autostart::launched_hidden() || nativehost::is_capture_launch(&args);
+      let attach_url = engine::attach_url_from_env();
+      let picking = attach_url.is_none() && profiles::resolve_on_launch(app.handle(), !hidden);
+      let locked = attach_url.is_none() && !picking && vault::is_locked(app.handle());
+      if !picking {
+        windowstate::restore(app.handle());
+      }
+      let started = match attach_url {
+        Some(url) => engine::attach_engine(app.handle(), &url).map_err(StartupError::from),
+        // The splash starts the engine once a profile is picked.
+        None if picking => Ok(()),
+        // The passphrase prompt starts the engine once the vault is open.
+        None if locked => Ok(()),
+        // Setup runs on the main thread, outside the async runtime, so it can block here.
+        None => tauri::async_runtime::block_on(engine::spawn_engine(app.handle())),
+      };
+      let mut failed: Option<StartupError> = None;
+      if let Err(e) = started {
+        eprintln!("[engine] failed to start: {}", e);
+        engine::record_error(app.handle(), e.to_string());
+        failed = startup::can_recover(app.handle()).then_some(e);
+      }
+      monitor::start(app.handle().clone());
+      backup::start(app.handle().clone());
+      previews::start(app.handle().clone());
+      maintenance::start(app.handle().clone());
+      engineupdate::start(app.handle().clone());
+      power::start(app.handle().clone());
+      connectivity::start(app.handle().clone());
+      events::start(app.handle().clone());
+      telemetry::start(app.handle().clone());
+      scheduler::start(app.handle().clone());
+      if locked {
+        vault::unlock_on_launch(app.handle().clone(), !hidden);
+      }
+      clipboard::start(app.handle().clone());
+      dropfolder::start(app.handle().clone());
+      tray::init(app.handle())?;
+      appmenu::init(app.handle())?;
+      deeplink::init(app.handle());
+      quickcapture::init(app.handle());
+      if hidden {
+        autostart::start_hidden(app.handle());
+        if let Some(err) = failed {
+          let app = app.handle().clone();
+          tauri::async_runtime::spawn(async move { startup::recover(&app, err).await });
+        }
+      } else if !picking && !locked {
+        splash::start(app.handle().clone(), failed);
+      }
+      let cwd = std::env::current_dir().unwrap_or_default();
+      fileopen::open(app.handle(), fileopen::paths_from_args(&args, &cwd));
       Ok(())
     })
     .on_window_event(|window, event| {
+      if let WindowEvent::Moved(_) | WindowEvent::Resized(_) = event {
+        windowstate::track(window);
+      }
+      if let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event {
+        if window.label() == "main" {
+          dropimport::handle_drop(window.app_handle(), paths.clone());
+        }
+      }
+      if let WindowEvent::Focused(true) = event {
+        if window.label() == "main" {
+          notifications::on_focus(window.app_handle());
+          badge::on_focus(window.app_handle());
+        }
+      }
       if let WindowEvent::CloseRequested { api, .. } = event {
         let app_handle = window.app_handle().clone();
         let label = window.label().to_string();
 
+        // Closing the splash (e.g. after a startup error) means quit; the main window is still hidden.
+        if label == splash::SPLASH_WINDOW {
+          api.prevent_close();
+          tauri::async_runtime::spawn(async move { tray::quit(&app_handle).await });
+          return;
+        }
+
+        // Only the main window owns the engine; other windows just close.
+        if label != "main" {
+          return;
+        }
+        windowstate::save(&app_handle);
+
         {
           let state = app_handle.state::<EngineState>();
           let already_allowed = *state.allow_close.lock().unwrap();
@@ -139,34 +364,18 @@ fn main() {
           api.prevent_close();
         }
 
-        // Pull state data out synchronously
-        let state = app_handle.state::<EngineState>();
-        let child_opt = state.child.lock().unwrap().take();
-
-        let (port, token) = {
-          let info = state.info.lock().unwrap();
-          (info.port, info.shutdown_token.clone())
-        };
+        // Background mode: only an explicit Quit shuts the engine down.
+        if settings::current(&app_handle).keep_running_in_background {
+          let _ = window.hide();
+          return;
+        }
 
         tauri::async_runtime::spawn(async move {
-          if let Some(child) = child_opt {
-            let mut graceful_ok = false;
-
-            if let (Some(p), Some(t)) = (port, token.as_deref()) {
-              match request_engine_shutdown(p, t).await {
-                Ok(_) => {
-                  println!("[engine] shutdown requested");
-                  graceful_ok = true;
-                }
-                Err(e) => eprintln!("[engine] shutdown request failed: {}", e),
-              }
-            }
-
-            if !graceful_ok {
-              let _ = child.kill();
-              println!("[engine] killed");
-            }
+          // Don't cut off an import or scrape that's still committing.
+          if !writeguard::confirm_quit(&app_handle).await {
+            return;
           }
+          sidecar::stop_all(&app_handle).await;
 
           // Allow the next CloseRequested to proceed.
           {
@@ -182,8 +391,34 @@ fn main() {
       }
     })
 
-    .run(tauri::generate_context!())
-    .expect("error while running tauri app");
+    .build(context)
+    .expect("error while building tauri app")
+    .run(|app_handle, event| {
+      #[cfg(target_os = "macos")]
+      if let RunEvent::Opened { urls } = &event {
+        fileopen::open_urls(app_handle, urls);
+      }
+      if let RunEvent::Exit = &event {
+        previews::clear(app_handle);
+      }
+      // Exits that bypass the window close path (e.g. Cmd+Q) still wait for the sidecars.
+      if let RunEvent::ExitRequested { api, code, .. } = event {
+        windowstate::save(app_handle);
+        if sidecar::any_running(app_handle) {
+          api.prevent_exit();
+          let app_handle = app_handle.clone();
+          tauri::async_runtime::spawn(async move {
+            if !writeguard::confirm_quit(&app_handle).await {
+              return;
+            }
+            // Also keeps the supervisor from respawning what we're stopping.
+            *app_handle.state::<EngineState>().allow_close.lock().unwrap() = true;
+            sidecar::stop_all(&app_handle).await;
+            app_handle.exit(code.unwrap_or(0));
+          });
+        }
+      }
+    });
 }
 
 #[tauri::command]
@@ -192,7 +427,7 @@ async fn export_db(app: tauri::AppHandle) -> Result<String, String> {
   let file_path = app
     .dialog()
     .file()
-    .set_title("Export JobHunt database")
+    .set_title(i18n::t("dialog.export_db"))
     .set_file_name("jobhunt.db")
     .blocking_save_file()
     .ok_or("Export cancelled")?;
@@ -200,14 +435,14 @@ async fn export_db(app: tauri::AppHandle) -> Result<String, String> {
   // Convert FilePath -> PathBuf (handles file:// URIs too)
   let dest = file_path.into_path().map_err(|e| e.to_string())?;
 
-  let port = app.state::<EngineState>().info.lock().unwrap().port;
-  if let Some(p) = port {
-    let url = format!("http://127.0.0.1:{}/db/checkpoint", p);
-    let _ = reqwest::Client::new().post(url).send().await;
+  let endpoint = app.state::<EngineState>().info.lock().unwrap().endpoint.clone();
+  if let Some(endpoint) = endpoint {
+    let url = endpoint.url("/db/checkpoint");
+    let _ = proxy::send(&app, transport::client(&endpoint).post(url)).await;
   }
 
-  // Copy DB file from app data dir
-  let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+  // Copy DB file from the data dir
+  let data_dir = engine::data_dir(&app)?;
   let src = data_dir.join("jobhunt.db");
 
   if !src.exists() {
@@ -219,3 +454,590 @@ async fn export_db(app: tauri::AppHandle) -> Result<String, String> {
   Ok(dest.to_string_lossy().to_string())
 }
 
+
+#[tauri::command]
+async fn restart_engine(app: tauri::AppHandle) -> Result<(), String> {
+  Ok(engine::restart_engine(&app).await?)
+}
+
+/// Resolves with the engine's port once it is serving, or `null` when it
+/// serves on a socket and must be reached through `engine_fetch`.
+#[tauri::command]
+async fn wait_for_engine_ready(
+  app: tauri::AppHandle,
+  timeout_ms: Option<u64>,
+) -> Result<Option<u16>, String> {
+  let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_READY_TIMEOUT_MS));
+  engine::wait_for_ready(&app, timeout).await.map(|e| e.port())
+}
+
+/// Restarts the engine and resolves only once the new instance is serving,
+/// so the UI can resume requests without racing the respawn.
+#[tauri::command]
+async fn restart_engine_and_wait(
+  app: tauri::AppHandle,
+  timeout_ms: Option<u64>,
+) -> Result<Option<u16>, String> {
+  let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_READY_TIMEOUT_MS));
+
+  engine::restart_engine(&app).await?;
+  engine::wait_for_ready(&app, timeout).await.map(|e| e.port())
+}
+
+/// Installs a newer engine build if one is published, restarting onto it.
+/// Resolves with the new version, or `null` when already up to date.
+#[tauri::command]
+async fn update_engine(app: tauri::AppHandle) -> Result<Option<String>, String> {
+  engineupdate::check_and_apply(&app).await
+}
+
+#[tauri::command]
+async fn capture_engine_dump(app: tauri::AppHandle) -> Result<String, String> {
+  let path = monitor::capture_engine_dump(&app).await?;
+  Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn get_settings(app: tauri::AppHandle) -> Settings {
+  settings::current(&app)
+}
+
+#[tauri::command]
+fn update_settings(app: tauri::AppHandle, settings: Settings) -> Result<(), String> {
+  settings::replace(&app, settings)?;
+  proxy::limit_changed(&app);
+  Ok(())
+}
+
+/// The language native dialogs, the tray, and notifications are shown in.
+#[tauri::command]
+fn get_locale() -> String {
+  i18n::locale().to_string()
+}
+
+/// Switches native UI text to `lang` (e.g. `fr-CA`), matching the webview.
+/// Resolves with the supported language actually used.
+#[tauri::command]
+fn set_locale(app: tauri::AppHandle, lang: String) -> Result<String, String> {
+  i18n::set_locale(&app, &lang)
+}
+
+/// Moves the engine's data to `new_path`, restarting the engine there and
+/// rolling back if anything fails before it comes up.
+#[tauri::command]
+async fn move_data_dir(app: tauri::AppHandle, new_path: PathBuf) -> Result<(), String> {
+  datadir::move_data_dir(&app, new_path).await
+}
+
+/// Reports whether the engine is running and where. Pass `ping` to also hit `/health`.
+#[tauri::command]
+async fn engine_status(app: tauri::AppHandle, ping: Option<bool>) -> Result<EngineStatus, String> {
+  Ok(engine::status(&app, ping.unwrap_or(false)).await)
+}
+
+/// The engine's listening sockets, and whether any is reachable off this machine.
+#[tauri::command]
+async fn network_audit(app: tauri::AppHandle) -> Result<NetworkAudit, String> {
+  netaudit::audit(&app).await
+}
+
+/// Whether this profile's data is encrypted, and whether it is unlocked.
+#[tauri::command]
+fn vault_status(app: tauri::AppHandle) -> VaultStatus {
+  vault::status(&app)
+}
+
+/// Unlocks the profile's encrypted data and starts the engine if it was waiting.
+#[tauri::command]
+async fn unlock_vault(app: tauri::AppHandle, passphrase: String) -> Result<(), String> {
+  vault::unlock(&app, passphrase).await
+}
+
+/// Stops the engine and forgets the key until the passphrase is entered again.
+#[tauri::command]
+async fn lock_vault(app: tauri::AppHandle) -> Result<(), String> {
+  vault::lock(&app).await
+}
+
+/// Sets, changes or (with no `new_passphrase`) removes the passphrase, re-encrypting data to match.
+#[tauri::command]
+async fn set_vault_passphrase(
+  app: tauri::AppHandle,
+  current_passphrase: Option<String>,
+  new_passphrase: Option<String>,
+) -> Result<(), String> {
+  vault::set_passphrase(&app, current_passphrase, new_passphrase).await
+}
+
+/// Last observed internet connectivity; `None` until the first check finishes.
+#[tauri::command]
+fn connectivity_status(app: tauri::AppHandle) -> Option<ConnectivityStatus> {
+  connectivity::current(&app)
+}
+
+/// The proxy outbound traffic uses and where it came from; `None` means direct.
+#[tauri::command]
+fn get_proxy_status(app: tauri::AppHandle) -> Option<ProxyConfig> {
+  netproxy::resolve(&app)
+}
+
+/// Stores the password for `proxy_username`, or clears it with `None`. The
+/// engine picks it up the next time it starts.
+#[tauri::command]
+fn set_proxy_password(password: Option<String>) -> Result<(), String> {
+  netproxy::set_password(password)
+}
+
+/// Path of the current engine log, for "Open logs folder".
+#[tauri::command]
+fn get_log_path(app: tauri::AppHandle) -> Result<String, String> {
+  let path = match logs::log_path(&app) {
+    Some(path) => path,
+    None => engine::data_dir(&app)?.join("logs").join("engine.log"),
+  };
+  Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn get_recent_logs(app: tauri::AppHandle, n: Option<usize>) -> Vec<LogLine> {
+  logs::recent(&app, n.unwrap_or(200))
+}
+
+/// Filters the buffered engine log: minimum `level`, records since an RFC 3339
+/// time, and text the record `contains`.
+#[tauri::command]
+fn query_logs(
+  app: tauri::AppHandle,
+  level: Option<String>,
+  since: Option<String>,
+  contains: Option<String>,
+) -> Result<Vec<LogLine>, String> {
+  logs::query(&app, level.as_deref(), since.as_deref(), contains.as_deref())
+}
+
+/// Sets the dock/taskbar badge to `count`; 0 clears it.
+#[tauri::command]
+fn update_badge(app: tauri::AppHandle, count: u32) -> Result<(), String> {
+  badge::update_badge(&app, count)
+}
+
+/// Opts in to (or out of) the daily anonymous telemetry upload.
+#[tauri::command]
+fn set_telemetry(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+  telemetry::set_enabled(&app, enabled)
+}
+
+/// Exactly what the next telemetry upload would send.
+#[tauri::command]
+fn export_telemetry(app: tauri::AppHandle) -> TelemetryBatch {
+  telemetry::export(&app)
+}
+
+#[tauri::command]
+fn list_schedules(app: tauri::AppHandle) -> Result<Vec<Schedule>, String> {
+  scheduler::list(&app)
+}
+
+/// Saves a recurring engine request (new, or replacing one with the same id).
+/// Returns it with its id and next run time filled in.
+#[tauri::command]
+fn add_schedule(app: tauri::AppHandle, schedule: Schedule) -> Result<Schedule, String> {
+  scheduler::add(&app, schedule)
+}
+
+#[tauri::command]
+fn remove_schedule(app: tauri::AppHandle, id: String) -> Result<(), String> {
+  scheduler::remove(&app, &id)
+}
+
+#[tauri::command]
+async fn set_log_level(app: tauri::AppHandle, level: String) -> Result<(), String> {
+  logs::set_engine_level(&app, &level).await
+}
+
+/// Links that arrived before the UI was listening (including the one that
+/// launched the app). Call once on startup, then listen for `deep-link`.
+#[tauri::command]
+fn take_pending_deep_links(app: tauri::AppHandle) -> Vec<DeepLinkRoute> {
+  deeplink::take_pending(&app)
+}
+
+/// Proxies a request to the engine, adding auth and retrying transient failures.
+/// Pass a unique `request_id` to be able to cancel it with `cancel_request`.
+#[tauri::command]
+async fn engine_fetch(
+  app: tauri::AppHandle,
+  method: String,
+  path: String,
+  body: Option<serde_json::Value>,
+  request_id: Option<String>,
+) -> Result<FetchResponse, String> {
+  proxy::webview_fetch(&app, &method, &path, body, request_id).await
+}
+
+/// Installs a newer app version if there is one, stopping the engine cleanly
+/// first, then restarts. Emits `app-update-progress` while downloading.
+#[tauri::command]
+async fn install_app_update(app: tauri::AppHandle) -> Result<(), String> {
+  appupdate::install(&app).await
+}
+
+/// Cancels a pending `engine_fetch`, which then rejects with "request cancelled".
+/// Resolves false if the request already finished.
+#[tauri::command]
+fn cancel_request(app: tauri::AppHandle, id: String) -> bool {
+  proxy::cancel(&app, &id)
+}
+
+/// How many `engine_fetch` requests are running and queued.
+#[tauri::command]
+fn get_request_pool_stats(app: tauri::AppHandle) -> PoolStats {
+  proxy::stats(&app)
+}
+
+/// Power source, idle time, and whether background work may run. Changes
+/// to the last are also sent as `activity-changed`.
+#[tauri::command]
+fn get_activity(app: tauri::AppHandle) -> power::Activity {
+  power::activity(&app)
+}
+
+/// Rebuilds indexes, refreshes statistics, and compacts the database now,
+/// sending `maintenance-progress` per task. Fails if a run is already going.
+#[tauri::command]
+async fn run_maintenance(app: tauri::AppHandle) -> Result<Vec<TaskProgress>, String> {
+  telemetry::count(&app, "run_maintenance");
+  maintenance::run_now(&app).await
+}
+
+/// Runs the troubleshooting checklist: engine binary, connection, health and
+/// latency, data dir, disk space, clock, and proxy. Each check passes, warns,
+/// or fails with a detail line.
+#[tauri::command]
+async fn run_diagnostics(app: tauri::AppHandle) -> DiagnosticsReport {
+  telemetry::count(&app, "run_diagnostics");
+  diagnostics::run(&app).await
+}
+
+/// Imports the saved jobs and applications in a LinkedIn or Indeed data
+/// export, or in one picked from a dialog when no path is given. Progress is
+/// emitted as `archive-import-progress`; the report lists every record as
+/// created, duplicate, or failed. `None` if the dialog was cancelled.
+#[tauri::command]
+async fn import_archive(
+  app: tauri::AppHandle,
+  path: Option<String>,
+) -> Result<Option<ImportReport>, String> {
+  telemetry::count(&app, "import_archive");
+  match path {
+    Some(path) => archiveimport::import(&app, &PathBuf::from(path)).await.map(Some),
+    None => archiveimport::pick_and_import(&app).await,
+  }
+}
+
+#[tauri::command]
+fn get_data_dir(app: tauri::AppHandle) -> Result<String, String> {
+  Ok(engine::data_dir(&app)?.to_string_lossy().to_string())
+}
+
+/// Moves the engine's data to `path`, or to a folder picked from a dialog when
+/// no path is given. Copy progress is emitted as `data-dir-progress`.
+#[tauri::command]
+async fn set_data_dir(app: tauri::AppHandle, path: Option<PathBuf>) -> Result<String, String> {
+  let dest = match path {
+    Some(p) => p,
+    None => app
+      .dialog()
+      .file()
+      .set_title(i18n::t("dialog.data_dir"))
+      .blocking_pick_folder()
+      .ok_or("Cancelled")?
+      .into_path()
+      .map_err(|e| e.to_string())?,
+  };
+
+  datadir::move_data_dir(&app, dest.clone()).await?;
+  Ok(dest.to_string_lossy().to_string())
+}
+
+/// Writes a timestamped zip of the data dir into `dest` (default: `<data dir>/backups`).
+#[tauri::command]
+async fn create_backup(app: tauri::AppHandle, dest: Option<PathBuf>) -> Result<String, String> {
+  telemetry::count(&app, "create_backup");
+  let dest = match dest {
+    Some(d) => d,
+    None => backup::default_backup_dir(&app)?,
+  };
+  let path = backup::create_backup(&app, dest).await?;
+  Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn restore_backup(app: tauri::AppHandle, src: PathBuf) -> Result<(), String> {
+  backup::restore_backup(&app, src).await
+}
+
+#[tauri::command]
+fn list_drop_folders(app: tauri::AppHandle) -> Vec<DropFolder> {
+  dropfolder::list(&app)
+}
+
+/// Starts watching `path` (created if missing) for files to import.
+#[tauri::command]
+fn add_drop_folder(app: tauri::AppHandle, path: PathBuf) -> Result<Vec<DropFolder>, String> {
+  dropfolder::add(&app, path)
+}
+
+#[tauri::command]
+fn set_drop_folder_enabled(
+  app: tauri::AppHandle,
+  path: PathBuf,
+  enabled: bool,
+) -> Result<Vec<DropFolder>, String> {
+  dropfolder::set_enabled(&app, &path, enabled)
+}
+
+#[tauri::command]
+fn remove_drop_folder(app: tauri::AppHandle, path: PathBuf) -> Result<Vec<DropFolder>, String> {
+  dropfolder::remove(&app, &path)
+}
+
+/// Zips logs, the latest crash report, and system info for a bug report; returns
+/// the path, or null if the save dialog was cancelled.
+#[tauri::command]
+async fn create_support_bundle(app: tauri::AppHandle) -> Result<Option<String>, String> {
+  support::create_support_bundle(&app).await
+}
+
+#[tauri::command]
+fn secret_set(name: String, value: String) -> Result<(), String> {
+  secrets::set(&name, &value)
+}
+
+#[tauri::command]
+fn secret_get(name: String) -> Result<Option<String>, String> {
+  secrets::get(&name)
+}
+
+#[tauri::command]
+fn secret_delete(name: String) -> Result<(), String> {
+  secrets::delete(&name)
+}
+
+/// Registers the app to start hidden in the tray at login.
+#[tauri::command]
+fn set_autostart(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+  autostart::set_enabled(&app, enabled)
+}
+
+#[tauri::command]
+fn get_autostart(app: tauri::AppHandle) -> Result<bool, String> {
+  autostart::is_enabled(&app)
+}
+
+/// Latest CPU/RSS sample of the engine process, or `None` if it isn't running.
+#[tauri::command]
+fn engine_metrics(app: tauri::AppHandle) -> Option<EngineMetrics> {
+  monitor::metrics(&app)
+}
+
+#[tauri::command]
+fn list_profiles(app: tauri::AppHandle) -> Vec<ProfileInfo> {
+  profiles::list(&app)
+}
+
+#[tauri::command]
+fn create_profile(
+  app: tauri::AppHandle,
+  name: String,
+  data_dir: Option<PathBuf>,
+) -> Result<(), String> {
+  profiles::create(&app, &name, data_dir)
+}
+
+#[tauri::command]
+fn delete_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+  profiles::delete(&app, &name)
+}
+
+/// Shuts down the current engine and boots one against `name`'s data dir.
+#[tauri::command]
+async fn switch_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+  telemetry::count(&app, "switch_profile");
+  profiles::switch(&app, &name).await
+}
+
+/// Used by the splash to decide whether to show the profile picker.
+#[tauri::command]
+fn get_launch_profiles(app: tauri::AppHandle) -> LaunchProfiles {
+  profiles::launch_profiles(&app)
+}
+
+#[tauri::command]
+async fn select_launch_profile(app: tauri::AppHandle, name: String) -> Result<(), String> {
+  profiles::select_on_launch(&app, &name).await
+}
+
+/// Opens a file picker and copies the chosen files into the attachment store.
+#[tauri::command]
+async fn add_attachments(
+  app: tauri::AppHandle,
+  kind: Option<String>,
+) -> Result<Vec<Attachment>, String> {
+  telemetry::count(&app, "add_attachments");
+  attachments::pick_and_import(&app, kind.as_deref().unwrap_or("resume")).await
+}
+
+#[tauri::command]
+async fn list_attachments(app: tauri::AppHandle) -> Result<Vec<Attachment>, String> {
+  attachments::list(&app).await
+}
+
+#[tauri::command]
+async fn delete_attachment(app: tauri::AppHandle, id: String) -> Result<(), String> {
+  attachments::delete(&app, &id).await
+}
+
+#[tauri::command]
+async fn reveal_attachment(app: tauri::AppHandle, id: String) -> Result<(), String> {
+  attachments::reveal(&app, &id).await
+}
+
+/// Screenshots the screen (`full`) or a selected area (`region`) and attaches
+/// it to job `job_id`. Resolves with `null` if the user cancelled.
+#[tauri::command]
+async fn capture_screenshot(
+  app: tauri::AppHandle,
+  mode: CaptureMode,
+  job_id: i64,
+) -> Result<Option<Attachment>, String> {
+  telemetry::count(&app, "capture_screenshot");
+  screenshot::capture(&app, mode, job_id).await
+}
+
+/// Drafts the application or follow-up email for job `job_id` (`kind` is
+/// `application`, the default, or `follow_up`) as a mailto: link or an .eml
+/// file with the résumé attached. Resolves with the .eml file's path.
+#[tauri::command]
+async fn draft_email(
+  app: tauri::AppHandle,
+  job_id: i64,
+  kind: Option<String>,
+  to: Option<String>,
+  delivery: emaildraft::Delivery,
+) -> Result<Option<PathBuf>, String> {
+  telemetry::count(&app, "draft_email");
+  let kind = kind.unwrap_or_else(|| "application".into());
+  emaildraft::draft(&app, job_id, &kind, to.as_deref().unwrap_or(""), delivery).await
+}
+
+/// Opens a temporary copy of an attachment in the default viewer. The copy
+/// is deleted after an hour or when the app exits.
+#[tauri::command]
+async fn open_attachment_preview(app: tauri::AppHandle, id: String) -> Result<(), String> {
+  previews::open_preview(&app, &id).await
+}
+
+/// Plain text and detected sections of a PDF/DOCX résumé.
+#[tauri::command]
+async fn extract_text(path: PathBuf) -> Result<extract::ExtractedText, extract::ExtractError> {
+  tauri::async_runtime::spawn_blocking(move || extract::extract_text(&path))
+    .await
+    .map_err(|e| extract::ExtractError::Io(e.to_string()))?
+}
+
+/// Exports the job pipeline as `csv` or `xlsx`; prompts for a path when `dest` is
+/// omitted. Null if the save dialog was cancelled.
+#[tauri::command]
+async fn export_applications(
+  app: tauri::AppHandle,
+  format: String,
+  dest: Option<PathBuf>,
+) -> Result<Option<export::ExportSummary>, String> {
+  telemetry::count(&app, "export_applications");
+  export::export_applications(&app, &format, dest).await
+}
+
+/// Opens the interview as an `.ics` in the default calendar app, or saves it via a dialog.
+#[tauri::command]
+async fn export_interview_ics(
+  app: tauri::AppHandle,
+  interview: ics::Interview,
+  save: Option<bool>,
+) -> Result<Option<String>, String> {
+  telemetry::count(&app, "export_interview_ics");
+  let path = ics::export(&app, &interview, save.unwrap_or(false))?;
+  Ok(path.map(|p| p.to_string_lossy().to_string()))
+}
+
+/// Opens a separate window for one job so postings can sit side by side. Returns its label.
+#[tauri::command]
+async fn open_job_window(app: tauri::AppHandle, job_id: i64) -> Result<String, String> {
+  telemetry::count(&app, "open_job_window");
+  jobwindow::open(&app, job_id)
+}
+
+#[tauri::command]
+fn list_job_windows(app: tauri::AppHandle) -> Vec<JobWindow> {
+  jobwindow::list(&app)
+}
+
+/// Prints a job's summary: `print` opens the system print dialog, `pdf` saves a PDF
+/// via a save dialog and returns its path.
+#[tauri::command]
+async fn print_application(
+  app: tauri::AppHandle,
+  job_id: i64,
+  mode: String,
+) -> Result<Option<String>, String> {
+  telemetry::count(&app, "print_application");
+  printing::print_application(&app, job_id, &mode).await
+}
+
+/// Called by a print view once it has rendered, to show the native print dialog.
+#[tauri::command]
+async fn print_window(window: tauri::WebviewWindow) -> Result<(), String> {
+  printing::print_window(&window)
+}
+
+#[tauri::command]
+async fn open_quick_capture(app: tauri::AppHandle) -> Result<(), String> {
+  quickcapture::open(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_quick_capture_prefill(app: tauri::AppHandle) -> Option<String> {
+  quickcapture::prefill(&app)
+}
+
+/// Adds the captured posting through the engine; returns false if it was already known.
+#[tauri::command]
+async fn submit_quick_capture(
+  app: tauri::AppHandle,
+  url: String,
+  title: Option<String>,
+  company: Option<String>,
+) -> Result<bool, String> {
+  telemetry::count(&app, "submit_quick_capture");
+  quickcapture::submit(&app, url, title, company).await
+}
+
+#[tauri::command]
+fn set_quick_capture_shortcut(app: tauri::AppHandle, shortcut: String) -> Result<(), String> {
+  quickcapture::set_shortcut(&app, &shortcut)
+}
+
+/// Registers the native messaging host with installed browsers. `extension_ids`
+/// are the Chromium extension IDs allowed to connect; omit to reuse the saved ones.
+#[tauri::command]
+fn install_native_host(
+  app: tauri::AppHandle,
+  extension_ids: Option<Vec<String>>,
+) -> Result<Vec<nativehost::HostInstall>, String> {
+  nativehost::install(&app, extension_ids)
+}
+
+#[tauri::command]
+fn uninstall_native_host(app: tauri::AppHandle) -> Result<(), String> {
+  nativehost::uninstall(&app)
+}
@@ -0,0 +1,218 @@
+//! Database upkeep: rebuilds indexes, refreshes query statistics, compacts
+//! the file, and truncates the write-ahead log, one engine task at a time.
+//!
+//! Runs on its own once the user has been idle long enough and the last run
+//! is old enough, or on demand. Each task counts as an in-flight engine write,
+//! so quitting mid-task goes through the usual "quit anyway?" prompt.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{engine, power, proxy, settings};
+
+// In the order they run: VACUUM rewrites the file, so the WAL is flushed last.
+const TASKS: &[&str] = &["reindex", "analyze", "vacuum", "checkpoint"];
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// After an idle run, the next waits at least this long, so a task that keeps
+// failing or a user who keeps coming back doesn't start one every minute.
+const RETRY_AFTER: Duration = Duration::from_secs(60 * 60);
+// VACUUM copies the whole database; give a large one time to finish.
+const TASK_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+const DEFAULT_IDLE_MINS: u64 = 15;
+const DEFAULT_INTERVAL_DAYS: u64 = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+  Running,
+  Done,
+  Failed,
+  /// Not run because the user came back during an idle run.
+  Skipped,
+}
+
+/// Sent as `maintenance-progress` as each task starts and ends.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskProgress {
+  pub task: String,
+  /// 1-based position among `total` tasks.
+  pub step: usize,
+  pub total: usize,
+  pub status: TaskStatus,
+  pub duration_ms: Option<u64>,
+  pub size_before: Option<u64>,
+  pub size_after: Option<u64>,
+  pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskResult {
+  duration_ms: u64,
+  size_before: u64,
+  size_after: u64,
+}
+
+#[derive(Default)]
+pub struct MaintenanceState {
+  /// Set while a run is in progress, so idle and manual runs don't overlap.
+  running: AtomicBool,
+  last_idle_run: Mutex<Option<Instant>>,
+}
+
+struct Running<'a>(&'a AtomicBool);
+
+impl Drop for Running<'_> {
+  fn drop(&mut self) {
+    self.0.store(false, Ordering::SeqCst);
+  }
+}
+
+async fn run_task(app: &AppHandle, task: &str) -> Result<TaskResult, String> {
+  let req = proxy::engine_request(
+    app,
+    reqwest::Method::POST,
+    &format!("/db/maintenance/{}", task),
+  )
+  .timeout(TASK_TIMEOUT);
+  let resp = proxy::send(app, req).await.map_err(|e| e.to_string())?;
+  let status = resp.status();
+  let body = resp.text().await.map_err(|e| e.to_string())?;
+  if !status.is_success() {
+    return Err(format!("engine returned HTTP {}: {}", status, body.trim()));
+  }
+  serde_json::from_str(&body).map_err(|e| e.to_string())
+}
+
+fn record(app: &AppHandle, task: &str) {
+  let mut s = settings::current(app);
+  s.maintenance_last_run
+    .insert(task.to_string(), Local::now());
+  if let Err(e) = settings::replace(app, s) {
+    eprintln!("[maintenance] could not record {}: {}", task, e);
+  }
+}
+
+/// Whether an idle run may start, or go on to its next task.
+async fn user_idle(app: &AppHandle) -> bool {
+  let mins = settings::current(app)
+    .maintenance_idle_mins
+    .unwrap_or(DEFAULT_IDLE_MINS);
+  if power::activity(app).on_battery == Some(true) {
+    return false;
+  }
+  // Without an idle reading there's no telling the user is away.
+  power::idle_secs().await.is_some_and(|idle| idle >= mins * 60)
+}
+
+/// Runs every task in turn, emitting progress. An idle run stops between
+/// tasks once the user is back. A failed task doesn't stop the rest.
+async fn run(app: &AppHandle, idle: bool) -> Result<Vec<TaskProgress>, String> {
+  let state = app.state::<MaintenanceState>();
+  if state.running.swap(true, Ordering::SeqCst) {
+    return Err("maintenance is already running".into());
+  }
+  let _running = Running(&state.running);
+  if !engine::is_running(app) {
+    return Err("the engine is not running".into());
+  }
+  println!(
+    "[maintenance] starting {} run",
+    if idle { "idle" } else { "manual" }
+  );
+
+  let mut results = Vec::with_capacity(TASKS.len());
+  for (i, task) in TASKS.iter().enumerate() {
+    let mut progress = TaskProgress {
+      task: task.to_string(),
+      step: i + 1,
+      total: TASKS.len(),
+      status: TaskStatus::Running,
+      duration_ms: None,
+      size_before: None,
+      size_after: None,
+      error: None,
+    };
+    if idle && !user_idle(app).await {
+      progress.status = TaskStatus::Skipped;
+      let _ = app.emit("maintenance-progress", progress.clone());
+      results.push(progress);
+      continue;
+    }
+    let _ = app.emit("maintenance-progress", progress.clone());
+
+    match run_task(app, task).await {
+      Ok(result) => {
+        progress.status = TaskStatus::Done;
+        progress.duration_ms = Some(result.duration_ms);
+        progress.size_before = Some(result.size_before);
+        progress.size_after = Some(result.size_after);
+        record(app, task);
+      }
+      Err(e) => {
+        eprintln!("[maintenance] {} failed: {}", task, e);
+        progress.status = TaskStatus::Failed;
+        progress.error = Some(e);
+      }
+    }
+    let _ = app.emit("maintenance-progress", progress.clone());
+    results.push(progress);
+  }
+
+  let done = results
+    .iter()
+    .filter(|r| r.status == TaskStatus::Done)
+    .count();
+  println!("[maintenance] {} of {} tasks done", done, TASKS.len());
+  Ok(results)
+}
+
+/// Runs maintenance now, whether or not the user is idle.
+pub async fn run_now(app: &AppHandle) -> Result<Vec<TaskProgress>, String> {
+  run(app, false).await
+}
+
+/// Due when some task hasn't succeeded within the interval. An interval of
+/// zero days turns idle runs off.
+fn due(app: &AppHandle) -> bool {
+  let s = settings::current(app);
+  let days = s.maintenance_interval_days.unwrap_or(DEFAULT_INTERVAL_DAYS);
+  if days == 0 {
+    return false;
+  }
+  let cutoff = Local::now() - TimeDelta::days(days as i64);
+  TASKS.iter().any(|task| {
+    s.maintenance_last_run
+      .get(*task)
+      .is_none_or(|last| *last < cutoff)
+  })
+}
+
+/// Checks every `CHECK_INTERVAL` for a due run while the user is idle.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(CHECK_INTERVAL).await;
+      if !due(&app) || !user_idle(&app).await || !engine::is_running(&app) {
+        continue;
+      }
+      {
+        let state = app.state::<MaintenanceState>();
+        let mut last = state.last_idle_run.lock().unwrap();
+        if state.running.load(Ordering::SeqCst) || last.is_some_and(|t| t.elapsed() < RETRY_AFTER) {
+          continue;
+        }
+        *last = Some(Instant::now());
+      }
+      if let Err(e) = run(&app, true).await {
+        eprintln!("[maintenance] {}", e);
+      }
+    }
+  });
+}
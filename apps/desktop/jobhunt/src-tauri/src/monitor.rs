@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::engine::{self, EngineState};
+use crate::{i18n, proxy, settings, tray};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_ALERT_INTERVAL_SECS: u64 = 600;
+pub const DUMPS_DIR: &str = "dumps";
+const DUMP_PREFIX: &str = "heap-";
+// An engine that stays over memory_alert_mb writes a profile every interval;
+// only the newest few are worth keeping.
+const DUMP_RETENTION: usize = 5;
+
+#[derive(Clone, Serialize)]
+struct MemoryAlert {
+  path: String,
+  rss_mb: u64,
+  limit_mb: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct MemoryCeiling {
+  rss_mb: u64,
+  limit_mb: u64,
+}
+
+/// The engine's latest resource sample.
+#[derive(Clone, Serialize)]
+pub struct EngineMetrics {
+  pub pid: u32,
+  /// Percent of one core; can exceed 100 on multi-core machines.
+  pub cpu_percent: f32,
+  pub rss_mb: u64,
+}
+
+#[derive(Default)]
+pub struct MonitorState(Mutex<Option<EngineMetrics>>);
+
+fn sample(sys: &mut System, pid: u32) -> Option<EngineMetrics> {
+  let sys_pid = Pid::from_u32(pid);
+  sys.refresh_processes_specifics(
+    ProcessesToUpdate::Some(&[sys_pid]),
+    true,
+    ProcessRefreshKind::nothing().with_memory().with_cpu(),
+  );
+  sys.process(sys_pid).map(|p| EngineMetrics {
+    pid,
+    cpu_percent: p.cpu_usage(),
+    rss_mb: p.memory() / (1024 * 1024),
+  })
+}
+
+pub fn metrics(app: &AppHandle) -> Option<EngineMetrics> {
+  app.state::<MonitorState>().0.lock().unwrap().clone()
+}
+
+fn record(app: &AppHandle, metrics: Option<EngineMetrics>) {
+  let tooltip = match &metrics {
+    Some(m) => i18n::t_with(
+      "tray.tooltip.metrics",
+      &[
+        ("cpu", &i18n::number(m.cpu_percent as f64, 0)),
+        ("mb", &i18n::number(m.rss_mb as f64, 0)),
+      ],
+    ),
+    None => i18n::t("tray.tooltip"),
+  };
+  tray::set_tooltip(app, &tooltip);
+  *app.state::<MonitorState>().0.lock().unwrap() = metrics;
+}
+
+/// Restarts an engine that has grown past `memory_ceiling_mb`.
+async fn enforce_ceiling(app: &AppHandle, rss_mb: u64, limit_mb: u64) {
+  eprintln!(
+    "[monitor] engine RSS {} MB exceeds ceiling of {} MB, restarting",
+    rss_mb, limit_mb
+  );
+  let _ = app.emit("engine-memory-ceiling", MemoryCeiling { rss_mb, limit_mb });
+  if let Err(e) = engine::restart_engine(app).await {
+    eprintln!("[monitor] restart failed: {}", e);
+    engine::record_error(app, format!("memory ceiling restart failed: {}", e));
+  }
+}
+
+/// Saves a heap profile of the running engine under `<data dir>/dumps`.
+pub async fn capture_engine_dump(app: &AppHandle) -> Result<PathBuf, String> {
+  if app.state::<EngineState>().info.lock().unwrap().endpoint.is_none() {
+    return Err("engine is not running".into());
+  }
+  // The profile is sampled allocation stacks rather than memory contents, but
+  // it still maps out the engine's internals, so only signed requests get it.
+  let req = proxy::engine_request(app, reqwest::Method::GET, "/debug/pprof/heap");
+  let resp = proxy::send(app, req).await.map_err(|e| e.to_string())?;
+  if !resp.status().is_success() {
+    return Err(format!("heap profile returned HTTP {}", resp.status()));
+  }
+  let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+
+  let dir = engine::data_dir(app)?.join(DUMPS_DIR);
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+  let ts = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default();
+  let path = dir.join(format!("{}{}.pprof", DUMP_PREFIX, ts));
+  std::fs::write(&path, &bytes).map_err(|e| e.to_string())?;
+
+  println!("[monitor] heap profile written to {}", path.display());
+  prune_dumps(&dir, DUMP_RETENTION);
+  Ok(path)
+}
+
+/// Heap profiles in `dir`, newest first.
+fn list_dumps(dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return Vec::new();
+  };
+  let mut dumps: Vec<(PathBuf, SystemTime)> = entries
+    .flatten()
+    .filter(|e| {
+      let name = e.file_name().to_string_lossy().to_string();
+      name.starts_with(DUMP_PREFIX) && name.ends_with(".pprof")
+    })
+    .filter_map(|e| Some((e.path(), e.metadata().ok()?.modified().ok()?)))
+    .collect();
+  dumps.sort_by_key(|d| std::cmp::Reverse(d.1));
+  dumps
+}
+
+fn prune_dumps(dir: &Path, keep: usize) {
+  for (path, _) in list_dumps(dir).into_iter().skip(keep) {
+    match std::fs::remove_file(&path) {
+      Ok(()) => println!("[monitor] pruned {}", path.display()),
+      Err(e) => eprintln!("[monitor] failed to prune {}: {}", path.display(), e),
+    }
+  }
+}
+
+/// Samples the engine's CPU and RSS, captures a heap profile whenever RSS
+/// crosses `memory_alert_mb` (at most once per `memory_alert_interval_secs`),
+/// and restarts the engine past `memory_ceiling_mb`.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let mut sys = System::new();
+    let mut last_alert: Option<Instant> = None;
+
+    loop {
+      tokio::time::sleep(SAMPLE_INTERVAL).await;
+
+      let pid = app.state::<EngineState>().info.lock().unwrap().pid;
+      let metrics = pid.and_then(|pid| sample(&mut sys, pid));
+      record(&app, metrics.clone());
+      let Some(rss_mb) = metrics.map(|m| m.rss_mb) else {
+        continue;
+      };
+
+      let settings = settings::current(&app);
+      if let Some(limit_mb) = settings.memory_ceiling_mb {
+        if rss_mb > limit_mb {
+          enforce_ceiling(&app, rss_mb, limit_mb).await;
+          continue;
+        }
+      }
+
+      let Some(limit_mb) = settings.memory_alert_mb else {
+        continue;
+      };
+      if rss_mb <= limit_mb {
+        continue;
+      }
+
+      let interval = Duration::from_secs(
+        settings
+          .memory_alert_interval_secs
+          .unwrap_or(DEFAULT_ALERT_INTERVAL_SECS),
+      );
+      if last_alert.is_some_and(|t| t.elapsed() < interval) {
+        continue;
+      }
+      last_alert = Some(Instant::now());
+
+      eprintln!("[monitor] engine RSS {} MB exceeds {} MB", rss_mb, limit_mb);
+      match capture_engine_dump(&app).await {
+        Ok(path) => {
+          let _ = app.emit(
+            "engine-memory-alert",
+            MemoryAlert {
+              path: path.to_string_lossy().to_string(),
+              rss_mb,
+              limit_mb,
+            },
+          );
+        }
+        Err(e) => eprintln!("[monitor] heap profile failed: {}", e),
+      }
+    }
+  });
+}
@@ -0,0 +1,589 @@
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+
+use crate::transport::{self, Endpoint};
+use crate::{engine, i18n, proxy, settings};
+
+/// Name the extension passes to `runtime.sendNativeMessage`.
+pub const HOST_NAME: &str = "com.jobhunt.native";
+/// `browser_specific_settings.gecko.id` in the extension manifest.
+const FIREFOX_EXTENSION_ID: &str = "extension@jobhunt.app";
+const CAPTURE_ROUTE: &str = "capture";
+// Browsers allow 1 MB from the host; a capture is a URL and two strings.
+const MAX_MESSAGE: usize = 1024 * 1024;
+// A capture that launched the app waits this long for the engine to boot.
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+// In the app's config dir while the engine serves; how the host reaches it.
+const BRIDGE_FILE: &str = "extension-bridge.json";
+// The only engine routes the extension may call through the host.
+const ENGINE_ROUTES: &[(&str, &str)] = &[
+  ("GET", "/health"),
+  ("GET", "/api/profile"),
+  ("POST", "/api/llm"),
+  ("POST", "/api/cover-letter/save"),
+  ("POST", "/api/log"),
+];
+// LLM calls can take a while; anything slower than this has stalled.
+const ENGINE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Browsers we register the host with.
+const BROWSERS: &[Browser] = &[
+  Browser {
+    name: "chrome",
+    firefox: false,
+    linux: ".config/google-chrome",
+    macos: "Library/Application Support/Google/Chrome",
+    windows: r"Software\Google\Chrome",
+  },
+  Browser {
+    name: "chromium",
+    firefox: false,
+    linux: ".config/chromium",
+    macos: "Library/Application Support/Chromium",
+    windows: r"Software\Chromium",
+  },
+  Browser {
+    name: "edge",
+    firefox: false,
+    linux: ".config/microsoft-edge",
+    macos: "Library/Application Support/Microsoft Edge",
+    windows: r"Software\Microsoft\Edge",
+  },
+  Browser {
+    name: "brave",
+    firefox: false,
+    linux: ".config/BraveSoftware/Brave-Browser",
+    macos: "Library/Application Support/BraveSoftware/Brave-Browser",
+    windows: r"Software\BraveSoftware\Brave-Browser",
+  },
+  Browser {
+    name: "firefox",
+    firefox: true,
+    linux: ".mozilla",
+    macos: "Library/Application Support/Mozilla",
+    windows: r"Software\Mozilla",
+  },
+];
+
+struct Browser {
+  name: &'static str,
+  firefox: bool,
+  // Profile root relative to the home dir; the host is only installed if it exists.
+  linux: &'static str,
+  macos: &'static str,
+  // Registry key under HKCU.
+  #[cfg_attr(not(windows), allow(dead_code))]
+  windows: &'static str,
+}
+
+impl Browser {
+  fn hosts_dir_name(&self) -> &'static str {
+    match (self.firefox, cfg!(target_os = "linux")) {
+      (true, true) => "native-messaging-hosts",
+      _ => "NativeMessagingHosts",
+    }
+  }
+}
+
+#[derive(Clone, Serialize)]
+pub struct HostInstall {
+  pub browser: String,
+  pub manifest: String,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Request {
+  Ping,
+  Capture {
+    url: String,
+    title: Option<String>,
+    company: Option<String>,
+  },
+  Engine {
+    method: String,
+    path: String,
+    body: Option<serde_json::Value>,
+  },
+}
+
+#[derive(Serialize)]
+struct Reply {
+  ok: bool,
+  version: &'static str,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  error: Option<String>,
+  // An engine reply, for `Request::Engine`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  status: Option<u16>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  body: Option<String>,
+}
+
+impl Reply {
+  fn ok() -> Self {
+    Reply {
+      ok: true,
+      version: env!("CARGO_PKG_VERSION"),
+      error: None,
+      status: None,
+      body: None,
+    }
+  }
+
+  fn err(error: impl ToString) -> Self {
+    Reply {
+      ok: false,
+      version: env!("CARGO_PKG_VERSION"),
+      error: Some(error.to_string()),
+      status: None,
+      body: None,
+    }
+  }
+
+  fn engine(status: u16, body: String) -> Self {
+    Reply {
+      status: Some(status),
+      body: Some(body),
+      ..Reply::ok()
+    }
+  }
+}
+
+/// What the host needs to reach the running app's engine: where it serves
+/// and the session key to sign with.
+#[derive(Serialize, Deserialize)]
+struct Bridge {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  port: Option<u16>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  socket: Option<PathBuf>,
+  key: String,
+}
+
+impl Bridge {
+  fn endpoint(&self) -> Option<Endpoint> {
+    match (self.port, &self.socket) {
+      (Some(port), _) => Some(Endpoint::Tcp(port)),
+      (None, Some(path)) => Some(Endpoint::Socket(path.clone())),
+      (None, None) => None,
+    }
+  }
+}
+
+#[derive(Clone, Serialize)]
+struct Captured {
+  url: String,
+  added: bool,
+}
+
+/// Chromium browsers start the host with the caller's origin as the first
+/// argument; Firefox passes the path of our manifest.
+pub fn launched_as_host() -> bool {
+  let manifest = format!("{}.json", HOST_NAME);
+  std::env::args()
+    .nth(1)
+    .is_some_and(|a| a.starts_with("chrome-extension://") || a.ends_with(&manifest))
+}
+
+/// True if `args` is a launch for a `jobhunt://capture` link, which should not
+/// bring any window to the front.
+pub fn is_capture_launch(args: &[String]) -> bool {
+  match args {
+    [_, link] => Url::parse(link).is_ok_and(|u| u.host_str() == Some(CAPTURE_ROUTE)),
+    _ => false,
+  }
+}
+
+fn read_message(input: &mut impl Read) -> std::io::Result<Option<Vec<u8>>> {
+  let mut len = [0u8; 4];
+  match input.read_exact(&mut len) {
+    Ok(()) => {}
+    Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+    Err(e) => return Err(e),
+  }
+  let len = u32::from_ne_bytes(len) as usize;
+  if len > MAX_MESSAGE {
+    return Err(std::io::Error::other(format!("message of {} bytes is too large", len)));
+  }
+  let mut buf = vec![0u8; len];
+  input.read_exact(&mut buf)?;
+  Ok(Some(buf))
+}
+
+fn write_message(output: &mut impl Write, reply: &Reply) -> std::io::Result<()> {
+  let body = serde_json::to_vec(reply)?;
+  output.write_all(&(body.len() as u32).to_ne_bytes())?;
+  output.write_all(&body)?;
+  output.flush()
+}
+
+/// Hands `link` to the app: the running instance receives it through the
+/// single-instance plugin, otherwise this starts the app in the background.
+fn launch(link: &Url) -> std::io::Result<()> {
+  #[cfg(target_os = "macos")]
+  let mut cmd = {
+    let mut cmd = Command::new("open");
+    cmd.arg("-g").arg(link.as_str());
+    cmd
+  };
+  #[cfg(not(target_os = "macos"))]
+  let mut cmd = {
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.arg(link.as_str());
+    cmd
+  };
+  // The browser's pipes must not leak into the app, or the port stays open.
+  cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+  #[cfg(windows)]
+  {
+    use std::os::windows::process::CommandExt;
+    use windows_sys::Win32::System::Threading::CREATE_BREAKAWAY_FROM_JOB;
+    // Chrome kills the host's job when the port closes; the app has to outlive it.
+    cmd.creation_flags(CREATE_BREAKAWAY_FROM_JOB);
+  }
+  cmd.spawn().map(|_| ())
+}
+
+fn capture(url: &str, title: Option<String>, company: Option<String>) -> Result<(), String> {
+  let page = Url::parse(url).map_err(|e| format!("invalid url {:?}: {}", url, e))?;
+  if !matches!(page.scheme(), "http" | "https") {
+    return Err(format!("not a web page: {}", url));
+  }
+  let mut link = Url::parse(&format!("jobhunt://{}", CAPTURE_ROUTE)).map_err(|e| e.to_string())?;
+  {
+    let mut query = link.query_pairs_mut();
+    query.append_pair("url", page.as_str());
+    if let Some(title) = title.filter(|t| !t.is_empty()) {
+      query.append_pair("title", &title);
+    }
+    if let Some(company) = company.filter(|c| !c.is_empty()) {
+      query.append_pair("company", &company);
+    }
+  }
+  launch(&link).map_err(|e| format!("could not start JobHunt: {}", e))
+}
+
+/// Forwards one of `ENGINE_ROUTES` to the engine of the running app.
+async fn forward(
+  config_dir: &Path,
+  method: &str,
+  path: &str,
+  body: Option<serde_json::Value>,
+) -> Result<Reply, String> {
+  if !ENGINE_ROUTES.iter().any(|&(m, p)| m == method && p == path) {
+    return Err(format!("{} {} is not available to the extension", method, path));
+  }
+  let bridge = std::fs::read(config_dir.join(BRIDGE_FILE))
+    .ok()
+    .and_then(|raw| serde_json::from_slice::<Bridge>(&raw).ok());
+  let Some((endpoint, key)) = bridge.and_then(|b| Some((b.endpoint()?, b.key))) else {
+    return Err("JobHunt is not running".into());
+  };
+
+  let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+  let client = transport::client(&endpoint);
+  let mut req = client.request(method, endpoint.url(path)).timeout(ENGINE_TIMEOUT);
+  if let Some(body) = &body {
+    req = req.json(body);
+  }
+  let mut req = req.build().map_err(|e| e.to_string())?;
+  proxy::sign(&key, &mut req)?;
+  let resp = client.execute(req).await.map_err(|e| e.to_string())?;
+  let status = resp.status().as_u16();
+  let body = resp.text().await.map_err(|e| e.to_string())?;
+  Ok(Reply::engine(status, body))
+}
+
+/// Native messaging mode: answers length-prefixed JSON messages on stdio
+/// until the browser closes the port. Nothing else may write to stdout.
+/// `identifier` locates the app's config dir, as Tauri would.
+pub fn run(identifier: &str) {
+  let config_dir = dirs::config_dir().map(|dir| dir.join(identifier));
+  let mut input = std::io::stdin().lock();
+  let mut output = std::io::stdout().lock();
+  loop {
+    let msg = match read_message(&mut input) {
+      Ok(Some(msg)) => msg,
+      Ok(None) => return,
+      Err(e) => {
+        eprintln!("[nativehost] {}", e);
+        return;
+      }
+    };
+    let reply = match serde_json::from_slice::<Request>(&msg) {
+      Ok(Request::Ping) => Reply::ok(),
+      Ok(Request::Capture {
+        url,
+        title,
+        company,
+      }) => match capture(&url, title, company) {
+        Ok(()) => Reply::ok(),
+        Err(e) => Reply::err(e),
+      },
+      Ok(Request::Engine { method, path, body }) => {
+        let forwarded = match &config_dir {
+          Some(dir) => tauri::async_runtime::block_on(forward(dir, &method, &path, body)),
+          None => Err("no config directory for this user".into()),
+        };
+        forwarded.unwrap_or_else(Reply::err)
+      }
+      Err(e) => Reply::err(e),
+    };
+    if let Err(e) = write_message(&mut output, &reply) {
+      eprintln!("[nativehost] {}", e);
+      return;
+    }
+  }
+}
+
+/// Adds a `jobhunt://capture?url=...` link through the engine once the user
+/// confirms, waiting for it to come up if the capture is what launched the
+/// app. Any web page can open such a link, so it is never added unasked.
+pub fn handle_capture(app: &AppHandle, link: &Url) {
+  let mut body = serde_json::json!({ "source": "extension" });
+  for (key, value) in link.query_pairs() {
+    if matches!(key.as_ref(), "url" | "title" | "company") {
+      body[key.as_ref()] = serde_json::Value::String(value.into_owned());
+    }
+  }
+  let Some(url) = body["url"].as_str().map(|s| s.to_string()) else {
+    eprintln!("[nativehost] capture without a url");
+    return;
+  };
+
+  let label = match body["title"].as_str() {
+    Some(title) => format!("{}\n{}", title, url),
+    None => url.clone(),
+  };
+  let handle = app.clone();
+  app
+    .dialog()
+    .message(i18n::t_with("capture.confirm.message", &[("page", &label)]))
+    .title(i18n::t("capture.confirm.title"))
+    .buttons(MessageDialogButtons::OkCancelCustom(
+      i18n::t("clipboard.confirm.add"),
+      i18n::t("clipboard.confirm.cancel"),
+    ))
+    .show(move |add| {
+      if add {
+        ingest(handle, url, body);
+      } else {
+        println!("[nativehost] capture of {} declined", url);
+      }
+    });
+}
+
+fn ingest(app: AppHandle, url: String, body: serde_json::Value) {
+  tauri::async_runtime::spawn(async move {
+    if let Err(e) = engine::wait_for_ready(&app, READY_TIMEOUT).await {
+      eprintln!("[nativehost] {}", e);
+      return;
+    }
+    match proxy::engine_fetch(&app, "POST", "/api/ingest", Some(body)).await {
+      Ok(resp) if resp.status < 400 => {
+        let added = serde_json::from_str::<serde_json::Value>(&resp.body)
+          .ok()
+          .and_then(|v| v.get("added")?.as_bool())
+          .unwrap_or(false);
+        println!("[nativehost] captured {} (new: {})", url, added);
+        let _ = app.emit("extension-capture", Captured { url, added });
+      }
+      Ok(resp) => eprintln!("[nativehost] ingest returned HTTP {}: {}", resp.status, resp.body.trim()),
+      Err(e) => eprintln!("[nativehost] ingest failed: {}", e),
+    }
+  });
+}
+
+/// Lets the host reach the engine now serving on `endpoint`. Only the owner
+/// can read the file, as with the engine's own environment.
+pub fn publish(app: &AppHandle, endpoint: &Endpoint, key: &str) -> Result<(), String> {
+  let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let bridge = Bridge {
+    port: endpoint.port(),
+    socket: match endpoint {
+      Endpoint::Socket(path) => Some(path.clone()),
+      Endpoint::Tcp(_) => None,
+    },
+    key: key.to_string(),
+  };
+  let json = serde_json::to_vec(&bridge).map_err(|e| e.to_string())?;
+
+  let tmp = dir.join(format!("{}.tmp", BRIDGE_FILE));
+  let _ = std::fs::remove_file(&tmp);
+  let mut options = std::fs::OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  let mut file = options.open(&tmp).map_err(|e| e.to_string())?;
+  file.write_all(&json).map_err(|e| e.to_string())?;
+  std::fs::rename(&tmp, dir.join(BRIDGE_FILE)).map_err(|e| e.to_string())
+}
+
+/// Removes what `publish` wrote once the engine has stopped.
+pub fn retract(app: &AppHandle) {
+  if let Ok(dir) = app.path().app_config_dir() {
+    match std::fs::remove_file(dir.join(BRIDGE_FILE)) {
+      Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+        eprintln!("[nativehost] could not remove {}: {}", BRIDGE_FILE, e)
+      }
+      _ => {}
+    }
+  }
+}
+
+pub fn is_capture(route: &[String]) -> bool {
+  route.first().is_some_and(|s| s == CAPTURE_ROUTE)
+}
+
+/// The binary browsers should start. AppImages run from a temporary mount,
+/// so point at the image itself.
+fn host_path() -> Result<PathBuf, String> {
+  #[cfg(target_os = "linux")]
+  if let Some(image) = std::env::var_os("APPIMAGE") {
+    return Ok(PathBuf::from(image));
+  }
+  std::env::current_exe().map_err(|e| e.to_string())
+}
+
+fn manifest(browser: &Browser, path: &str, extension_ids: &[String]) -> serde_json::Value {
+  let mut manifest = serde_json::json!({
+    "name": HOST_NAME,
+    "description": "JobHunt desktop app",
+    "path": path,
+    "type": "stdio",
+  });
+  if browser.firefox {
+    manifest["allowed_extensions"] = serde_json::json!([FIREFOX_EXTENSION_ID]);
+  } else {
+    let origins: Vec<String> = extension_ids
+      .iter()
+      .map(|id| format!("chrome-extension://{}/", id))
+      .collect();
+    manifest["allowed_origins"] = serde_json::json!(origins);
+  }
+  manifest
+}
+
+/// Where the manifest for `browser` goes, or `None` if the browser isn't installed.
+fn manifest_path(app: &AppHandle, browser: &Browser) -> Result<Option<PathBuf>, String> {
+  let file = format!("{}.json", HOST_NAME);
+  if cfg!(windows) {
+    // Windows finds the manifest through the registry, so it can live with our data.
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    return Ok(Some(dir.join("native-messaging").join(browser.name).join(file)));
+  }
+  let home = app.path().home_dir().map_err(|e| e.to_string())?;
+  let root = home.join(if cfg!(target_os = "macos") {
+    browser.macos
+  } else {
+    browser.linux
+  });
+  if !root.is_dir() {
+    return Ok(None);
+  }
+  Ok(Some(root.join(browser.hosts_dir_name()).join(file)))
+}
+
+#[cfg(windows)]
+fn reg(args: &[&str]) -> Result<(), String> {
+  use std::os::windows::process::CommandExt;
+  use windows_sys::Win32::System::Threading::CREATE_NO_WINDOW;
+
+  let status = Command::new("reg")
+    .args(args)
+    .creation_flags(CREATE_NO_WINDOW)
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .map_err(|e| e.to_string())?;
+  if !status.success() {
+    return Err(format!("reg {} failed ({})", args[0], status));
+  }
+  Ok(())
+}
+
+#[cfg(windows)]
+fn register(browser: &Browser, manifest: &std::path::Path) -> Result<(), String> {
+  let key = format!(r"HKCU\{}\NativeMessagingHosts\{}", browser.windows, HOST_NAME);
+  let value = manifest.to_string_lossy();
+  reg(&["add", &key, "/ve", "/t", "REG_SZ", "/d", &value, "/f"])
+}
+
+#[cfg(windows)]
+fn unregister(browser: &Browser) {
+  let key = format!(r"HKCU\{}\NativeMessagingHosts\{}", browser.windows, HOST_NAME);
+  let _ = reg(&["delete", &key, "/f"]);
+}
+
+/// Writes the host manifest for every installed browser. Chromium browsers
+/// only admit the extension IDs in `extension_ids`, which are remembered for
+/// later reinstalls.
+pub fn install(
+  app: &AppHandle,
+  extension_ids: Option<Vec<String>>,
+) -> Result<Vec<HostInstall>, String> {
+  if let Some(ids) = extension_ids {
+    // Chromium extension IDs are 32 letters from a-p.
+    let valid = |id: &String| id.len() == 32 && id.chars().all(|c| ('a'..='p').contains(&c));
+    if let Some(bad) = ids.iter().find(|id| !valid(id)) {
+      return Err(format!("invalid extension id {:?}", bad));
+    }
+    let mut next = settings::current(app);
+    next.browser_extension_ids = ids;
+    settings::replace(app, next)?;
+  }
+  let extension_ids = settings::current(app).browser_extension_ids;
+  let exe = host_path()?;
+  let exe = exe.to_string_lossy();
+
+  let mut installed = Vec::new();
+  for browser in BROWSERS {
+    if !browser.firefox && extension_ids.is_empty() {
+      continue;
+    }
+    let Some(path) = manifest_path(app, browser)? else {
+      continue;
+    };
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let body = serde_json::to_vec_pretty(&manifest(browser, &exe, &extension_ids))
+      .map_err(|e| e.to_string())?;
+    std::fs::write(&path, body).map_err(|e| e.to_string())?;
+    #[cfg(windows)]
+    register(browser, &path)?;
+
+    println!("[nativehost] installed for {} at {}", browser.name, path.display());
+    installed.push(HostInstall {
+      browser: browser.name.to_string(),
+      manifest: path.to_string_lossy().to_string(),
+    });
+  }
+  Ok(installed)
+}
+
+pub fn uninstall(app: &AppHandle) -> Result<(), String> {
+  for browser in BROWSERS {
+    #[cfg(windows)]
+    unregister(browser);
+    if let Some(path) = manifest_path(app, browser)? {
+      if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| e.to_string())?;
+      }
+    }
+  }
+  println!("[nativehost] uninstalled");
+  Ok(())
+}
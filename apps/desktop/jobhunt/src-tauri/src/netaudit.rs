@@ -0,0 +1,162 @@
+use std::fmt;
+use std::net::SocketAddr;
+
+use listeners::{Listener, Protocol, SocketState};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+use crate::engine::{self, EngineState};
+use crate::transport::Endpoint;
+use crate::{i18n, tray};
+
+#[derive(Clone, Serialize)]
+pub struct ListeningSocket {
+  pub protocol: &'static str,
+  pub address: String,
+  pub port: u16,
+  pub loopback: bool,
+}
+
+/// The engine's open sockets, for the diagnostics screen.
+#[derive(Clone, Serialize)]
+pub struct NetworkAudit {
+  pub pid: Option<u32>,
+  pub sockets: Vec<ListeningSocket>,
+  /// True when the engine accepts TCP connections from other machines.
+  pub exposed: bool,
+}
+
+fn is_loopback(addr: &SocketAddr) -> bool {
+  match addr {
+    SocketAddr::V4(v4) => v4.ip().is_loopback(),
+    // An IPv4-mapped ::ffff:127.0.0.1 is loopback too.
+    SocketAddr::V6(v6) => {
+      v6.ip().is_loopback() || v6.ip().to_ipv4_mapped().is_some_and(|ip| ip.is_loopback())
+    }
+  }
+}
+
+fn listening(l: &Listener) -> bool {
+  match l.protocol {
+    Protocol::TCP => l.state == SocketState::Listen,
+    // UDP has no listen state; a bound socket is one that can receive.
+    Protocol::UDP => true,
+  }
+}
+
+/// Lists the sockets `pid` is listening on. Blocking: walks the OS socket table.
+pub fn audit_pid(pid: u32) -> Result<NetworkAudit, String> {
+  let all = listeners::get_all().map_err(|e| e.to_string())?;
+  let mut sockets: Vec<ListeningSocket> = all
+    .iter()
+    .filter(|l| l.process.pid == pid && listening(l))
+    .map(|l| ListeningSocket {
+      protocol: match l.protocol {
+        Protocol::TCP => "tcp",
+        Protocol::UDP => "udp",
+      },
+      address: l.socket.ip().to_string(),
+      port: l.socket.port(),
+      loopback: is_loopback(&l.socket),
+    })
+    .collect();
+  sockets.sort_by(|a, b| (a.protocol, a.port, &a.address).cmp(&(b.protocol, b.port, &b.address)));
+  let exposed = sockets.iter().any(|s| s.protocol == "tcp" && !s.loopback);
+  Ok(NetworkAudit {
+    pid: Some(pid),
+    sockets,
+    exposed,
+  })
+}
+
+/// Audits the running engine, found by PID or, when attached, by its port.
+pub async fn audit(app: &AppHandle) -> Result<NetworkAudit, String> {
+  let (pid, port) = {
+    let state = app.state::<EngineState>();
+    let info = state.info.lock().unwrap();
+    (info.pid, info.endpoint.as_ref().and_then(Endpoint::port))
+  };
+  tauri::async_runtime::spawn_blocking(move || {
+    let pid = match (pid, port) {
+      (Some(pid), _) => pid,
+      (None, Some(port)) => listeners::get_process_by_port(port, Protocol::TCP)
+        .map_err(|e| format!("no process is listening on port {}: {}", port, e))?
+        .pid,
+      (None, None) => {
+        return Ok(NetworkAudit {
+          pid: None,
+          sockets: Vec::new(),
+          exposed: false,
+        })
+      }
+    };
+    audit_pid(pid)
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+/// Why a freshly started engine can't be trusted to be local-only.
+pub enum LoopbackError {
+  /// It listens on an address other machines can reach.
+  Exposed(String),
+  /// Its sockets couldn't be listed, so loopback-only binding is unconfirmed.
+  Unverified(String),
+}
+
+impl fmt::Display for LoopbackError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      LoopbackError::Exposed(e) | LoopbackError::Unverified(e) => f.write_str(e),
+    }
+  }
+}
+
+/// Checks a freshly started engine only listens on loopback. An engine whose
+/// sockets can't be listed fails the check too.
+pub async fn verify_loopback(pid: u32) -> Result<(), LoopbackError> {
+  let audit = tauri::async_runtime::spawn_blocking(move || audit_pid(pid))
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r)
+    .map_err(|e| {
+      LoopbackError::Unverified(format!("Could not list the JobHunt engine's sockets: {}", e))
+    })?;
+  let exposed: Vec<String> = audit
+    .sockets
+    .iter()
+    .filter(|s| s.protocol == "tcp" && !s.loopback)
+    .map(|s| format!("{}:{}", s.address, s.port))
+    .collect();
+  if exposed.is_empty() {
+    return Ok(());
+  }
+  Err(LoopbackError::Exposed(format!(
+    "The JobHunt engine is listening on {}, which other machines on your network can reach.",
+    exposed.join(", ")
+  )))
+}
+
+/// Stops an engine that failed [`verify_loopback`] and tells the user why.
+pub fn report_exposure(app: &AppHandle, err: LoopbackError) {
+  let (title, message) = match &err {
+    LoopbackError::Exposed(_) => ("netaudit.title", "netaudit.message"),
+    LoopbackError::Unverified(_) => ("netaudit.unverified.title", "netaudit.unverified.message"),
+  };
+  let err = err.to_string();
+  eprintln!("[netaudit] {}", err);
+  engine::record_error(app, err.clone());
+
+  let handle = app.clone();
+  tauri::async_runtime::spawn(async move { engine::stop_engine(&handle).await });
+  let handle = app.clone();
+  app
+    .dialog()
+    .message(i18n::t_with(message, &[("error", &err)]))
+    .title(i18n::t(title))
+    .kind(MessageDialogKind::Error)
+    .show(move |_| {
+      tauri::async_runtime::spawn(async move { tray::quit(&handle).await });
+    });
+}
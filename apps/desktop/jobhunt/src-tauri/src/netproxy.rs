@@ -0,0 +1,416 @@
+//! Outbound HTTP proxy support. The proxy comes from settings when one is
+//! set there, otherwise from the system: proxy env vars first, then the
+//! OS's own configuration. Requests to the engine never use it.
+
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::secrets;
+use crate::settings::{self, Settings};
+
+// Keychain entry for the proxy password; the username lives in settings.
+pub const PASSWORD_SECRET: &str = "proxy:password";
+// Always bypassed, whatever the proxy's own exception list says.
+const LOOPBACK: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
+/// The proxy outbound traffic goes through. Credentials are not included.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ProxyConfig {
+  pub http: Option<String>,
+  pub https: Option<String>,
+  /// Hosts that go direct, comma-separated; always includes loopback.
+  pub no_proxy: String,
+  /// `settings`, `environment`, or `system`.
+  pub source: &'static str,
+}
+
+/// `host:port` -> `http://host:port`; proxies are addressed over plain HTTP
+/// unless a scheme says otherwise.
+fn normalize(url: &str) -> Option<String> {
+  let url = url.trim();
+  if url.is_empty() {
+    return None;
+  }
+  Some(if url.contains("://") {
+    url.to_string()
+  } else {
+    format!("http://{}", url)
+  })
+}
+
+/// Joins exception lists, dropping blanks and duplicates and adding loopback.
+fn bypass_list<'a>(hosts: impl IntoIterator<Item = &'a str>) -> String {
+  let mut out: Vec<String> = LOOPBACK.iter().map(|h| h.to_string()).collect();
+  for host in hosts {
+    let host = host.trim();
+    if !host.is_empty() && !out.iter().any(|h| h == host) {
+      out.push(host.to_string());
+    }
+  }
+  out.join(",")
+}
+
+fn from_env() -> Option<ProxyConfig> {
+  let var = |names: &[&str]| {
+    names
+      .iter()
+      .find_map(|n| std::env::var(n).ok())
+      .and_then(|v| normalize(&v))
+  };
+  let all = var(&["ALL_PROXY", "all_proxy"]);
+  let http = var(&["HTTP_PROXY", "http_proxy"]).or_else(|| all.clone());
+  let https = var(&["HTTPS_PROXY", "https_proxy"]).or(all);
+  if http.is_none() && https.is_none() {
+    return None;
+  }
+  let no_proxy = std::env::var("NO_PROXY")
+    .or_else(|_| std::env::var("no_proxy"))
+    .unwrap_or_default();
+  Some(ProxyConfig {
+    http,
+    https,
+    no_proxy: bypass_list(no_proxy.split(',')),
+    source: "environment",
+  })
+}
+
+/// The proxy in effect right now, or `None` to connect directly.
+pub fn resolve(app: &AppHandle) -> Option<ProxyConfig> {
+  resolve_for(&settings::current(app))
+}
+
+fn resolve_for(s: &Settings) -> Option<ProxyConfig> {
+  if let Some(url) = s.proxy_url.as_deref().and_then(normalize) {
+    let bypass = s.proxy_bypass.clone().unwrap_or_default();
+    return Some(ProxyConfig {
+      http: Some(url.clone()),
+      https: Some(url),
+      no_proxy: bypass_list(bypass.split(',')),
+      source: "settings",
+    });
+  }
+  from_env().or_else(imp::detect)
+}
+
+/// The username from settings and password from the keychain, if both are set.
+fn credentials(app: &AppHandle) -> Option<(String, String)> {
+  credentials_for(&settings::current(app))
+}
+
+fn credentials_for(s: &Settings) -> Option<(String, String)> {
+  let user = s.proxy_username.clone().filter(|u| !u.is_empty())?;
+  match secrets::get(PASSWORD_SECRET) {
+    Ok(Some(password)) => Some((user, password)),
+    Ok(None) => None,
+    Err(e) => {
+      eprintln!("[netproxy] could not read proxy password: {}", e);
+      None
+    }
+  }
+}
+
+fn encode_userinfo(s: &str) -> String {
+  s.bytes()
+    .map(|b| match b {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+        (b as char).to_string()
+      }
+      _ => format!("%{:02X}", b),
+    })
+    .collect()
+}
+
+/// `url` with `user:password@` spliced in, for consumers that only take a URL.
+fn with_credentials(url: &str, creds: Option<&(String, String)>) -> String {
+  let Some((user, password)) = creds else {
+    return url.to_string();
+  };
+  let Some((scheme, rest)) = url.split_once("://") else {
+    return url.to_string();
+  };
+  // Credentials already in the URL win.
+  if rest
+    .split('/')
+    .next()
+    .is_some_and(|authority| authority.contains('@'))
+  {
+    return url.to_string();
+  }
+  format!(
+    "{}://{}:{}@{}",
+    scheme,
+    encode_userinfo(user),
+    encode_userinfo(password),
+    rest
+  )
+}
+
+/// A client builder for traffic leaving the machine, routed through the proxy.
+pub fn outbound(app: &AppHandle) -> Result<ClientBuilder, String> {
+  // Start from nothing so reqwest's own env handling can't disagree with ours.
+  let mut builder = reqwest::Client::builder().no_proxy();
+  let Some(config) = resolve(app) else {
+    return Ok(builder);
+  };
+  let creds = credentials(app);
+  let bypass = NoProxy::from_string(&config.no_proxy);
+  let proxies = [
+    config.http.as_deref().map(Proxy::http),
+    config.https.as_deref().map(Proxy::https),
+  ];
+  for proxy in proxies.into_iter().flatten() {
+    let mut proxy = proxy.map_err(|e| format!("invalid proxy: {}", e))?;
+    if let Some((user, password)) = &creds {
+      proxy = proxy.basic_auth(user, password);
+    }
+    builder = builder.proxy(proxy.no_proxy(bypass.clone()));
+  }
+  Ok(builder)
+}
+
+/// The proxy URL for HTTPS traffic with credentials included, e.g. for the updater.
+pub fn https_url(app: &AppHandle) -> Option<String> {
+  let url = resolve(app)?.https?;
+  Some(with_credentials(&url, credentials(app).as_ref()))
+}
+
+/// Proxy env vars for the engine, in both cases since Go and the tools it
+/// shells out to disagree about which they read.
+pub fn engine_env(app: &AppHandle) -> Vec<(&'static str, String)> {
+  engine_env_for(&settings::current(app))
+}
+
+/// [`engine_env`] for `settings`, for when there is no app to ask.
+pub fn engine_env_for(settings: &Settings) -> Vec<(&'static str, String)> {
+  let Some(config) = resolve_for(settings) else {
+    return Vec::new();
+  };
+  let creds = credentials_for(settings);
+  let mut env = Vec::new();
+  if let Some(url) = &config.http {
+    let url = with_credentials(url, creds.as_ref());
+    env.push(("HTTP_PROXY", url.clone()));
+    env.push(("http_proxy", url));
+  }
+  if let Some(url) = &config.https {
+    let url = with_credentials(url, creds.as_ref());
+    env.push(("HTTPS_PROXY", url.clone()));
+    env.push(("https_proxy", url));
+  }
+  env.push(("NO_PROXY", config.no_proxy.clone()));
+  env.push(("no_proxy", config.no_proxy));
+  env
+}
+
+/// Stores or clears the proxy password in the keychain.
+pub fn set_password(password: Option<String>) -> Result<(), String> {
+  match password.filter(|p| !p.is_empty()) {
+    Some(p) => secrets::set(PASSWORD_SECRET, &p),
+    None => secrets::delete(PASSWORD_SECRET),
+  }
+}
+
+/// Internet Options' per-user proxy, which is also what WinHTTP-less apps use.
+#[cfg(windows)]
+mod imp {
+  use windows_sys::Win32::System::Registry::{
+    RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD, RRF_RT_REG_SZ,
+  };
+
+  use super::{bypass_list, normalize, ProxyConfig};
+
+  const KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Internet Settings";
+
+  fn wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+  }
+
+  fn read_dword(name: &str) -> Option<u32> {
+    let (key, name) = (wide(KEY), wide(name));
+    let mut value = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let err = unsafe {
+      RegGetValueW(
+        HKEY_CURRENT_USER,
+        key.as_ptr(),
+        name.as_ptr(),
+        RRF_RT_REG_DWORD,
+        std::ptr::null_mut(),
+        &mut value as *mut u32 as *mut _,
+        &mut size,
+      )
+    };
+    (err == 0).then_some(value)
+  }
+
+  fn read_string(name: &str) -> Option<String> {
+    let (key, name) = (wide(KEY), wide(name));
+    let mut size = 0u32;
+    let query = |buf: *mut u16, size: &mut u32| unsafe {
+      RegGetValueW(
+        HKEY_CURRENT_USER,
+        key.as_ptr(),
+        name.as_ptr(),
+        RRF_RT_REG_SZ,
+        std::ptr::null_mut(),
+        buf as *mut _,
+        size,
+      )
+    };
+    if query(std::ptr::null_mut(), &mut size) != 0 || size == 0 {
+      return None;
+    }
+    let mut buf = vec![0u16; (size as usize).div_ceil(2)];
+    if query(buf.as_mut_ptr(), &mut size) != 0 {
+      return None;
+    }
+    let len = buf.iter().position(|c| *c == 0).unwrap_or(buf.len());
+    Some(String::from_utf16_lossy(&buf[..len]))
+  }
+
+  /// `ProxyServer` is either `host:port` for everything or per-scheme, as
+  /// in `http=host:port;https=host:port`.
+  pub fn detect() -> Option<ProxyConfig> {
+    if read_dword("ProxyEnable")? == 0 {
+      return None;
+    }
+    let server = read_string("ProxyServer")?;
+    let (mut http, mut https) = (None, None);
+    if server.contains('=') {
+      for part in server.split(';') {
+        match part.split_once('=') {
+          Some(("http", addr)) => http = normalize(addr),
+          Some(("https", addr)) => https = normalize(addr),
+          _ => {}
+        }
+      }
+    } else {
+      http = normalize(&server);
+      https = http.clone();
+    }
+    if http.is_none() && https.is_none() {
+      return None;
+    }
+    // "<local>" means plain host names; Go and reqwest can't express it.
+    let overrides = read_string("ProxyOverride").unwrap_or_default();
+    let hosts = overrides.split(';').filter(|h| *h != "<local>");
+    Some(ProxyConfig {
+      http,
+      https,
+      no_proxy: bypass_list(hosts),
+      source: "system",
+    })
+  }
+}
+
+/// Network preferences as reported by `scutil --proxy`.
+#[cfg(target_os = "macos")]
+mod imp {
+  use std::collections::HashMap;
+
+  use super::{bypass_list, normalize, ProxyConfig};
+
+  pub fn detect() -> Option<ProxyConfig> {
+    let out = std::process::Command::new("scutil")
+      .arg("--proxy")
+      .output()
+      .ok()?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    let mut values = HashMap::new();
+    let mut exceptions = Vec::new();
+    let mut in_exceptions = false;
+    for line in text.lines() {
+      let line = line.trim();
+      if line.starts_with("ExceptionsList") {
+        in_exceptions = true;
+        continue;
+      }
+      if in_exceptions {
+        if line == "}" {
+          in_exceptions = false;
+        } else if let Some((_, host)) = line.split_once(" : ") {
+          exceptions.push(host.trim().to_string());
+        }
+        continue;
+      }
+      if let Some((key, value)) = line.split_once(" : ") {
+        values.insert(key.trim().to_string(), value.trim().to_string());
+      }
+    }
+    let proxy = |scheme: &str| {
+      if values.get(&format!("{}Enable", scheme)).map(String::as_str) != Some("1") {
+        return None;
+      }
+      let host = values.get(&format!("{}Proxy", scheme))?;
+      match values.get(&format!("{}Port", scheme)) {
+        Some(port) => normalize(&format!("{}:{}", host, port)),
+        None => normalize(host),
+      }
+    };
+    let (http, https) = (proxy("HTTP"), proxy("HTTPS"));
+    if http.is_none() && https.is_none() {
+      return None;
+    }
+    Some(ProxyConfig {
+      http,
+      https,
+      no_proxy: bypass_list(exceptions.iter().map(String::as_str)),
+      source: "system",
+    })
+  }
+}
+
+/// GNOME's proxy settings; other desktops rely on the env vars.
+#[cfg(all(unix, not(target_os = "macos")))]
+mod imp {
+  use super::{bypass_list, normalize, ProxyConfig};
+
+  fn gsettings(schema: &str, key: &str) -> Option<String> {
+    let out = std::process::Command::new("gsettings")
+      .args(["get", schema, key])
+      .output()
+      .ok()?;
+    if !out.status.success() {
+      return None;
+    }
+    Some(String::from_utf8_lossy(&out.stdout).trim().to_string())
+  }
+
+  fn proxy(scheme: &str) -> Option<String> {
+    let schema = format!("org.gnome.system.proxy.{}", scheme);
+    let host = gsettings(&schema, "host")?;
+    let host = host.trim_matches('\'');
+    if host.is_empty() {
+      return None;
+    }
+    let port = gsettings(&schema, "port").unwrap_or_default();
+    match port.as_str() {
+      "" | "0" => normalize(host),
+      port => normalize(&format!("{}:{}", host, port)),
+    }
+  }
+
+  pub fn detect() -> Option<ProxyConfig> {
+    if gsettings("org.gnome.system.proxy", "mode")? != "'manual'" {
+      return None;
+    }
+    let (http, https) = (proxy("http"), proxy("https"));
+    if http.is_none() && https.is_none() {
+      return None;
+    }
+    // A GVariant string list: ['localhost', '127.0.0.0/8'].
+    let ignore = gsettings("org.gnome.system.proxy", "ignore-hosts").unwrap_or_default();
+    let hosts = ignore
+      .trim_start_matches('[')
+      .trim_end_matches(']')
+      .split(',')
+      .map(|h| h.trim().trim_matches('\''));
+    Some(ProxyConfig {
+      http,
+      https,
+      no_proxy: bypass_list(hosts),
+      source: "system",
+    })
+  }
+}
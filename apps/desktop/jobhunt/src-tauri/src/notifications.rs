@@ -0,0 +1,179 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::{clipboard, deeplink, i18n, settings};
+
+// A scrape publishes one event per new job; collect them into one notification.
+const BATCH_WINDOW: Duration = Duration::from_secs(5);
+// Focusing the app this soon after a notification counts as clicking it.
+const CLICK_WINDOW: Duration = Duration::from_secs(120);
+
+/// Engine event type -> notification category, as used in `settings.notifications`.
+const CATEGORIES: &[(&str, &str)] = &[
+  ("job_created", "new_jobs"),
+  ("deadline_approaching", "deadlines"),
+];
+
+#[derive(Deserialize)]
+struct EngineEvent {
+  #[serde(rename = "type")]
+  kind: String,
+  id: Option<i64>,
+  title: Option<String>,
+}
+
+/// What clicking (i.e. focusing the app right after) a notification does.
+pub enum ClickAction {
+  /// Route to a `jobhunt://` deep link.
+  Open(String),
+  /// Offer to add a job posting URL through the engine's ingest endpoint.
+  /// Focus alone is not consent, so this asks first.
+  Ingest(String),
+}
+
+#[derive(Default)]
+struct Pending {
+  // Action for the last notification shown.
+  target: Option<(ClickAction, Instant)>,
+  // New jobs seen during the current batch window.
+  new_jobs: Vec<i64>,
+  new_count: usize,
+  batching: bool,
+}
+
+#[derive(Default)]
+pub struct NotificationState(Mutex<Pending>);
+
+pub fn enabled(app: &AppHandle, category: &str) -> bool {
+  settings::current(app)
+    .notifications
+    .get(category)
+    .copied()
+    .unwrap_or(true)
+}
+
+fn main_focused(app: &AppHandle) -> bool {
+  app
+    .get_webview_window("main")
+    .is_some_and(|w| w.is_visible().unwrap_or(false) && w.is_focused().unwrap_or(false))
+}
+
+pub fn show(app: &AppHandle, title: &str, body: &str, action: ClickAction) {
+  if let Err(e) = app.notification().builder().title(title).body(body).show() {
+    eprintln!("[notifications] {}", e);
+    return;
+  }
+  app.state::<NotificationState>().0.lock().unwrap().target = Some((action, Instant::now()));
+}
+
+fn notify_new_jobs(app: &AppHandle) {
+  let (ids, count) = {
+    let state = app.state::<NotificationState>();
+    let mut pending = state.0.lock().unwrap();
+    pending.batching = false;
+    (std::mem::take(&mut pending.new_jobs), std::mem::take(&mut pending.new_count))
+  };
+  if count == 0 || main_focused(app) {
+    return;
+  }
+  let (body, target) = match (count, ids.as_slice()) {
+    (1, [id]) => (i18n::t("notify.new_jobs.one"), format!("jobhunt://job/{}", id)),
+    _ => (
+      i18n::t_with("notify.new_jobs.many", &[("count", &i18n::number(count as f64, 0))]),
+      "jobhunt://jobs".to_string(),
+    ),
+  };
+  show(app, &i18n::t("notify.new_jobs.title"), &body, ClickAction::Open(target));
+}
+
+fn notify(app: &AppHandle, event: &EngineEvent) {
+  let target = match event.id {
+    Some(id) => format!("jobhunt://job/{}", id),
+    None => "jobhunt://jobs".to_string(),
+  };
+  if event.kind == "deadline_approaching" {
+    let body = match &event.title {
+      Some(title) => i18n::t_with("notify.deadline.named", &[("title", title)]),
+      None => i18n::t("notify.deadline.unnamed"),
+    };
+    show(app, &i18n::t("notify.deadline.title"), &body, ClickAction::Open(target));
+  }
+}
+
+/// Called when the main window gains focus. Desktop notifications don't
+/// report clicks, so focusing the app shortly after one routes to its item.
+pub fn on_focus(app: &AppHandle) {
+  let target = app.state::<NotificationState>().0.lock().unwrap().target.take();
+  let Some((action, shown_at)) = target else {
+    return;
+  };
+  if shown_at.elapsed() > CLICK_WINDOW {
+    return;
+  }
+  match action {
+    ClickAction::Open(url) => {
+      if let Ok(url) = Url::parse(&url) {
+        deeplink::handle(app, &url);
+      }
+    }
+    ClickAction::Ingest(url) => confirm_ingest(app, url),
+  }
+}
+
+fn confirm_ingest(app: &AppHandle, url: String) {
+  let handle = app.clone();
+  app
+    .dialog()
+    .message(i18n::t_with("clipboard.confirm.message", &[("url", &url)]))
+    .title(i18n::t("notify.clipboard.title"))
+    .buttons(MessageDialogButtons::OkCancelCustom(
+      i18n::t("clipboard.confirm.add"),
+      i18n::t("clipboard.confirm.cancel"),
+    ))
+    .show(move |add| {
+      if add {
+        clipboard::ingest(&handle, url);
+      }
+    });
+}
+
+/// Turns selected engine events into native notifications. New jobs are
+/// collected for a few seconds so a scrape produces one notification.
+pub fn handle(app: &AppHandle, event: &serde_json::Value) {
+  let Ok(event) = serde_json::from_value::<EngineEvent>(event.clone()) else {
+    return;
+  };
+  let Some((_, category)) = CATEGORIES.iter().find(|(kind, _)| *kind == event.kind) else {
+    return;
+  };
+  if !enabled(app, category) {
+    return;
+  }
+
+  if event.kind != "job_created" {
+    if !main_focused(app) {
+      notify(app, &event);
+    }
+    return;
+  }
+
+  let start_batch = {
+    let state = app.state::<NotificationState>();
+    let mut pending = state.0.lock().unwrap();
+    pending.new_count += 1;
+    pending.new_jobs.extend(event.id);
+    !std::mem::replace(&mut pending.batching, true)
+  };
+  if start_batch {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+      tokio::time::sleep(BATCH_WINDOW).await;
+      notify_new_jobs(&app);
+    });
+  }
+}
@@ -0,0 +1,322 @@
+use std::ffi::OsStr;
+use std::fs::{File, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, Signal, System, UpdateKind};
+
+pub const PID_FILE: &str = "engine.pid";
+// The flock the engine takes on its data dir; see engine/cmd/engine/main.go.
+pub const LOCK_FILE: &str = "engine.lock";
+// How long an orphan gets to exit after SIGTERM, and again after SIGKILL.
+const TERM_WAIT: Duration = Duration::from_secs(3);
+const KILL_WAIT: Duration = Duration::from_secs(3);
+const KILL_POLL: Duration = Duration::from_millis(100);
+
+/// `engine.pid`: written when the app spawns an engine, removed when it exits.
+/// One left behind means the previous session ended without cleaning up.
+#[derive(Serialize, Deserialize)]
+struct PidFile {
+  pid: u32,
+  /// The app process that spawned it.
+  app_pid: u32,
+  /// Unix seconds.
+  started: u64,
+}
+
+fn now_secs() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default()
+}
+
+pub fn write(data_dir: &Path, pid: u32) {
+  let record = PidFile {
+    pid,
+    app_pid: std::process::id(),
+    started: now_secs(),
+  };
+  let result = serde_json::to_string(&record)
+    .map_err(|e| e.to_string())
+    .and_then(|json| std::fs::write(data_dir.join(PID_FILE), json).map_err(|e| e.to_string()));
+  if let Err(e) = result {
+    eprintln!("[pidfile] could not record engine pid: {}", e);
+  }
+}
+
+pub fn remove(data_dir: &Path) {
+  let _ = std::fs::remove_file(data_dir.join(PID_FILE));
+}
+
+fn read(data_dir: &Path) -> Option<PidFile> {
+  let text = std::fs::read_to_string(data_dir.join(PID_FILE)).ok()?;
+  serde_json::from_str(&text).ok()
+}
+
+fn is_engine_binary(name: &OsStr) -> bool {
+  name.to_string_lossy().to_lowercase().starts_with("engine")
+}
+
+/// Whether `process` is an engine serving `data_dir`: the right binary, and
+/// either running in the data dir or pointed at it through the environment.
+fn is_our_engine(process: &Process, data_dir: &Path) -> bool {
+  if !is_engine_binary(process.name()) {
+    return false;
+  }
+  let same = |p: &Path| p == data_dir || p.canonicalize().is_ok_and(|p| p == data_dir);
+  if process.cwd().is_some_and(same) {
+    return true;
+  }
+  process.environ().iter().any(|var| {
+    var
+      .to_string_lossy()
+      .strip_prefix("JOBHUNT_DATA_DIR=")
+      .is_some_and(|dir| same(Path::new(dir)))
+  })
+}
+
+/// Whether some process still holds the engine's lock on `data_dir`.
+fn lock_held(data_dir: &Path) -> bool {
+  let Ok(file) = File::open(data_dir.join(LOCK_FILE)) else {
+    return false;
+  };
+  match file.try_lock() {
+    Ok(()) => {
+      let _ = file.unlock();
+      false
+    }
+    Err(TryLockError::WouldBlock) => true,
+    Err(TryLockError::Error(_)) => false,
+  }
+}
+
+fn refresh(sys: &mut System, which: ProcessesToUpdate) {
+  sys.refresh_processes_specifics(
+    which,
+    true,
+    ProcessRefreshKind::nothing()
+      .with_cwd(UpdateKind::Always)
+      .with_environ(UpdateKind::Always),
+  );
+}
+
+/// Engines left over from an earlier session that still serve `data_dir`.
+/// The pidfile names the usual suspect and is checked alone; every process
+/// is only scanned when that comes up empty but something still holds the
+/// engine lock, e.g. an engine whose pidfile was lost.
+fn stale_engines(sys: &mut System, data_dir: &Path, recorded: Option<&PidFile>) -> Vec<Pid> {
+  let own = Pid::from_u32(std::process::id());
+  let ours = |pid: &Pid, process: &Process| {
+    *pid != own && process.parent() != Some(own) && is_our_engine(process, data_dir)
+  };
+  if let Some(record) = recorded {
+    let pid = Pid::from_u32(record.pid);
+    refresh(sys, ProcessesToUpdate::Some(&[pid]));
+    if sys.process(pid).is_some_and(|p| ours(&pid, p)) {
+      return vec![pid];
+    }
+  }
+  if !lock_held(data_dir) {
+    return Vec::new();
+  }
+  refresh(sys, ProcessesToUpdate::All);
+  sys
+    .processes()
+    .iter()
+    .filter(|(pid, process)| ours(pid, process))
+    .map(|(pid, _)| *pid)
+    .collect()
+}
+
+async fn wait_gone(sys: &mut System, pids: &[Pid], timeout: Duration) -> Vec<Pid> {
+  let deadline = Instant::now() + timeout;
+  loop {
+    sys.refresh_processes_specifics(
+      ProcessesToUpdate::Some(pids),
+      true,
+      ProcessRefreshKind::nothing(),
+    );
+    let alive: Vec<Pid> = pids
+      .iter()
+      .copied()
+      .filter(|p| sys.process(*p).is_some())
+      .collect();
+    if alive.is_empty() || Instant::now() >= deadline {
+      return alive;
+    }
+    tokio::time::sleep(KILL_POLL).await;
+  }
+}
+
+/// Runs before spawning an engine: stops any orphaned engine from a previous
+/// session that still holds `data_dir`, asking first (SIGTERM, so it can
+/// close the database) and killing it if that doesn't work, then clears its
+/// pidfile and lock so the new engine can start. Fails if an orphan refuses
+/// to die.
+pub async fn reap_stale(data_dir: &Path) -> Result<(), String> {
+  let data_dir: PathBuf = data_dir
+    .canonicalize()
+    .unwrap_or_else(|_| data_dir.to_path_buf());
+  let recorded = read(&data_dir);
+  if let Some(record) = &recorded {
+    println!(
+      "[pidfile] found pidfile for engine {} (spawned by app {})",
+      record.pid, record.app_pid
+    );
+  }
+
+  let mut sys = System::new();
+  let stale = stale_engines(&mut sys, &data_dir, recorded.as_ref());
+  let mut survivors = stale.clone();
+  if !stale.is_empty() {
+    // Windows has no SIGTERM; there kill_with returns None and we go straight to killing.
+    let mut asked = false;
+    for pid in &stale {
+      eprintln!("[pidfile] stopping orphaned engine {}", pid);
+      if let Some(process) = sys.process(*pid) {
+        asked |= process.kill_with(Signal::Term).unwrap_or(false);
+      }
+    }
+    if asked {
+      survivors = wait_gone(&mut sys, &survivors, TERM_WAIT).await;
+    }
+    for pid in &survivors {
+      eprintln!("[pidfile] killing orphaned engine {}", pid);
+      if let Some(process) = sys.process(*pid) {
+        process.kill();
+      }
+    }
+    survivors = wait_gone(&mut sys, &survivors, KILL_WAIT).await;
+  }
+  if !survivors.is_empty() {
+    let list: Vec<String> = survivors.iter().map(|p| p.to_string()).collect();
+    return Err(format!(
+      "An engine from a previous session (pid {}) is still using {} and could not be stopped.",
+      list.join(", "),
+      data_dir.display()
+    ));
+  }
+
+  if recorded.is_some() || !stale.is_empty() {
+    // Nothing is left that could hold the lock, so the file is only debris.
+    remove(&data_dir);
+    let _ = std::fs::remove_file(data_dir.join(LOCK_FILE));
+    println!("[pidfile] cleared stale engine lock");
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jobhunt-pidfile-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.canonicalize().unwrap()
+  }
+
+  fn reap(dir: &Path) -> Result<(), String> {
+    tauri::async_runtime::block_on(reap_stale(dir))
+  }
+
+  /// Runs a copy of `sleep` named `name` in `dir` under a shell, so that like
+  /// an orphan it isn't our child. Returns the shell and the sleeper's pid.
+  #[cfg(unix)]
+  fn sleeper(dir: &Path, name: &str) -> (std::process::Child, u32) {
+    use std::io::BufRead;
+    let sleep = ["/bin/sleep", "/usr/bin/sleep"]
+      .into_iter()
+      .find(|p| Path::new(p).exists())
+      .unwrap();
+    std::fs::copy(sleep, dir.join(name)).unwrap();
+    let mut shell = std::process::Command::new("sh")
+      .args(["-c", &format!("./{} 30 & echo $!; wait", name)])
+      .current_dir(dir)
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap();
+    let mut line = String::new();
+    std::io::BufReader::new(shell.stdout.take().unwrap())
+      .read_line(&mut line)
+      .unwrap();
+    (shell, line.trim().parse().unwrap())
+  }
+
+  #[test]
+  fn write_read_and_remove_round_trip() {
+    let dir = temp_dir("round-trip");
+    write(&dir, 4242);
+    let record = read(&dir).unwrap();
+    assert_eq!(record.pid, 4242);
+    assert_eq!(record.app_pid, std::process::id());
+    remove(&dir);
+    assert!(read(&dir).is_none());
+  }
+
+  #[test]
+  fn lock_held_only_while_another_handle_holds_it() {
+    let dir = temp_dir("lock");
+    assert!(!lock_held(&dir));
+    let file = File::create(dir.join(LOCK_FILE)).unwrap();
+    file.lock().unwrap();
+    assert!(lock_held(&dir));
+    drop(file);
+    assert!(!lock_held(&dir));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn reaping_a_dead_engine_clears_its_pidfile_and_lock() {
+    let dir = temp_dir("dead");
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    child.wait().unwrap();
+    write(&dir, child.id());
+    std::fs::write(dir.join(LOCK_FILE), "").unwrap();
+
+    reap(&dir).unwrap();
+    assert!(!dir.join(PID_FILE).exists());
+    assert!(!dir.join(LOCK_FILE).exists());
+  }
+
+  #[test]
+  fn reaping_leaves_the_app_itself_alone() {
+    let dir = temp_dir("self");
+    write(&dir, std::process::id());
+    reap(&dir).unwrap();
+    assert!(!dir.join(PID_FILE).exists());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn reaping_stops_an_orphaned_engine() {
+    let dir = temp_dir("orphan");
+    let (mut shell, pid) = sleeper(&dir, "engine");
+    write(&dir, pid);
+
+    reap(&dir).unwrap();
+    assert!(!dir.join(PID_FILE).exists());
+    // The shell waits on its sleeper, so this returns at once only if it was stopped.
+    let started = Instant::now();
+    shell.wait().unwrap();
+    assert!(started.elapsed() < Duration::from_secs(10));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn reaping_skips_a_recorded_pid_that_is_not_an_engine() {
+    let dir = temp_dir("reused");
+    let (mut shell, pid) = sleeper(&dir, "sleeper");
+    write(&dir, pid);
+
+    reap(&dir).unwrap();
+    let mut sys = System::new();
+    refresh(&mut sys, ProcessesToUpdate::Some(&[Pid::from_u32(pid)]));
+    assert!(sys.process(Pid::from_u32(pid)).is_some());
+    std::process::Command::new("kill").arg(pid.to_string()).status().unwrap();
+    shell.wait().unwrap();
+  }
+}
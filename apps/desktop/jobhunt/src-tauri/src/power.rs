@@ -0,0 +1,450 @@
+//! Tells the engine about sleep/wake and AC/battery changes so its scheduler
+//! can pause and catch up, and checks the engine is still answering on wake.
+//!
+//! Windows reports suspend and resume directly. Elsewhere a wake is noticed
+//! when the wall clock jumps past a timer tick, which is what a suspended
+//! process sees; the power source is polled from the OS.
+//!
+//! The power source, and how long the user has been idle when a setting holds
+//! work until then, decide whether background work (scheduled runs, the
+//! engine's own polling) may go ahead under the user's settings. Reading idle
+//! time starts a process on macOS and Linux, so it is skipped otherwise.
+
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::engine::{self, EngineState};
+use crate::{proxy, settings};
+
+const TICK: Duration = Duration::from_secs(5);
+// A tick that overshoots by this much on the wall clock means the machine slept.
+const SLEEP_GAP: Duration = Duration::from_secs(30);
+const POWER_POLL_TICKS: u32 = 6;
+// A wake reported by both Windows and the clock check within this window is one wake.
+const RESUME_DEDUPE: Duration = Duration::from_secs(60);
+const HEALTH_ATTEMPTS: u32 = 3;
+const HEALTH_RETRY: Duration = Duration::from_secs(2);
+// A resume the engine misses leaves its polls paused, so it is retried.
+const RESUME_ATTEMPTS: u32 = 5;
+const RESUME_RETRY: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerEvent {
+  Suspend,
+  Resume,
+  Ac,
+  Battery,
+}
+
+#[derive(Clone, Serialize)]
+struct PowerChanged {
+  event: PowerEvent,
+  slept_secs: Option<u64>,
+}
+
+/// Sent as `activity-changed` when background work becomes allowed or held.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Activity {
+  /// `None` when the machine has no battery, or it couldn't be read.
+  pub on_battery: Option<bool>,
+  /// Seconds since the last keyboard or mouse input, if the OS reports it
+  /// and `background_min_idle_mins` is set.
+  pub idle_secs: Option<u64>,
+  /// Whether background work may run under the current settings.
+  pub allowed: bool,
+}
+
+#[derive(Default)]
+pub struct PowerState {
+  last_resume: Mutex<Option<SystemTime>>,
+  activity: Mutex<Option<Activity>>,
+  // Whether the engine has taken the current hold or release.
+  engine_synced: Mutex<bool>,
+}
+
+#[cfg(windows)]
+mod imp {
+  use std::sync::OnceLock;
+
+  use tauri::AppHandle;
+  use windows_sys::Win32::Foundation::HANDLE;
+  use windows_sys::Win32::System::Power::{
+    GetSystemPowerStatus, PowerRegisterSuspendResumeNotification,
+    DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS, SYSTEM_POWER_STATUS,
+  };
+  use windows_sys::Win32::System::SystemInformation::GetTickCount;
+  use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+  use windows_sys::Win32::UI::WindowsAndMessaging::{
+    DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND,
+  };
+
+  static APP: OnceLock<AppHandle> = OnceLock::new();
+
+  unsafe extern "system" fn on_power(
+    _context: *const core::ffi::c_void,
+    kind: u32,
+    _setting: *const core::ffi::c_void,
+  ) -> u32 {
+    if let Some(app) = APP.get() {
+      match kind {
+        PBT_APMSUSPEND => super::suspended(app),
+        PBT_APMRESUMEAUTOMATIC => super::resumed(app, None),
+        _ => {}
+      }
+    }
+    0
+  }
+
+  pub fn watch_suspend(app: &AppHandle) {
+    if APP.set(app.clone()).is_err() {
+      return;
+    }
+    // Registered for the life of the process, so the parameters are never freed.
+    let params = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+      Callback: Some(on_power),
+      Context: std::ptr::null_mut(),
+    }));
+    let mut registration = std::ptr::null_mut();
+    let err = unsafe {
+      PowerRegisterSuspendResumeNotification(
+        DEVICE_NOTIFY_CALLBACK,
+        params as *mut _ as HANDLE,
+        &mut registration,
+      )
+    };
+    if err != 0 {
+      eprintln!("[power] suspend notifications unavailable (error {})", err);
+    }
+  }
+
+  pub fn on_battery() -> Option<bool> {
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+      return None;
+    }
+    match status.ACLineStatus {
+      0 => Some(true),
+      1 => Some(false),
+      _ => None,
+    }
+  }
+
+  pub fn idle_secs() -> Option<u64> {
+    let mut info = LASTINPUTINFO {
+      cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+      dwTime: 0,
+    };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+      return None;
+    }
+    // Both are 32-bit tick counts; wrapping_sub survives the 49-day rollover.
+    let now = unsafe { GetTickCount() };
+    Some(u64::from(now.wrapping_sub(info.dwTime)) / 1000)
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+  use tauri::AppHandle;
+
+  pub fn watch_suspend(_app: &AppHandle) {}
+
+  pub fn on_battery() -> Option<bool> {
+    let out = std::process::Command::new("pmset")
+      .args(["-g", "batt"])
+      .output()
+      .ok()?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    if text.contains("'Battery Power'") {
+      Some(true)
+    } else if text.contains("'AC Power'") {
+      Some(false)
+    } else {
+      None
+    }
+  }
+
+  /// Seconds since the last input, from the HID system's idle time, which
+  /// it reports in nanoseconds.
+  pub fn idle_secs() -> Option<u64> {
+    let out = std::process::Command::new("ioreg")
+      .args(["-c", "IOHIDSystem", "-d", "4"])
+      .output()
+      .ok()?;
+    let text = String::from_utf8_lossy(&out.stdout);
+    let line = text.lines().find(|l| l.contains("\"HIDIdleTime\""))?;
+    let nanos: u64 = line.rsplit('=').next()?.trim().parse().ok()?;
+    Some(nanos / 1_000_000_000)
+  }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod imp {
+  use tauri::AppHandle;
+
+  pub fn watch_suspend(_app: &AppHandle) {}
+
+  /// On battery when every mains supply is offline; `None` without one (desktops).
+  pub fn on_battery() -> Option<bool> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut mains_online = None;
+    for entry in entries.flatten() {
+      let read = |name: &str| {
+        std::fs::read_to_string(entry.path().join(name))
+          .map(|s| s.trim().to_string())
+          .unwrap_or_default()
+      };
+      if read("type") == "Mains" {
+        mains_online = Some(mains_online.unwrap_or(false) || read("online") == "1");
+      }
+    }
+    mains_online.map(|online| !online)
+  }
+
+  fn run(program: &str, args: &[&str]) -> Option<String> {
+    let out = std::process::Command::new(program).args(args).output().ok()?;
+    if !out.status.success() {
+      return None;
+    }
+    Some(String::from_utf8_lossy(&out.stdout).into_owned())
+  }
+
+  /// From xprintidle on X11, or GNOME's idle monitor on Wayland; both report
+  /// milliseconds. `None` elsewhere.
+  pub fn idle_secs() -> Option<u64> {
+    if let Some(ms) = run("xprintidle", &[]).and_then(|s| s.trim().parse::<u64>().ok()) {
+      return Some(ms / 1000);
+    }
+    // Prints "(uint64 12345,)".
+    let out = run(
+      "gdbus",
+      &[
+        "call",
+        "--session",
+        "--dest",
+        "org.gnome.Mutter.IdleMonitor",
+        "--object-path",
+        "/org/gnome/Mutter/IdleMonitor/Core",
+        "--method",
+        "org.gnome.Mutter.IdleMonitor.GetIdletime",
+      ],
+    )?;
+    let digits: String = out
+      .trim_start_matches(|c: char| !c.is_ascii_digit())
+      .chars()
+      .take_while(|c| c.is_ascii_digit())
+      .collect();
+    Some(digits.parse::<u64>().ok()? / 1000)
+  }
+}
+
+async fn notify_engine(app: &AppHandle, event: PowerEvent) {
+  let body = serde_json::json!({ "event": event });
+  let attempts = match event {
+    PowerEvent::Resume => RESUME_ATTEMPTS,
+    _ => 1,
+  };
+  for attempt in 1..=attempts {
+    match proxy::engine_fetch(app, "POST", "/control/power", Some(body.clone())).await {
+      Ok(resp) if resp.status < 400 => return,
+      Ok(resp) => eprintln!("[power] engine returned HTTP {}: {}", resp.status, resp.body.trim()),
+      Err(e) => eprintln!("[power] could not notify engine of {:?}: {}", event, e),
+    }
+    if attempt < attempts {
+      tokio::time::sleep(RESUME_RETRY).await;
+    }
+  }
+}
+
+fn announce(app: &AppHandle, event: PowerEvent, slept: Option<Duration>) {
+  println!("[power] {:?}", event);
+  let _ = app.emit(
+    "power-changed",
+    PowerChanged {
+      event,
+      slept_secs: slept.map(|d| d.as_secs()),
+    },
+  );
+}
+
+/// Applies the background work settings to the power source and idle time.
+/// A condition the OS can't report doesn't hold work back.
+fn allowed(app: &AppHandle, on_battery: Option<bool>, idle_secs: Option<u64>) -> bool {
+  let s = settings::current(app);
+  if s.background_on_ac_only && on_battery == Some(true) {
+    return false;
+  }
+  match (s.background_min_idle_mins, idle_secs) {
+    (Some(mins), Some(idle)) => idle >= mins * 60,
+    _ => true,
+  }
+}
+
+/// Seconds since the last keyboard or mouse input, read now.
+pub async fn idle_secs() -> Option<u64> {
+  tauri::async_runtime::spawn_blocking(imp::idle_secs)
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Only the minimum idle setting needs the idle time on every poll.
+fn polls_idle(app: &AppHandle) -> bool {
+  settings::current(app).background_min_idle_mins.is_some()
+}
+
+/// The last polled activity, or a fresh reading before the first poll.
+pub fn activity(app: &AppHandle) -> Activity {
+  if let Some(activity) = *app.state::<PowerState>().activity.lock().unwrap() {
+    return activity;
+  }
+  let on_battery = imp::on_battery();
+  let idle_secs = polls_idle(app).then(imp::idle_secs).flatten();
+  Activity {
+    on_battery,
+    idle_secs,
+    allowed: allowed(app, on_battery, idle_secs),
+  }
+}
+
+/// Whether scheduled background work may run now.
+pub fn background_allowed(app: &AppHandle) -> bool {
+  activity(app).allowed
+}
+
+/// Records a new reading, and tells the UI and engine when work becomes
+/// allowed or held. A hold is re-sent every poll, so a restarted engine,
+/// which starts out unheld, picks it up; a release is re-sent every poll
+/// until the engine has taken it, so a missed one can't leave work held.
+async fn update_activity(app: &AppHandle, on_battery: Option<bool>) {
+  let idle_secs = if polls_idle(app) {
+    idle_secs().await
+  } else {
+    None
+  };
+  let current = Activity {
+    on_battery,
+    idle_secs,
+    allowed: allowed(app, on_battery, idle_secs),
+  };
+  let state = app.state::<PowerState>();
+  let previous = state.activity.lock().unwrap().replace(current);
+  let changed = previous.is_none_or(|p| p.allowed != current.allowed);
+  if changed {
+    println!(
+      "[power] background work {}",
+      if current.allowed { "allowed" } else { "held" }
+    );
+    let _ = app.emit("activity-changed", current);
+    *state.engine_synced.lock().unwrap() = false;
+  }
+  if current.allowed && *state.engine_synced.lock().unwrap() {
+    return;
+  }
+  let body = serde_json::json!({ "held": !current.allowed, "idleSecs": idle_secs });
+  let synced = match proxy::engine_fetch(app, "POST", "/control/activity", Some(body)).await {
+    Ok(resp) if resp.status < 400 => true,
+    Ok(resp) => {
+      eprintln!("[power] engine returned HTTP {}: {}", resp.status, resp.body.trim());
+      false
+    }
+    Err(e) => {
+      eprintln!("[power] could not send activity to engine: {}", e);
+      false
+    }
+  };
+  *state.engine_synced.lock().unwrap() = synced;
+}
+
+/// Restarts the engine if it stopped answering while the machine slept.
+async fn recheck_engine(app: &AppHandle) {
+  if *app.state::<EngineState>().attached.lock().unwrap() || !engine::is_running(app) {
+    return;
+  }
+  for attempt in 1..=HEALTH_ATTEMPTS {
+    let endpoint = app.state::<EngineState>().info.lock().unwrap().endpoint.clone();
+    if let Some(endpoint) = endpoint {
+      if engine::ping_health(&endpoint).await {
+        return;
+      }
+    }
+    if attempt < HEALTH_ATTEMPTS {
+      tokio::time::sleep(HEALTH_RETRY).await;
+    }
+  }
+  eprintln!("[power] engine unresponsive after wake; restarting");
+  engine::record_error(app, "engine stopped responding after the system woke".into());
+  if let Err(e) = engine::restart_engine(app).await {
+    eprintln!("[power] restart failed: {}", e);
+  }
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn suspended(app: &AppHandle) {
+  announce(app, PowerEvent::Suspend, None);
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move { notify_engine(&app, PowerEvent::Suspend).await });
+}
+
+fn resumed(app: &AppHandle, slept: Option<Duration>) {
+  {
+    let state = app.state::<PowerState>();
+    let mut last = state.last_resume.lock().unwrap();
+    let now = SystemTime::now();
+    let recent = last
+      .and_then(|t| now.duration_since(t).ok())
+      .is_some_and(|d| d < RESUME_DEDUPE);
+    if recent {
+      return;
+    }
+    *last = Some(now);
+  }
+  announce(app, PowerEvent::Resume, slept);
+  let app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    recheck_engine(&app).await;
+    notify_engine(&app, PowerEvent::Resume).await;
+  });
+}
+
+/// Watches for wake-ups and power source changes for the life of the app.
+pub fn start(app: AppHandle) {
+  imp::watch_suspend(&app);
+  tauri::async_runtime::spawn(async move {
+    let mut last_tick = SystemTime::now();
+    let mut on_battery = imp::on_battery();
+    let mut ticks = 0u32;
+    loop {
+      tokio::time::sleep(TICK).await;
+
+      let now = SystemTime::now();
+      let elapsed = now.duration_since(last_tick).unwrap_or_default();
+      last_tick = now;
+      if elapsed > TICK + SLEEP_GAP {
+        resumed(&app, Some(elapsed - TICK));
+      }
+
+      ticks = ticks.wrapping_add(1);
+      if !ticks.is_multiple_of(POWER_POLL_TICKS) {
+        continue;
+      }
+      let current = imp::on_battery();
+      if current.is_some() && current != on_battery {
+        on_battery = current;
+        let event = if current == Some(true) {
+          PowerEvent::Battery
+        } else {
+          PowerEvent::Ac
+        };
+        announce(&app, event, None);
+        notify_engine(&app, event).await;
+      }
+      update_activity(&app, on_battery).await;
+    }
+  });
+}
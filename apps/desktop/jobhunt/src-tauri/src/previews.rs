@@ -0,0 +1,143 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::attachments::{self, Attachment};
+use crate::engine;
+
+const PREVIEW_DIR: &str = "previews";
+// Long enough to read a job spec, short enough that copies don't pile up.
+const PREVIEW_TTL: Duration = Duration::from_secs(60 * 60);
+const SWEEP_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+fn preview_dir(app: &AppHandle) -> Result<PathBuf, String> {
+  Ok(engine::data_dir(app)?.join(PREVIEW_DIR))
+}
+
+/// Creates `dir` readable only by the current user. On Windows the data
+/// dir's inherited ACL already limits it to the user.
+fn create_private_dir(dir: &Path) -> Result<(), String> {
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    std::fs::DirBuilder::new()
+      .recursive(true)
+      .mode(0o700)
+      .create(dir)
+      .map_err(|e| e.to_string())?;
+    // A dir that already existed keeps its old mode unless reset.
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).map_err(|e| e.to_string())
+  }
+  #[cfg(windows)]
+  {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())
+  }
+}
+
+fn write_private_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+  let mut options = std::fs::OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  let mut file = options.open(path).map_err(|e| e.to_string())?;
+  file.write_all(bytes).map_err(|e| e.to_string())
+}
+
+fn random_id() -> Result<String, String> {
+  let mut bytes = [0u8; 8];
+  getrandom::fill(&mut bytes).map_err(|e| e.to_string())?;
+  Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Downloads `attachment` from the engine into a private preview folder.
+/// The copy is removed after `PREVIEW_TTL`, or when the app exits.
+pub async fn private_copy(app: &AppHandle, attachment: &Attachment) -> Result<PathBuf, String> {
+  let bytes = attachments::contents(app, attachment).await?;
+
+  // A folder per preview keeps the original file name for the viewer's title
+  // bar without two previews of same-named files colliding.
+  let dir = preview_dir(app)?;
+  create_private_dir(&dir)?;
+  let folder = dir.join(random_id()?);
+  create_private_dir(&folder)?;
+  // The name came from the user's file system; never let it leave the folder.
+  let name = Path::new(&attachment.name)
+    .file_name()
+    .map(|n| n.to_os_string())
+    .unwrap_or_else(|| attachment.id.clone().into());
+  let path = folder.join(name);
+  write_private_file(&path, &bytes)?;
+  println!(
+    "[previews] copied {} ({} bytes)",
+    attachment.name,
+    bytes.len()
+  );
+  Ok(path)
+}
+
+/// Opens a private copy of attachment `id` in the default viewer.
+pub async fn open_preview(app: &AppHandle, id: &str) -> Result<(), String> {
+  let attachment = attachments::find(app, id).await?;
+  let path = private_copy(app, &attachment).await?;
+  app
+    .opener()
+    .open_path(path.to_string_lossy(), None::<&str>)
+    .map_err(|e| e.to_string())
+}
+
+/// Removes previews last touched more than `ttl` ago; `None` removes them all.
+/// A viewer that still has a file open can keep it from being removed on
+/// Windows; it is retried on the next sweep.
+fn sweep(app: &AppHandle, ttl: Option<Duration>) {
+  let Ok(dir) = preview_dir(app) else {
+    return;
+  };
+  let Ok(entries) = std::fs::read_dir(&dir) else {
+    return;
+  };
+  let now = SystemTime::now();
+  for entry in entries.flatten() {
+    let path = entry.path();
+    let expired = match ttl {
+      None => true,
+      Some(ttl) => entry
+        .metadata()
+        .and_then(|m| m.modified())
+        .map(|modified| now.duration_since(modified).unwrap_or_default() >= ttl)
+        .unwrap_or(true),
+    };
+    if !expired {
+      continue;
+    }
+    let removed = if path.is_dir() {
+      std::fs::remove_dir_all(&path)
+    } else {
+      std::fs::remove_file(&path)
+    };
+    if let Err(e) = removed {
+      eprintln!("[previews] could not remove {}: {}", path.display(), e);
+    }
+  }
+}
+
+/// Removes every preview. Called on exit.
+pub fn clear(app: &AppHandle) {
+  sweep(app, None);
+}
+
+/// Removes expired previews every `SWEEP_INTERVAL`, starting with any left
+/// behind by a previous run that didn't exit cleanly.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      sweep(&app, Some(PREVIEW_TTL));
+      tokio::time::sleep(SWEEP_INTERVAL).await;
+    }
+  });
+}
@@ -0,0 +1,532 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::{i18n, proxy};
+
+const LABEL_PREFIX: &str = "print-";
+
+// US Letter in points, with 0.75in margins.
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 54.0;
+const FOOTER_SIZE: f32 = 8.0;
+
+// Helvetica advance widths (per 1000 em) for ' '..='~'; the standard 14
+// fonts need no embedding, but the writer has to measure text itself.
+const HELVETICA: [u16; 95] = [
+  278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+  556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+  611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+  667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+  222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+const HELVETICA_BOLD: [u16; 95] = [
+  278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+  556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+  611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+  667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+  278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum PrintMode {
+  Print,
+  Pdf,
+}
+
+impl PrintMode {
+  fn parse(s: &str) -> Result<Self, String> {
+    match s.to_lowercase().as_str() {
+      "print" => Ok(PrintMode::Print),
+      "pdf" => Ok(PrintMode::Pdf),
+      other => Err(format!("unknown print mode {:?}; use print or pdf", other)),
+    }
+  }
+}
+
+/// A job as returned by the engine's `/jobs/{id}`.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct JobSummary {
+  company: String,
+  title: String,
+  location: String,
+  work_mode: String,
+  score: i64,
+  tags: Option<Vec<String>>,
+  date: String,
+  seen_from_source: String,
+  url: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct JobDescription {
+  description: String,
+}
+
+async fn fetch<T: for<'de> Deserialize<'de>>(app: &AppHandle, path: &str) -> Result<T, String> {
+  let resp = proxy::engine_fetch(app, "GET", path, None).await?;
+  if resp.status == 404 {
+    return Err("job not found".into());
+  }
+  if resp.status >= 400 {
+    return Err(format!("engine returned HTTP {}: {}", resp.status, resp.body.trim()));
+  }
+  serde_json::from_str(&resp.body).map_err(|e| e.to_string())
+}
+
+/// Prints a job's summary, either through the system print dialog or as a
+/// PDF saved where the user picks. Returns the PDF's path in `pdf` mode.
+pub async fn print_application(
+  app: &AppHandle,
+  job_id: i64,
+  mode: &str,
+) -> Result<Option<String>, String> {
+  if job_id <= 0 {
+    return Err(format!("invalid job id {}", job_id));
+  }
+  match PrintMode::parse(mode)? {
+    PrintMode::Print => open_print_view(app, job_id).map(|_| None),
+    PrintMode::Pdf => save_pdf(app, job_id).await.map(Some),
+  }
+}
+
+/// Opens the job in a light, print-styled window; the page calls
+/// `print_window` once its content has loaded.
+fn open_print_view(app: &AppHandle, job_id: i64) -> Result<(), String> {
+  let label = format!("{}{}", LABEL_PREFIX, job_id);
+  if let Some(window) = app.get_webview_window(&label) {
+    window.set_focus().map_err(|e| e.to_string())?;
+    return window.print().map_err(|e| e.to_string());
+  }
+  WebviewWindowBuilder::new(
+    app,
+    &label,
+    WebviewUrl::App(format!("job.html?id={}&print=1", job_id).into()),
+  )
+  .title(i18n::t_with("window.print", &[("id", &job_id.to_string())]))
+  .inner_size(720.0, 860.0)
+  .focused(true)
+  .build()
+  .map_err(|e| e.to_string())?;
+  println!("[printing] opened print view for job {}", job_id);
+  Ok(())
+}
+
+/// Shows the native print dialog for a print window.
+pub fn print_window(window: &WebviewWindow) -> Result<(), String> {
+  if !window.label().starts_with(LABEL_PREFIX) {
+    return Err("only print windows can be printed".into());
+  }
+  window.print().map_err(|e| e.to_string())
+}
+
+async fn save_pdf(app: &AppHandle, job_id: i64) -> Result<String, String> {
+  let job: JobSummary = fetch(app, &format!("/jobs/{}", job_id)).await?;
+  let desc: JobDescription = fetch(app, &format!("/jobs/{}/description", job_id)).await?;
+
+  let dest: PathBuf = app
+    .dialog()
+    .file()
+    .set_title(i18n::t("dialog.pdf"))
+    .set_file_name(format!("{}.pdf", file_stem(&job)))
+    .add_filter("PDF", &["pdf"])
+    .blocking_save_file()
+    .ok_or("Save cancelled")?
+    .into_path()
+    .map_err(|e| e.to_string())?;
+
+  let pdf = render(&job, &plain_text(&desc.description));
+  std::fs::write(&dest, pdf).map_err(|e| e.to_string())?;
+  println!("[printing] saved job {} to {}", job_id, dest.display());
+  Ok(dest.to_string_lossy().to_string())
+}
+
+fn file_stem(job: &JobSummary) -> String {
+  let name = format!("{} - {}", job.company, job.title);
+  let clean: String = name
+    .chars()
+    .map(|c| if c.is_control() || r#"\/:*?"<>|"#.contains(c) { '_' } else { c })
+    .collect();
+  let clean = clean.trim().trim_matches('.');
+  if clean.is_empty() || clean == "-" {
+    "application".into()
+  } else {
+    clean.chars().take(120).collect()
+  }
+}
+
+/// Reduces a posting's HTML to text, keeping paragraph and list breaks.
+fn plain_text(html: &str) -> String {
+  let mut out = String::new();
+  let mut rest = html;
+  while let Some(start) = rest.find('<') {
+    out.push_str(&rest[..start]);
+    let Some(end) = rest[start..].find('>') else {
+      rest = &rest[start..];
+      break;
+    };
+    let tag = rest[start + 1..start + end].trim().to_lowercase();
+    let name = tag.trim_start_matches('/').split([' ', '/']).next().unwrap_or("");
+    match name {
+      "br" | "p" | "div" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "ul" | "ol" => {
+        out.push('\n')
+      }
+      "li" if !tag.starts_with('/') => out.push_str("\n• "),
+      _ => {}
+    }
+    rest = &rest[start + end + 1..];
+  }
+  out.push_str(rest);
+
+  let text = decode_entities(&out);
+  let mut result = String::new();
+  let mut blank = 0;
+  for line in text.lines().map(|l| l.split_whitespace().collect::<Vec<_>>().join(" ")) {
+    if line.is_empty() {
+      blank += 1;
+      continue;
+    }
+    if !result.is_empty() {
+      result.push_str(if blank > 0 { "\n\n" } else { "\n" });
+    }
+    blank = 0;
+    result.push_str(&line);
+  }
+  result
+}
+
+fn decode_entities(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  let mut rest = s;
+  while let Some(amp) = rest.find('&') {
+    out.push_str(&rest[..amp]);
+    rest = &rest[amp..];
+    let Some(semi) = rest[..rest.len().min(10)].find(';') else {
+      out.push('&');
+      rest = &rest[1..];
+      continue;
+    };
+    let entity = &rest[1..semi];
+    let decoded = match entity {
+      "amp" => Some('&'),
+      "lt" => Some('<'),
+      "gt" => Some('>'),
+      "quot" => Some('"'),
+      "apos" => Some('\''),
+      "nbsp" => Some(' '),
+      _ => entity
+        .strip_prefix("#x")
+        .or_else(|| entity.strip_prefix("#X"))
+        .and_then(|h| u32::from_str_radix(h, 16).ok())
+        .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+        .and_then(char::from_u32),
+    };
+    match decoded {
+      Some(c) => {
+        out.push(c);
+        rest = &rest[semi + 1..];
+      }
+      None => {
+        out.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  out.push_str(rest);
+  out
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Font {
+  Regular,
+  Bold,
+}
+
+impl Font {
+  fn resource(self) -> &'static str {
+    match self {
+      Font::Regular => "F1",
+      Font::Bold => "F2",
+    }
+  }
+
+  fn width(self, text: &str, size: f32) -> f32 {
+    let table = match self {
+      Font::Regular => &HELVETICA,
+      Font::Bold => &HELVETICA_BOLD,
+    };
+    let units: u32 = text
+      .chars()
+      .map(|c| match c {
+        ' '..='~' => table[c as usize - 32] as u32,
+        _ => 556,
+      })
+      .sum();
+    units as f32 * size / 1000.0
+  }
+}
+
+/// A run of text placed on a page.
+struct Placed {
+  x: f32,
+  y: f32,
+  font: Font,
+  size: f32,
+  text: String,
+}
+
+/// Flows paragraphs onto pages, top to bottom.
+struct Layout {
+  pages: Vec<Vec<Placed>>,
+  y: f32,
+}
+
+impl Layout {
+  fn new() -> Self {
+    Layout {
+      pages: vec![Vec::new()],
+      y: PAGE_HEIGHT - MARGIN,
+    }
+  }
+
+  fn gap(&mut self, points: f32) {
+    self.y -= points;
+  }
+
+  fn paragraph(&mut self, text: &str, font: Font, size: f32) {
+    let leading = size * 1.4;
+    for line in wrap(text, font, size, PAGE_WIDTH - 2.0 * MARGIN) {
+      if self.y - leading < MARGIN + FOOTER_SIZE * 2.0 {
+        self.pages.push(Vec::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+      }
+      self.y -= leading;
+      self.pages.last_mut().unwrap().push(Placed {
+        x: MARGIN,
+        y: self.y,
+        font,
+        size,
+        text: line,
+      });
+    }
+  }
+}
+
+/// Greedy word wrap; words wider than the line are broken by character.
+fn wrap(text: &str, font: Font, size: f32, max: f32) -> Vec<String> {
+  let mut lines = Vec::new();
+  let mut line = String::new();
+  for word in text.split_whitespace() {
+    let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+    if font.width(&candidate, size) <= max {
+      line = candidate;
+      continue;
+    }
+    if !line.is_empty() {
+      lines.push(std::mem::take(&mut line));
+    }
+    for c in word.chars() {
+      if !line.is_empty() && font.width(&format!("{}{}", line, c), size) > max {
+        lines.push(std::mem::take(&mut line));
+      }
+      line.push(c);
+    }
+  }
+  if !line.is_empty() || lines.is_empty() {
+    lines.push(line);
+  }
+  lines
+}
+
+fn render(job: &JobSummary, description: &str) -> Vec<u8> {
+  let mut layout = Layout::new();
+  layout.paragraph(&job.title, Font::Bold, 18.0);
+  layout.paragraph(&job.company, Font::Regular, 13.0);
+  layout.gap(6.0);
+
+  let date = if job.date.starts_with("0001-") {
+    String::new()
+  } else {
+    i18n::engine_date(&job.date)
+  };
+  let score = i18n::t_with("pdf.score", &[("score", &job.score.to_string())]);
+  let meta: Vec<&str> = [
+    job.location.as_str(),
+    job.work_mode.as_str(),
+    score.as_str(),
+    date.as_str(),
+    job.seen_from_source.as_str(),
+  ]
+  .into_iter()
+  .filter(|s| !s.is_empty())
+  .collect();
+  layout.paragraph(&meta.join("  ·  "), Font::Regular, 10.0);
+  let tags = job.tags.as_deref().unwrap_or_default();
+  if !tags.is_empty() {
+    let tags = i18n::t_with("pdf.tags", &[("tags", &tags.join(", "))]);
+    layout.paragraph(&tags, Font::Regular, 10.0);
+  }
+  if !job.url.is_empty() {
+    layout.paragraph(&job.url, Font::Regular, 9.0);
+  }
+
+  layout.gap(12.0);
+  layout.paragraph(&i18n::t("pdf.description"), Font::Bold, 12.0);
+  layout.gap(2.0);
+  if description.is_empty() {
+    layout.paragraph(&i18n::t("pdf.no_description"), Font::Regular, 10.0);
+  }
+  for para in description.split("\n\n") {
+    for line in para.lines() {
+      layout.paragraph(line, Font::Regular, 10.0);
+    }
+    layout.gap(5.0);
+  }
+
+  let printed = i18n::date(chrono::Utc::now().date_naive());
+  let total = layout.pages.len().to_string();
+  for (i, page) in layout.pages.iter_mut().enumerate() {
+    let footer = i18n::t_with(
+      "pdf.footer",
+      &[
+        ("date", &printed),
+        ("page", &(i + 1).to_string()),
+        ("total", &total),
+      ],
+    );
+    page.push(Placed {
+      x: MARGIN,
+      y: MARGIN - FOOTER_SIZE,
+      font: Font::Regular,
+      size: FOOTER_SIZE,
+      text: footer,
+    });
+  }
+  write_pdf(&layout.pages, &format!("{} — {}", job.title, job.company))
+}
+
+/// Maps a char to its WinAnsiEncoding byte, the encoding the standard fonts use.
+fn win_ansi(c: char) -> u8 {
+  match c {
+    ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+    '€' => 0x80,
+    '‚' => 0x82,
+    'ƒ' => 0x83,
+    '„' => 0x84,
+    '…' => 0x85,
+    '†' => 0x86,
+    '‡' => 0x87,
+    'ˆ' => 0x88,
+    '‰' => 0x89,
+    'Š' => 0x8a,
+    '‹' => 0x8b,
+    'Œ' => 0x8c,
+    'Ž' => 0x8e,
+    '‘' => 0x91,
+    '’' => 0x92,
+    '“' => 0x93,
+    '”' => 0x94,
+    '•' => 0x95,
+    '–' => 0x96,
+    '—' => 0x97,
+    '˜' => 0x98,
+    '™' => 0x99,
+    'š' => 0x9a,
+    '›' => 0x9b,
+    'œ' => 0x9c,
+    'ž' => 0x9e,
+    'Ÿ' => 0x9f,
+    _ => b'?',
+  }
+}
+
+/// A PDF string literal in WinAnsiEncoding.
+fn pdf_string(text: &str) -> Vec<u8> {
+  let mut out = vec![b'('];
+  for c in text.chars() {
+    let b = win_ansi(c);
+    if matches!(b, b'(' | b')' | b'\\') {
+      out.push(b'\\');
+    }
+    out.push(b);
+  }
+  out.push(b')');
+  out
+}
+
+fn write_pdf(pages: &[Vec<Placed>], title: &str) -> Vec<u8> {
+  // Objects 1-5 are fixed; each page then takes a page and a content object.
+  let mut objects: Vec<Vec<u8>> = Vec::new();
+  let page_ids: Vec<usize> = (0..pages.len()).map(|i| 6 + i * 2).collect();
+  objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+  let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+  objects.push(
+    format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes(),
+  );
+  for base in ["Helvetica", "Helvetica-Bold"] {
+    objects.push(
+      format!(
+        "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+        base
+      )
+      .into_bytes(),
+    );
+  }
+  let mut info = b"<< /Producer (JobHunt) /Title ".to_vec();
+  info.extend(pdf_string(title));
+  info.extend(b" >>");
+  objects.push(info);
+
+  for (page, id) in pages.iter().zip(&page_ids) {
+    let mut content = Vec::new();
+    for p in page {
+      content.extend(
+        format!("BT /{} {} Tf {:.2} {:.2} Td ", p.font.resource(), p.size, p.x, p.y).into_bytes(),
+      );
+      content.extend(pdf_string(&p.text));
+      content.extend(b" Tj ET\n");
+    }
+    objects.push(
+      format!(
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+         /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+        PAGE_WIDTH,
+        PAGE_HEIGHT,
+        id + 1
+      )
+      .into_bytes(),
+    );
+    let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+    stream.extend(content);
+    stream.extend(b"endstream");
+    objects.push(stream);
+  }
+
+  let mut out = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+  let mut offsets = Vec::with_capacity(objects.len());
+  for (i, body) in objects.iter().enumerate() {
+    offsets.push(out.len());
+    out.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+    out.extend(body);
+    out.extend(b"\nendobj\n");
+  }
+  let xref = out.len();
+  out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+  for offset in offsets {
+    out.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+  }
+  out.extend(
+    format!(
+      "trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{}\n%%EOF\n",
+      objects.len() + 1,
+      xref
+    )
+    .into_bytes(),
+  );
+  out
+}
@@ -0,0 +1,92 @@
+//! Ties the engine's lifetime to ours so a crashed or force-killed app never
+//! leaves an orphaned engine holding the port and data dir lock.
+//!
+//! On Windows the engine is placed in a Job Object that the OS kills when our
+//! last handle to it closes, which happens however the app exits. Unix has no
+//! portable equivalent we can apply from outside the child, so the engine is
+//! handed our PID via `JOBHUNT_PARENT_PID` and exits when it gets reparented.
+
+#[cfg(windows)]
+mod imp {
+  use std::sync::OnceLock;
+
+  use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+  use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+    SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+  };
+  use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
+
+  struct Job(HANDLE);
+
+  // The handle is only ever passed to thread-safe Win32 calls.
+  unsafe impl Send for Job {}
+  unsafe impl Sync for Job {}
+
+  // Never closed on purpose: the OS closes it when we exit, killing the job.
+  static JOB: OnceLock<Option<Job>> = OnceLock::new();
+
+  fn create_job() -> Option<Job> {
+    unsafe {
+      let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+      if job.is_null() {
+        return None;
+      }
+
+      let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+      info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+      let ok = SetInformationJobObject(
+        job,
+        JobObjectExtendedLimitInformation,
+        &info as *const _ as *const _,
+        std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+      );
+      if ok == 0 {
+        CloseHandle(job);
+        return None;
+      }
+      Some(Job(job))
+    }
+  }
+
+  pub fn adopt(pid: u32) -> Result<(), String> {
+    let job = JOB
+      .get_or_init(create_job)
+      .as_ref()
+      .ok_or("could not create job object")?;
+
+    unsafe {
+      let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+      if process.is_null() {
+        return Err(std::io::Error::last_os_error().to_string());
+      }
+      let ok = AssignProcessToJobObject(job.0, process);
+      CloseHandle(process);
+      if ok == 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(not(windows))]
+mod imp {
+  // The engine watches JOBHUNT_PARENT_PID itself; nothing to do from here.
+  pub fn adopt(_pid: u32) -> Result<(), String> {
+    Ok(())
+  }
+}
+
+/// Binds the engine process `pid` to the app's lifetime.
+pub fn adopt(pid: u32) {
+  if let Err(e) = imp::adopt(pid) {
+    eprintln!("[procguard] could not bind engine {} to app lifetime: {}", pid, e);
+  }
+}
+
+/// Env passed to the engine so it can notice when the app is gone.
+pub fn parent_pid_env() -> (&'static str, String) {
+  ("JOBHUNT_PARENT_PID", std::process::id().to_string())
+}
@@ -0,0 +1,255 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::cli;
+use crate::engine::{self, EngineState};
+use crate::settings::{self, Profile, Settings};
+use crate::{splash, vault, windowstate};
+
+/// The implicit profile that uses the top-level `data_dir` setting.
+pub const DEFAULT_PROFILE: &str = "default";
+const PROFILES_DIR: &str = "profiles";
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct ProfileState {
+  // True while the splash is waiting for the user to pick a profile.
+  picking: Mutex<bool>,
+  // A profile given with --profile, active for this launch only.
+  launch: Mutex<Option<String>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ProfileInfo {
+  pub name: String,
+  pub data_dir: String,
+  pub active: bool,
+}
+
+/// What the splash needs to decide whether to show the profile picker.
+#[derive(Clone, Serialize)]
+pub struct LaunchProfiles {
+  pub pick: bool,
+  pub profiles: Vec<ProfileInfo>,
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+  let valid = !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ' '));
+  if valid {
+    Ok(())
+  } else {
+    Err(format!("invalid profile name {:?}: use letters, digits, spaces, - and _", name))
+  }
+}
+
+/// The data dir for profile `name` under `settings`.
+pub fn data_dir(app: &AppHandle, settings: &Settings, name: &str) -> Result<PathBuf, String> {
+  let base = app.path().app_data_dir().map_err(|e| e.to_string())?;
+  data_dir_in(&base, settings, name)
+}
+
+/// [`data_dir`] with the app data dir given as `base`.
+pub fn data_dir_in(base: &Path, settings: &Settings, name: &str) -> Result<PathBuf, String> {
+  if name == DEFAULT_PROFILE {
+    if let Some(dir) = &settings.data_dir {
+      return Ok(dir.clone());
+    }
+    return Ok(base.to_path_buf());
+  }
+  let profile = settings
+    .profiles
+    .iter()
+    .find(|p| p.name == name)
+    .ok_or_else(|| format!("no profile named {:?}", name))?;
+  if let Some(dir) = &profile.data_dir {
+    return Ok(dir.clone());
+  }
+  Ok(base.join(PROFILES_DIR).join(&profile.name))
+}
+
+/// The profile saved as active in `settings`.
+pub fn active_name(settings: &Settings) -> &str {
+  settings.active_profile.as_deref().unwrap_or(DEFAULT_PROFILE)
+}
+
+/// The profile this launch runs: the one given with --profile, if any,
+/// otherwise the saved one.
+pub fn active(app: &AppHandle) -> String {
+  if let Some(name) = &*app.state::<ProfileState>().launch.lock().unwrap() {
+    return name.clone();
+  }
+  active_name(&settings::current(app)).to_string()
+}
+
+pub fn list(app: &AppHandle) -> Vec<ProfileInfo> {
+  let settings = settings::current(app);
+  let active = active(app);
+  let active = active.as_str();
+  std::iter::once(DEFAULT_PROFILE)
+    .chain(settings.profiles.iter().map(|p| p.name.as_str()))
+    .map(|name| ProfileInfo {
+      name: name.to_string(),
+      data_dir: data_dir(app, &settings, name)
+        .map(|d| d.to_string_lossy().to_string())
+        .unwrap_or_default(),
+      active: name == active,
+    })
+    .collect()
+}
+
+pub fn create(app: &AppHandle, name: &str, dir: Option<PathBuf>) -> Result<(), String> {
+  validate_name(name)?;
+  let mut next = settings::current(app);
+  if name == DEFAULT_PROFILE || next.profiles.iter().any(|p| p.name == name) {
+    return Err(format!("profile {:?} already exists", name));
+  }
+  next.profiles.push(Profile {
+    name: name.to_string(),
+    data_dir: dir,
+  });
+  settings::replace(app, next)
+}
+
+/// Forgets a profile. Its data dir is left on disk.
+pub fn delete(app: &AppHandle, name: &str) -> Result<(), String> {
+  let mut next = settings::current(app);
+  if active_name(&next) == name || active(app) == name {
+    return Err("cannot delete the active profile; switch to another one first".into());
+  }
+  let before = next.profiles.len();
+  next.profiles.retain(|p| p.name != name);
+  if next.profiles.len() == before {
+    return Err(format!("no profile named {:?}", name));
+  }
+  settings::replace(app, next)
+}
+
+fn set_active(app: &AppHandle, name: &str) -> Result<(), String> {
+  let mut next = settings::current(app);
+  // Resolving the dir doubles as the existence check.
+  data_dir(app, &next, name)?;
+  next.active_profile = (name != DEFAULT_PROFILE).then(|| name.to_string());
+  settings::replace(app, next)?;
+  *app.state::<ProfileState>().launch.lock().unwrap() = None;
+  Ok(())
+}
+
+/// Makes `name` active for this launch only, leaving the saved profile alone.
+fn set_launch(app: &AppHandle, name: &str) -> Result<(), String> {
+  data_dir(app, &settings::current(app), name)?;
+  *app.state::<ProfileState>().launch.lock().unwrap() = Some(name.to_string());
+  Ok(())
+}
+
+/// Stops the engine, makes `name` the active profile and boots an engine
+/// against its data dir. Falls back to the previous profile if that fails.
+pub async fn switch(app: &AppHandle, name: &str) -> Result<(), String> {
+  activate(app, name, set_active).await
+}
+
+/// [`switch`] for a `--profile` flag passed to a second launch: the profile
+/// is active until the app quits, but not saved.
+pub async fn switch_for_launch(app: &AppHandle, name: &str) -> Result<(), String> {
+  activate(app, name, set_launch).await
+}
+
+async fn activate(
+  app: &AppHandle,
+  name: &str,
+  select: fn(&AppHandle, &str) -> Result<(), String>,
+) -> Result<(), String> {
+  if *app.state::<EngineState>().attached.lock().unwrap() {
+    return Err("attached to an external engine; profiles are not available".into());
+  }
+  if cli::args().data_dir.is_some() {
+    return Err("the data directory is pinned by --data-dir for this session".into());
+  }
+  let previous = active(app);
+  if previous == name {
+    return Ok(());
+  }
+  windowstate::save(app);
+  let restore = app.state::<ProfileState>().launch.lock().unwrap().clone();
+  select(app, name)?;
+
+  engine::stop_engine(app).await;
+  // An encrypted profile starts once the user has entered its passphrase.
+  if vault::is_locked(app) {
+    windowstate::restore(app);
+    vault::unlock_on_launch(app.clone(), false);
+    println!("[profiles] switched {:?} -> {:?}; waiting for the passphrase", previous, name);
+    return Ok(());
+  }
+  let started = match engine::spawn_engine(app).await {
+    Ok(()) => engine::wait_for_ready(app, READY_TIMEOUT).await.map(|_| ()),
+    Err(e) => Err(e.into()),
+  };
+  if let Err(e) = started {
+    engine::stop_engine(app).await;
+    match restore {
+      Some(launch) => set_launch(app, &launch)?,
+      None => set_active(app, &previous)?,
+    }
+    engine::spawn_engine(app).await?;
+    return Err(format!("could not start profile {:?}: {}", name, e));
+  }
+
+  windowstate::restore(app);
+  println!("[profiles] switched {:?} -> {:?}", previous, name);
+  Ok(())
+}
+
+/// Applies a `--profile` flag from the command line before the engine starts,
+/// for this launch only. Returns true if the splash should offer a picker
+/// instead of starting right away.
+pub fn resolve_on_launch(app: &AppHandle, interactive: bool) -> bool {
+  let picking = match &cli::args().profile {
+    Some(name) => {
+      if let Err(e) = set_launch(app, name) {
+        eprintln!("[profiles] {}", e);
+      }
+      false
+    }
+    None => {
+      let settings = settings::current(app);
+      interactive && settings.pick_profile_on_launch && !settings.profiles.is_empty()
+    }
+  };
+  *app.state::<ProfileState>().picking.lock().unwrap() = picking;
+  picking
+}
+
+pub fn launch_profiles(app: &AppHandle) -> LaunchProfiles {
+  LaunchProfiles {
+    pick: *app.state::<ProfileState>().picking.lock().unwrap(),
+    profiles: list(app),
+  }
+}
+
+/// Called from the splash picker: starts the engine on the chosen profile.
+pub async fn select_on_launch(app: &AppHandle, name: &str) -> Result<(), String> {
+  {
+    let state = app.state::<ProfileState>();
+    let mut picking = state.picking.lock().unwrap();
+    if !*picking {
+      return Err("no profile selection pending".into());
+    }
+    set_active(app, name)?;
+    *picking = false;
+  }
+  windowstate::restore(app);
+  if vault::is_locked(app) {
+    vault::unlock_on_launch(app.clone(), true);
+    return Ok(());
+  }
+  engine::spawn_engine(app).await?;
+  splash::start(app.clone(), None);
+  Ok(())
+}
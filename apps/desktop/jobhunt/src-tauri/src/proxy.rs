@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{oneshot, Notify};
+
+use crate::engine::{self, EngineState};
+use crate::settings;
+use crate::transport::{self, Endpoint};
+
+// Must match the engine's internal/httpapi/signing.go.
+const TIMESTAMP_HEADER: &str = "X-JobHunt-Timestamp";
+const NONCE_HEADER: &str = "X-JobHunt-Nonce";
+const SIGNATURE_HEADER: &str = "X-JobHunt-Signature";
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BASE: Duration = Duration::from_millis(200);
+const READY_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_CONCURRENCY: usize = 8;
+const CANCELLED: &str = "request cancelled";
+
+#[derive(Serialize)]
+pub struct FetchResponse {
+  pub status: u16,
+  pub headers: HashMap<String, String>,
+  pub body: String,
+}
+
+/// Live counts for the `engine_fetch` pool, for the diagnostics screen.
+#[derive(Clone, Serialize)]
+pub struct PoolStats {
+  pub limit: usize,
+  pub active: usize,
+  /// Requests waiting for a free slot.
+  pub queued: usize,
+  pub completed: u64,
+  pub failed: u64,
+  pub cancelled: u64,
+  pub retries: u64,
+}
+
+#[derive(Default)]
+struct Pool {
+  active: usize,
+  queued: usize,
+  completed: u64,
+  failed: u64,
+  cancelled: u64,
+  retries: u64,
+  // Requests the webview gave an id, so it can cancel them.
+  cancels: HashMap<String, oneshot::Sender<()>>,
+}
+
+/// Bounds how many `engine_fetch` requests reach the engine at once, so a
+/// bulk action in the UI queues instead of flooding it.
+#[derive(Default)]
+pub struct ProxyState {
+  pool: Mutex<Pool>,
+  freed: Notify,
+}
+
+// Held while a request is in flight; frees its slot even if the request is cancelled.
+struct Slot<'a>(&'a ProxyState);
+
+impl Drop for Slot<'_> {
+  fn drop(&mut self) {
+    self.0.pool.lock().unwrap().active -= 1;
+    self.0.freed.notify_waiters();
+  }
+}
+
+// Held while waiting for a slot.
+struct Waiting<'a>(&'a ProxyState);
+
+impl Drop for Waiting<'_> {
+  fn drop(&mut self) {
+    self.0.pool.lock().unwrap().queued -= 1;
+  }
+}
+
+fn concurrency(app: &AppHandle) -> usize {
+  settings::current(app)
+    .fetch_concurrency
+    .unwrap_or(DEFAULT_CONCURRENCY)
+    .max(1)
+}
+
+async fn acquire<'a>(app: &AppHandle, state: &'a ProxyState) -> Slot<'a> {
+  state.pool.lock().unwrap().queued += 1;
+  let _waiting = Waiting(state);
+  loop {
+    // Registered before checking, so a slot freed in between still wakes us.
+    let freed = state.freed.notified();
+    tokio::pin!(freed);
+    freed.as_mut().enable();
+    {
+      let mut pool = state.pool.lock().unwrap();
+      if pool.active < concurrency(app) {
+        pool.active += 1;
+        return Slot(state);
+      }
+    }
+    freed.await;
+  }
+}
+
+/// Wakes queued requests after `fetch_concurrency` changes, in case it grew.
+pub fn limit_changed(app: &AppHandle) {
+  app.state::<ProxyState>().freed.notify_waiters();
+}
+
+pub fn stats(app: &AppHandle) -> PoolStats {
+  let state = app.state::<ProxyState>();
+  let pool = state.pool.lock().unwrap();
+  PoolStats {
+    limit: concurrency(app),
+    active: pool.active,
+    queued: pool.queued,
+    completed: pool.completed,
+    failed: pool.failed,
+    cancelled: pool.cancelled,
+    retries: pool.retries,
+  }
+}
+
+/// Cancels the `engine_fetch` started with `id`, whether it is queued or in
+/// flight. Returns false if no such request is pending.
+pub fn cancel(app: &AppHandle, id: &str) -> bool {
+  let sender = app.state::<ProxyState>().pool.lock().unwrap().cancels.remove(id);
+  match sender {
+    Some(tx) => {
+      let _ = tx.send(());
+      true
+    }
+    None => false,
+  }
+}
+
+// Exponential, plus up to half again at random so a burst of failed requests
+// doesn't retry in lockstep.
+fn backoff(attempt: u32) -> Duration {
+  let base = RETRY_BASE * 2u32.pow(attempt - 1);
+  let mut bytes = [0u8; 2];
+  let _ = getrandom::fill(&mut bytes);
+  let jitter = u16::from_le_bytes(bytes) as f64 / u16::MAX as f64 / 2.0;
+  base + base.mul_f64(jitter)
+}
+
+// Retrying a POST could apply it twice, so only methods that are safe to repeat retry.
+fn is_idempotent(method: &Method) -> bool {
+  matches!(
+    *method,
+    Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+  )
+}
+
+fn is_transient(status: StatusCode) -> bool {
+  matches!(
+    status,
+    StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+  )
+}
+
+/// A request to the running engine. Send it with [`send`] so it gets signed.
+pub fn engine_request(app: &AppHandle, method: Method, path: &str) -> reqwest::RequestBuilder {
+  let endpoint = app.state::<EngineState>().info.lock().unwrap().endpoint.clone();
+  // Before the engine is serving this targets port 0, which fails to connect.
+  let endpoint = endpoint.unwrap_or(Endpoint::Tcp(0));
+  transport::client(&endpoint)
+    .request(method, endpoint.url(path))
+    .timeout(REQUEST_TIMEOUT)
+}
+
+/// HMAC-SHA256 over timestamp, nonce, method, path with query, and the body's
+/// hex SHA-256, keyed with this engine session's key.
+fn signature(
+  key: &str,
+  timestamp: u64,
+  nonce: &str,
+  method: &str,
+  path: &str,
+  body_sha256: &str,
+) -> String {
+  let mut mac =
+    Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+  mac.update(
+    format!(
+      "{}\n{}\n{}\n{}\n{}",
+      timestamp, nonce, method, path, body_sha256
+    )
+    .as_bytes(),
+  );
+  mac
+    .finalize()
+    .into_bytes()
+    .iter()
+    .map(|b| format!("{:02x}", b))
+    .collect()
+}
+
+/// Signs a request whose body, if any, is in memory. A streamed body can't be
+/// read here; sign those with [`sign_streamed`].
+pub fn sign(key: &str, req: &mut reqwest::Request) -> Result<(), String> {
+  let body = match req.body() {
+    None => &[][..],
+    Some(body) => body
+      .as_bytes()
+      .ok_or("a streamed body needs its SHA-256 to be signed")?,
+  };
+  let digest = format!("{:x}", Sha256::digest(body));
+  sign_streamed(key, req, &digest)
+}
+
+/// Signs a request with `body_sha256`, the hex SHA-256 of the body it will
+/// stream, computed by the caller from the source.
+pub fn sign_streamed(
+  key: &str,
+  req: &mut reqwest::Request,
+  body_sha256: &str,
+) -> Result<(), String> {
+  let timestamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_err(|e| e.to_string())?
+    .as_secs();
+  let mut nonce = [0u8; 16];
+  getrandom::fill(&mut nonce).map_err(|e| e.to_string())?;
+  let nonce: String = nonce.iter().map(|b| format!("{:02x}", b)).collect();
+  let url = req.url();
+  let path = match url.query() {
+    Some(q) => format!("{}?{}", url.path(), q),
+    None => url.path().to_string(),
+  };
+  let sig = signature(
+    key,
+    timestamp,
+    &nonce,
+    req.method().as_str(),
+    &path,
+    body_sha256,
+  );
+
+  let headers = req.headers_mut();
+  for (name, value) in [
+    (TIMESTAMP_HEADER, timestamp.to_string()),
+    (NONCE_HEADER, nonce),
+    (SIGNATURE_HEADER, sig),
+  ] {
+    let value = reqwest::header::HeaderValue::from_str(&value).map_err(|e| e.to_string())?;
+    headers.insert(name, value);
+  }
+  Ok(())
+}
+
+/// Why a request to the engine got no response.
+#[derive(Debug)]
+pub enum SendError {
+  /// There was no key to sign with, or signing failed, so nothing was sent:
+  /// the engine refuses unsigned requests on every route but /health.
+  Unsigned(String),
+  Http(reqwest::Error),
+}
+
+impl fmt::Display for SendError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SendError::Unsigned(e) => write!(f, "could not sign the engine request: {}", e),
+      SendError::Http(e) => e.fmt(f),
+    }
+  }
+}
+
+/// Signs and sends a request to the engine, usually one built by
+/// [`engine_request`]. Each request gets a fresh nonce, so the engine can
+/// refuse replays.
+pub async fn send(
+  app: &AppHandle,
+  req: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, SendError> {
+  send_signed(app, req, None).await
+}
+
+/// [`send`] for a request with a streamed body whose hex SHA-256 is `body_sha256`.
+pub async fn send_streamed(
+  app: &AppHandle,
+  req: reqwest::RequestBuilder,
+  body_sha256: &str,
+) -> Result<reqwest::Response, SendError> {
+  send_signed(app, req, Some(body_sha256)).await
+}
+
+async fn send_signed(
+  app: &AppHandle,
+  req: reqwest::RequestBuilder,
+  body_sha256: Option<&str>,
+) -> Result<reqwest::Response, SendError> {
+  let (client, req) = req.build_split();
+  let mut req = req.map_err(SendError::Http)?;
+  let key = app.state::<EngineState>().info.lock().unwrap().auth_key.clone();
+  let key = key.ok_or_else(|| SendError::Unsigned("no key for the engine".into()))?;
+  match body_sha256 {
+    Some(digest) => sign_streamed(&key, &mut req, digest),
+    None => sign(&key, &mut req),
+  }
+  .map_err(SendError::Unsigned)?;
+  client.execute(req).await.map_err(SendError::Http)
+}
+
+async fn send_once(
+  app: &AppHandle,
+  method: &Method,
+  path: &str,
+  body: Option<&serde_json::Value>,
+) -> Result<reqwest::Response, SendError> {
+  // Re-read each attempt: the engine may have restarted on a new endpoint/key.
+  let mut req = engine_request(app, method.clone(), path);
+  if let Some(b) = body {
+    req = req.json(b);
+  }
+  send(app, req).await
+}
+
+// `pool` is None for the app's own requests, which shouldn't wait behind the UI's.
+async fn fetch(
+  app: &AppHandle,
+  method: &Method,
+  path: &str,
+  body: Option<&serde_json::Value>,
+  pool: Option<&ProxyState>,
+) -> Result<FetchResponse, String> {
+  let attempts = if is_idempotent(method) { MAX_ATTEMPTS } else { 1 };
+
+  let mut last_err = String::new();
+  for attempt in 0..attempts {
+    if attempt > 0 {
+      if let Some(state) = pool {
+        state.pool.lock().unwrap().retries += 1;
+      }
+      tokio::time::sleep(backoff(attempt)).await;
+    }
+    engine::wait_for_ready(app, READY_TIMEOUT).await?;
+
+    // The slot is given back between attempts so backoff doesn't hold it.
+    let _slot = match pool {
+      Some(state) => Some(acquire(app, state).await),
+      None => None,
+    };
+    match send_once(app, method, path, body).await {
+      Ok(resp) if is_transient(resp.status()) && attempt + 1 < attempts => {
+        last_err = format!("engine returned HTTP {}", resp.status());
+      }
+      Ok(resp) => {
+        let status = resp.status().as_u16();
+        let headers = resp
+          .headers()
+          .iter()
+          .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+          .collect();
+        let body = resp.text().await.map_err(|e| e.to_string())?;
+        return Ok(FetchResponse {
+          status,
+          headers,
+          body,
+        });
+      }
+      Err(SendError::Http(e)) if e.is_connect() || e.is_timeout() => last_err = e.to_string(),
+      Err(e) => return Err(e.to_string()),
+    }
+  }
+
+  Err(last_err)
+}
+
+fn parse(method: &str, path: &str) -> Result<Method, String> {
+  if !path.starts_with('/') {
+    return Err(format!("path must start with '/': {}", path));
+  }
+  Method::from_bytes(method.to_uppercase().as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Sends a request to the engine for the app itself, retrying transient failures.
+pub async fn engine_fetch(
+  app: &AppHandle,
+  method: &str,
+  path: &str,
+  body: Option<serde_json::Value>,
+) -> Result<FetchResponse, String> {
+  let method = parse(method, path)?;
+  fetch(app, &method, path, body.as_ref(), None).await
+}
+
+/// Sends a request to the engine on the webview's behalf, so the endpoint and
+/// token never have to be handed to JS. With the engine on a socket, this is
+/// the only way the webview can reach it. At most `fetch_concurrency`
+/// requests run at once; given a `request_id`, the request can be cancelled
+/// with [`cancel`] while it waits or runs.
+pub async fn webview_fetch(
+  app: &AppHandle,
+  method: &str,
+  path: &str,
+  body: Option<serde_json::Value>,
+  request_id: Option<String>,
+) -> Result<FetchResponse, String> {
+  let method = parse(method, path)?;
+  let state = app.state::<ProxyState>();
+  let cancelled = match &request_id {
+    Some(id) => {
+      let mut pool = state.pool.lock().unwrap();
+      if pool.cancels.contains_key(id) {
+        return Err(format!("request id {} is already in use", id));
+      }
+      let (tx, rx) = oneshot::channel();
+      pool.cancels.insert(id.clone(), tx);
+      Some(rx)
+    }
+    None => None,
+  };
+
+  let pooled = fetch(app, &method, path, body.as_ref(), Some(state.inner()));
+  let (result, was_cancelled) = match cancelled {
+    Some(rx) => tokio::select! {
+      result = pooled => (result, false),
+      _ = rx => (Err(CANCELLED.to_string()), true),
+    },
+    None => (pooled.await, false),
+  };
+
+  let mut pool = state.pool.lock().unwrap();
+  if let Some(id) = &request_id {
+    pool.cancels.remove(id);
+  }
+  match &result {
+    Ok(_) => pool.completed += 1,
+    Err(_) if was_cancelled => pool.cancelled += 1,
+    Err(_) => pool.failed += 1,
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // The same vector is checked against Signer.mac in the engine's
+  // internal/httpapi/signing_test.go.
+  const KEY: &str = "test-key";
+  const VECTOR_MAC: &str = "a6a5237a3e5faecfe60ffce250fdab65bf6ac2efb2c11c310bc54f8003ac1429";
+
+  fn header(req: &reqwest::Request, name: &str) -> String {
+    req.headers()[name].to_str().unwrap().to_string()
+  }
+
+  /// Recomputes the signature `sign` put on `req` over `path`, the path and
+  /// query the engine will see.
+  fn check(req: &reqwest::Request, path: &str, body: &[u8]) {
+    let timestamp = header(req, TIMESTAMP_HEADER).parse().unwrap();
+    let expected = signature(
+      KEY,
+      timestamp,
+      &header(req, NONCE_HEADER),
+      req.method().as_str(),
+      path,
+      &format!("{:x}", Sha256::digest(body)),
+    );
+    assert_eq!(header(req, SIGNATURE_HEADER), expected, "{}", path);
+  }
+
+  fn request(method: Method, url: &str) -> reqwest::RequestBuilder {
+    reqwest::Client::new().request(method, url)
+  }
+
+  #[test]
+  fn signature_matches_the_engine_vector() {
+    let body_sha256 = format!("{:x}", Sha256::digest(br#"{"a":1}"#));
+    let sig = signature(
+      KEY,
+      1_700_000_000,
+      "nonce-1",
+      "POST",
+      "/api/jobs?q=a%20b&x=1",
+      &body_sha256,
+    );
+    assert_eq!(sig, VECTOR_MAC);
+  }
+
+  #[test]
+  fn sign_covers_the_escaped_path_and_query() {
+    let cases = [
+      ("http://127.0.0.1:1/health", "/health"),
+      (
+        "http://127.0.0.1:1/api/jobs?q=a b&x=1",
+        "/api/jobs?q=a%20b&x=1",
+      ),
+      (
+        "http://127.0.0.1:1/api/attachments/résumé.pdf?kind=cv",
+        "/api/attachments/r%C3%A9sum%C3%A9.pdf?kind=cv",
+      ),
+      (
+        "http://127.0.0.1:1/api/attachments/a%2Fb.pdf",
+        "/api/attachments/a%2Fb.pdf",
+      ),
+    ];
+    for (url, path) in cases {
+      let mut req = request(Method::GET, url).build().unwrap();
+      sign(KEY, &mut req).unwrap();
+      check(&req, path, b"");
+    }
+  }
+
+  #[test]
+  fn sign_covers_the_body() {
+    let mut req = request(Method::POST, "http://127.0.0.1:1/api/ingest")
+      .json(&serde_json::json!({ "a": 1 }))
+      .build()
+      .unwrap();
+    sign(KEY, &mut req).unwrap();
+    check(&req, "/api/ingest", br#"{"a":1}"#);
+  }
+
+  #[test]
+  fn sign_uses_a_fresh_nonce_each_time() {
+    let mut first = request(Method::GET, "http://127.0.0.1:1/jobs").build().unwrap();
+    let mut second = request(Method::GET, "http://127.0.0.1:1/jobs").build().unwrap();
+    sign(KEY, &mut first).unwrap();
+    sign(KEY, &mut second).unwrap();
+    assert_ne!(header(&first, NONCE_HEADER), header(&second, NONCE_HEADER));
+  }
+
+  #[test]
+  fn streamed_bodies_are_signed_with_their_digest() {
+    let stream = futures_util::stream::iter([Ok::<_, std::io::Error>(b"file".to_vec())]);
+    let mut req = request(Method::POST, "http://127.0.0.1:1/api/import?name=cv.pdf")
+      .body(reqwest::Body::wrap_stream(stream))
+      .build()
+      .unwrap();
+    assert!(sign(KEY, &mut req).is_err());
+
+    let digest = format!("{:x}", Sha256::digest(b"file"));
+    sign_streamed(KEY, &mut req, &digest).unwrap();
+    check(&req, "/api/import?name=cv.pdf", b"file");
+  }
+}
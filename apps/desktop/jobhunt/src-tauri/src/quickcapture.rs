@@ -0,0 +1,108 @@
+use tauri::{AppHandle, Manager, Url, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::{i18n, proxy, settings};
+
+pub const QUICK_CAPTURE_WINDOW: &str = "quick-capture";
+pub const DEFAULT_SHORTCUT: &str = "CommandOrControl+Shift+J";
+
+fn shortcut_setting(app: &AppHandle) -> String {
+  settings::current(app)
+    .quick_capture_shortcut
+    .unwrap_or_else(|| DEFAULT_SHORTCUT.to_string())
+}
+
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+  tauri_plugin_global_shortcut::Builder::new()
+    .with_handler(|app, _shortcut: &Shortcut, event: ShortcutEvent| {
+      if event.state() == ShortcutState::Pressed {
+        if let Err(e) = open(app) {
+          eprintln!("[quickcapture] {}", e);
+        }
+      }
+    })
+    .build()
+}
+
+/// Registers the configured shortcut. The app still starts if another
+/// program already owns it.
+pub fn init(app: &AppHandle) {
+  let shortcut = shortcut_setting(app);
+  if let Err(e) = app.global_shortcut().register(shortcut.as_str()) {
+    eprintln!("[quickcapture] could not register {}: {}", shortcut, e);
+  }
+}
+
+/// Swaps the registered shortcut for `shortcut` and persists it.
+pub fn set_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+  let parsed: Shortcut = shortcut.parse().map_err(|e| format!("{}", e))?;
+  let old = shortcut_setting(app);
+  let shortcuts = app.global_shortcut();
+  let _ = shortcuts.unregister(old.as_str());
+  if let Err(e) = shortcuts.register(parsed) {
+    let _ = shortcuts.register(old.as_str());
+    return Err(e.to_string());
+  }
+
+  let mut next = settings::current(app);
+  next.quick_capture_shortcut = Some(shortcut.to_string());
+  settings::replace(app, next)
+}
+
+/// Shows the quick-add window, creating it on first use.
+pub fn open(app: &AppHandle) -> tauri::Result<()> {
+  if let Some(window) = app.get_webview_window(QUICK_CAPTURE_WINDOW) {
+    window.show()?;
+    window.set_focus()?;
+    return Ok(());
+  }
+  WebviewWindowBuilder::new(
+    app,
+    QUICK_CAPTURE_WINDOW,
+    WebviewUrl::App("quick-capture.html".into()),
+  )
+  .title(i18n::t("window.quick_capture"))
+  .inner_size(420.0, 240.0)
+  .resizable(false)
+  .always_on_top(true)
+  .center()
+  .focused(true)
+  .build()?;
+  Ok(())
+}
+
+/// The clipboard contents if they look like a URL, to prefill the form.
+pub fn prefill(app: &AppHandle) -> Option<String> {
+  let text = app.clipboard().read_text().ok()?;
+  let url = Url::parse(text.trim()).ok()?;
+  matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+/// Sends the capture to the engine and closes the window.
+pub async fn submit(
+  app: &AppHandle,
+  url: String,
+  title: Option<String>,
+  company: Option<String>,
+) -> Result<bool, String> {
+  let body = serde_json::json!({
+    "url": url,
+    "title": title.unwrap_or_default(),
+    "company": company.unwrap_or_default(),
+    "source": "quick-capture",
+  });
+  let resp = proxy::engine_fetch(app, "POST", "/api/ingest", Some(body)).await?;
+  if resp.status >= 400 {
+    return Err(format!("engine returned HTTP {}: {}", resp.status, resp.body.trim()));
+  }
+  let added = serde_json::from_str::<serde_json::Value>(&resp.body)
+    .ok()
+    .and_then(|v| v.get("added")?.as_bool())
+    .unwrap_or(false);
+
+  if let Some(window) = app.get_webview_window(QUICK_CAPTURE_WINDOW) {
+    let _ = window.close();
+  }
+  Ok(added)
+}
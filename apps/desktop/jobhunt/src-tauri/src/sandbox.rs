@@ -0,0 +1,220 @@
+//! Runs the engine with fewer rights than the app, as defense in depth for
+//! a process that parses untrusted pages and mail.
+//!
+//! On Windows the engine goes into its own Job Object, nested in procguard's,
+//! that caps its memory and optionally CPU, and in strict mode cuts it off
+//! from the desktop and clipboard. The shell plugin can't run code between
+//! fork and exec, so on Unix the engine confines itself at startup from
+//! `JOBHUNT_HARDENING`: its own process group, no core dumps, a data size
+//! limit, and in strict mode a seccomp filter on Linux. Each restriction that
+//! can't be applied is logged and skipped rather than failing the launch.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::settings::{self, Settings};
+
+const DEFAULT_MEMORY_LIMIT_MB: u64 = 4096;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Hardening {
+  /// Spawn the engine with the app's full rights.
+  Off,
+  /// Memory (and, if set, CPU) limits and no core dumps.
+  #[default]
+  Standard,
+  /// Also block the desktop and clipboard on Windows and kernel-level
+  /// syscalls the engine never needs on Linux.
+  Strict,
+}
+
+impl Hardening {
+  fn as_str(self) -> &'static str {
+    match self {
+      Hardening::Off => "off",
+      Hardening::Standard => "standard",
+      Hardening::Strict => "strict",
+    }
+  }
+}
+
+struct Limits {
+  hardening: Hardening,
+  memory_mb: u64,
+  cpu_percent: Option<u8>,
+}
+
+fn limits_for(s: &Settings) -> Limits {
+  Limits {
+    hardening: s.engine_hardening,
+    memory_mb: s.engine_memory_limit_mb.unwrap_or(DEFAULT_MEMORY_LIMIT_MB),
+    cpu_percent: s.engine_cpu_percent.filter(|p| (1..100).contains(p)),
+  }
+}
+
+#[cfg(windows)]
+mod imp {
+  use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+  use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, JobObjectBasicUIRestrictions,
+    JobObjectCpuRateControlInformation, JobObjectExtendedLimitInformation, SetInformationJobObject,
+    JOBOBJECTINFOCLASS, JOBOBJECT_BASIC_UI_RESTRICTIONS, JOBOBJECT_CPU_RATE_CONTROL_INFORMATION,
+    JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JOB_OBJECT_CPU_RATE_CONTROL_ENABLE, JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+    JOB_OBJECT_LIMIT_DIE_ON_UNHANDLED_EXCEPTION, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+    JOB_OBJECT_UILIMIT_DESKTOP, JOB_OBJECT_UILIMIT_DISPLAYSETTINGS, JOB_OBJECT_UILIMIT_EXITWINDOWS,
+    JOB_OBJECT_UILIMIT_READCLIPBOARD, JOB_OBJECT_UILIMIT_SYSTEMPARAMETERS,
+    JOB_OBJECT_UILIMIT_WRITECLIPBOARD,
+  };
+  use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SET_QUOTA, PROCESS_TERMINATE};
+
+  use super::{Hardening, Limits};
+
+  fn set<T>(job: HANDLE, class: JOBOBJECTINFOCLASS, info: &T) -> Result<(), String> {
+    let ok = unsafe {
+      SetInformationJobObject(
+        job,
+        class,
+        info as *const T as *const _,
+        std::mem::size_of::<T>() as u32,
+      )
+    };
+    if ok == 0 {
+      return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(())
+  }
+
+  /// Sets each limit on `job`, reporting every one that was refused.
+  fn configure(job: HANDLE, limits: &Limits) -> Vec<(&'static str, Result<(), String>)> {
+    let mut results = Vec::new();
+
+    let mut memory: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+    memory.BasicLimitInformation.LimitFlags =
+      JOB_OBJECT_LIMIT_PROCESS_MEMORY | JOB_OBJECT_LIMIT_DIE_ON_UNHANDLED_EXCEPTION;
+    memory.ProcessMemoryLimit = (limits.memory_mb * 1024 * 1024) as usize;
+    results.push((
+      "memory limit",
+      set(job, JobObjectExtendedLimitInformation, &memory),
+    ));
+
+    if let Some(percent) = limits.cpu_percent {
+      let cpu = JOBOBJECT_CPU_RATE_CONTROL_INFORMATION {
+        ControlFlags: JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+        // In hundredths of a percent of all processors.
+        Anonymous: JOBOBJECT_CPU_RATE_CONTROL_INFORMATION_0 {
+          CpuRate: u32::from(percent) * 100,
+        },
+      };
+      results.push((
+        "CPU cap",
+        set(job, JobObjectCpuRateControlInformation, &cpu),
+      ));
+    }
+
+    // The filler's browser runs in this job too, so its windows stay usable.
+    if limits.hardening == Hardening::Strict {
+      let ui = JOBOBJECT_BASIC_UI_RESTRICTIONS {
+        UIRestrictionsClass: JOB_OBJECT_UILIMIT_DESKTOP
+          | JOB_OBJECT_UILIMIT_DISPLAYSETTINGS
+          | JOB_OBJECT_UILIMIT_EXITWINDOWS
+          | JOB_OBJECT_UILIMIT_READCLIPBOARD
+          | JOB_OBJECT_UILIMIT_SYSTEMPARAMETERS
+          | JOB_OBJECT_UILIMIT_WRITECLIPBOARD,
+      };
+      results.push((
+        "desktop and clipboard restrictions",
+        set(job, JobObjectBasicUIRestrictions, &ui),
+      ));
+    }
+    results
+  }
+
+  /// Puts `pid` in a new job holding `limits`. The job is nested in
+  /// procguard's, which needs Windows 8 or later. Our handle is closed
+  /// afterwards; the job, and its limits, live as long as the engine does.
+  pub fn restrict(pid: u32, limits: &Limits) -> Vec<(&'static str, Result<(), String>)> {
+    unsafe {
+      let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+      if job.is_null() {
+        let e = std::io::Error::last_os_error().to_string();
+        return vec![("job object", Err(e))];
+      }
+      let mut results = configure(job, limits);
+
+      let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, 0, pid);
+      let assigned = if process.is_null() {
+        Err(std::io::Error::last_os_error().to_string())
+      } else {
+        let ok = AssignProcessToJobObject(job, process);
+        CloseHandle(process);
+        if ok == 0 {
+          Err(std::io::Error::last_os_error().to_string())
+        } else {
+          Ok(())
+        }
+      };
+      CloseHandle(job);
+      // Limits on a job the engine never joined don't apply to anything.
+      if let Err(e) = assigned {
+        results = vec![("job object", Err(e))];
+      }
+      results
+    }
+  }
+}
+
+#[cfg(not(windows))]
+mod imp {
+  use super::Limits;
+
+  // The engine confines itself from `engine_env`, and logs what it couldn't
+  // apply. A CPU cap would need cgroups, which aren't ours to set up.
+  pub fn restrict(_pid: u32, limits: &Limits) -> Vec<(&'static str, Result<(), String>)> {
+    match limits.cpu_percent {
+      Some(_) => vec![("CPU cap", Err("only supported on Windows".into()))],
+      None => Vec::new(),
+    }
+  }
+}
+
+/// Env telling the engine how to confine itself on Unix.
+pub fn engine_env(app: &AppHandle) -> Vec<(&'static str, String)> {
+  engine_env_for(&settings::current(app))
+}
+
+/// [`engine_env`] for `settings`, for when there is no app to ask.
+pub fn engine_env_for(settings: &Settings) -> Vec<(&'static str, String)> {
+  let limits = limits_for(settings);
+  if limits.hardening == Hardening::Off {
+    return Vec::new();
+  }
+  vec![
+    ("JOBHUNT_HARDENING", limits.hardening.as_str().to_string()),
+    ("JOBHUNT_MEMORY_LIMIT_MB", limits.memory_mb.to_string()),
+  ]
+}
+
+/// Applies the OS-enforced limits to the freshly spawned engine `pid`.
+pub fn restrict(app: &AppHandle, pid: u32) {
+  restrict_for(&settings::current(app), pid)
+}
+
+/// [`restrict`] for `settings`, for when there is no app to ask.
+pub fn restrict_for(settings: &Settings, pid: u32) {
+  let limits = limits_for(settings);
+  if limits.hardening == Hardening::Off {
+    println!(
+      "[sandbox] hardening is off; engine {} runs unrestricted",
+      pid
+    );
+    return;
+  }
+  for (what, result) in imp::restrict(pid, &limits) {
+    match result {
+      Ok(()) => println!("[sandbox] engine {}: {} applied", pid, what),
+      Err(e) => eprintln!("[sandbox] engine {}: could not apply {}: {}", pid, what, e),
+    }
+  }
+}
@@ -0,0 +1,540 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, TimeDelta, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{engine, power, proxy};
+
+const SCHEDULE_FILE: &str = "schedules.json";
+const TICK: Duration = Duration::from_secs(30);
+// A run this late is treated as missed (e.g. the machine was asleep) rather than just slow.
+const LATE_AFTER: TimeDelta = TimeDelta::minutes(5);
+// How far ahead next_run() looks before deciding an expression never fires.
+const SEARCH_DAYS: i64 = 366 * 4;
+const ALLOWED_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// What to do about runs missed while the app or machine was asleep.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedRuns {
+  /// Fire once for all of them, as soon as possible.
+  #[default]
+  RunOnce,
+  /// Drop them and wait for the next scheduled time.
+  Skip,
+}
+
+/// A recurring engine request, e.g. `POST /scrape/run` at `0 8 * * *`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+  pub id: String,
+  pub name: String,
+  /// Five-field cron expression (minute hour day-of-month month day-of-week)
+  /// in local time, or one of `@hourly`, `@daily`, `@weekly`, `@monthly`.
+  pub cron: String,
+  pub method: String,
+  pub path: String,
+  pub body: Option<serde_json::Value>,
+  pub enabled: bool,
+  pub missed: MissedRuns,
+  pub next_run: Option<DateTime<Local>>,
+  pub last_run: Option<DateTime<Local>>,
+  /// HTTP status of the last run, or `None` if it never reached the engine.
+  pub last_status: Option<u16>,
+  pub last_error: Option<String>,
+}
+
+impl Default for Schedule {
+  fn default() -> Self {
+    Schedule {
+      id: String::new(),
+      name: String::new(),
+      cron: String::new(),
+      method: "POST".into(),
+      path: String::new(),
+      body: None,
+      enabled: true,
+      missed: MissedRuns::default(),
+      next_run: None,
+      last_run: None,
+      last_status: None,
+      last_error: None,
+    }
+  }
+}
+
+/// Sent as `schedule-run` after each run, or when missed runs are skipped.
+#[derive(Clone, Serialize)]
+pub struct ScheduleRun {
+  pub id: String,
+  pub name: String,
+  pub ok: bool,
+  pub status: Option<u16>,
+  pub error: Option<String>,
+  /// Scheduled times that passed while asleep and were folded into this run or skipped.
+  pub missed: u32,
+  pub skipped: bool,
+}
+
+/// Serializes read-modify-write of `schedules.json` between commands and the ticker.
+#[derive(Default)]
+pub struct SchedulerState(Mutex<()>);
+
+/// Schedules live with the profile's data, so each profile has its own.
+fn schedule_path(app: &AppHandle) -> Result<PathBuf, String> {
+  Ok(engine::data_dir(app)?.join(SCHEDULE_FILE))
+}
+
+fn load(app: &AppHandle) -> Result<Vec<Schedule>, String> {
+  let path = schedule_path(app)?;
+  match std::fs::read_to_string(&path) {
+    Ok(s) => serde_json::from_str(&s).map_err(|e| format!("{}: {}", path.display(), e)),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+fn save(app: &AppHandle, schedules: &[Schedule]) -> Result<(), String> {
+  let path = schedule_path(app)?;
+  if let Some(dir) = path.parent() {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+  }
+  let json = serde_json::to_string_pretty(schedules).map_err(|e| e.to_string())?;
+  std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Loads the schedules, applies `f`, and saves them, all under the state lock.
+fn update<T>(
+  app: &AppHandle,
+  f: impl FnOnce(&mut Vec<Schedule>) -> Result<T, String>,
+) -> Result<T, String> {
+  let state = app.state::<SchedulerState>();
+  let _guard = state.0.lock().unwrap();
+  let mut schedules = load(app)?;
+  let out = f(&mut schedules)?;
+  save(app, &schedules)?;
+  Ok(out)
+}
+
+/// One cron field as the set of values it allows.
+struct Field(Vec<bool>);
+
+impl Field {
+  fn parse(spec: &str, min: u32, max: u32) -> Result<Self, String> {
+    let mut allowed = vec![false; max as usize + 1];
+    for part in spec.split(',') {
+      let (range, step) = match part.split_once('/') {
+        Some((r, s)) => (r, s.parse::<u32>().map_err(|_| format!("bad step {:?}", s))?),
+        None => (part, 1),
+      };
+      if step == 0 {
+        return Err(format!("bad step in {:?}", part));
+      }
+      let (lo, hi) = match range {
+        "*" => (min, max),
+        r => match r.split_once('-') {
+          Some((a, b)) => (number(a)?, number(b)?),
+          None if step > 1 => (number(r)?, max),
+          None => (number(r)?, number(r)?),
+        },
+      };
+      if lo < min || hi > max || lo > hi {
+        return Err(format!("{:?} is outside {}-{}", part, min, max));
+      }
+      for v in (lo..=hi).step_by(step as usize) {
+        allowed[v as usize] = true;
+      }
+    }
+    Ok(Field(allowed))
+  }
+
+  fn has(&self, v: u32) -> bool {
+    self.0.get(v as usize).copied().unwrap_or(false)
+  }
+
+  fn is_any(&self, min: u32) -> bool {
+    self.0[min as usize..].iter().all(|b| *b)
+  }
+}
+
+fn number(s: &str) -> Result<u32, String> {
+  s.parse().map_err(|_| format!("bad number {:?}", s))
+}
+
+/// A parsed five-field cron expression.
+struct Cron {
+  minute: Field,
+  hour: Field,
+  dom: Field,
+  month: Field,
+  dow: Field,
+}
+
+impl Cron {
+  fn parse(expr: &str) -> Result<Self, String> {
+    let expr = match expr.trim() {
+      "@hourly" => "0 * * * *",
+      "@daily" | "@midnight" => "0 0 * * *",
+      "@weekly" => "0 0 * * 0",
+      "@monthly" => "0 0 1 * *",
+      other => other,
+    };
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let [minute, hour, dom, month, dow] = fields[..] else {
+      return Err(format!("expected 5 cron fields, got {}", fields.len()));
+    };
+    let mut dow = Field::parse(dow, 0, 7)?;
+    // Both 0 and 7 mean Sunday.
+    if dow.has(7) {
+      dow.0[0] = true;
+    }
+    Ok(Cron {
+      minute: Field::parse(minute, 0, 59)?,
+      hour: Field::parse(hour, 0, 23)?,
+      dom: Field::parse(dom, 1, 31)?,
+      month: Field::parse(month, 1, 12)?,
+      dow,
+    })
+  }
+
+  /// Standard cron rule: when both day fields are restricted, either may match.
+  fn day_matches(&self, t: &NaiveDateTime) -> bool {
+    let dom = self.dom.has(t.day());
+    let dow = self.dow.has(t.weekday().num_days_from_sunday());
+    match (self.dom.is_any(1), self.dow.is_any(0)) {
+      (true, _) => dow,
+      (_, true) => dom,
+      _ => dom || dow,
+    }
+  }
+
+  /// The first matching local time strictly after `after`.
+  fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+    let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+    let limit = start + TimeDelta::days(SEARCH_DAYS);
+    let mut t = start;
+    while t < limit {
+      if !self.month.has(t.month()) || !self.day_matches(&t) {
+        t = (t.date() + TimeDelta::days(1)).and_hms_opt(0, 0, 0)?;
+        continue;
+      }
+      if !self.hour.has(t.hour()) {
+        t = t.with_minute(0)? + TimeDelta::hours(1);
+        continue;
+      }
+      if !self.minute.has(t.minute()) {
+        t += TimeDelta::minutes(1);
+        continue;
+      }
+      // Times skipped by a DST jump don't exist; move on to the next match.
+      if let Some(local) = Local.from_local_datetime(&t).earliest() {
+        if local > after {
+          return Some(local);
+        }
+      }
+      t += TimeDelta::minutes(1);
+    }
+    None
+  }
+}
+
+/// Counts scheduled times in (`from`, `until`], capped so a long sleep stays cheap.
+fn count_between(cron: &Cron, from: DateTime<Local>, until: DateTime<Local>) -> u32 {
+  let mut n = 0;
+  let mut t = from;
+  while n < 1000 {
+    match cron.next_after(t) {
+      Some(next) if next <= until => {
+        n += 1;
+        t = next;
+      }
+      _ => break,
+    }
+  }
+  n
+}
+
+fn validate(s: &Schedule) -> Result<Cron, String> {
+  if s.name.trim().is_empty() {
+    return Err("schedule needs a name".into());
+  }
+  if !s.path.starts_with('/') {
+    return Err(format!("path must start with '/': {}", s.path));
+  }
+  if !ALLOWED_METHODS.contains(&s.method.to_uppercase().as_str()) {
+    return Err(format!("unsupported method {:?}", s.method));
+  }
+  Cron::parse(&s.cron).map_err(|e| format!("invalid cron {:?}: {}", s.cron, e))
+}
+
+fn new_id() -> Result<String, String> {
+  let mut buf = [0u8; 8];
+  getrandom::fill(&mut buf).map_err(|e| e.to_string())?;
+  Ok(buf.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+pub fn list(app: &AppHandle) -> Result<Vec<Schedule>, String> {
+  let state = app.state::<SchedulerState>();
+  let _guard = state.0.lock().unwrap();
+  load(app)
+}
+
+/// Adds `schedule`, or replaces the one with the same id. Returns it with its next run filled in.
+pub fn add(app: &AppHandle, mut schedule: Schedule) -> Result<Schedule, String> {
+  let cron = validate(&schedule)?;
+  schedule.method = schedule.method.to_uppercase();
+  schedule.next_run = cron.next_after(Local::now());
+  if schedule.next_run.is_none() {
+    return Err(format!("cron {:?} never fires", schedule.cron));
+  }
+  if schedule.id.is_empty() {
+    schedule.id = new_id()?;
+  }
+  update(app, |schedules| {
+    match schedules.iter_mut().find(|s| s.id == schedule.id) {
+      Some(existing) => *existing = schedule.clone(),
+      None => schedules.push(schedule.clone()),
+    }
+    Ok(())
+  })?;
+  println!("[scheduler] {} ({}) next at {:?}", schedule.name, schedule.cron, schedule.next_run);
+  Ok(schedule)
+}
+
+pub fn remove(app: &AppHandle, id: &str) -> Result<(), String> {
+  update(app, |schedules| {
+    let before = schedules.len();
+    schedules.retain(|s| s.id != id);
+    if schedules.len() == before {
+      return Err(format!("no schedule {:?}", id));
+    }
+    Ok(())
+  })
+}
+
+/// A due schedule, with how many runs it missed.
+struct Due {
+  schedule: Schedule,
+  missed: u32,
+}
+
+/// Picks the schedules that are due and advances their next run past now.
+/// Most ticks find nothing due, and then the file isn't rewritten.
+fn take_due(app: &AppHandle) -> Result<Vec<Due>, String> {
+  let now = Local::now();
+  let state = app.state::<SchedulerState>();
+  let _guard = state.0.lock().unwrap();
+  let mut schedules = load(app)?;
+  let mut changed = false;
+  let mut due = Vec::new();
+  for s in schedules.iter_mut().filter(|s| s.enabled) {
+    let Ok(cron) = Cron::parse(&s.cron) else {
+      continue;
+    };
+    let Some(next) = s.next_run else {
+      s.next_run = cron.next_after(now);
+      // An expression that never fires stays None; don't rewrite for it every tick.
+      changed |= s.next_run.is_some();
+      continue;
+    };
+    if next > now {
+      continue;
+    }
+    let late = now - next > LATE_AFTER;
+    let missed = if late { 1 + count_between(&cron, next, now) } else { 0 };
+    s.next_run = cron.next_after(now);
+    changed = true;
+    due.push(Due {
+      schedule: s.clone(),
+      missed,
+    });
+  }
+  if changed {
+    save(app, &schedules)?;
+  }
+  Ok(due)
+}
+
+async fn run(app: &AppHandle, due: Due) {
+  let s = due.schedule;
+  if due.missed > 0 && s.missed == MissedRuns::Skip {
+    println!("[scheduler] {} skipped {} missed run(s)", s.name, due.missed);
+    let _ = app.emit(
+      "schedule-run",
+      ScheduleRun {
+        id: s.id,
+        name: s.name,
+        ok: true,
+        status: None,
+        error: None,
+        missed: due.missed,
+        skipped: true,
+      },
+    );
+    return;
+  }
+
+  let result = proxy::engine_fetch(app, &s.method, &s.path, s.body.clone()).await;
+  let (status, error) = match &result {
+    Ok(resp) if resp.status >= 400 => {
+      (Some(resp.status), Some(format!("HTTP {}: {}", resp.status, resp.body.trim())))
+    }
+    Ok(resp) => (Some(resp.status), None),
+    Err(e) => (None, Some(e.clone())),
+  };
+  match &error {
+    Some(e) => eprintln!("[scheduler] {} failed: {}", s.name, e),
+    None => println!("[scheduler] {} ran ({} {})", s.name, s.method, s.path),
+  }
+
+  let id = s.id.clone();
+  let recorded = update(app, |schedules| {
+    if let Some(saved) = schedules.iter_mut().find(|x| x.id == id) {
+      saved.last_run = Some(Local::now());
+      saved.last_status = status;
+      saved.last_error = error.clone();
+    }
+    Ok(())
+  });
+  if let Err(e) = recorded {
+    eprintln!("[scheduler] could not record run: {}", e);
+  }
+  let _ = app.emit(
+    "schedule-run",
+    ScheduleRun {
+      id: s.id,
+      name: s.name,
+      ok: error.is_none(),
+      status,
+      error,
+      missed: due.missed,
+      skipped: false,
+    },
+  );
+}
+
+/// Checks for due schedules every `TICK` and fires them at the engine.
+/// While the power settings hold background work, due schedules wait; once
+/// released, a long wait counts as missed runs like a sleep would.
+pub fn start(app: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(TICK).await;
+      if !power::background_allowed(&app) {
+        continue;
+      }
+      let due = match take_due(&app) {
+        Ok(due) => due,
+        Err(e) => {
+          eprintln!("[scheduler] {}", e);
+          continue;
+        }
+      };
+      for d in due {
+        run(&app, d).await;
+      }
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Local> {
+    Local.with_ymd_and_hms(y, mo, d, h, mi, 0).earliest().unwrap()
+  }
+
+  fn next(expr: &str, after: DateTime<Local>) -> NaiveDateTime {
+    Cron::parse(expr)
+      .unwrap()
+      .next_after(after)
+      .unwrap()
+      .naive_local()
+  }
+
+  fn naive(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> NaiveDateTime {
+    local(y, mo, d, h, mi).naive_local()
+  }
+
+  #[test]
+  fn parse_rejects_malformed_expressions() {
+    for expr in [
+      "",
+      "* * * *",
+      "* * * * * *",
+      "60 * * * *",
+      "* 24 * * *",
+      "*/0 * * * *",
+      "5-1 * * * *",
+      "a * * * *",
+      "* * 0 * *",
+    ] {
+      assert!(Cron::parse(expr).is_err(), "{:?} parsed", expr);
+    }
+  }
+
+  #[test]
+  fn parse_accepts_lists_ranges_steps_and_macros() {
+    for expr in [
+      "*/15 9-17 * * 1-5",
+      "0,30 * 1,15 * *",
+      "5/10 * * * *",
+      "@hourly",
+      "@daily",
+      "@weekly",
+      "@monthly",
+      " 0 0 * * 7 ",
+    ] {
+      assert!(Cron::parse(expr).is_ok(), "{:?} failed", expr);
+    }
+  }
+
+  #[test]
+  fn next_after_is_strictly_later() {
+    let at = local(2024, 3, 5, 10, 0);
+    assert_eq!(next("0 * * * *", at), naive(2024, 3, 5, 11, 0));
+    assert_eq!(next("* * * * *", at), naive(2024, 3, 5, 10, 1));
+  }
+
+  #[test]
+  fn next_after_follows_steps_and_ranges() {
+    let at = local(2024, 3, 5, 10, 7);
+    assert_eq!(next("*/15 * * * *", at), naive(2024, 3, 5, 10, 15));
+    assert_eq!(next("0 9-17 * * *", local(2024, 3, 5, 17, 30)), naive(2024, 3, 6, 9, 0));
+    assert_eq!(next("5/20 * * * *", at), naive(2024, 3, 5, 10, 25));
+  }
+
+  #[test]
+  fn next_after_treats_7_as_sunday() {
+    // 2024-03-05 is a Tuesday; the next Sunday is the 10th.
+    let at = local(2024, 3, 5, 10, 0);
+    assert_eq!(next("0 8 * * 7", at), naive(2024, 3, 10, 8, 0));
+    assert_eq!(next("@weekly", at), naive(2024, 3, 10, 0, 0));
+  }
+
+  #[test]
+  fn next_after_matches_either_restricted_day_field() {
+    // The 15th, or any Friday: Friday the 8th comes first.
+    let at = local(2024, 3, 5, 10, 0);
+    assert_eq!(next("0 0 15 * 5", at), naive(2024, 3, 8, 0, 0));
+    assert_eq!(next("0 0 15 * *", at), naive(2024, 3, 15, 0, 0));
+  }
+
+  #[test]
+  fn next_after_skips_to_an_allowed_month() {
+    let at = local(2024, 3, 5, 10, 0);
+    assert_eq!(next("0 0 1 6 *", at), naive(2024, 6, 1, 0, 0));
+    assert_eq!(next("@monthly", at), naive(2024, 4, 1, 0, 0));
+  }
+
+  #[test]
+  fn next_after_finds_nothing_for_impossible_dates() {
+    let cron = Cron::parse("0 0 31 2 *").unwrap();
+    assert!(cron.next_after(local(2024, 3, 5, 10, 0)).is_none());
+  }
+}
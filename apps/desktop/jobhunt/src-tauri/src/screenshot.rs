@@ -0,0 +1,307 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+
+use crate::attachments::{self, Attachment};
+use crate::engine;
+
+const CAPTURE_DIR: &str = "captures";
+const KIND: &str = "screenshot";
+// Time for the window to disappear before the screen is grabbed.
+const HIDE_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureMode {
+  /// Every display.
+  Full,
+  /// An area the user drags out with the OS's own selection tool.
+  Region,
+}
+
+#[cfg(windows)]
+mod imp {
+  use std::io::BufWriter;
+  use std::path::Path;
+  use std::process::Command;
+  use std::time::{Duration, Instant};
+
+  use sha2::{Digest, Sha256};
+  use tauri::AppHandle;
+  use tauri_plugin_clipboard_manager::ClipboardExt;
+  use windows_sys::Win32::Graphics::Gdi::{
+    BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits,
+    ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, CAPTUREBLT, DIB_RGB_COLORS,
+    SRCCOPY,
+  };
+  use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+  };
+
+  use super::CaptureMode;
+
+  // How long the user has to finish a Snipping Tool selection.
+  const SNIP_TIMEOUT: Duration = Duration::from_secs(60);
+  const SNIP_POLL: Duration = Duration::from_millis(250);
+
+  fn write_png(dest: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), String> {
+    let file = std::fs::File::create(dest).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(rgba).map_err(|e| e.to_string())
+  }
+
+  /// Copies the whole virtual desktop, every monitor, out of the screen DC.
+  fn grab_screen() -> Result<(u32, u32, Vec<u8>), String> {
+    // SAFETY: every handle is created, used, and released on this thread,
+    // and `pixels` holds exactly the 32-bit rows GetDIBits is asked for.
+    unsafe {
+      let x = GetSystemMetrics(SM_XVIRTUALSCREEN);
+      let y = GetSystemMetrics(SM_YVIRTUALSCREEN);
+      let width = GetSystemMetrics(SM_CXVIRTUALSCREEN);
+      let height = GetSystemMetrics(SM_CYVIRTUALSCREEN);
+      if width <= 0 || height <= 0 {
+        return Err("no display to capture".into());
+      }
+
+      let screen = GetDC(std::ptr::null_mut());
+      let memory = CreateCompatibleDC(screen);
+      let bitmap = CreateCompatibleBitmap(screen, width, height);
+      let previous = SelectObject(memory, bitmap);
+      // CAPTUREBLT includes layered windows, like tooltips and menus.
+      let copied = BitBlt(
+        memory,
+        0,
+        0,
+        width,
+        height,
+        screen,
+        x,
+        y,
+        SRCCOPY | CAPTUREBLT,
+      );
+
+      let mut info: BITMAPINFO = std::mem::zeroed();
+      info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+      info.bmiHeader.biWidth = width;
+      // Negative height asks for rows top to bottom.
+      info.bmiHeader.biHeight = -height;
+      info.bmiHeader.biPlanes = 1;
+      info.bmiHeader.biBitCount = 32;
+      info.bmiHeader.biCompression = BI_RGB;
+      let mut pixels = vec![0u8; width as usize * height as usize * 4];
+      let rows = GetDIBits(
+        memory,
+        bitmap,
+        0,
+        height as u32,
+        pixels.as_mut_ptr().cast(),
+        &mut info,
+        DIB_RGB_COLORS,
+      );
+
+      SelectObject(memory, previous);
+      DeleteObject(bitmap);
+      DeleteDC(memory);
+      ReleaseDC(std::ptr::null_mut(), screen);
+
+      if copied == 0 || rows != height {
+        return Err("could not copy the screen".into());
+      }
+      // GDI hands back BGRA with an undefined alpha byte.
+      for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+        px[3] = 255;
+      }
+      Ok((width as u32, height as u32, pixels))
+    }
+  }
+
+  fn clipboard_digest(app: &AppHandle) -> Option<Vec<u8>> {
+    let image = app.clipboard().read_image().ok()?;
+    Some(Sha256::digest(image.rgba()).to_vec())
+  }
+
+  /// Opens the Snipping Tool's selection overlay, which puts the snip on
+  /// the clipboard, and waits for a new image to show up there.
+  fn snip(app: &AppHandle, dest: &Path) -> Result<bool, String> {
+    let before = clipboard_digest(app);
+    Command::new("explorer")
+      .arg("ms-screenclip:")
+      .spawn()
+      .map_err(|e| e.to_string())?;
+
+    let deadline = Instant::now() + SNIP_TIMEOUT;
+    while Instant::now() < deadline {
+      std::thread::sleep(SNIP_POLL);
+      let Some(digest) = clipboard_digest(app) else {
+        continue;
+      };
+      if Some(&digest) == before.as_ref() {
+        continue;
+      }
+      let image = app.clipboard().read_image().map_err(|e| e.to_string())?;
+      write_png(dest, image.width(), image.height(), image.rgba())?;
+      return Ok(true);
+    }
+    // Most likely dismissed with Esc, which leaves no trace to wait on.
+    Ok(false)
+  }
+
+  pub fn capture(app: &AppHandle, mode: CaptureMode, dest: &Path) -> Result<bool, String> {
+    match mode {
+      CaptureMode::Full => {
+        let (width, height, rgba) = grab_screen()?;
+        write_png(dest, width, height, &rgba)?;
+        Ok(true)
+      }
+      CaptureMode::Region => snip(app, dest),
+    }
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+  use std::path::Path;
+  use std::process::{Command, Stdio};
+
+  use tauri::AppHandle;
+
+  use super::CaptureMode;
+
+  pub fn capture(_app: &AppHandle, mode: CaptureMode, dest: &Path) -> Result<bool, String> {
+    // -x: no shutter sound. -i: the system's crosshair selection.
+    let mut args = vec!["-x"];
+    if let CaptureMode::Region = mode {
+      args.push("-i");
+    }
+    let status = Command::new("screencapture")
+      .args(&args)
+      .arg(dest)
+      .stdin(Stdio::null())
+      .status()
+      .map_err(|e| e.to_string())?;
+    if !status.success() {
+      return Err(format!("screencapture exited with {}", status));
+    }
+    // Pressing Esc during a selection exits cleanly without writing anything.
+    Ok(dest.exists())
+  }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod imp {
+  use std::path::Path;
+  use std::process::{Command, Output, Stdio};
+
+  use tauri::AppHandle;
+
+  use super::CaptureMode;
+
+  // None when the tool isn't installed.
+  fn run(program: &str, args: &[&str]) -> Option<Result<Output, String>> {
+    match Command::new(program)
+      .args(args)
+      .stdin(Stdio::null())
+      .output()
+    {
+      Ok(out) => Some(Ok(out)),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+      Err(e) => Some(Err(e.to_string())),
+    }
+  }
+
+  /// Tries the desktop's own screenshot tools in turn, then generic ones.
+  /// On wlroots compositors a region comes from slurp and is shot by grim.
+  pub fn capture(_app: &AppHandle, mode: CaptureMode, dest: &Path) -> Result<bool, String> {
+    let path = dest.to_string_lossy();
+    let attempts: Vec<(&str, Vec<&str>)> = match mode {
+      CaptureMode::Full => vec![
+        ("gnome-screenshot", vec!["-f", &path]),
+        ("spectacle", vec!["-b", "-n", "-f", "-o", &path]),
+        ("grim", vec![&path]),
+        ("scrot", vec!["-o", &path]),
+        ("import", vec!["-window", "root", &path]),
+      ],
+      CaptureMode::Region => vec![
+        ("gnome-screenshot", vec!["-a", "-f", &path]),
+        ("spectacle", vec!["-b", "-n", "-r", "-o", &path]),
+        ("slurp", vec![]),
+        ("scrot", vec!["-s", "-o", &path]),
+        ("import", vec![&path]),
+      ],
+    };
+    for (program, args) in attempts {
+      let Some(out) = run(program, &args) else {
+        continue;
+      };
+      let out = out?;
+      if program == "slurp" {
+        if !out.status.success() {
+          return Ok(false);
+        }
+        let geometry = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        match run("grim", &["-g", &geometry, &path]) {
+          Some(out) => out?,
+          None => continue,
+        };
+      }
+      // Cancelling a selection writes nothing, whatever the exit status.
+      return Ok(dest.exists());
+    }
+    Err("no screenshot tool found; install gnome-screenshot, spectacle, grim, or scrot".into())
+  }
+}
+
+fn capture_path(app: &AppHandle) -> Result<PathBuf, String> {
+  let dir = engine::data_dir(app)?.join(CAPTURE_DIR);
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let stamp = chrono::Local::now().format("%Y-%m-%d %H.%M.%S");
+  Ok(dir.join(format!("Screenshot {}.png", stamp)))
+}
+
+/// Captures the screen, or a region the user selects, with the OS's own
+/// tools and stores it as an attachment of job `job_id`. The main window is
+/// hidden meanwhile so it doesn't cover the posting. Returns `None` if the
+/// user cancelled the selection.
+pub async fn capture(
+  app: &AppHandle,
+  mode: CaptureMode,
+  job_id: i64,
+) -> Result<Option<Attachment>, String> {
+  let dest = capture_path(app)?;
+  let main = app
+    .get_webview_window("main")
+    .filter(|w| w.is_visible().unwrap_or(false));
+  if let Some(main) = &main {
+    let _ = main.hide();
+    tokio::time::sleep(HIDE_DELAY).await;
+  }
+
+  let handle = app.clone();
+  let path = dest.clone();
+  let captured = tauri::async_runtime::spawn_blocking(move || imp::capture(&handle, mode, &path))
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+
+  if let Some(main) = &main {
+    let _ = main.show();
+    let _ = main.set_focus();
+  }
+  if !captured? {
+    println!("[screenshot] {:?} capture cancelled", mode);
+    return Ok(None);
+  }
+
+  let stored = attachments::import(app, &dest, KIND, Some(job_id)).await;
+  // The store keeps its own copy.
+  let _ = std::fs::remove_file(&dest);
+  let attachment = stored?;
+  println!("[screenshot] saved {} for job {}", attachment.name, job_id);
+  Ok(Some(attachment))
+}
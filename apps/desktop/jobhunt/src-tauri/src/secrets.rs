@@ -0,0 +1,56 @@
+use keyring::Entry;
+
+// Same service name the engine uses, so both sides see the same entries.
+const SERVICE: &str = "jobhunt";
+
+/// Secrets the engine needs, and the env var each one is handed over in.
+const ENGINE_SECRETS: &[(&str, &str)] = &[
+  ("groq:api_key", "JOBHUNT_GROQ_API_KEY"),
+  ("imap:password", "JOBHUNT_IMAP_PASSWORD"),
+];
+
+fn entry(name: &str) -> Result<Entry, String> {
+  let name = name.trim();
+  if name.is_empty() {
+    return Err("secret name is empty".into());
+  }
+  Entry::new(SERVICE, &format!("jobhunt:{}", name)).map_err(|e| e.to_string())
+}
+
+pub fn set(name: &str, value: &str) -> Result<(), String> {
+  if value.trim().is_empty() {
+    return Err("secret value is empty".into());
+  }
+  entry(name)?.set_password(value).map_err(|e| e.to_string())
+}
+
+/// The stored value, or `None` if nothing is stored under `name`.
+pub fn get(name: &str) -> Result<Option<String>, String> {
+  match entry(name)?.get_password() {
+    Ok(value) => Ok(Some(value)),
+    Err(keyring::Error::NoEntry) => Ok(None),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+pub fn delete(name: &str) -> Result<(), String> {
+  match entry(name)?.delete_credential() {
+    Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+/// Env vars carrying the engine's secrets, read from the keychain at spawn
+/// time so they never have to be written to disk.
+pub fn engine_env() -> Vec<(&'static str, String)> {
+  ENGINE_SECRETS
+    .iter()
+    .filter_map(|(name, var)| match get(name) {
+      Ok(value) => value.map(|v| (*var, v)),
+      Err(e) => {
+        eprintln!("[secrets] could not read {}: {}", name, e);
+        None
+      }
+    })
+    .collect()
+}
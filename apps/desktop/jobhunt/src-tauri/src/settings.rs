@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::sandbox::Hardening;
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// A named profile with its own data dir and engine instance.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+  pub name: String,
+  /// `None` means `<app data dir>/profiles/<name>`.
+  pub data_dir: Option<PathBuf>,
+}
+
+/// A folder whose new files are imported automatically.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DropFolder {
+  pub path: PathBuf,
+  pub enabled: bool,
+}
+
+/// Desktop-shell settings. Engine settings live in the engine's own config.yml.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+  /// Hide to the tray on close instead of quitting, so the engine keeps running.
+  pub keep_running_in_background: bool,
+  /// Where the engine keeps its data. `None` means the default app data dir.
+  pub data_dir: Option<PathBuf>,
+  /// Serve the engine on a Unix socket (a named pipe on Windows) instead of a
+  /// TCP port. The webview then reaches it only through `engine_fetch`.
+  pub engine_socket: bool,
+  /// Pin the engine to this loopback port instead of the default.
+  pub engine_port: Option<u16>,
+  /// Seconds to wait for the engine to exit after a shutdown request before killing it.
+  pub shutdown_timeout_secs: Option<u64>,
+  /// Capture a heap profile when the engine's RSS exceeds this many MB.
+  pub memory_alert_mb: Option<u64>,
+  /// Minimum seconds between two memory alerts.
+  pub memory_alert_interval_secs: Option<u64>,
+  /// Restart the engine when its RSS exceeds this many MB.
+  pub memory_ceiling_mb: Option<u64>,
+  /// Take a backup of the data dir once a day.
+  pub backup_daily: bool,
+  /// Where scheduled backups go. `None` means `<data dir>/backups`.
+  pub backup_dir: Option<PathBuf>,
+  /// How many scheduled backups to keep.
+  pub backup_retention: Option<usize>,
+  /// Per-category notification toggles (`new_jobs`, `deadlines`, `imports`). Categories not
+  /// listed are on.
+  pub notifications: BTreeMap<String, bool>,
+  /// Profiles besides the implicit `default` one, which uses `data_dir`.
+  pub profiles: Vec<Profile>,
+  /// Profile the engine runs against. `None` means `default`.
+  pub active_profile: Option<String>,
+  /// Ask which profile to open at launch when there is more than one.
+  pub pick_profile_on_launch: bool,
+  /// Offer to add job posting URLs copied to the clipboard.
+  pub watch_clipboard: bool,
+  /// Global shortcut for the quick-add window. `None` means Ctrl/Cmd+Shift+J.
+  pub quick_capture_shortcut: Option<String>,
+  /// Chromium extension IDs allowed to talk to the native messaging host.
+  pub browser_extension_ids: Vec<String>,
+  /// Download and switch to newer engine builds without a desktop update.
+  pub engine_auto_update: bool,
+  /// Upload anonymous usage counts and crash totals once a day. Off unless the user opts in.
+  pub telemetry: bool,
+  /// Where telemetry batches go. `None` means the project's collector.
+  pub telemetry_url: Option<String>,
+  /// Folders watched for PDFs, HTML pages, and emails to import.
+  pub drop_folders: Vec<DropFolder>,
+  /// Proxy for outbound traffic, e.g. `http://proxy:8080`. `None` means use
+  /// the system's.
+  pub proxy_url: Option<String>,
+  /// Username for `proxy_url`; the password is kept in the OS keychain.
+  pub proxy_username: Option<String>,
+  /// Comma-separated hosts that bypass `proxy_url`.
+  pub proxy_bypass: Option<String>,
+  /// Language for dialogs, the tray, and notifications, e.g. `de`. `None`
+  /// follows the OS.
+  pub locale: Option<String>,
+  /// Most `engine_fetch` requests sent to the engine at once; the rest wait
+  /// their turn. `None` means 8.
+  pub fetch_concurrency: Option<usize>,
+  /// Hold scheduled runs and the engine's background polling while on battery.
+  pub background_on_ac_only: bool,
+  /// Hold them until the user has been idle this many minutes. `None` runs
+  /// them whenever they're due.
+  pub background_min_idle_mins: Option<u64>,
+  /// Run database maintenance once the user has been idle this many minutes.
+  /// `None` means 15.
+  pub maintenance_idle_mins: Option<u64>,
+  /// Days between idle maintenance runs. `None` means 7; 0 turns them off.
+  pub maintenance_interval_days: Option<u64>,
+  /// When each maintenance task last succeeded, by task name.
+  pub maintenance_last_run: BTreeMap<String, DateTime<Local>>,
+  /// How tightly the engine is confined: `off`, `standard`, or `strict`.
+  pub engine_hardening: Hardening,
+  /// Memory the OS lets the engine use, in MB, unlike `memory_ceiling_mb`,
+  /// which restarts it. `None` means 4096.
+  pub engine_memory_limit_mb: Option<u64>,
+  /// Share of all CPU cores the engine may use, in percent. Windows only;
+  /// `None` means no cap.
+  pub engine_cpu_percent: Option<u8>,
+}
+
+impl Settings {
+  /// Points the active profile at `dir`.
+  pub fn set_data_dir(&mut self, dir: PathBuf) {
+    let active = self.active_profile.clone();
+    match self.profiles.iter_mut().find(|p| Some(&p.name) == active.as_ref()) {
+      Some(profile) => profile.data_dir = Some(dir),
+      None => self.data_dir = Some(dir),
+    }
+  }
+}
+
+#[derive(Default)]
+pub struct SettingsState(pub Mutex<Settings>);
+
+pub fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+  let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+  Ok(dir.join(SETTINGS_FILE))
+}
+
+/// Reads settings from disk, falling back to defaults if the file is missing or unreadable.
+pub fn load(app: &AppHandle) -> Settings {
+  match settings_path(app) {
+    Ok(path) => load_from(&path),
+    Err(_) => Settings::default(),
+  }
+}
+
+/// [`load`] for a settings file at `path`, for when there is no app to ask.
+pub fn load_from(path: &Path) -> Settings {
+  match std::fs::read_to_string(path) {
+    Ok(s) => serde_json::from_str(&s).unwrap_or_else(|e| {
+      eprintln!("[settings] ignoring invalid {}: {}", path.display(), e);
+      Settings::default()
+    }),
+    Err(_) => Settings::default(),
+  }
+}
+
+pub fn save(app: &AppHandle, settings: &Settings) -> Result<(), String> {
+  let path = settings_path(app)?;
+  if let Some(dir) = path.parent() {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+  }
+  let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+  std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Returns a snapshot of the current settings.
+pub fn current(app: &AppHandle) -> Settings {
+  app.state::<SettingsState>().0.lock().unwrap().clone()
+}
+
+/// Persists `settings` and makes them current.
+pub fn replace(app: &AppHandle, settings: Settings) -> Result<(), String> {
+  save(app, &settings)?;
+  *app.state::<SettingsState>().0.lock().unwrap() = settings;
+  Ok(())
+}
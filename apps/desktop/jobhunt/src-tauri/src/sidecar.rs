@@ -0,0 +1,342 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_shell::process::{Command, CommandChild, CommandEvent};
+use tokio::sync::Notify;
+
+use crate::startup::StartupError;
+use crate::{procguard, supervisor};
+
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+const KILL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Resolves once a freshly spawned sidecar is serving, with its port if it
+/// has one. Called with the spawn's generation so it can notice being superseded.
+pub type Handshake =
+  Box<dyn FnOnce(AppHandle, u64) -> BoxFuture<'static, Result<Option<u16>, String>> + Send>;
+
+/// How to start one instance of a sidecar.
+pub struct Launch {
+  pub command: Command,
+  /// Written to stdin right after spawning, for secrets that must stay out of argv and env.
+  pub stdin: Option<String>,
+  /// `None` means the sidecar counts as ready as soon as it has spawned.
+  pub handshake: Option<Handshake>,
+}
+
+/// What the supervisor does when a sidecar exits without being asked to.
+#[derive(Clone, Copy, PartialEq)]
+pub enum RestartPolicy {
+  Never,
+  /// Respawn with exponential backoff until it stays up.
+  Backoff,
+}
+
+pub struct Exit {
+  pub code: Option<i32>,
+  pub signal: Option<i32>,
+  /// True when nobody asked the process to stop.
+  pub crashed: bool,
+}
+
+/// A helper process the app starts, watches, and stops. The engine is one;
+/// each new sidecar implements this and is registered at startup.
+pub trait Sidecar: Send + Sync + 'static {
+  fn name(&self) -> &'static str;
+
+  /// When the app quits, sidecars stop in ascending order, so anything that
+  /// talks to the engine should sort before it.
+  fn stop_order(&self) -> u8;
+
+  fn restart_policy(&self) -> RestartPolicy {
+    RestartPolicy::Never
+  }
+
+  fn launch(&self, app: &AppHandle) -> Result<Launch, StartupError>;
+
+  /// Called once the process is running, before any of its output arrives.
+  fn started(&self, _app: &AppHandle, _pid: u32) {}
+
+  /// Receives each chunk of stdout/stderr.
+  fn output(&self, _app: &AppHandle, stream: &str, text: &str) {
+    print!("[{} {}] {}", self.name(), stream, text);
+  }
+
+  /// Called when the current instance terminates.
+  fn exited(&self, _app: &AppHandle, _exit: &Exit) {}
+
+  /// Asks the sidecar to exit on its own; it is killed if this fails or it
+  /// outlives `shutdown_timeout`.
+  fn request_stop(&self, _app: &AppHandle) -> BoxFuture<'static, Result<(), String>> {
+    Box::pin(async { Err("no graceful shutdown".to_string()) })
+  }
+
+  fn shutdown_timeout(&self, _app: &AppHandle) -> Duration {
+    DEFAULT_SHUTDOWN_TIMEOUT
+  }
+
+  fn record_error(&self, _app: &AppHandle, err: String) {
+    eprintln!("[{}] {}", self.name(), err);
+  }
+}
+
+#[derive(Default)]
+struct SlotInfo {
+  child: Option<CommandChild>,
+  // Bumped on every spawn so events from a previous instance can't clobber the new one.
+  generation: u64,
+  ready: bool,
+  port: Option<u16>,
+}
+
+struct Slot {
+  spec: Arc<dyn Sidecar>,
+  info: Mutex<SlotInfo>,
+  // Signalled once the handshake succeeds.
+  ready: Notify,
+  // Signalled when the process terminates.
+  exited: Notify,
+}
+
+/// The registered sidecars, by name.
+#[derive(Default)]
+pub struct SidecarManager(Mutex<BTreeMap<&'static str, Arc<Slot>>>);
+
+pub fn register(app: &AppHandle, spec: impl Sidecar) {
+  let name = spec.name();
+  let slot = Arc::new(Slot {
+    spec: Arc::new(spec),
+    info: Mutex::new(SlotInfo::default()),
+    ready: Notify::new(),
+    exited: Notify::new(),
+  });
+  app.state::<SidecarManager>().0.lock().unwrap().insert(name, slot);
+}
+
+fn slot(app: &AppHandle, name: &str) -> Result<Arc<Slot>, String> {
+  app
+    .state::<SidecarManager>()
+    .0
+    .lock()
+    .unwrap()
+    .get(name)
+    .cloned()
+    .ok_or_else(|| format!("no sidecar named {:?}", name))
+}
+
+pub fn is_running(app: &AppHandle, name: &str) -> bool {
+  slot(app, name).is_ok_and(|s| s.info.lock().unwrap().child.is_some())
+}
+
+/// Whether `generation` is still the live instance of `name`.
+pub fn is_current(app: &AppHandle, name: &str, generation: u64) -> bool {
+  slot(app, name).is_ok_and(|s| {
+    let info = s.info.lock().unwrap();
+    info.generation == generation && info.child.is_some()
+  })
+}
+
+pub fn record_error(app: &AppHandle, name: &str, err: String) {
+  if let Ok(s) = slot(app, name) {
+    s.spec.record_error(app, err);
+  }
+}
+
+fn on_terminated(
+  app: &AppHandle,
+  slot: &Slot,
+  generation: u64,
+  code: Option<i32>,
+  signal: Option<i32>,
+) {
+  let name = slot.spec.name();
+  println!("[{}] terminated: code {:?}, signal {:?}", name, code, signal);
+  let current = {
+    let mut info = slot.info.lock().unwrap();
+    if info.generation == generation {
+      // stop() takes the child before shutting it down, so a child still
+      // in place means nobody asked this process to exit.
+      let crashed = info.child.take().is_some();
+      info.ready = false;
+      info.port = None;
+      Some(crashed)
+    } else {
+      None
+    }
+  };
+  slot.exited.notify_waiters();
+
+  let Some(crashed) = current else {
+    return;
+  };
+  slot.spec.exited(app, &Exit { code, signal, crashed });
+  if crashed && slot.spec.restart_policy() == RestartPolicy::Backoff {
+    supervisor::crashed(app, name, code);
+  }
+}
+
+/// Starts `name`, forwards its output, and runs its handshake in the background.
+pub fn spawn(app: &AppHandle, name: &str) -> Result<(), StartupError> {
+  let slot = slot(app, name)?;
+  let launch = slot.spec.launch(app)?;
+
+  let (mut rx, mut child) = launch.command.spawn().map_err(|e| match e {
+    tauri_plugin_shell::Error::Io(io) => {
+      StartupError::from_io(format!("could not start {}: {}", name, io), &io)
+    }
+    other => StartupError::Other(format!("could not start {}: {}", name, other)),
+  })?;
+  if let Some(line) = launch.stdin {
+    if let Err(e) = child.write(line.as_bytes()) {
+      let _ = child.kill();
+      return Err(format!("could not write to {}'s stdin: {}", name, e).into());
+    }
+  }
+  let pid = child.pid();
+  procguard::adopt(pid);
+
+  let generation = {
+    let mut info = slot.info.lock().unwrap();
+    *info = SlotInfo {
+      child: Some(child),
+      generation: info.generation + 1,
+      ..SlotInfo::default()
+    };
+    info.generation
+  };
+  slot.spec.started(app, pid);
+
+  let app_handle = app.clone();
+  let events = slot.clone();
+  tauri::async_runtime::spawn(async move {
+    while let Some(event) = rx.recv().await {
+      match event {
+        CommandEvent::Stdout(bytes) => {
+          events.spec.output(&app_handle, "stdout", &String::from_utf8_lossy(&bytes));
+        }
+        CommandEvent::Stderr(bytes) => {
+          events.spec.output(&app_handle, "stderr", &String::from_utf8_lossy(&bytes));
+        }
+        CommandEvent::Terminated(payload) => {
+          on_terminated(&app_handle, &events, generation, payload.code, payload.signal);
+        }
+        other => println!("[{}] {:?}", events.spec.name(), other),
+      }
+    }
+  });
+
+  let app_handle = app.clone();
+  let waiting = slot.clone();
+  tauri::async_runtime::spawn(async move {
+    let result = match launch.handshake {
+      Some(handshake) => handshake(app_handle.clone(), generation).await,
+      None => Ok(None),
+    };
+    match result {
+      Ok(port) => {
+        {
+          let mut info = waiting.info.lock().unwrap();
+          if info.generation != generation || info.child.is_none() {
+            return;
+          }
+          info.ready = true;
+          info.port = port;
+        }
+        waiting.ready.notify_waiters();
+      }
+      Err(e) => waiting.spec.record_error(&app_handle, e),
+    }
+  });
+
+  println!("[{}] started", name);
+  Ok(())
+}
+
+/// Asks `name` to shut down and waits for it to exit, killing it if the
+/// request fails or it outlives its shutdown timeout.
+/// Returns false when it wasn't running.
+pub async fn stop(app: &AppHandle, name: &str) -> bool {
+  let Ok(slot) = slot(app, name) else {
+    return false;
+  };
+  let (child, ready) = {
+    let mut info = slot.info.lock().unwrap();
+    (info.child.take(), info.ready)
+  };
+  let Some(child) = child else {
+    return false;
+  };
+  let exited = slot.exited.notified();
+  let timeout = slot.spec.shutdown_timeout(app);
+
+  let mut graceful_ok = false;
+  if ready {
+    match slot.spec.request_stop(app).await {
+      Ok(()) => {
+        println!("[{}] shutdown requested", name);
+        graceful_ok = tokio::time::timeout(timeout, exited).await.is_ok();
+        if !graceful_ok {
+          eprintln!("[{}] did not exit within {:?}", name, timeout);
+        }
+      }
+      Err(e) => eprintln!("[{}] shutdown request failed: {}", name, e),
+    }
+  }
+
+  if !graceful_ok {
+    let exited = slot.exited.notified();
+    let _ = child.kill();
+    println!("[{}] killed", name);
+    // Give the OS a moment to release ports and file locks.
+    let _ = tokio::time::timeout(KILL_TIMEOUT, exited).await;
+  }
+  true
+}
+
+/// Stops every sidecar in `stop_order`, e.g. the scraper before the engine it talks to.
+pub async fn stop_all(app: &AppHandle) {
+  let mut slots: Vec<Arc<Slot>> =
+    app.state::<SidecarManager>().0.lock().unwrap().values().cloned().collect();
+  slots.sort_by_key(|s| s.spec.stop_order());
+  for s in slots {
+    stop(app, s.spec.name()).await;
+  }
+}
+
+pub fn any_running(app: &AppHandle) -> bool {
+  app
+    .state::<SidecarManager>()
+    .0
+    .lock()
+    .unwrap()
+    .values()
+    .any(|s| s.info.lock().unwrap().child.is_some())
+}
+
+/// Resolves with `name`'s port (if any) once its handshake completes, or errors after `timeout`.
+pub async fn wait_ready(
+  app: &AppHandle,
+  name: &str,
+  timeout: Duration,
+) -> Result<Option<u16>, String> {
+  let slot = slot(app, name)?;
+  let wait = async {
+    loop {
+      // Register before checking so a notify between the check and the await isn't lost.
+      let notified = slot.ready.notified();
+      {
+        let info = slot.info.lock().unwrap();
+        if info.ready {
+          return info.port;
+        }
+      }
+      notified.await;
+    }
+  };
+  tokio::time::timeout(timeout, wait)
+    .await
+    .map_err(|_| format!("{} did not become ready within {} ms", name, timeout.as_millis()))
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::engine::{self, EngineState};
+use crate::startup::{self, StartupError};
+
+// Covers first-run migrations on a large DB; past this the splash shows an error.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(45);
+
+pub const SPLASH_WINDOW: &str = "splash";
+
+/// Waits for the engine to answer `/health` on the port we gave it.
+async fn wait_until_healthy(app: &AppHandle) -> Result<(), String> {
+  engine::wait_for_ready(app, STARTUP_TIMEOUT)
+    .await
+    .map(|_| ())
+}
+
+fn show_error(app: &AppHandle, err: &StartupError) {
+  if let Some(splash) = app.get_webview_window(SPLASH_WINDOW) {
+    let msg = serde_json::to_string(&err.to_string()).unwrap_or_default();
+    let _ = splash.eval(format!("window.showError({})", msg));
+  }
+}
+
+fn show_starting(app: &AppHandle) {
+  if let Some(splash) = app.get_webview_window(SPLASH_WINDOW) {
+    let _ = splash.eval("window.showStarting()");
+  }
+}
+
+/// Keeps the main window hidden behind the splash until the engine is
+/// serving. `failed` is set when setup already couldn't spawn it; either way
+/// a failure offers the recovery dialog instead of leaving a dead splash.
+pub fn start(app: AppHandle, failed: Option<StartupError>) {
+  tauri::async_runtime::spawn(async move {
+    if let Some(err) = failed {
+      show_error(&app, &err);
+      if !startup::recover(&app, err).await {
+        return;
+      }
+      show_starting(&app);
+    }
+    loop {
+      match wait_until_healthy(&app).await {
+        Ok(()) => {
+          if let Some(main) = app.get_webview_window("main") {
+            let _ = main.show();
+            let _ = main.set_focus();
+          }
+          // destroy() rather than close(): close would run the shutdown path.
+          if let Some(splash) = app.get_webview_window(SPLASH_WINDOW) {
+            let _ = splash.destroy();
+          }
+          return;
+        }
+        Err(e) => {
+          // The engine's own failure says more than the readiness timeout.
+          let last = app
+            .state::<EngineState>()
+            .info
+            .lock()
+            .unwrap()
+            .last_error
+            .clone();
+          let err = StartupError::from(last.unwrap_or(e));
+          show_error(&app, &err);
+          if !startup::can_recover(&app) || !startup::recover(&app, err).await {
+            return;
+          }
+          show_starting(&app);
+        }
+      }
+    }
+  });
+}
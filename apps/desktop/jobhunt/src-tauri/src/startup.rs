@@ -0,0 +1,230 @@
+use std::path::{Path, PathBuf};
+use std::{fmt, io};
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{
+  DialogExt, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::engine::{self, EngineState};
+use crate::{appupdate, backup, i18n, logs};
+
+/// Why the engine couldn't be started, sorted into the cases we can give the
+/// user something to do about. Each carries the original error text. The
+/// spawn path builds these where the cause is known; plain `String` errors
+/// from elsewhere become `Other`.
+pub enum StartupError {
+  /// The engine binary is gone, typically quarantined by antivirus.
+  MissingBinary(String),
+  /// The binary no longer matches the one this build shipped with.
+  Damaged(String),
+  PermissionDenied(String),
+  PortBusy(String),
+  /// The database failed its check after an update; carries the backup
+  /// taken before the update, if there is one.
+  UpdateCheck(String, Option<PathBuf>),
+  Other(String),
+}
+
+impl StartupError {
+  /// Sorts an I/O failure by its kind; `message` says what was being done.
+  pub fn from_io(message: String, e: &io::Error) -> Self {
+    match e.kind() {
+      io::ErrorKind::NotFound => StartupError::MissingBinary(message),
+      io::ErrorKind::PermissionDenied => StartupError::PermissionDenied(message),
+      io::ErrorKind::AddrInUse => StartupError::PortBusy(message),
+      _ => StartupError::Other(message),
+    }
+  }
+
+  fn message(&self) -> &str {
+    match self {
+      StartupError::MissingBinary(m)
+      | StartupError::Damaged(m)
+      | StartupError::PermissionDenied(m)
+      | StartupError::PortBusy(m)
+      | StartupError::UpdateCheck(m, _)
+      | StartupError::Other(m) => m,
+    }
+  }
+
+  /// Catalog key prefix for this kind's title and hint.
+  fn key(&self) -> &'static str {
+    match self {
+      StartupError::MissingBinary(_) => "startup.missing",
+      StartupError::Damaged(_) => "startup.damaged",
+      StartupError::PermissionDenied(_) => "startup.blocked",
+      StartupError::PortBusy(_) => "startup.port_busy",
+      StartupError::UpdateCheck(..) => "startup.update_check",
+      StartupError::Other(_) => "startup.other",
+    }
+  }
+
+  fn hint(&self) -> String {
+    i18n::t(&format!("{}.hint", self.key()))
+  }
+
+  fn title(&self) -> String {
+    i18n::t(&format!("{}.title", self.key()))
+  }
+}
+
+impl From<String> for StartupError {
+  fn from(message: String) -> Self {
+    StartupError::Other(message)
+  }
+}
+
+impl From<StartupError> for String {
+  fn from(err: StartupError) -> Self {
+    err.to_string()
+  }
+}
+
+impl fmt::Display for StartupError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.message())
+  }
+}
+
+/// What the user chose to do about a failed start.
+#[derive(PartialEq)]
+pub enum Recovery {
+  Retry,
+  Quit,
+  /// After a failed update check: keep the data as it is.
+  StartAnyway,
+  RestoreBackup(PathBuf),
+}
+
+pub fn open_logs(app: &AppHandle) {
+  let dir = logs::log_path(app)
+    .and_then(|p| p.parent().map(|d| d.to_path_buf()))
+    .or_else(|| engine::data_dir(app).ok().map(|d| d.join("logs")));
+  let Some(dir) = dir else {
+    eprintln!("[startup] no logs folder to open");
+    return;
+  };
+  let _ = std::fs::create_dir_all(&dir);
+  if let Err(e) = app.opener().open_path(dir.to_string_lossy(), None::<&str>) {
+    eprintln!("[startup] open logs failed: {}", e);
+  }
+}
+
+async fn ask(app: &AppHandle, err: &StartupError, labels: &[String; 3]) -> MessageDialogResult {
+  let (tx, rx) = tokio::sync::oneshot::channel();
+  app
+    .dialog()
+    .message(format!("{}\n\n{}", err.hint(), err.message()))
+    .title(err.title())
+    .kind(MessageDialogKind::Error)
+    .buttons(MessageDialogButtons::YesNoCancelCustom(
+      labels[0].clone(),
+      labels[1].clone(),
+      labels[2].clone(),
+    ))
+    .show_with_result(move |result| {
+      let _ = tx.send(result);
+    });
+  rx.await.unwrap_or_default()
+}
+
+/// Offers the backup taken before the update, or starting on the data as it is.
+async fn report_update_check(
+  app: &AppHandle,
+  err: &StartupError,
+  backup: Option<&Path>,
+) -> Recovery {
+  let restore = i18n::t("startup.restore_backup");
+  let start = i18n::t("startup.start_anyway");
+  let quit = i18n::t("startup.quit");
+  let (hint, buttons) = match backup {
+    Some(_) => (
+      err.hint(),
+      MessageDialogButtons::YesNoCancelCustom(restore.clone(), start.clone(), quit),
+    ),
+    None => (
+      i18n::t("startup.update_check.hint_no_backup"),
+      MessageDialogButtons::OkCancelCustom(start.clone(), quit),
+    ),
+  };
+  let (tx, rx) = tokio::sync::oneshot::channel();
+  app
+    .dialog()
+    .message(format!("{}\n\n{}", hint, err.message()))
+    .title(err.title())
+    .kind(MessageDialogKind::Warning)
+    .buttons(buttons)
+    .show_with_result(move |result| {
+      let _ = tx.send(result);
+    });
+
+  let result = rx.await.unwrap_or_default();
+  let chose = |label: &str| matches!(&result, MessageDialogResult::Custom(l) if l == label);
+  match backup {
+    Some(path) if matches!(result, MessageDialogResult::Yes) || chose(&restore) => {
+      Recovery::RestoreBackup(path.to_path_buf())
+    }
+    Some(_) if matches!(result, MessageDialogResult::No) || chose(&start) => Recovery::StartAnyway,
+    None if matches!(result, MessageDialogResult::Ok) || chose(&start) => Recovery::StartAnyway,
+    _ => Recovery::Quit,
+  }
+}
+
+/// Shows `err` with a remediation hint and Retry / Open Logs / Quit buttons.
+/// Open Logs leaves the dialog's question open, so it is asked again.
+pub async fn report(app: &AppHandle, err: &StartupError) -> Recovery {
+  if let StartupError::UpdateCheck(_, backup) = err {
+    return report_update_check(app, err, backup.as_deref()).await;
+  }
+  let labels = ["startup.retry", "startup.open_logs", "startup.quit"].map(i18n::t);
+  loop {
+    match ask(app, err, &labels).await {
+      MessageDialogResult::Yes => return Recovery::Retry,
+      MessageDialogResult::Custom(label) if label == labels[0] => return Recovery::Retry,
+      MessageDialogResult::No => open_logs(app),
+      MessageDialogResult::Custom(label) if label == labels[1] => open_logs(app),
+      _ => return Recovery::Quit,
+    }
+  }
+}
+
+/// Reports `err` and restarts the engine for as long as the user keeps
+/// choosing Retry. Returns false if they chose Quit, after quitting.
+pub async fn recover(app: &AppHandle, mut err: StartupError) -> bool {
+  loop {
+    eprintln!("[startup] {}", err);
+    engine::record_error(app, err.to_string());
+    let recovery = report(app, &err).await;
+    if recovery == Recovery::Quit {
+      app.exit(1);
+      return false;
+    }
+    // Either answer settles a failed update check; don't ask again next start.
+    if matches!(recovery, Recovery::StartAnyway | Recovery::RestoreBackup(_)) {
+      match engine::data_dir(app) {
+        Ok(dir) => appupdate::dismiss_check(&dir),
+        Err(e) => eprintln!("[startup] {}", e),
+      }
+    }
+    // A fresh attempt shouldn't be blamed on the last one's error.
+    app.state::<EngineState>().info.lock().unwrap().last_error = None;
+    let restarted = match recovery {
+      Recovery::RestoreBackup(path) => backup::restore_backup(app, path)
+        .await
+        .map_err(StartupError::from),
+      _ => engine::restart_engine(app).await,
+    };
+    match restarted {
+      Ok(()) => return true,
+      Err(e) => err = e,
+    }
+  }
+}
+
+/// Whether a failed start should be offered to the user at all: an attached
+/// engine isn't ours to restart.
+pub fn can_recover(app: &AppHandle) -> bool {
+  !*app.state::<EngineState>().attached.lock().unwrap()
+}
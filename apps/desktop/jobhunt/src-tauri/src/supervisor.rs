@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::engine::EngineState;
+use crate::sidecar;
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// A sidecar that stays up this long counts as healthy and resets the backoff.
+const STABLE_AFTER: Duration = Duration::from_secs(120);
+const READY_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Default)]
+struct SupervisorInfo {
+  // Restarts since the sidecar last stayed up for STABLE_AFTER.
+  failures: u32,
+  last_restart: Option<Instant>,
+  restarting: bool,
+}
+
+/// Restart bookkeeping per sidecar name.
+#[derive(Default)]
+pub struct SupervisorState(Mutex<HashMap<String, SupervisorInfo>>);
+
+#[derive(Clone, Serialize)]
+struct Reconnecting {
+  attempt: u32,
+  delay_ms: u64,
+  exit_code: Option<i32>,
+}
+
+#[derive(Clone, Serialize)]
+struct Reconnected {
+  port: Option<u16>,
+}
+
+fn backoff(attempt: u32) -> Duration {
+  let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+  INITIAL_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+/// Called when a sidecar exits without being asked to. Starts a restart loop
+/// for it unless one is already running. Emits `<name>-reconnecting` before
+/// each attempt and `<name>-reconnected` once it is back.
+pub fn crashed(app: &AppHandle, name: &str, exit_code: Option<i32>) {
+  {
+    let state = app.state::<SupervisorState>();
+    let mut all = state.0.lock().unwrap();
+    let sup = all.entry(name.to_string()).or_default();
+    if sup.restarting {
+      return;
+    }
+    sup.restarting = true;
+    if sup.last_restart.is_some_and(|t| t.elapsed() >= STABLE_AFTER) {
+      sup.failures = 0;
+    }
+  }
+
+  eprintln!("[supervisor] {} exited unexpectedly (code {:?})", name, exit_code);
+
+  let app = app.clone();
+  let name = name.to_string();
+  tauri::async_runtime::spawn(async move {
+    restart_loop(&app, &name, exit_code).await;
+  });
+}
+
+fn with_info<T>(app: &AppHandle, name: &str, f: impl FnOnce(&mut SupervisorInfo) -> T) -> T {
+  let state = app.state::<SupervisorState>();
+  let mut all = state.0.lock().unwrap();
+  f(all.entry(name.to_string()).or_default())
+}
+
+async fn restart_loop(app: &AppHandle, name: &str, exit_code: Option<i32>) {
+  loop {
+    let attempt = with_info(app, name, |sup| {
+      sup.failures += 1;
+      sup.failures
+    });
+    let delay = backoff(attempt);
+
+    let _ = app.emit(
+      &format!("{}-reconnecting", name),
+      Reconnecting {
+        attempt,
+        delay_ms: delay.as_millis() as u64,
+        exit_code,
+      },
+    );
+    println!("[supervisor] {} restart attempt {} in {:?}", name, attempt, delay);
+    tokio::time::sleep(delay).await;
+
+    // The app may be closing, or someone else may have restarted it meanwhile.
+    if *app.state::<EngineState>().allow_close.lock().unwrap() || sidecar::is_running(app, name) {
+      break;
+    }
+
+    if let Err(e) = sidecar::spawn(app, name) {
+      eprintln!("[supervisor] {} respawn failed: {}", name, e);
+      sidecar::record_error(app, name, format!("respawn failed: {}", e));
+      continue;
+    }
+
+    match sidecar::wait_ready(app, name, READY_TIMEOUT).await {
+      Ok(port) => {
+        with_info(app, name, |sup| {
+          sup.last_restart = Some(Instant::now());
+          sup.restarting = false;
+        });
+        println!("[supervisor] {} back (port {:?})", name, port);
+        let _ = app.emit(&format!("{}-reconnected", name), Reconnected { port });
+        return;
+      }
+      Err(e) => {
+        eprintln!("[supervisor] {}", e);
+        sidecar::record_error(app, name, e);
+        sidecar::stop(app, name).await;
+      }
+    }
+  }
+
+  with_info(app, name, |sup| sup.restarting = false);
+}
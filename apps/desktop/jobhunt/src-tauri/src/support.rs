@@ -0,0 +1,183 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::engine::{self, EngineState};
+use crate::{datadir, i18n, logs, settings};
+
+// engine.log plus the most recent rotated file is plenty for a bug report.
+const LOG_FILES: usize = 2;
+// Settings keys whose values could identify the user or grant access.
+const SENSITIVE_KEYS: &[&str] = &["url", "token", "secret", "password", "key", "username"];
+const REDACTED: &str = "[redacted]";
+
+/// `system.json` in the bundle.
+#[derive(Serialize)]
+struct SystemInfo {
+  created: u64,
+  app_version: String,
+  engine_version: Option<String>,
+  engine_running: bool,
+  engine_last_error: Option<String>,
+  os: String,
+  os_version: String,
+  arch: String,
+  data_dir: String,
+  data_dir_bytes: Option<u64>,
+}
+
+fn now_secs() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default()
+}
+
+/// Swaps the user's home directory for `~`, so paths don't carry their account name.
+fn scrub(text: &str, home: Option<&str>) -> String {
+  match home {
+    Some(home) if !home.is_empty() => text.replace(home, "~"),
+    _ => text.to_string(),
+  }
+}
+
+/// Blanks out sensitive settings and scrubs home paths from the rest.
+fn redact(value: &mut Value, home: Option<&str>) {
+  match value {
+    Value::String(s) => *s = scrub(s, home),
+    Value::Array(items) => items.iter_mut().for_each(|v| redact(v, home)),
+    Value::Object(map) => {
+      for (key, v) in map.iter_mut() {
+        let sensitive = SENSITIVE_KEYS.iter().any(|k| key.contains(k));
+        if sensitive && !v.is_null() && !v.is_boolean() {
+          *v = Value::String(REDACTED.into());
+        } else {
+          redact(v, home);
+        }
+      }
+    }
+    _ => {}
+  }
+}
+
+fn latest_crash(data_dir: &Path) -> Option<PathBuf> {
+  let mut reports: Vec<PathBuf> = std::fs::read_dir(data_dir.join("crashes"))
+    .ok()?
+    .flatten()
+    .map(|e| e.path())
+    .filter(|p| p.extension().is_some_and(|x| x == "txt"))
+    .collect();
+  // crash-<unix secs>.txt, so the name order is the time order.
+  reports.sort();
+  reports.pop()
+}
+
+fn system_info(app: &AppHandle, data_dir: &Path, home: Option<&str>) -> SystemInfo {
+  let (engine_version, engine_last_error) = {
+    let state = app.state::<EngineState>();
+    let info = state.info.lock().unwrap();
+    (info.version.clone(), info.last_error.clone())
+  };
+  SystemInfo {
+    created: now_secs(),
+    app_version: app.package_info().version.to_string(),
+    engine_version,
+    engine_running: engine::is_running(app),
+    engine_last_error: engine_last_error.map(|e| scrub(&e, home)),
+    os: std::env::consts::OS.to_string(),
+    os_version: sysinfo::System::long_os_version().unwrap_or_else(|| "unknown".into()),
+    arch: std::env::consts::ARCH.to_string(),
+    data_dir: scrub(&data_dir.to_string_lossy(), home),
+    data_dir_bytes: datadir::dir_size(data_dir).ok(),
+  }
+}
+
+/// Everything that goes into the bundle, as (name in zip, contents).
+fn collect(app: &AppHandle) -> Result<Vec<(String, Vec<u8>)>, String> {
+  let data_dir = engine::data_dir(app)?;
+  let home = app
+    .path()
+    .home_dir()
+    .ok()
+    .map(|h| h.to_string_lossy().to_string());
+  let home = home.as_deref();
+  let mut entries = Vec::new();
+
+  let info = system_info(app, &data_dir, home);
+  let json = serde_json::to_vec_pretty(&info).map_err(|e| e.to_string())?;
+  entries.push(("system.json".to_string(), json));
+
+  let mut snapshot = serde_json::to_value(settings::current(app)).map_err(|e| e.to_string())?;
+  redact(&mut snapshot, home);
+  let json = serde_json::to_vec_pretty(&snapshot).map_err(|e| e.to_string())?;
+  entries.push(("settings.json".to_string(), json));
+
+  for path in logs::log_files(app).into_iter().take(LOG_FILES) {
+    let Ok(bytes) = std::fs::read(&path) else {
+      continue;
+    };
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let text = scrub(&String::from_utf8_lossy(&bytes), home);
+    entries.push((format!("logs/{}", name), text.into_bytes()));
+  }
+
+  if let Some(path) = latest_crash(&data_dir) {
+    if let Ok(text) = std::fs::read_to_string(&path) {
+      let name = path.file_name().unwrap_or_default().to_string_lossy();
+      entries.push((format!("crashes/{}", name), scrub(&text, home).into_bytes()));
+    }
+  }
+  Ok(entries)
+}
+
+fn write_zip(dest: &Path, entries: &[(String, Vec<u8>)]) -> Result<(), String> {
+  let file = File::create(dest).map_err(|e| e.to_string())?;
+  let mut zip = ZipWriter::new(file);
+  let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+  for (name, bytes) in entries {
+    zip
+      .start_file(name.as_str(), options)
+      .map_err(|e| e.to_string())?;
+    zip.write_all(bytes).map_err(|e| e.to_string())?;
+  }
+  zip.finish().map_err(|e| e.to_string())?;
+  Ok(())
+}
+
+/// Zips recent engine logs, the latest crash report, versions, OS info,
+/// data dir size, and a redacted settings snapshot into a file the user
+/// picks, ready to attach to a GitHub issue. Returns the path written, or
+/// `None` if the user cancelled.
+pub async fn create_support_bundle(app: &AppHandle) -> Result<Option<String>, String> {
+  let Some(file) = app
+    .dialog()
+    .file()
+    .set_title(i18n::t("dialog.support"))
+    .set_file_name(format!("jobhunt-support-{}.zip", now_secs()))
+    .add_filter("ZIP", &["zip"])
+    .blocking_save_file()
+  else {
+    return Ok(None);
+  };
+  let dest: PathBuf = file.into_path().map_err(|e| e.to_string())?;
+
+  let handle = app.clone();
+  let path = dest.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    let entries = collect(&handle)?;
+    write_zip(&path, &entries)
+  })
+  .await
+  .map_err(|e| e.to_string())??;
+
+  println!("[support] wrote {}", dest.display());
+  Ok(Some(dest.to_string_lossy().to_string()))
+}
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{netproxy, settings};
+
+const QUEUE_FILE: &str = "telemetry.json";
+const UPLOAD_URL: &str = "https://telemetry.jobhunt.app/v1/batch";
+const SCHEMA: u32 = 1;
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const UPLOAD_EVERY: u64 = 24 * 60 * 60;
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(20);
+// Startup samples kept per batch; the oldest are dropped past this.
+const MAX_STARTUP_SAMPLES: usize = 50;
+
+/// Everything one upload sends. No paths, job data, or settings: counts,
+/// durations, and a random install id that is not tied to the user.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetryBatch {
+  pub schema: u32,
+  pub install_id: String,
+  pub app_version: String,
+  pub os: String,
+  pub arch: String,
+  /// Unix seconds when this batch started collecting.
+  pub period_start: u64,
+  pub app_crashes: u64,
+  pub engine_crashes: u64,
+  /// Milliseconds from launch until the engine first answered /health.
+  pub startup_ms: Vec<u64>,
+  /// Times each feature was used, by command name.
+  pub features: BTreeMap<String, u64>,
+}
+
+impl TelemetryBatch {
+  fn is_empty(&self) -> bool {
+    self.app_crashes == 0
+      && self.engine_crashes == 0
+      && self.startup_ms.is_empty()
+      && self.features.is_empty()
+  }
+}
+
+/// `telemetry.json`: the batch being collected and when the last one went out.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Queue {
+  pending: TelemetryBatch,
+  last_upload: Option<u64>,
+}
+
+/// The local queue, loaded on first use. Counting always happens locally;
+/// nothing leaves the machine unless `telemetry` is turned on in settings.
+pub struct TelemetryState {
+  queue: Mutex<Option<Queue>>,
+  launched: Instant,
+  startup_recorded: AtomicBool,
+}
+
+impl Default for TelemetryState {
+  fn default() -> Self {
+    TelemetryState {
+      queue: Mutex::new(None),
+      launched: Instant::now(),
+      startup_recorded: AtomicBool::new(false),
+    }
+  }
+}
+
+fn now_secs() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default()
+}
+
+fn queue_path(app: &AppHandle) -> Result<PathBuf, String> {
+  Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join(QUEUE_FILE))
+}
+
+fn new_install_id() -> String {
+  let mut buf = [0u8; 16];
+  let _ = getrandom::fill(&mut buf);
+  buf.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn fresh_batch(install_id: String) -> TelemetryBatch {
+  TelemetryBatch {
+    schema: SCHEMA,
+    install_id,
+    period_start: now_secs(),
+    ..TelemetryBatch::default()
+  }
+}
+
+fn load(app: &AppHandle) -> Queue {
+  let mut queue: Queue = queue_path(app)
+    .ok()
+    .and_then(|p| std::fs::read_to_string(p).ok())
+    .and_then(|s| serde_json::from_str(&s).ok())
+    .unwrap_or_default();
+  if queue.pending.install_id.is_empty() {
+    queue.pending = fresh_batch(new_install_id());
+  }
+  queue
+}
+
+fn save(app: &AppHandle, queue: &Queue) {
+  let result = queue_path(app).and_then(|path| {
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(queue).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+  });
+  if let Err(e) = result {
+    eprintln!("[telemetry] could not save queue: {}", e);
+  }
+}
+
+/// Applies `f` to the queue and writes it back.
+fn update<T>(app: &AppHandle, f: impl FnOnce(&mut Queue) -> T) -> T {
+  let state = app.state::<TelemetryState>();
+  let mut guard = state.queue.lock().unwrap();
+  let queue = guard.get_or_insert_with(|| load(app));
+  let out = f(queue);
+  save(app, queue);
+  out
+}
+
+/// Counts one use of `feature`.
+pub fn count(app: &AppHandle, feature: &str) {
+  update(app, |q| *q.pending.features.entry(feature.to_string()).or_default() += 1);
+}
+
+pub fn engine_crashed(app: &AppHandle) {
+  update(app, |q| q.pending.engine_crashes += 1);
+}
+
+/// Records how long this launch took to get a ready engine; later restarts are ignored.
+pub fn engine_ready(app: &AppHandle) {
+  let state = app.state::<TelemetryState>();
+  if state.startup_recorded.swap(true, Ordering::SeqCst) {
+    return;
+  }
+  let ms = state.launched.elapsed().as_millis() as u64;
+  update(app, |q| {
+    let samples = &mut q.pending.startup_ms;
+    samples.push(ms);
+    let excess = samples.len().saturating_sub(MAX_STARTUP_SAMPLES);
+    samples.drain(..excess);
+  });
+}
+
+/// The batch as it would be uploaded right now.
+pub fn export(app: &AppHandle) -> TelemetryBatch {
+  let mut batch = update(app, |q| q.pending.clone());
+  batch.app_version = app.package_info().version.to_string();
+  batch.os = std::env::consts::OS.to_string();
+  batch.arch = std::env::consts::ARCH.to_string();
+  batch
+}
+
+pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+  let mut s = settings::current(app);
+  s.telemetry = enabled;
+  settings::replace(app, s)?;
+  println!("[telemetry] uploads {}", if enabled { "enabled" } else { "disabled" });
+  Ok(())
+}
+
+async fn upload(app: &AppHandle, batch: &TelemetryBatch) -> Result<(), String> {
+  let url = settings::current(app)
+    .telemetry_url
+    .unwrap_or_else(|| UPLOAD_URL.to_string());
+  let resp = netproxy::outbound(app)?
+    .timeout(UPLOAD_TIMEOUT)
+    .build()
+    .map_err(|e| e.to_string())?
+    .post(url)
+    .json(batch)
+    .send()
+    .await
+    .map_err(|e| e.to_string())?;
+  if resp.status().is_success() {
+    Ok(())
+  } else {
+    Err(format!("upload returned HTTP {}", resp.status()))
+  }
+}
+
+/// Counts app panics, then checks hourly whether a daily batch is due to upload.
+pub fn start(app: AppHandle) {
+  let hook_app = app.clone();
+  let previous = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |info| {
+    // A panic while the queue is locked would deadlock on update(); skip counting then.
+    let state = hook_app.state::<TelemetryState>();
+    if let Ok(mut guard) = state.queue.try_lock() {
+      let queue = guard.get_or_insert_with(|| load(&hook_app));
+      queue.pending.app_crashes += 1;
+      save(&hook_app, queue);
+    }
+    previous(info);
+  }));
+
+  tauri::async_runtime::spawn(async move {
+    loop {
+      tokio::time::sleep(CHECK_INTERVAL).await;
+      if !settings::current(&app).telemetry {
+        continue;
+      }
+      let due = update(&app, |q| {
+        !q.pending.is_empty()
+          && q.last_upload.is_none_or(|t| now_secs().saturating_sub(t) >= UPLOAD_EVERY)
+      });
+      if !due {
+        continue;
+      }
+      let batch = export(&app);
+      match upload(&app, &batch).await {
+        Ok(()) => {
+          // Keep whatever was counted while the upload was in flight.
+          update(&app, |q| {
+            let sent = std::mem::replace(&mut q.pending, fresh_batch(batch.install_id.clone()));
+            q.pending.app_crashes = sent.app_crashes.saturating_sub(batch.app_crashes);
+            q.pending.engine_crashes =
+              sent.engine_crashes.saturating_sub(batch.engine_crashes);
+            q.pending.startup_ms =
+              sent.startup_ms.get(batch.startup_ms.len()..).unwrap_or_default().to_vec();
+            for (feature, n) in sent.features {
+              let left = n.saturating_sub(batch.features.get(&feature).copied().unwrap_or(0));
+              if left > 0 {
+                q.pending.features.insert(feature, left);
+              }
+            }
+            q.last_upload = Some(now_secs());
+          });
+          println!("[telemetry] uploaded batch");
+        }
+        Err(e) => eprintln!("[telemetry] upload failed: {}", e),
+      }
+    }
+  });
+}
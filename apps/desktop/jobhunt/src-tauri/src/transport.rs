@@ -0,0 +1,116 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Unix socket paths are limited to sun_path: 104 bytes on macOS, 108 on Linux.
+#[cfg(unix)]
+const MAX_SOCKET_PATH: usize = 103;
+// The engine binds its socket inside this directory and keeps it at 0700, so
+// only the user running it can reach the socket.
+pub const SOCKET_DIR: &str = "run";
+#[cfg(unix)]
+const SOCKET_FILE: &str = "engine.sock";
+
+/// Where the engine serves its API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+  /// A TCP port on 127.0.0.1.
+  Tcp(u16),
+  /// A Unix domain socket, or on Windows a named pipe.
+  Socket(PathBuf),
+}
+
+impl Endpoint {
+  pub fn port(&self) -> Option<u16> {
+    match self {
+      Endpoint::Tcp(port) => Some(*port),
+      Endpoint::Socket(_) => None,
+    }
+  }
+
+  /// Base URL for requests. Over a socket the host is ignored, but reqwest
+  /// still needs one.
+  pub fn url(&self, path: &str) -> String {
+    match self {
+      Endpoint::Tcp(port) => format!("http://127.0.0.1:{}{}", port, path),
+      Endpoint::Socket(_) => format!("http://localhost{}", path),
+    }
+  }
+}
+
+impl fmt::Display for Endpoint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Endpoint::Tcp(port) => write!(f, "port {}", port),
+      Endpoint::Socket(path) => write!(f, "{}", path.display()),
+    }
+  }
+}
+
+/// The socket a data dir's engine listens on when it isn't given a port.
+/// Named pipes live in their own namespace, so on Windows the name is derived
+/// from the data dir instead.
+pub fn socket_path(data_dir: &Path) -> Result<PathBuf, String> {
+  #[cfg(windows)]
+  {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(data_dir.to_string_lossy().as_bytes());
+    let id: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(PathBuf::from(format!(r"\\.\pipe\jobhunt-engine-{}", id)))
+  }
+  #[cfg(unix)]
+  {
+    let path = data_dir.join(SOCKET_DIR).join(SOCKET_FILE);
+    if path.as_os_str().len() > MAX_SOCKET_PATH {
+      return Err(format!(
+        "socket path {} is too long; move the data dir or use a TCP port",
+        path.display()
+      ));
+    }
+    Ok(path)
+  }
+}
+
+/// Removes a socket file left behind by an engine that didn't exit cleanly,
+/// which would otherwise make the next bind fail. Pipes vanish with their process.
+pub fn remove_stale(endpoint: &Endpoint) {
+  #[cfg(unix)]
+  if let Endpoint::Socket(path) = endpoint {
+    match std::fs::remove_file(path) {
+      Ok(()) => println!("[transport] removed stale {}", path.display()),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+      Err(e) => eprintln!("[transport] could not remove {}: {}", path.display(), e),
+    }
+  }
+  #[cfg(windows)]
+  let _ = endpoint;
+}
+
+fn build(endpoint: &Endpoint) -> reqwest::Client {
+  // The engine is on this machine; a system proxy couldn't reach it.
+  let builder = reqwest::Client::builder().no_proxy();
+  let builder = match endpoint {
+    Endpoint::Tcp(_) => builder,
+    #[cfg(unix)]
+    Endpoint::Socket(path) => builder.unix_socket(path.as_path()),
+    #[cfg(windows)]
+    Endpoint::Socket(path) => builder.windows_named_pipe(path.as_path()),
+  };
+  builder.build().expect("build engine http client")
+}
+
+/// A client that connects to `endpoint`. Clients are cheap to clone and keep
+/// their connection pool, so the last one is reused until the endpoint changes.
+/// It has no overall timeout, which would cut off `/events`; set one per request.
+pub fn client(endpoint: &Endpoint) -> reqwest::Client {
+  static CACHED: Mutex<Option<(Endpoint, reqwest::Client)>> = Mutex::new(None);
+  let mut cached = CACHED.lock().unwrap();
+  match &*cached {
+    Some((e, client)) if e == endpoint => client.clone(),
+    _ => {
+      let client = build(endpoint);
+      *cached = Some((endpoint.clone(), client.clone()));
+      client
+    }
+  }
+}
@@ -0,0 +1,141 @@
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::engine::{self, EngineState};
+use crate::transport::Endpoint;
+use crate::{i18n, sidecar, writeguard};
+
+const MAIN_WINDOW: &str = "main";
+
+/// Holds the menu items so engine lifecycle and language changes can update their text.
+pub struct TrayState {
+  status: MenuItem<Wry>,
+  // Items with fixed labels, by catalog key.
+  labeled: Vec<(MenuItem<Wry>, &'static str)>,
+}
+
+fn status_text(app: &AppHandle) -> String {
+  match app.state::<EngineState>().info.lock().unwrap().endpoint {
+    Some(Endpoint::Tcp(port)) => i18n::t_with("tray.status.port", &[("port", &port.to_string())]),
+    Some(Endpoint::Socket(_)) => i18n::t("tray.status.socket"),
+    None => i18n::t("tray.status.stopped"),
+  }
+}
+
+/// Re-reads engine state into the tray's status line.
+pub fn refresh_status(app: &AppHandle) {
+  if let Some(tray) = app.try_state::<TrayState>() {
+    let _ = tray.status.set_text(status_text(app));
+  }
+}
+
+/// Re-reads every menu label after the language changes.
+pub fn refresh_labels(app: &AppHandle) {
+  if let Some(tray) = app.try_state::<TrayState>() {
+    for (item, key) in &tray.labeled {
+      let _ = item.set_text(i18n::t(key));
+    }
+  }
+  refresh_status(app);
+}
+
+pub fn set_tooltip(app: &AppHandle, text: &str) {
+  if let Some(tray) = app.tray_by_id("main") {
+    let _ = tray.set_tooltip(Some(text));
+  }
+}
+
+fn toggle_main_window(app: &AppHandle) {
+  let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+    return;
+  };
+  if window.is_visible().unwrap_or(false) {
+    let _ = window.hide();
+  } else {
+    let _ = window.show();
+    let _ = window.set_focus();
+  }
+}
+
+/// Shuts the sidecars down gracefully, engine last, then exits the app.
+/// Does nothing if the user decides to let pending writes finish.
+pub async fn quit(app: &AppHandle) {
+  if !writeguard::confirm_quit(app).await {
+    return;
+  }
+  *app.state::<EngineState>().allow_close.lock().unwrap() = true;
+  sidecar::stop_all(app).await;
+  app.exit(0);
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+  match event.id().as_ref() {
+    "toggle" => toggle_main_window(app),
+    "restart" => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move {
+        if let Err(e) = engine::restart_engine(&app).await {
+          eprintln!("[tray] restart failed: {}", e);
+        }
+      });
+    }
+    "data" => match engine::data_dir(app) {
+      Ok(dir) => {
+        if let Err(e) = app
+          .opener()
+          .open_path(dir.to_string_lossy(), None::<&str>)
+        {
+          eprintln!("[tray] open data folder failed: {}", e);
+        }
+      }
+      Err(e) => eprintln!("[tray] {}", e),
+    },
+    "quit" => {
+      let app = app.clone();
+      tauri::async_runtime::spawn(async move { quit(&app).await });
+    }
+    _ => {}
+  }
+}
+
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+  let item = |id: &str, key: &str| MenuItem::with_id(app, id, i18n::t(key), true, None::<&str>);
+  let toggle = item("toggle", "tray.toggle")?;
+  let status = MenuItem::with_id(app, "status", status_text(app), false, None::<&str>)?;
+  let restart = item("restart", "tray.restart")?;
+  let data = item("data", "tray.data")?;
+  let quit = item("quit", "tray.quit")?;
+
+  let menu = Menu::with_items(
+    app,
+    &[
+      &toggle,
+      &PredefinedMenuItem::separator(app)?,
+      &status,
+      &restart,
+      &data,
+      &PredefinedMenuItem::separator(app)?,
+      &quit,
+    ],
+  )?;
+
+  let mut tray = TrayIconBuilder::with_id("main")
+    .menu(&menu)
+    .tooltip(i18n::t("tray.tooltip"))
+    .on_menu_event(on_menu_event);
+  if let Some(icon) = app.default_window_icon() {
+    tray = tray.icon(icon.clone());
+  }
+  tray.build(app)?;
+
+  let labeled = vec![
+    (toggle, "tray.toggle"),
+    (restart, "tray.restart"),
+    (data, "tray.data"),
+    (quit, "tray.quit"),
+  ];
+  app.manage(TrayState { status, labeled });
+  Ok(())
+}
@@ -0,0 +1,747 @@
+//! Optional passphrase encryption for personal data in the engine's data dir.
+//!
+//! The passphrase never leaves this process: it is stretched with Argon2id
+//! into a 256-bit key, and only the key is handed to the engine, as one JSON
+//! line on its stdin. `vault.json` in the data dir holds the salt, the KDF
+//! parameters and a hash to check the key against; its presence means the
+//! profile is encrypted and the engine must not start until it is unlocked.
+//!
+//! Setting, changing or removing the passphrase writes `vault.pending.json`
+//! instead, and only replaces `vault.json` once an engine started with the
+//! rekey has answered /health, i.e. finished resealing. The pending file
+//! holds each key masked with the other, so if the app dies mid-change
+//! either passphrase unlocks both keys and the reseal is simply retried.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::engine::{self, EngineState};
+use crate::{i18n, splash};
+
+const VAULT_FILE: &str = "vault.json";
+const PENDING_FILE: &str = "vault.pending.json";
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const MIN_PASSPHRASE_LEN: usize = 8;
+// Argon2id with 64 MiB and 3 passes: well under a second on a laptop.
+const M_COST_KIB: u32 = 64 * 1024;
+const T_COST: u32 = 3;
+const P_COST: u32 = 1;
+const PROMPT_ATTEMPTS: u32 = 3;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct VaultFile {
+  version: u32,
+  salt: String,
+  m_cost: u32,
+  t_cost: u32,
+  p_cost: u32,
+  /// SHA-256 of a label and the derived key, to reject a wrong passphrase
+  /// before the engine tries to decrypt with it.
+  verifier: String,
+}
+
+/// A passphrase change the engine hasn't confirmed yet.
+#[derive(Serialize, Deserialize)]
+struct PendingFile {
+  /// The `vault.json` to install, or `None` when the passphrase is being removed.
+  file: Option<VaultFile>,
+  nonce: String,
+  /// The new key masked with the previous one; empty when either is missing.
+  new_key: String,
+  /// The previous key masked with the new one; empty when either is missing.
+  previous_key: String,
+}
+
+struct VaultKey {
+  dir: PathBuf,
+  key: [u8; KEY_LEN],
+}
+
+/// Key material the next engine spawn needs to reseal data after the
+/// passphrase was set, changed or removed.
+struct Rekey {
+  dir: PathBuf,
+  previous: Option<[u8; KEY_LEN]>,
+}
+
+#[derive(Default)]
+pub struct VaultState {
+  key: Mutex<Option<VaultKey>>,
+  rekey: Mutex<Option<Rekey>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct VaultStatus {
+  pub enabled: bool,
+  pub locked: bool,
+}
+
+/// The line written to the engine's stdin. Matches internal/vault.Handoff.
+#[derive(Serialize)]
+struct Handoff {
+  key: String,
+  previous: String,
+  rekey: bool,
+}
+
+fn hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex(s: &str) -> Result<[u8; KEY_LEN], String> {
+  if s.len() != KEY_LEN * 2 || !s.is_ascii() {
+    return Err("malformed key".into());
+  }
+  let mut out = [0u8; KEY_LEN];
+  for (i, byte) in out.iter_mut().enumerate() {
+    *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|e| e.to_string())?;
+  }
+  Ok(out)
+}
+
+fn vault_path(dir: &Path) -> PathBuf {
+  dir.join(VAULT_FILE)
+}
+
+fn pending_path(dir: &Path) -> PathBuf {
+  dir.join(PENDING_FILE)
+}
+
+/// Whether the profile in `dir` is encrypted, or in the middle of changing that.
+pub fn configured(dir: &Path) -> bool {
+  vault_path(dir).exists() || pending_path(dir).exists()
+}
+
+fn load(dir: &Path) -> Result<Option<VaultFile>, String> {
+  match std::fs::read_to_string(vault_path(dir)) {
+    Ok(s) => serde_json::from_str(&s)
+      .map(Some)
+      .map_err(|e| format!("{} is unreadable: {}", VAULT_FILE, e)),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+fn load_pending(dir: &Path) -> Result<Option<PendingFile>, String> {
+  match std::fs::read_to_string(pending_path(dir)) {
+    Ok(s) => serde_json::from_str(&s)
+      .map(Some)
+      .map_err(|e| format!("{} is unreadable: {}", PENDING_FILE, e)),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+fn write_json<T: Serialize>(dir: &Path, name: &str, value: &T) -> Result<(), String> {
+  let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+  let tmp = dir.join(format!("{}.tmp", name));
+  std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+  std::fs::rename(&tmp, dir.join(name)).map_err(|e| e.to_string())
+}
+
+fn remove_if_exists(path: &Path) -> Result<(), String> {
+  match std::fs::remove_file(path) {
+    Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+    _ => Ok(()),
+  }
+}
+
+fn verifier(key: &[u8; KEY_LEN]) -> String {
+  let mut hasher = Sha256::new();
+  hasher.update(b"jobhunt-vault-verifier\0");
+  hasher.update(key);
+  format!("{:x}", hasher.finalize())
+}
+
+/// XORs `value` with a pad derived from `key`, `nonce` and `label`. Applying
+/// it twice gives `value` back.
+fn mask(
+  key: &[u8; KEY_LEN],
+  nonce: &str,
+  label: &str,
+  value: &[u8; KEY_LEN],
+) -> Result<[u8; KEY_LEN], String> {
+  let mut mac = Hmac::<Sha256>::new_from_slice(key).map_err(|e| e.to_string())?;
+  mac.update(b"jobhunt-vault-rekey\0");
+  mac.update(label.as_bytes());
+  mac.update(nonce.as_bytes());
+  let pad = mac.finalize().into_bytes();
+  let mut out = *value;
+  for (byte, p) in out.iter_mut().zip(pad.iter()) {
+    *byte ^= p;
+  }
+  Ok(out)
+}
+
+fn derive(passphrase: &str, file: &VaultFile) -> Result<[u8; KEY_LEN], String> {
+  let salt = base64::engine::general_purpose::STANDARD
+    .decode(&file.salt)
+    .map_err(|e| e.to_string())?;
+  let params = Params::new(file.m_cost, file.t_cost, file.p_cost, Some(KEY_LEN))
+    .map_err(|e| e.to_string())?;
+  let mut key = [0u8; KEY_LEN];
+  Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+    .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+    .map_err(|e| e.to_string())?;
+  Ok(key)
+}
+
+/// Derives on a blocking thread (Argon2 is deliberately slow) and checks the result.
+async fn derive_checked(passphrase: String, file: VaultFile) -> Result<[u8; KEY_LEN], String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let key = derive(&passphrase, &file)?;
+    if verifier(&key) != file.verifier {
+      return Err("wrong passphrase".to_string());
+    }
+    Ok(key)
+  })
+  .await
+  .map_err(|e| e.to_string())?
+}
+
+fn new_vault_file(passphrase: &str) -> Result<(VaultFile, [u8; KEY_LEN]), String> {
+  let mut salt = [0u8; SALT_LEN];
+  getrandom::fill(&mut salt).map_err(|e| e.to_string())?;
+  let mut file = VaultFile {
+    version: 1,
+    salt: base64::engine::general_purpose::STANDARD.encode(salt),
+    m_cost: M_COST_KIB,
+    t_cost: T_COST,
+    p_cost: P_COST,
+    verifier: String::new(),
+  };
+  let key = derive(passphrase, &file)?;
+  file.verifier = verifier(&key);
+  Ok((file, key))
+}
+
+fn unlocked_key(app: &AppHandle, dir: &Path) -> Option<[u8; KEY_LEN]> {
+  let state = app.state::<VaultState>();
+  let key = state.key.lock().unwrap();
+  key.as_ref().filter(|k| k.dir == dir).map(|k| k.key)
+}
+
+/// Carries the unlocked key and any pending rekey over to `new` after the
+/// profile's files were moved there from `old`, so the engine can start on
+/// `new` without asking for the passphrase again.
+pub fn rebind(app: &AppHandle, old: &Path, new: &Path) {
+  let state = app.state::<VaultState>();
+  let mut key = state.key.lock().unwrap();
+  if let Some(key) = key.as_mut().filter(|k| k.dir == old) {
+    key.dir = new.to_path_buf();
+  }
+  let mut rekey = state.rekey.lock().unwrap();
+  if let Some(rekey) = rekey.as_mut().filter(|r| r.dir == old) {
+    rekey.dir = new.to_path_buf();
+  }
+}
+
+/// Whether the next engine on `dir` is to reseal its data.
+pub fn rekey_pending(app: &AppHandle, dir: &Path) -> bool {
+  let state = app.state::<VaultState>();
+  let rekey = state.rekey.lock().unwrap();
+  rekey.as_ref().is_some_and(|r| r.dir == dir)
+}
+
+/// Whether an engine on `dir` needs a key: `vault.json` exists, or a pending
+/// change is setting one.
+fn enabled(dir: &Path) -> Result<bool, String> {
+  Ok(match load_pending(dir)? {
+    Some(pending) => pending.file.is_some(),
+    None => vault_path(dir).exists(),
+  })
+}
+
+pub fn status(app: &AppHandle) -> VaultStatus {
+  let Ok(dir) = engine::data_dir(app) else {
+    return VaultStatus {
+      enabled: false,
+      locked: false,
+    };
+  };
+  // An unreadable pending file keeps the engine from starting, so show it as locked.
+  let enabled = enabled(&dir).unwrap_or(true);
+  // A change left over from an earlier run needs a passphrase to finish, even a removal.
+  let locked = if pending_path(&dir).exists() {
+    !rekey_pending(app, &dir)
+  } else {
+    enabled && unlocked_key(app, &dir).is_none()
+  };
+  VaultStatus { enabled, locked }
+}
+
+pub fn is_locked(app: &AppHandle) -> bool {
+  status(app).locked
+}
+
+fn announce(app: &AppHandle) {
+  let _ = app.emit("vault-changed", status(app));
+}
+
+/// The stdin line for an engine about to start on `dir`, or `None` when the
+/// profile has no vault. Errors while the vault is locked.
+///
+/// A rekey stays pending, and is handed to every spawn, until
+/// `confirm_rekey` sees an engine come up with it.
+pub fn handoff(app: &AppHandle, dir: &Path) -> Result<Option<String>, String> {
+  let previous = {
+    let state = app.state::<VaultState>();
+    let rekey = state.rekey.lock().unwrap();
+    rekey.as_ref().filter(|r| r.dir == dir).map(|r| r.previous)
+  };
+  if previous.is_none() && pending_path(dir).exists() {
+    return Err("JobHunt is locked; enter your passphrase to unlock it".into());
+  }
+  let key = if enabled(dir)? {
+    Some(unlocked_key(app, dir).ok_or("JobHunt is locked; enter your passphrase to unlock it")?)
+  } else {
+    None
+  };
+  if key.is_none() && previous.is_none() {
+    return Ok(None);
+  }
+  let line = Handoff {
+    key: key.map(|k| hex(&k)).unwrap_or_default(),
+    previous: previous.flatten().map(|k| hex(&k)).unwrap_or_default(),
+    rekey: previous.is_some(),
+  };
+  let mut json = serde_json::to_string(&line).map_err(|e| e.to_string())?;
+  json.push('\n');
+  Ok(Some(json))
+}
+
+/// Called once an engine started with the pending rekey is serving, which
+/// it only does after resealing. Installs the new `vault.json` (or removes
+/// it) and stops handing the previous key out.
+pub fn confirm_rekey(app: &AppHandle, dir: &Path) -> Result<(), String> {
+  if !rekey_pending(app, dir) {
+    return Ok(());
+  }
+  if let Some(pending) = load_pending(dir)? {
+    match &pending.file {
+      Some(file) => write_json(dir, VAULT_FILE, file)?,
+      None => remove_if_exists(&vault_path(dir))?,
+    }
+    remove_if_exists(&pending_path(dir))?;
+  }
+  let state = app.state::<VaultState>();
+  let mut rekey = state.rekey.lock().unwrap();
+  rekey.take_if(|r| r.dir == dir);
+  println!("[vault] reseal confirmed");
+  Ok(())
+}
+
+/// Recovers both keys of a change an earlier run didn't finish, from either
+/// the old passphrase or the new one. Returns the new key (`None` when
+/// removing) and the previous one.
+async fn resume_pending(
+  passphrase: String,
+  dir: &Path,
+  pending: PendingFile,
+) -> Result<(Option<[u8; KEY_LEN]>, Option<[u8; KEY_LEN]>), String> {
+  let damaged = || format!("{} does not match {}", PENDING_FILE, VAULT_FILE);
+  let Some(current) = load(dir)? else {
+    // Setting a first passphrase: there is no previous key.
+    let file = pending.file.ok_or_else(damaged)?;
+    return Ok((Some(derive_checked(passphrase, file).await?), None));
+  };
+  match derive_checked(passphrase.clone(), current.clone()).await {
+    Ok(previous) => {
+      let Some(file) = pending.file else {
+        return Ok((None, Some(previous)));
+      };
+      let key = mask(&previous, &pending.nonce, "new", &unhex(&pending.new_key)?)?;
+      if verifier(&key) != file.verifier {
+        return Err(damaged());
+      }
+      Ok((Some(key), Some(previous)))
+    }
+    Err(e) => {
+      let Some(file) = pending.file else {
+        return Err(e);
+      };
+      let key = derive_checked(passphrase, file).await?;
+      let previous = mask(
+        &key,
+        &pending.nonce,
+        "previous",
+        &unhex(&pending.previous_key)?,
+      )?;
+      if verifier(&previous) != current.verifier {
+        return Err(damaged());
+      }
+      Ok((Some(key), Some(previous)))
+    }
+  }
+}
+
+/// Checks `passphrase` and, if the engine isn't running yet, starts it.
+pub async fn unlock(app: &AppHandle, passphrase: String) -> Result<(), String> {
+  let dir = engine::data_dir(app)?;
+  let state = app.state::<VaultState>();
+  match load_pending(&dir)? {
+    Some(pending) => {
+      let (key, previous) = resume_pending(passphrase, &dir, pending).await?;
+      *state.key.lock().unwrap() = key.map(|key| VaultKey {
+        dir: dir.clone(),
+        key,
+      });
+      *state.rekey.lock().unwrap() = Some(Rekey { dir, previous });
+      println!("[vault] unlocked; finishing an interrupted passphrase change");
+    }
+    None => {
+      let file = load(&dir)?.ok_or("no passphrase is set for this profile")?;
+      let key = derive_checked(passphrase, file).await?;
+      *state.key.lock().unwrap() = Some(VaultKey { dir, key });
+      println!("[vault] unlocked");
+    }
+  }
+  announce(app);
+
+  if !engine::is_running(app) {
+    engine::spawn_engine(app).await?;
+  }
+  Ok(())
+}
+
+/// Stops the engine and forgets the key until the next unlock.
+pub async fn lock(app: &AppHandle) -> Result<(), String> {
+  if !status(app).enabled {
+    return Err("no passphrase is set for this profile".into());
+  }
+  engine::stop_engine(app).await;
+  *app.state::<VaultState>().key.lock().unwrap() = None;
+  println!("[vault] locked");
+  announce(app);
+  Ok(())
+}
+
+/// Sets, changes (`current` required) or, with `new` as `None`, removes the
+/// passphrase, then restarts the engine so it reseals existing data.
+pub async fn set_passphrase(
+  app: &AppHandle,
+  current: Option<String>,
+  new: Option<String>,
+) -> Result<(), String> {
+  if *app.state::<EngineState>().attached.lock().unwrap() {
+    return Err("attached to an external engine; it can't be re-encrypted from here".into());
+  }
+  if new.as_ref().is_some_and(|p| p.chars().count() < MIN_PASSPHRASE_LEN) {
+    return Err(format!("passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+  }
+  let dir = engine::data_dir(app)?;
+  if pending_path(&dir).exists() {
+    return Err("a passphrase change is still being applied; try again in a moment".into());
+  }
+  let previous = match load(&dir)? {
+    Some(file) => {
+      let current = current.ok_or("enter your current passphrase")?;
+      Some(derive_checked(current, file).await?)
+    }
+    None if new.is_none() => return Err("no passphrase is set for this profile".into()),
+    None => None,
+  };
+
+  let (file, key) = match new {
+    Some(passphrase) => {
+      let (file, key) =
+        tauri::async_runtime::spawn_blocking(move || new_vault_file(&passphrase))
+          .await
+          .map_err(|e| e.to_string())??;
+      (Some(file), Some(key))
+    }
+    None => (None, None),
+  };
+  let mut nonce = [0u8; SALT_LEN];
+  getrandom::fill(&mut nonce).map_err(|e| e.to_string())?;
+  let nonce = hex(&nonce);
+  let (new_key, previous_key) = match (key, previous) {
+    (Some(key), Some(previous)) => (
+      hex(&mask(&previous, &nonce, "new", &key)?),
+      hex(&mask(&key, &nonce, "previous", &previous)?),
+    ),
+    _ => Default::default(),
+  };
+  write_json(
+    &dir,
+    PENDING_FILE,
+    &PendingFile {
+      file,
+      nonce,
+      new_key,
+      previous_key,
+    },
+  )?;
+
+  {
+    let state = app.state::<VaultState>();
+    *state.key.lock().unwrap() = key.map(|key| VaultKey {
+      dir: dir.clone(),
+      key,
+    });
+    *state.rekey.lock().unwrap() = Some(Rekey { dir, previous });
+  }
+  announce(app);
+  println!("[vault] passphrase {}", if key.is_some() { "set" } else { "removed" });
+  Ok(engine::restart_engine(app).await?)
+}
+
+#[cfg(windows)]
+mod imp {
+  use std::os::windows::process::CommandExt;
+  use std::process::{Command, Stdio};
+
+  use windows_sys::Win32::System::Threading::CREATE_NO_WINDOW;
+
+  pub fn prompt(message: &str) -> Result<Option<String>, String> {
+    let script = format!(
+      "$c = Get-Credential -UserName 'JobHunt' -Message '{}'; \
+       if ($c) {{ [Console]::Out.Write($c.GetNetworkCredential().Password) }}",
+      message.replace('\'', "''")
+    );
+    let out = Command::new("powershell")
+      .args(["-NoProfile", "-Command", &script])
+      .creation_flags(CREATE_NO_WINDOW)
+      .stdin(Stdio::null())
+      .output()
+      .map_err(|e| e.to_string())?;
+    let pass = String::from_utf8_lossy(&out.stdout).to_string();
+    Ok((!pass.is_empty()).then_some(pass))
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+  use std::process::{Command, Stdio};
+
+  pub fn prompt(message: &str) -> Result<Option<String>, String> {
+    let dialog = format!(
+      "display dialog \"{}\" default answer \"\" with hidden answer with title \"JobHunt\" \
+       with icon caution",
+      message.replace('\\', "\\\\").replace('"', "\\\"")
+    );
+    let out = Command::new("osascript")
+      .args(["-e", &dialog, "-e", "text returned of result"])
+      .stdin(Stdio::null())
+      .output()
+      .map_err(|e| e.to_string())?;
+    // osascript exits non-zero when the user presses Cancel.
+    if !out.status.success() {
+      return Ok(None);
+    }
+    let pass = String::from_utf8_lossy(&out.stdout)
+      .trim_end_matches('\n')
+      .to_string();
+    Ok((!pass.is_empty()).then_some(pass))
+  }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod imp {
+  use std::process::{Command, Stdio};
+
+  pub fn prompt(message: &str) -> Result<Option<String>, String> {
+    let attempts: [(&str, Vec<&str>); 2] = [
+      (
+        "zenity",
+        vec!["--entry", "--hide-text", "--title", "JobHunt", "--text", message],
+      ),
+      ("kdialog", vec!["--title", "JobHunt", "--password", message]),
+    ];
+    for (program, args) in attempts {
+      let out = match Command::new(program).args(&args).stdin(Stdio::null()).output() {
+        Ok(out) => out,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+        Err(e) => return Err(e.to_string()),
+      };
+      if !out.status.success() {
+        return Ok(None);
+      }
+      let pass = String::from_utf8_lossy(&out.stdout)
+        .trim_end_matches('\n')
+        .to_string();
+      return Ok((!pass.is_empty()).then_some(pass));
+    }
+    Err("no passphrase dialog available (install zenity or kdialog)".into())
+  }
+}
+
+/// Asks for the passphrase with a native dialog before the engine starts.
+/// If the user cancels or gives up, the app stays locked and the window can
+/// unlock it later with `unlock_vault`.
+pub fn unlock_on_launch(app: AppHandle, show_splash: bool) {
+  tauri::async_runtime::spawn(async move {
+    let mut message = i18n::t("vault.prompt");
+    for _ in 0..PROMPT_ATTEMPTS {
+      let asked = message.clone();
+      let answer = tauri::async_runtime::spawn_blocking(move || imp::prompt(&asked))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+      let passphrase = match answer {
+        Ok(Some(passphrase)) => passphrase,
+        Ok(None) => break,
+        Err(e) => {
+          eprintln!("[vault] {}", e);
+          break;
+        }
+      };
+      match unlock(&app, passphrase).await {
+        Ok(()) => {
+          if show_splash {
+            splash::start(app.clone(), None);
+          }
+          return;
+        }
+        Err(e) => message = i18n::t_with("vault.prompt_retry", &[("error", &e)]),
+      }
+    }
+    println!("[vault] still locked");
+    engine::record_error(&app, "JobHunt is locked".into());
+    announce(&app);
+    if show_splash {
+      if let Some(main) = app.get_webview_window("main") {
+        let _ = main.show();
+        let _ = main.set_focus();
+      }
+      if let Some(splash) = app.get_webview_window(splash::SPLASH_WINDOW) {
+        let _ = splash.destroy();
+      }
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// A vault file with cheap KDF parameters, so tests don't spend seconds in Argon2.
+  fn vault_file(passphrase: &str, salt: u8) -> (VaultFile, [u8; KEY_LEN]) {
+    let mut file = VaultFile {
+      version: 1,
+      salt: base64::engine::general_purpose::STANDARD.encode([salt; SALT_LEN]),
+      m_cost: 64,
+      t_cost: 1,
+      p_cost: 1,
+      verifier: String::new(),
+    };
+    let key = derive(passphrase, &file).unwrap();
+    file.verifier = verifier(&key);
+    (file, key)
+  }
+
+  fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jobhunt-vault-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+  }
+
+  /// A data dir part way through changing the passphrase from `old` to `new`
+  /// (either may be absent), as `set_passphrase` leaves it.
+  fn interrupted(name: &str, old: Option<&str>, new: Option<&str>) -> PathBuf {
+    let dir = temp_dir(name);
+    let previous = old.map(|p| {
+      let (file, key) = vault_file(p, 1);
+      write_json(&dir, VAULT_FILE, &file).unwrap();
+      key
+    });
+    let (file, key) = match new.map(|p| vault_file(p, 2)) {
+      Some((file, key)) => (Some(file), Some(key)),
+      None => (None, None),
+    };
+    let nonce = "00112233445566778899aabbccddeeff".to_string();
+    let (new_key, previous_key) = match (key, previous) {
+      (Some(key), Some(previous)) => (
+        hex(&mask(&previous, &nonce, "new", &key).unwrap()),
+        hex(&mask(&key, &nonce, "previous", &previous).unwrap()),
+      ),
+      _ => Default::default(),
+    };
+    let pending = PendingFile {
+      file,
+      nonce,
+      new_key,
+      previous_key,
+    };
+    write_json(&dir, PENDING_FILE, &pending).unwrap();
+    dir
+  }
+
+  type Keys = (Option<[u8; KEY_LEN]>, Option<[u8; KEY_LEN]>);
+
+  fn resume(dir: &Path, passphrase: &str) -> Result<Keys, String> {
+    let pending = load_pending(dir).unwrap().unwrap();
+    tauri::async_runtime::block_on(resume_pending(passphrase.into(), dir, pending))
+  }
+
+  #[test]
+  fn mask_applied_twice_gives_the_value_back() {
+    let (key, value) = ([7u8; KEY_LEN], [9u8; KEY_LEN]);
+    let masked = mask(&key, "nonce", "new", &value).unwrap();
+    assert_ne!(masked, value);
+    assert_eq!(mask(&key, "nonce", "new", &masked).unwrap(), value);
+    // The label and nonce are part of the pad.
+    assert_ne!(mask(&key, "nonce", "previous", &value).unwrap(), masked);
+    assert_ne!(mask(&key, "other", "new", &value).unwrap(), masked);
+  }
+
+  #[test]
+  fn hex_round_trips_and_unhex_rejects_malformed_keys() {
+    let key = [0xab; KEY_LEN];
+    assert_eq!(unhex(&hex(&key)).unwrap(), key);
+    assert!(unhex("abcd").is_err());
+    assert!(unhex(&"zz".repeat(KEY_LEN)).is_err());
+  }
+
+  #[test]
+  fn resume_recovers_both_keys_from_either_passphrase() {
+    let dir = interrupted("change", Some("old passphrase"), Some("new passphrase"));
+    let (_, old_key) = vault_file("old passphrase", 1);
+    let (_, new_key) = vault_file("new passphrase", 2);
+    for passphrase in ["old passphrase", "new passphrase"] {
+      let (key, previous) = resume(&dir, passphrase).unwrap();
+      assert_eq!(key, Some(new_key), "{}", passphrase);
+      assert_eq!(previous, Some(old_key), "{}", passphrase);
+    }
+    assert!(resume(&dir, "neither").is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+
+  #[test]
+  fn resume_a_first_passphrase_has_no_previous_key() {
+    let dir = interrupted("first", None, Some("new passphrase"));
+    let (_, new_key) = vault_file("new passphrase", 2);
+    assert_eq!(resume(&dir, "new passphrase").unwrap(), (Some(new_key), None));
+    assert!(resume(&dir, "wrong passphrase").is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+
+  #[test]
+  fn resume_a_removal_needs_the_old_passphrase() {
+    let dir = interrupted("remove", Some("old passphrase"), None);
+    let (_, old_key) = vault_file("old passphrase", 1);
+    assert_eq!(resume(&dir, "old passphrase").unwrap(), (None, Some(old_key)));
+    assert!(resume(&dir, "wrong passphrase").is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+
+  #[test]
+  fn resume_rejects_a_pending_file_for_another_vault() {
+    let dir = interrupted("mismatch", Some("old passphrase"), Some("new passphrase"));
+    // vault.json replaced by one the pending change wasn't made against.
+    let (other, _) = vault_file("other passphrase", 3);
+    write_json(&dir, VAULT_FILE, &other).unwrap();
+    let err = resume(&dir, "new passphrase").unwrap_err();
+    assert!(err.contains("does not match"), "{}", err);
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+}
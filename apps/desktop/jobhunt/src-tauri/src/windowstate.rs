@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime, Window};
+
+use crate::profiles;
+
+const STATE_FILE: &str = "window-state.json";
+const MAIN_WINDOW: &str = "main";
+// How much of the window's top edge must land on a monitor for it to be grabbable.
+const MIN_VISIBLE: i64 = 100;
+const TITLE_BAR: i64 = 40;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct Bounds {
+  x: i32,
+  y: i32,
+  width: u32,
+  height: u32,
+}
+
+/// The main window's last normal (not maximized) bounds, in physical pixels.
+#[derive(Clone, Serialize, Deserialize)]
+struct WindowGeometry {
+  bounds: Bounds,
+  maximized: bool,
+  monitor: Option<String>,
+}
+
+/// `window-state.json`, keyed by profile name.
+#[derive(Default, Serialize, Deserialize)]
+struct StateFile {
+  profiles: HashMap<String, WindowGeometry>,
+}
+
+/// The geometry tracked since the window was last restored.
+#[derive(Default)]
+pub struct WindowState(Mutex<Option<WindowGeometry>>);
+
+fn state_path(app: &AppHandle) -> Result<PathBuf, String> {
+  Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join(STATE_FILE))
+}
+
+fn load(app: &AppHandle) -> StateFile {
+  state_path(app)
+    .ok()
+    .and_then(|p| std::fs::read_to_string(p).ok())
+    .and_then(|s| serde_json::from_str(&s).ok())
+    .unwrap_or_default()
+}
+
+/// Records the main window's geometry as it moves and resizes.
+pub fn track<R: Runtime>(window: &Window<R>) {
+  if window.label() != MAIN_WINDOW || window.is_minimized().unwrap_or(false) {
+    return;
+  }
+  let maximized = window.is_maximized().unwrap_or(false);
+  let state = window.state::<WindowState>();
+  let mut geometry = state.0.lock().unwrap();
+  // A maximized window reports the monitor's size; keep the bounds to un-maximize to.
+  if maximized {
+    if let Some(g) = geometry.as_mut() {
+      g.maximized = true;
+      return;
+    }
+  }
+  let (Ok(pos), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+    return;
+  };
+  *geometry = Some(WindowGeometry {
+    bounds: Bounds {
+      x: pos.x,
+      y: pos.y,
+      width: size.width,
+      height: size.height,
+    },
+    maximized,
+    monitor: window
+      .current_monitor()
+      .ok()
+      .flatten()
+      .and_then(|m| m.name().cloned()),
+  });
+}
+
+/// Writes the tracked geometry under the active profile.
+pub fn save(app: &AppHandle) {
+  let Some(geometry) = app.state::<WindowState>().0.lock().unwrap().clone() else {
+    return;
+  };
+  let profile = profiles::active(app);
+  let mut file = load(app);
+  file.profiles.insert(profile, geometry);
+  let result = state_path(app).and_then(|path| {
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+  });
+  if let Err(e) = result {
+    eprintln!("[windowstate] could not save: {}", e);
+  }
+}
+
+/// How many pixels of the window's title strip fall inside `monitor`'s work area.
+fn visible_title(bounds: &Bounds, monitor: &Monitor) -> i64 {
+  let area = monitor.work_area();
+  let (ax, ay) = (area.position.x as i64, area.position.y as i64);
+  let (aw, ah) = (area.size.width as i64, area.size.height as i64);
+  let (x, y, w) = (bounds.x as i64, bounds.y as i64, bounds.width as i64);
+  let overlap_x = (x + w).min(ax + aw) - x.max(ax);
+  let overlap_y = (y + TITLE_BAR).min(ay + ah) - y.max(ay);
+  if overlap_y <= 0 {
+    return 0;
+  }
+  overlap_x.max(0)
+}
+
+/// Fits `bounds` onto `monitor`: shrunk to its work area and centred on it.
+fn centred_on(bounds: &Bounds, monitor: &Monitor) -> Bounds {
+  let area = monitor.work_area();
+  let width = bounds.width.min(area.size.width);
+  let height = bounds.height.min(area.size.height);
+  Bounds {
+    x: area.position.x + ((area.size.width - width) / 2) as i32,
+    y: area.position.y + ((area.size.height - height) / 2) as i32,
+    width,
+    height,
+  }
+}
+
+/// Picks where to put a saved window on the monitors attached now: where it
+/// was if that's still on screen, otherwise centred on its old monitor or
+/// the primary one.
+fn placement(window: &tauri::WebviewWindow, geometry: &WindowGeometry) -> Option<Bounds> {
+  let monitors = window.available_monitors().ok()?;
+  if monitors
+    .iter()
+    .any(|m| visible_title(&geometry.bounds, m) >= MIN_VISIBLE)
+  {
+    return Some(geometry.bounds);
+  }
+  let fallback = monitors
+    .iter()
+    .find(|m| m.name().is_some() && m.name() == geometry.monitor.as_ref())
+    .cloned()
+    .or_else(|| window.primary_monitor().ok().flatten())
+    .or_else(|| monitors.into_iter().next())?;
+  Some(centred_on(&geometry.bounds, &fallback))
+}
+
+/// Moves the main window to where it was last time for the active profile.
+pub fn restore(app: &AppHandle) {
+  let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+    return;
+  };
+  let profile = profiles::active(app);
+  let Some(geometry) = load(app).profiles.remove(&profile) else {
+    return;
+  };
+  let Some(bounds) = placement(&window, &geometry) else {
+    return;
+  };
+  let _ = window.unmaximize();
+  let _ = window.set_size(PhysicalSize::new(bounds.width, bounds.height));
+  let _ = window.set_position(PhysicalPosition::new(bounds.x, bounds.y));
+  if geometry.maximized {
+    let _ = window.maximize();
+  }
+  *app.state::<WindowState>().0.lock().unwrap() = Some(WindowGeometry { bounds, ..geometry });
+}
@@ -0,0 +1,117 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::{engine, i18n, proxy};
+
+const POLL: Duration = Duration::from_secs(1);
+
+/// One in-flight write as reported by the engine's `/control/writes`.
+#[derive(Deserialize)]
+struct WriteOp {
+  label: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Writes {
+  active: Vec<WriteOp>,
+}
+
+/// Set while the "quit anyway?" dialog is up, so repeated quit attempts don't stack dialogs.
+#[derive(Default)]
+pub struct WriteGuardState(Mutex<bool>);
+
+/// The engine's in-flight writes. An engine that can't be asked has nothing
+/// we could wait for, so errors count as none. The endpoint only answers
+/// signed requests, which `engine_fetch` sends.
+async fn active(app: &AppHandle) -> Vec<WriteOp> {
+  if !engine::is_running(app) {
+    return Vec::new();
+  }
+  match proxy::engine_fetch(app, "GET", "/control/writes", None).await {
+    Ok(resp) if resp.status < 400 => serde_json::from_str::<Writes>(&resp.body)
+      .map(|w| w.active)
+      .unwrap_or_default(),
+    Ok(resp) => {
+      eprintln!("[writeguard] engine refused /control/writes: HTTP {}", resp.status);
+      Vec::new()
+    }
+    Err(_) => Vec::new(),
+  }
+}
+
+fn describe(ops: &[WriteOp]) -> String {
+  match ops {
+    [op] => i18n::t_with("writeguard.one", &[("op", &op.label)]),
+    _ => {
+      let list: Vec<String> = ops.iter().map(|op| format!("• {}", op.label)).collect();
+      i18n::t_with("writeguard.many", &[("ops", &list.join("\n"))])
+    }
+  }
+}
+
+/// Waits up to `timeout` for in-flight writes to finish, without asking the
+/// user. Returns false if some are still running.
+pub async fn wait_idle(app: &AppHandle, timeout: Duration) -> bool {
+  let idle = async {
+    while !active(app).await.is_empty() {
+      tokio::time::sleep(POLL).await;
+    }
+  };
+  tokio::time::timeout(timeout, idle).await.is_ok()
+}
+
+/// Decides whether quitting can go ahead. With no writes in flight that's
+/// immediate; otherwise asks the user, and also goes ahead on its own once
+/// the writes finish. Returns false if the user chose to keep the app open.
+pub async fn confirm_quit(app: &AppHandle) -> bool {
+  let ops = active(app).await;
+  if ops.is_empty() {
+    return true;
+  }
+  {
+    let state = app.state::<WriteGuardState>();
+    let mut open = state.0.lock().unwrap();
+    if *open {
+      return false;
+    }
+    *open = true;
+  }
+
+  let (tx, rx) = tokio::sync::oneshot::channel();
+  app
+    .dialog()
+    .message(describe(&ops))
+    .title(i18n::t("writeguard.title"))
+    .kind(MessageDialogKind::Warning)
+    .buttons(MessageDialogButtons::OkCancelCustom(
+      i18n::t("writeguard.quit"),
+      i18n::t("writeguard.keep"),
+    ))
+    .show(move |quit| {
+      let _ = tx.send(quit);
+    });
+
+  let finished = async {
+    loop {
+      tokio::time::sleep(POLL).await;
+      if active(app).await.is_empty() {
+        return;
+      }
+    }
+  };
+  let proceed = tokio::select! {
+    choice = rx => choice.unwrap_or(false),
+    _ = finished => {
+      println!("[writeguard] pending writes finished; quitting");
+      true
+    }
+  };
+
+  *app.state::<WriteGuardState>().0.lock().unwrap() = false;
+  proceed
+}
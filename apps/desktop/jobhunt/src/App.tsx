@@ -1,6 +1,6 @@
 // App.tsx
-import { useEffect, useMemo, useState } from "react";
-import { events, getJobs, seedJob, deleteJob } from "./api";
+import { useEffect, useMemo, useRef, useState } from "react";
+import { assetUrl, events, getJobs, seedJob, deleteJob, setJobDeadline } from "./api";
 import Preferences from "./Preferences";
 import Scraping from "./Scraping";
 import AutoApply from "./AutoApply";
@@ -8,13 +8,13 @@ import { useAutoApplyQueue } from "./useApplyQueue";
 import ErrorBoundary from "./ErrorBoundary";
 import Select from "./ui/Select";
 import { Command } from "@tauri-apps/plugin-shell";
-import { check } from "@tauri-apps/plugin-updater";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
+// The shell checks for, downloads and installs an update, shutting the engine
+// down cleanly before the restart. Resolves at once when there is none.
 async function checkForUpdates() {
-  const update = await check();
-  if (!update) return;
-  await update.downloadAndInstall();
+  await invoke("install_app_update");
 }
 
 export function ExportDbButton() {
@@ -55,8 +55,25 @@ type Job = {
   date: string;
   seenFromSource?: string;
   companyLogoURL?: string;
+  deadline?: string;
 };
 
+// The date part of an RFC 3339 deadline in local time, for a date input.
+function deadlineDate(deadline?: string) {
+  if (!deadline) return "";
+  const d = new Date(deadline);
+  const pad = (n: number) => String(n).padStart(2, "0");
+  return `${d.getFullYear()}-${pad(d.getMonth() + 1)}-${pad(d.getDate())}`;
+}
+
+// Languages the shell has catalogs for (src-tauri/locales).
+const LANGUAGES = [
+  { value: "en", label: "English" },
+  { value: "es", label: "Español" },
+  { value: "fr", label: "Français" },
+  { value: "de", label: "Deutsch" },
+];
+
 type SortKey   = "score" | "date" | "company" | "title";
 type WindowKey = "24h" | "7d" | "all";
 type View      = "jobs" | "prefs" | "scrape" | "apply";
@@ -69,21 +86,27 @@ export default function App() {
   const [view,      setView]      = useState<View>("jobs");
   const [jobs,      setJobs]      = useState<Job[]>([]);
   const [err,       setErr]       = useState("");
+  const [locale,    setLocale]    = useState("en");
 
   const { addToQueue, queueCount } = useAutoApplyQueue();
 
-  checkForUpdates().catch(console.error);
-
   const params = useMemo(() => {
     return new URLSearchParams({ sort, window: windowKey }).toString();
   }, [sort, windowKey]);
 
+  // Only the newest refresh matters; cancel one still waiting on the engine.
+  const inflight = useRef<AbortController | null>(null);
+
   async function refresh() {
+    inflight.current?.abort();
+    const ctrl = new AbortController();
+    inflight.current = ctrl;
     try {
       setErr("");
-      const data = await getJobs(params);
+      const data = await getJobs(params, ctrl.signal);
       setJobs(Array.isArray(data) ? data : []);
     } catch (e: any) {
+      if (ctrl.signal.aborted) return;
       setErr(String(e?.message ?? e));
       setJobs([]);
     }
@@ -91,12 +114,37 @@ export default function App() {
 
   useEffect(() => { startEngineDebug().catch(console.error); }, []);
 
+  useEffect(() => { checkForUpdates().catch(console.error); }, []);
+
+  // The shell localizes dialogs, the tray and notifications; follow its
+  // language, which can also change from another window.
   useEffect(() => {
-    refresh();
-    const stop = events((msg) => {
-      if (msg?.type === "job_created" || msg?.type === "job_deleted") refresh();
+    invoke<string>("get_locale").then(setLocale).catch(console.error);
+    const unlisten = listen<string>("locale-changed", (ev) => setLocale(ev.payload));
+    return () => { unlisten.then((f) => f()); };
+  }, []);
+
+  useEffect(() => { document.documentElement.lang = locale; }, [locale]);
+
+  // Items in the native menu bar that open a screen here.
+  useEffect(() => {
+    const unlisten = listen<{ action: string }>("menu-action", (ev) => {
+      if (ev.payload.action === "settings") setView("prefs");
     });
-    return stop;
+    return () => { unlisten.then((f) => f()); };
+  }, []);
+
+  function changeLocale(lang: string) {
+    invoke<string>("set_locale", { lang }).then(setLocale).catch((e) => setErr(String(e)));
+  }
+
+  useEffect(() => {
+    refresh();
+    const stop = events(["job_created", "job_deleted", "job_updated"], () => refresh());
+    return () => {
+      stop();
+      inflight.current?.abort();
+    };
   }, [params]);
 
   if (view === "prefs")  return <ErrorBoundary name="Preferences"><Preferences onBack={() => setView("jobs")} /></ErrorBoundary>;
@@ -154,12 +202,16 @@ export default function App() {
             { value: "all", label: "All time" },
           ]} width={200} />
         </div>
+        <div className="tool">
+          <div className="toolLabel">Language</div>
+          <Select value={locale} onChange={changeLocale} options={LANGUAGES} width={200} />
+        </div>
       </div>
 
       {err && (
         <div className="error">
           Engine not reachable yet? ({err})
-          <div className="small">Expected: http://127.0.0.1:38471/health</div>
+          <div className="small">See Engine → Engine Status for details.</div>
         </div>
       )}
 
@@ -169,7 +221,7 @@ export default function App() {
         )}
         {jobs.map((j) => {
           const logoSrc = j.companyLogoURL?.startsWith("/")
-            ? `http://127.0.0.1:38471${j.companyLogoURL}`
+            ? assetUrl(j.companyLogoURL)
             : j.companyLogoURL;
 
           return (
@@ -220,6 +272,15 @@ export default function App() {
                 >
                   Apply
                 </button>
+                <input
+                  type="date"
+                  className="input"
+                  style={{ fontSize: 12, padding: "3px 6px" }}
+                  value={deadlineDate(j.deadline)}
+                  onChange={(e) => setJobDeadline(j.id, e.target.value).then(refresh).catch((err) => setErr(String(err)))}
+                  title="Application deadline"
+                  aria-label={`Deadline for ${j.title}`}
+                />
                 <a className="link" href={j.url} target="_blank" rel="noreferrer" style={{ fontSize: 13 }} title="Open job posting">↗</a>
                 <button
                   className="iconBtn"
@@ -1,7 +1,58 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { normalizeConfig } from "./configNormalize";
+import type { ApplicantProfile } from "./types";
+
+// The shell knows whether the engine is on a port or a socket and signs each
+// request, so every call goes through its engine_fetch command.
+type EngineResponse = {
+  status: number;
+  headers: Record<string, string>;
+  body: string;
+};
+
+// With a `signal`, the request gets an id so aborting cancels it in the shell
+// and frees its slot in the request pool instead of waiting for the reply.
+async function engineFetch(
+  method: string,
+  path: string,
+  body?: unknown,
+  signal?: AbortSignal,
+): Promise<EngineResponse> {
+  if (!signal) return invoke<EngineResponse>("engine_fetch", { method, path, body });
+  signal.throwIfAborted();
+  const requestId = crypto.randomUUID();
+  const cancel = () => { invoke("cancel_request", { id: requestId }).catch(() => null); };
+  signal.addEventListener("abort", cancel, { once: true });
+  try {
+    return await invoke<EngineResponse>("engine_fetch", { method, path, body, requestId });
+  } finally {
+    signal.removeEventListener("abort", cancel);
+  }
+}
+
+function isOk(res: EngineResponse) {
+  return res.status >= 200 && res.status < 300;
+}
 
-export const ENGINE_BASE = "http://127.0.0.1:38471";
+async function engineJSON(
+  method: string,
+  path: string,
+  body?: unknown,
+  signal?: AbortSignal,
+): Promise<any> {
+  const res = await engineFetch(method, path, body, signal);
+  if (!isOk(res)) throw new Error(res.body);
+  return JSON.parse(res.body);
+}
 
+// Logos and attachments come through the shell's jobhunt-asset protocol;
+// Windows webviews reach it over http.
+export function assetUrl(path: string): string {
+  return navigator.userAgent.includes("Windows")
+    ? `http://jobhunt-asset.localhost${path}`
+    : `jobhunt-asset://localhost${path}`;
+}
 
 function apiLog(step: string, payload?: unknown) {
   const ts = new Date().toISOString();
@@ -13,28 +64,23 @@ function apiLog(step: string, payload?: unknown) {
 }
 
 
-export async function getJobs(qs?: string) {
-  const r = await fetch(qs ? `${ENGINE_BASE}/jobs?${qs}` : "/jobs");
-  if (!r.ok) throw new Error(await r.text());
-  return r.json();
+export async function getJobs(qs?: string, signal?: AbortSignal) {
+  return engineJSON("GET", qs ? `/jobs?${qs}` : "/jobs", undefined, signal);
 }
 
 export async function seedJob() {
-  const res = await fetch(`${ENGINE_BASE}/seed`, { method: "POST" });
-  if (!res.ok) throw new Error(await res.text());
-  return res.json();
+  return engineJSON("POST", "/seed");
 }
 
-export function events(onMessage: (data: any) => void) {
-  const es = new EventSource(`${ENGINE_BASE}/events`);
-  es.addEventListener("message", (ev) => {
-    try {
-      onMessage(JSON.parse((ev as MessageEvent).data));
-    } catch {
-      // ignore
-    }
-  });
-  return () => es.close();
+// The shell follows the engine's /events stream and re-emits each event as
+// `engine://event/<type>`; listen for the given types.
+export function events(types: string[], onMessage: (data: any) => void) {
+  const pending = types.map((type) =>
+    listen(`engine://event/${type}`, (ev) => onMessage(ev.payload))
+  );
+  return () => {
+    pending.forEach((p) => p.then((unlisten) => unlisten()));
+  };
 }
 
 export type Rule = {
@@ -72,35 +118,31 @@ export type EngineConfig = {
 };
 
 export async function getConfig(): Promise<EngineConfig> {
-  const res = await fetch(`${ENGINE_BASE}/config`);
-  if (!res.ok) throw new Error(await res.text());
-  const raw = await res.json();
+  const raw = await engineJSON("GET", "/config");
   return normalizeConfig(raw);
 }
 
 export async function putConfig(cfg: EngineConfig): Promise<EngineConfig> {
-  const res = await fetch(`${ENGINE_BASE}/config`, {
-    method: "PUT",
-    headers: { "Content-Type": "application/json" },
-    body: JSON.stringify(cfg),
-  });
-  if (!res.ok) throw new Error(await res.text());
-  const raw = await res.json();
+  const raw = await engineJSON("PUT", "/config", cfg);
   return normalizeConfig(raw);
 }
 
 export async function deleteJob(id: number) {
-  const res = await fetch(`${ENGINE_BASE}/jobs/${id}`, { method: "DELETE" });
-  if (!res.ok) throw new Error(await res.text());
-  return res.json();
+  return engineJSON("DELETE", `/jobs/${id}`);
+}
+
+// Sets a job's application deadline as a YYYY-MM-DD date; "" clears it.
+// The engine reminds about it a day ahead.
+export async function setJobDeadline(id: number, deadline: string) {
+  return engineJSON("PUT", `/jobs/${id}/deadline`, { deadline });
 }
 
 // Returns the scraped job description HTML/text stored in the engine DB.
 // Returns an empty string if the job has no description yet.
 export async function getJobDescription(id: number): Promise<string> {
-  const res = await fetch(`${ENGINE_BASE}/jobs/${id}/description`);
-  if (!res.ok) return "";
-  const data = await res.json();
+  const res = await engineFetch("GET", `/jobs/${id}/description`);
+  if (!isOk(res)) return "";
+  const data = JSON.parse(res.body);
   return data.description ?? "";
 }
 
@@ -113,15 +155,11 @@ export type ScrapeStatus = {
 };
 
 export async function getScrapeStatus(): Promise<ScrapeStatus> {
-  const res = await fetch(`${ENGINE_BASE}/scrape/status`);
-  if (!res.ok) throw new Error(await res.text());
-  return res.json();
+  return engineJSON("GET", "/scrape/status");
 }
 
 export async function runScrape(): Promise<{ ok: boolean; msg?: string }> {
-  const res = await fetch(`${ENGINE_BASE}/scrape/run`, { method: "POST" });
-  if (!res.ok) throw new Error(await res.text());
-  return res.json();
+  return engineJSON("POST", "/scrape/run");
 }
 
 export type EngineEmailConfig = {
@@ -144,12 +182,8 @@ export type EngineSourcesConfig = {
 };
 
 export async function setImapPassword(password: string) {
-  const res = await fetch(`${ENGINE_BASE}/api/secrets/imap`, {
-    method: "POST",
-    headers: { "Content-Type": "application/json" },
-    body: JSON.stringify({ password }),
-  });
-  if (!res.ok) throw new Error(await res.text());
+  const res = await engineFetch("POST", "/api/secrets/imap", { password });
+  if (!isOk(res)) throw new Error(res.body);
 }
 
 // ─── LLM proxy (Groq — goes through engine to avoid Tauri CSP + keep key secure) ─
@@ -168,36 +202,33 @@ export interface LLMRequest {
 // Calls the engine's /api/llm proxy which forwards to Groq.
 // Returns the raw text content from the model.
 export async function callLLM(req: LLMRequest): Promise<string> {
-  const res = await fetch(`${ENGINE_BASE}/api/llm`, {
-    method: "POST",
-    headers: { "Content-Type": "application/json" },
-    body: JSON.stringify(req),
-  });
-  if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || `LLM proxy error: ${res.status}`);
+  const res = await engineFetch("POST", "/api/llm", req);
+  if (!isOk(res)) {
+    throw new Error(res.body || `LLM proxy error: ${res.status}`);
   }
-  const data = await res.json();
+  const data = JSON.parse(res.body);
   // Engine normalizes Groq response to { text: "..." }
   return data.text ?? "";
 }
 
 export async function setGroqAPIKey(apiKey: string): Promise<void> {
-  const res = await fetch(`${ENGINE_BASE}/api/secrets/groq`, {
-    method: "POST",
-    headers: { "Content-Type": "application/json" },
-    body: JSON.stringify({ api_key: apiKey }),
-  });
-  if (!res.ok) throw new Error(await res.text());
+  const res = await engineFetch("POST", "/api/secrets/groq", { api_key: apiKey });
+  if (!isOk(res)) throw new Error(res.body);
 }
 
 export async function getGroqKeyStatus(): Promise<boolean> {
-  const res = await fetch(`${ENGINE_BASE}/api/secrets/groq/status`);
-  if (!res.ok) return false;
-  const data = await res.json();
+  const res = await engineFetch("GET", "/api/secrets/groq/status");
+  if (!isOk(res)) return false;
+  const data = JSON.parse(res.body);
   return data.has_key === true;
 }
 
+// Stores the applicant profile in the engine, where the browser extension reads it.
+export async function saveEngineProfile(profile: ApplicantProfile): Promise<void> {
+  const res = await engineFetch("POST", "/api/profile", profile);
+  if (!isOk(res)) throw new Error(res.body);
+}
+
 // ─── Apply — two-phase scrape + fill ─────────────────────────────────────────
 
 // A field scraped from the real ATS form by filler.js --scrape
@@ -217,19 +248,14 @@ export async function scrapeForm(jobId: number, url: string, atsType: string): P
   apiLog("scrapeForm.request", { jobId, url, atsType });
   const startedAt = performance.now();
 
-  const res = await fetch(`${ENGINE_BASE}/api/apply/scrape`, {
-    method: "POST",
-    headers: { "Content-Type": "application/json" },
-    body: JSON.stringify({ jobId, url, atsType }),
-  });
+  const res = await engineFetch("POST", "/api/apply/scrape", { jobId, url, atsType });
 
-  if (!res.ok) {
-    const text = await res.text();
-    apiLog("scrapeForm.error", { jobId, status: res.status, body: text });
-    throw new Error(text || `Scrape error: ${res.status}`);
+  if (!isOk(res)) {
+    apiLog("scrapeForm.error", { jobId, status: res.status, body: res.body });
+    throw new Error(res.body || `Scrape error: ${res.status}`);
   }
 
-  const data = await res.json();
+  const data = JSON.parse(res.body);
   apiLog("scrapeForm.response", {
     jobId,
     elapsedMs: Math.round(performance.now() - startedAt),
@@ -280,19 +306,14 @@ export async function fillForm(req: FillRequest): Promise<{ ok: boolean; pid?: n
   });
 
   const startedAt = performance.now();
-  const res = await fetch(`${ENGINE_BASE}/api/apply/fill`, {
-    method: "POST",
-    headers: { "Content-Type": "application/json" },
-    body: JSON.stringify(req),
-  });
+  const res = await engineFetch("POST", "/api/apply/fill", req);
 
-  if (!res.ok) {
-    const text = await res.text();
-    apiLog("fillForm.error", { jobId: req.jobId, status: res.status, body: text });
-    throw new Error(text || `Fill error: ${res.status}`);
+  if (!isOk(res)) {
+    apiLog("fillForm.error", { jobId: req.jobId, status: res.status, body: res.body });
+    throw new Error(res.body || `Fill error: ${res.status}`);
   }
 
-  const data = await res.json();
+  const data = JSON.parse(res.body);
   apiLog("fillForm.response", {
     jobId: req.jobId,
     elapsedMs: Math.round(performance.now() - startedAt),
@@ -313,9 +334,7 @@ export interface CompanyResult {
 export async function searchCompanies(q: string, ats?: "greenhouse" | "lever"): Promise<CompanyResult[]> {
   const params = new URLSearchParams({ q });
   if (ats) params.set("ats", ats);
-  const res = await fetch(`${ENGINE_BASE}/api/companies/search?${params}`);
-  if (!res.ok) throw new Error(await res.text());
-  const data = await res.json();
+  const data = await engineJSON("GET", `/api/companies/search?${params}`);
   return data.results as CompanyResult[];
 }
 
@@ -327,19 +346,11 @@ export async function discoverCompanies(
 ): Promise<{ results: CompanyResult[]; total: number }> {
   const params = new URLSearchParams({ source });
   if (keyword) params.set("q", keyword);
-  const res = await fetch(`${ENGINE_BASE}/api/companies/discover?${params}`);
-  if (!res.ok) throw new Error(await res.text());
-  return res.json();
+  return engineJSON("GET", `/api/companies/discover?${params}`);
 }
 
 export async function extractCompaniesFromText(text: string): Promise<CompanyResult[]> {
-  const res = await fetch(`${ENGINE_BASE}/api/companies/extract`, {
-    method: "POST",
-    headers: { "Content-Type": "text/plain" },
-    body: text,
-  });
-  if (!res.ok) throw new Error(await res.text());
-  const data = await res.json();
+  const data = await engineJSON("POST", "/api/companies/extract", { text });
   return data.results as CompanyResult[];
 }
 
@@ -362,23 +373,24 @@ export async function saveCoverLetter(
   });
 
   const startedAt = performance.now();
-  const res = await fetch(`${ENGINE_BASE}/api/cover-letter/save`, {
-    method: "POST",
-    headers: { "Content-Type": "application/json" },
-    body: JSON.stringify({ firstName, lastName, companyName, content: text, saveDir }),
+  const res = await engineFetch("POST", "/api/cover-letter/save", {
+    firstName,
+    lastName,
+    companyName,
+    content: text,
+    saveDir,
   });
 
-  if (!res.ok) {
-    const body = await res.text();
+  if (!isOk(res)) {
     apiLog("saveCoverLetter.error", {
       companyName,
       status: res.status,
-      body,
+      body: res.body,
     });
-    throw new Error(body);
+    throw new Error(res.body);
   }
 
-  const data = await res.json();
+  const data = JSON.parse(res.body);
   apiLog("saveCoverLetter.response", {
     companyName,
     elapsedMs: Math.round(performance.now() - startedAt),
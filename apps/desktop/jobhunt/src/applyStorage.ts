@@ -1,5 +1,6 @@
 // src/applyStorage.ts — localStorage helpers for profile and queue
 
+import { saveEngineProfile } from "./api";
 import type { ApplicantProfile, ApplicationDraft, ApplicationField } from "./types";
 
 export const PROFILE_KEY = "jh_applicant_profile_v1";
@@ -53,11 +54,7 @@ export function saveProfile(p: ApplicantProfile): void {
   localStorage.setItem(PROFILE_KEY, JSON.stringify(p));
   // Sync to engine so the browser extension can read the profile.
   // Fire-and-forget — if it fails the app still works normally.
-  fetch("http://127.0.0.1:38471/api/profile", {
-    method: "POST",
-    headers: { "Content-Type": "application/json" },
-    body: JSON.stringify(p),
-  }).catch(() => null);
+  saveEngineProfile(p).catch(() => null);
 }
 
 // Normalises a persisted draft to the current shape.
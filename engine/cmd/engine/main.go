@@ -1,37 +1,105 @@
 package main
 
 import (
+	"context"
 	"database/sql"
 	"fmt"
 	"log"
+	"log/slog"
 	"net"
 	"net/http"
 	"os"
 	"path/filepath"
+	"strconv"
 	"time"
 
 	"sync/atomic"
 
 	"jobhunt-engine/internal/config"
 	"jobhunt-engine/internal/events"
+	"jobhunt-engine/internal/harden"
 	"jobhunt-engine/internal/httpapi"
+	"jobhunt-engine/internal/localsock"
+	"jobhunt-engine/internal/logx"
 	"jobhunt-engine/internal/poll"
 	"jobhunt-engine/internal/scrape/types"
 	"jobhunt-engine/internal/store"
+	"jobhunt-engine/internal/vault"
 
 	_ "modernc.org/sqlite"
 
 	"github.com/gofrs/flock"
 )
 
+// version is stamped at release time via -ldflags "-X main.version=...".
+var version = "1.1.2"
+
+// watchParent exits the engine once the desktop app that spawned it is gone
+// (we get reparented), so a crashed app never leaves an orphan holding the lock.
+// Windows builds are covered by the app's Job Object; there Getppid is stable.
+func watchParent(parentPID int) {
+	for range time.Tick(time.Second) {
+		if os.Getppid() != parentPID {
+			log.Printf("parent process %d exited; shutting down", parentPID)
+			os.Exit(0)
+		}
+	}
+}
+
+// resealVault moves personal data from the previous vault key to the current
+// one after the passphrase was set, changed or removed.
+func resealVault(db *sql.DB, dataDir, previous string) error {
+	r, err := vault.NewResealer(previous)
+	if err != nil {
+		return err
+	}
+	for _, name := range []string{"applicant_profile.json", "attachments.json"} {
+		if err := r.File(filepath.Join(dataDir, name)); err != nil {
+			return err
+		}
+	}
+	files, err := httpapi.AttachmentsHandler{DataDir: dataDir}.ResealFiles(r)
+	if err != nil {
+		return err
+	}
+	log.Printf("[vault] resealed %d attachment files", files)
+	n, err := store.ResealDescriptions(context.Background(), db, r)
+	if err != nil {
+		return err
+	}
+	log.Printf("[vault] resealed %d job descriptions (encrypted=%t)", n, vault.Enabled())
+	return nil
+}
+
 func main() {
 	if err := run(); err != nil {
-		log.Printf("%v", err)
+		logx.Errorf("%v", err)
 		os.Exit(1)
 	}
 }
 
 func run() error {
+	slog.SetDefault(httpapi.NewLogger(os.Stderr))
+	// First, so nothing that handles untrusted input runs unconfined.
+	harden.Apply()
+
+	if p, err := strconv.Atoi(os.Getenv("JOBHUNT_PARENT_PID")); err == nil && p > 0 {
+		go watchParent(p)
+	}
+
+	// With a passphrase set, the desktop shell writes the derived key to stdin.
+	var handoff vault.Handoff
+	if os.Getenv("JOBHUNT_VAULT") == "stdin" {
+		h, err := vault.ReadHandoff(os.Stdin)
+		if err != nil {
+			return err
+		}
+		if err := vault.SetKey(h.Key); err != nil {
+			return err
+		}
+		handoff = h
+	}
+
 	dataDir := os.Getenv("JOBHUNT_DATA_DIR")
 	if dataDir == "" {
 		dataDir = "."
@@ -83,10 +151,10 @@ func run() error {
 
 		cfg, vr := config.NormalizeAndValidate(cfg)
 		if !vr.OK() {
-			log.Printf("[config] INVALID: %v", vr.Errors)
+			logx.Warnf("[config] INVALID: %v", vr.Errors)
 		}
 		for _, w := range vr.Warnings {
-			log.Printf("[config] WARN: %s", w)
+			logx.Warnf("[config] %s", w)
 		}
 
 		log.Printf("[config] GH=%d Lever=%d companiesPath=%s",
@@ -113,13 +181,13 @@ func run() error {
 		return fmt.Errorf("%s", err)
 	}
 	if _, err := db.Exec(`PRAGMA journal_mode=WAL;`); err != nil {
-		log.Printf("WARN: set WAL: %v", err)
+		logx.Warnf("set WAL: %v", err)
 	}
 	if _, err := db.Exec(`PRAGMA busy_timeout=5000;`); err != nil {
-		log.Printf("WARN: set busy_timeout: %v", err)
+		logx.Warnf("set busy_timeout: %v", err)
 	}
 	if _, err := db.Exec(`PRAGMA synchronous=NORMAL;`); err != nil {
-		log.Printf("WARN: set synchronous: %v", err)
+		logx.Warnf("set synchronous: %v", err)
 	}
 	db.SetMaxOpenConns(1)
 	db.SetMaxIdleConns(1)
@@ -129,8 +197,13 @@ func run() error {
 	if err := store.Migrate(db); err != nil {
 		return fmt.Errorf("%s", err)
 	}
+	if handoff.Rekey {
+		if err := resealVault(db, dataDir, handoff.Previous); err != nil {
+			return fmt.Errorf("reseal vault: %w", err)
+		}
+	}
 	if _, err := store.CleanupOldJobs(db); err != nil {
-		log.Printf("[retention] cleanup failed: %v", err)
+		logx.Errorf("[retention] cleanup failed: %v", err)
 	}
 
 	// SSE hub lives outside main now (importable by handlers)
@@ -138,6 +211,20 @@ func run() error {
 
 	// Background poller stays in main, but uses shared types + hub
 	poll.StartPoller(db, &cfgVal, &scrapeStatus, hub)
+	poll.StartDeadlineWatcher(db, hub)
+
+	// The desktop shell generates the signing key and passes it in, so it never
+	// appears in logs. Standalone runs get a random one nobody else knows,
+	// unless it is set by hand to attach the shell (same variable on both sides).
+	authKey := os.Getenv("JOBHUNT_AUTH_KEY")
+	_ = os.Unsetenv("JOBHUNT_AUTH_KEY") // keep it out of anything we spawn
+	if authKey == "" {
+		authKey, err = httpapi.RandomToken(32)
+		if err != nil {
+			return err
+		}
+	}
+	auth := httpapi.NewSigner(authKey)
 
 	// Build API mux from internal/httpapi package
 	mux := httpapi.NewMux(httpapi.Deps{
@@ -154,29 +241,39 @@ func run() error {
 		RunPollOnce: poll.PollOnce,
 
 		DataDir: dataDir,
+
+		Version: version,
 	})
 
-	// Bind to a predictable local port
-	addr := "127.0.0.1:38471"
-	ln, err := net.Listen("tcp", addr)
-	if err != nil {
-		return fmt.Errorf("%s", err)
+	// The desktop shell picks the port, or a socket when it is set to avoid
+	// TCP, and passes it in; the default is for running the engine on its own.
+	var ln net.Listener
+	if sock := os.Getenv("JOBHUNT_SOCKET"); sock != "" {
+		ln, err = localsock.Listen(sock)
+		if err != nil {
+			return fmt.Errorf("%s", err)
+		}
+		log.Printf("engine listening on %s (db=%s)", sock, dbPath)
+	} else {
+		port := "38471"
+		if p := os.Getenv("JOBHUNT_PORT"); p != "" {
+			port = p
+		}
+		addr := net.JoinHostPort("127.0.0.1", port)
+		ln, err = net.Listen("tcp", addr)
+		if err != nil {
+			return fmt.Errorf("%s", err)
+		}
+		log.Printf("engine listening on http://%s (db=%s)", addr, dbPath)
 	}
-	log.Printf("engine listening on http://%s (db=%s)", addr, dbPath)
 
 	srv := &http.Server{
-		Handler:           httpapi.Cors(mux),
+		Handler:           httpapi.Cors(auth.Guard(mux)),
 		ReadHeaderTimeout: 5 * time.Second,
 	}
 
-	shutdownToken, err := httpapi.RandomToken(32)
-	if err != nil {
-		return err
-	}
-	log.Printf("shutdown_token=%s", shutdownToken)
-
-	// /shutdown must be registered here because it needs srv + token
-	mux.HandleFunc("/shutdown", httpapi.ShutdownHandler(&shutdownToken, srv))
+	// /shutdown must be registered here because it needs srv
+	mux.HandleFunc("/shutdown", httpapi.ShutdownHandler(srv))
 
 	return fmt.Errorf("%s", srv.Serve(ln))
 }
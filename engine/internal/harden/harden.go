@@ -0,0 +1,44 @@
+// Package harden confines the engine at startup as the desktop shell asks
+// through JOBHUNT_HARDENING. The shell launches the engine through a plugin
+// that can't run code between fork and exec, so on Unix the engine applies
+// its own restrictions; on Windows the shell's Job Object does instead.
+//
+// Restrictions are inherited by everything the engine runs, including the
+// form filler and its browser, so none of them may get in its way.
+package harden
+
+import (
+	"log"
+	"os"
+	"strconv"
+
+	"jobhunt-engine/internal/logx"
+)
+
+type result struct {
+	what string
+	err  error
+}
+
+// Apply confines the process as JOBHUNT_HARDENING ("standard" or "strict")
+// asks, with JOBHUNT_MEMORY_LIMIT_MB as its data size limit. A restriction
+// that can't be applied is logged and skipped.
+func Apply() {
+	mode := os.Getenv("JOBHUNT_HARDENING")
+	if mode == "" || mode == "off" {
+		return
+	}
+	if mode != "standard" && mode != "strict" {
+		logx.Warnf("[harden] unknown mode %q; not hardening", mode)
+		return
+	}
+	memMB, _ := strconv.ParseUint(os.Getenv("JOBHUNT_MEMORY_LIMIT_MB"), 10, 64)
+
+	for _, r := range apply(mode == "strict", memMB) {
+		if r.err != nil {
+			logx.Warnf("[harden] could not apply %s: %v", r.what, r.err)
+			continue
+		}
+		log.Printf("[harden] %s applied", r.what)
+	}
+}
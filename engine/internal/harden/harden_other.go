@@ -0,0 +1,8 @@
+//go:build !unix
+
+package harden
+
+// On Windows the shell puts the engine in a limited Job Object instead.
+func apply(strict bool, memMB uint64) []result {
+	return nil
+}
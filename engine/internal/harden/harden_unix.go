@@ -0,0 +1,35 @@
+//go:build unix
+
+package harden
+
+import "syscall"
+
+// lowerLimit lowers both the soft and hard limit to at most n, so the
+// engine, and anything it runs, can't raise it again.
+func lowerLimit(resource int, n uint64) error {
+	var lim syscall.Rlimit
+	if err := syscall.Getrlimit(resource, &lim); err != nil {
+		return err
+	}
+	lim.Cur = min(lim.Cur, n)
+	lim.Max = min(lim.Max, n)
+	return syscall.Setrlimit(resource, &lim)
+}
+
+func apply(strict bool, memMB uint64) []result {
+	results := []result{
+		// Signals meant for the app's terminal or group no longer reach the engine.
+		{"own process group", syscall.Setpgid(0, 0)},
+		// A core dump would hold the vault key and decrypted personal data.
+		{"no core dumps", lowerLimit(syscall.RLIMIT_CORE, 0)},
+	}
+	if memMB > 0 {
+		// RLIMIT_DATA, not RLIMIT_AS: the Go runtime and the filler's browser
+		// reserve far more address space than they ever touch.
+		results = append(results, result{"data size limit", lowerLimit(syscall.RLIMIT_DATA, memMB<<20)})
+	}
+	if strict {
+		results = append(results, result{"syscall filter", seccomp()})
+	}
+	return results
+}
@@ -0,0 +1,9 @@
+//go:build unix && !linux
+
+package harden
+
+import "errors"
+
+func seccomp() error {
+	return errors.New("syscall filtering is only available on Linux")
+}
@@ -0,0 +1,294 @@
+package httpapi
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"io"
+	"net/http"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"sync"
+
+	"jobhunt-engine/internal/vault"
+)
+
+// AttachmentsHandler keeps a registry of résumés and cover letters that the
+// desktop shell has copied into its attachment store, so applications can
+// reference them by ID. The shell copies the files in; the engine seals them
+// on registration when a passphrase is set, and opens them when serving.
+
+type AttachmentsHandler struct {
+	DataDir string
+}
+
+type Attachment struct {
+	ID      string `json:"id"` // SHA-256 of the file contents
+	Name    string `json:"name"`
+	Kind    string `json:"kind"` // resume / cover_letter / other
+	Path    string `json:"path"`
+	Size    int64  `json:"size"`
+	AddedAt int64  `json:"addedAt"`         // unix seconds
+	JobID   *int64 `json:"jobId,omitempty"` // set for screenshots of a posting
+}
+
+var attachmentsMu sync.Mutex
+
+func (h AttachmentsHandler) registryPath() string {
+	dir := h.DataDir
+	if dir == "" {
+		dir = "."
+	}
+	return filepath.Join(dir, "attachments.json")
+}
+
+// storeDir is where the shell keeps attachment files; the engine only ever
+// serves files from inside it.
+func (h AttachmentsHandler) storeDir() string {
+	dir := h.DataDir
+	if dir == "" {
+		dir = "."
+	}
+	return filepath.Join(dir, "attachments")
+}
+
+var errOutsideStore = errors.New("path must be a file in the attachment store")
+
+// confine resolves p, following symlinks, and returns it if it names a
+// regular file inside the attachment store.
+func (h AttachmentsHandler) confine(p string) (string, error) {
+	root, err := filepath.EvalSymlinks(h.storeDir())
+	if err != nil {
+		return "", errOutsideStore
+	}
+	root, err = filepath.Abs(root)
+	if err != nil {
+		return "", errOutsideStore
+	}
+	resolved, err := filepath.EvalSymlinks(p)
+	if err != nil {
+		return "", errOutsideStore
+	}
+	resolved, err = filepath.Abs(resolved)
+	if err != nil {
+		return "", errOutsideStore
+	}
+	rel, err := filepath.Rel(root, resolved)
+	if err != nil || rel == "." || rel == ".." || strings.HasPrefix(rel, ".."+string(filepath.Separator)) || filepath.IsAbs(rel) {
+		return "", errOutsideStore
+	}
+	info, err := os.Stat(resolved)
+	if err != nil || !info.Mode().IsRegular() {
+		return "", errOutsideStore
+	}
+	return resolved, nil
+}
+
+func (h AttachmentsHandler) load() ([]Attachment, error) {
+	data, err := vault.ReadFile(h.registryPath())
+	if os.IsNotExist(err) {
+		return []Attachment{}, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	var list []Attachment
+	if err := json.Unmarshal(data, &list); err != nil {
+		return nil, err
+	}
+	return list, nil
+}
+
+func (h AttachmentsHandler) save(list []Attachment) error {
+	data, err := json.MarshalIndent(list, "", "  ")
+	if err != nil {
+		return err
+	}
+	return vault.WriteFile(h.registryPath(), data, 0o600)
+}
+
+// GET /api/attachments[?jobId=N]
+func (h AttachmentsHandler) List(w http.ResponseWriter, r *http.Request) {
+	var jobID *int64
+	if raw := r.URL.Query().Get("jobId"); raw != "" {
+		id, err := strconv.ParseInt(raw, 10, 64)
+		if err != nil {
+			http.Error(w, "invalid jobId", http.StatusBadRequest)
+			return
+		}
+		jobID = &id
+	}
+
+	attachmentsMu.Lock()
+	defer attachmentsMu.Unlock()
+
+	list, err := h.load()
+	if err != nil {
+		http.Error(w, "failed to read attachments: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	if jobID != nil {
+		matched := []Attachment{}
+		for _, a := range list {
+			if a.JobID != nil && *a.JobID == *jobID {
+				matched = append(matched, a)
+			}
+		}
+		list = matched
+	}
+	w.Header().Set("Content-Type", "application/json")
+	writeJSON(w, list)
+}
+
+// POST /api/attachments — registers (or updates) an attachment by ID
+func (h AttachmentsHandler) Register(w http.ResponseWriter, r *http.Request) {
+	body, err := io.ReadAll(io.LimitReader(r.Body, 64<<10))
+	if err != nil {
+		http.Error(w, "failed to read body", http.StatusBadRequest)
+		return
+	}
+	var a Attachment
+	if err := json.Unmarshal(body, &a); err != nil {
+		http.Error(w, "invalid json: "+err.Error(), http.StatusBadRequest)
+		return
+	}
+	if strings.TrimSpace(a.ID) == "" || strings.TrimSpace(a.Path) == "" {
+		http.Error(w, "id and path are required", http.StatusBadRequest)
+		return
+	}
+	a.Path, err = h.confine(a.Path)
+	if err != nil {
+		http.Error(w, err.Error(), http.StatusBadRequest)
+		return
+	}
+	if err := vault.SealFile(a.Path); err != nil {
+		http.Error(w, "failed to seal attachment: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+
+	attachmentsMu.Lock()
+	defer attachmentsMu.Unlock()
+
+	list, err := h.load()
+	if err != nil {
+		http.Error(w, "failed to read attachments: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	replaced := false
+	for i := range list {
+		if list[i].ID == a.ID {
+			list[i] = a
+			replaced = true
+		}
+	}
+	if !replaced {
+		list = append(list, a)
+	}
+	if err := h.save(list); err != nil {
+		http.Error(w, "failed to save attachments: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	writeJSON(w, map[string]any{"ok": true})
+}
+
+// GET /api/attachments/{id} — serves the file itself, with Range support
+func (h AttachmentsHandler) GetByPath(w http.ResponseWriter, r *http.Request) {
+	id := strings.TrimPrefix(r.URL.Path, "/api/attachments/")
+	if id == "" || strings.Contains(id, "/") {
+		http.NotFound(w, r)
+		return
+	}
+
+	attachmentsMu.Lock()
+	list, err := h.load()
+	attachmentsMu.Unlock()
+	if err != nil {
+		http.Error(w, "failed to read attachments: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	var found *Attachment
+	for i := range list {
+		if list[i].ID == id {
+			found = &list[i]
+			break
+		}
+	}
+	if found == nil {
+		http.NotFound(w, r)
+		return
+	}
+
+	// Registries written before paths were checked may point anywhere.
+	path, err := h.confine(found.Path)
+	if err != nil {
+		http.NotFound(w, r)
+		return
+	}
+	info, err := os.Stat(path)
+	if err != nil {
+		http.Error(w, "failed to stat attachment: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	data, err := vault.ReadFile(path)
+	if err != nil {
+		http.Error(w, "failed to open attachment: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	// ServeContent picks the Content-Type from the name and handles Range/If-* headers.
+	http.ServeContent(w, r, path, info.ModTime(), bytes.NewReader(data))
+}
+
+// ResealFiles moves every registered attachment file to the current vault
+// key. Run it after the registry itself has been resealed.
+func (h AttachmentsHandler) ResealFiles(r *vault.Resealer) (int, error) {
+	attachmentsMu.Lock()
+	defer attachmentsMu.Unlock()
+
+	list, err := h.load()
+	if err != nil {
+		return 0, err
+	}
+	n := 0
+	for _, a := range list {
+		path, err := h.confine(a.Path)
+		if err != nil {
+			continue
+		}
+		if err := r.File(path); err != nil {
+			return n, err
+		}
+		n++
+	}
+	return n, nil
+}
+
+// DELETE /api/attachments/{id}
+func (h AttachmentsHandler) DeleteByPath(w http.ResponseWriter, r *http.Request) {
+	id := strings.TrimPrefix(r.URL.Path, "/api/attachments/")
+	if id == "" || strings.Contains(id, "/") {
+		http.NotFound(w, r)
+		return
+	}
+
+	attachmentsMu.Lock()
+	defer attachmentsMu.Unlock()
+
+	list, err := h.load()
+	if err != nil {
+		http.Error(w, "failed to read attachments: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	kept := list[:0]
+	for _, a := range list {
+		if a.ID != id {
+			kept = append(kept, a)
+		}
+	}
+	if err := h.save(kept); err != nil {
+		http.Error(w, "failed to save attachments: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	w.WriteHeader(http.StatusNoContent)
+}
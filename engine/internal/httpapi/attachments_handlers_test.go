@@ -0,0 +1,74 @@
+package httpapi
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+// ─── helpers ─────────────────────────────────────────────────────────────────
+
+// storeWith returns a handler whose data dir holds an attachment store with
+// one file in it, and a file of the same name just outside the store.
+func storeWith(t *testing.T) (AttachmentsHandler, string, string) {
+	t.Helper()
+	h := AttachmentsHandler{DataDir: t.TempDir()}
+	if err := os.MkdirAll(h.storeDir(), 0o700); err != nil {
+		t.Fatal(err)
+	}
+	inside := filepath.Join(h.storeDir(), "resume.pdf")
+	outside := filepath.Join(h.DataDir, "resume.pdf")
+	for _, p := range []string{inside, outside} {
+		if err := os.WriteFile(p, []byte("%PDF"), 0o600); err != nil {
+			t.Fatal(err)
+		}
+	}
+	return h, inside, outside
+}
+
+// ─── confine ─────────────────────────────────────────────────────────────────
+
+func TestConfineAcceptsFilesInTheStore(t *testing.T) {
+	h, inside, _ := storeWith(t)
+	got, err := h.confine(inside)
+	if err != nil {
+		t.Fatal(err)
+	}
+	want, _ := filepath.EvalSymlinks(inside)
+	if got != want {
+		t.Fatalf("confine = %s, want %s", got, want)
+	}
+}
+
+func TestConfineRejectsPathsOutsideTheStore(t *testing.T) {
+	h, _, outside := storeWith(t)
+	for name, p := range map[string]string{
+		"outside":   outside,
+		"traversal": filepath.Join(h.storeDir(), "..", "resume.pdf"),
+		"store":     h.storeDir(),
+		"missing":   filepath.Join(h.storeDir(), "gone.pdf"),
+		"relative":  "resume.pdf",
+	} {
+		if _, err := h.confine(p); err == nil {
+			t.Errorf("%s: %s accepted", name, p)
+		}
+	}
+}
+
+func TestConfineRejectsSymlinksOutOfTheStore(t *testing.T) {
+	h, _, outside := storeWith(t)
+	link := filepath.Join(h.storeDir(), "link.pdf")
+	if err := os.Symlink(outside, link); err != nil {
+		t.Skipf("symlinks unavailable: %v", err)
+	}
+	if _, err := h.confine(link); err == nil {
+		t.Fatal("symlink out of the store accepted")
+	}
+}
+
+func TestConfineRejectsAStoreWithoutFiles(t *testing.T) {
+	h := AttachmentsHandler{DataDir: t.TempDir()}
+	if _, err := h.confine(filepath.Join(h.storeDir(), "resume.pdf")); err == nil {
+		t.Fatal("accepted a path in a store that doesn't exist")
+	}
+}
@@ -0,0 +1,136 @@
+package httpapi
+
+import (
+	"database/sql"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"strings"
+	"time"
+
+	"jobhunt-engine/internal/events"
+	"jobhunt-engine/internal/scrape"
+	"jobhunt-engine/internal/scrape/types"
+	"jobhunt-engine/internal/writes"
+)
+
+// BulkImportHandler adds jobs the desktop shell parsed out of another site's
+// data export (LinkedIn, Indeed), one batch per request, and reports what
+// happened to each record so the shell can show a per-row result.
+
+type BulkImportHandler struct {
+	DB  *sql.DB
+	Hub *events.Hub
+}
+
+const maxBulkRecords = 500
+
+type bulkRecord struct {
+	Title    string `json:"title"`
+	Company  string `json:"company"`
+	Location string `json:"location"`
+	URL      string `json:"url"`
+	Date     string `json:"date"`
+	Status   string `json:"status"`
+	Source   string `json:"source"`
+}
+
+type bulkReq struct {
+	Records []bulkRecord `json:"records"`
+}
+
+type bulkResult struct {
+	Index  int    `json:"index"`
+	Status string `json:"status"` // created | duplicate | error
+	ID     int64  `json:"id,omitempty"`
+	Error  string `json:"error,omitempty"`
+}
+
+// bulkDate reads the RFC 3339 dates the shell normalizes to; anything else is "now".
+func bulkDate(s string) time.Time {
+	s = strings.TrimSpace(s)
+	for _, layout := range []string{time.RFC3339, "2006-01-02"} {
+		if t, err := time.Parse(layout, s); err == nil {
+			return t.UTC()
+		}
+	}
+	return time.Now().UTC()
+}
+
+func (h BulkImportHandler) insert(r *http.Request, rec bulkRecord) (string, int64, error) {
+	u, err := url.Parse(strings.TrimSpace(rec.URL))
+	if err != nil || (u.Scheme != "http" && u.Scheme != "https") || u.Host == "" {
+		return "", 0, fmt.Errorf("url must be an absolute http(s) URL")
+	}
+	source := strings.TrimSpace(rec.Source)
+	if source == "" {
+		source = "import"
+	}
+	var tags []string
+	if status := strings.ToLower(strings.TrimSpace(rec.Status)); status != "" {
+		tags = append(tags, status)
+	}
+
+	id, added, err := scrape.InsertJobIfNew(r.Context(), h.DB, types.JobRow{
+		Company:        strings.TrimSpace(rec.Company),
+		Title:          strings.TrimSpace(rec.Title),
+		Location:       strings.TrimSpace(rec.Location),
+		URL:            u.String(),
+		Tags:           tags,
+		ReceivedAt:     bulkDate(rec.Date),
+		SeenFromSource: source,
+	})
+	if err != nil {
+		return "", 0, err
+	}
+	if added {
+		return "created", id, nil
+	}
+	return "duplicate", 0, nil
+}
+
+// POST /api/import/jobs  { "records": [{ "title", "company", "location", "url", "date", "status", "source" }] }
+func (h BulkImportHandler) Import(w http.ResponseWriter, r *http.Request) {
+	body, err := io.ReadAll(io.LimitReader(r.Body, 4<<20))
+	if err != nil {
+		http.Error(w, "failed to read body", http.StatusBadRequest)
+		return
+	}
+	var req bulkReq
+	if err := json.Unmarshal(body, &req); err != nil {
+		http.Error(w, "invalid json: "+err.Error(), http.StatusBadRequest)
+		return
+	}
+	if len(req.Records) > maxBulkRecords {
+		http.Error(w, fmt.Sprintf("at most %d records per request", maxBulkRecords), http.StatusRequestEntityTooLarge)
+		return
+	}
+
+	defer writes.Begin("import", fmt.Sprintf("Importing %d jobs", len(req.Records)))()
+
+	results := make([]bulkResult, 0, len(req.Records))
+	created, duplicates, failed := 0, 0, 0
+	for i, rec := range req.Records {
+		status, id, err := h.insert(r, rec)
+		if err != nil {
+			failed++
+			results = append(results, bulkResult{Index: i, Status: "error", Error: err.Error()})
+			continue
+		}
+		if status == "created" {
+			created++
+			h.Hub.Publish(`{"type":"job_created","id":` + fmt.Sprint(id) + `}`)
+		} else {
+			duplicates++
+		}
+		results = append(results, bulkResult{Index: i, Status: status, ID: id})
+	}
+	writeJSON(w, map[string]any{
+		"created":    created,
+		"duplicates": duplicates,
+		"errors":     failed,
+		"results":    results,
+	})
+}
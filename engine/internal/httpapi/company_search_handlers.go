@@ -253,6 +253,7 @@ func (h CompanyDiscoveryHandler) Discover(w http.ResponseWriter, r *http.Request
 
 // ─── POST /api/companies/extract ─────────────────────────────────────────────
 // Accepts raw text (job emails, HTML, anything) and extracts ATS slugs from URLs.
+// The desktop shell sends the text as JSON instead: { "text": "..." }.
 
 func (h CompanyDiscoveryHandler) Extract(w http.ResponseWriter, r *http.Request) {
 	body, err := io.ReadAll(io.LimitReader(r.Body, 1<<20)) // 1MB max
@@ -261,6 +262,16 @@ func (h CompanyDiscoveryHandler) Extract(w http.ResponseWriter, r *http.Request)
 		return
 	}
 	text := string(body)
+	if strings.HasPrefix(r.Header.Get("Content-Type"), "application/json") {
+		var req struct {
+			Text string `json:"text"`
+		}
+		if err := json.Unmarshal(body, &req); err != nil {
+			http.Error(w, "invalid json: "+err.Error(), http.StatusBadRequest)
+			return
+		}
+		text = req.Text
+	}
 	results := extractATSSlugsFromText(text)
 	writeJSON(w, map[string]any{"results": results})
 }
@@ -1,9 +1,16 @@
 package httpapi
 
 import (
+	"context"
 	"database/sql"
-	"net"
+	"log"
 	"net/http"
+	"strings"
+	"sync"
+	"time"
+
+	"jobhunt-engine/internal/logx"
+	"jobhunt-engine/internal/writes"
 )
 
 type DBHandler struct {
@@ -16,11 +23,7 @@ func (h DBHandler) Checkpoint(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
-	host, _, err := net.SplitHostPort(r.RemoteAddr)
-	if err != nil {
-		host = r.RemoteAddr
-	}
-	if host != "127.0.0.1" && host != "::1" && host != "localhost" {
+	if !isLocal(r) {
 		http.Error(w, "forbidden", http.StatusForbidden)
 		return
 	}
@@ -32,3 +35,73 @@ func (h DBHandler) Checkpoint(w http.ResponseWriter, r *http.Request) {
 
 	w.WriteHeader(http.StatusNoContent)
 }
+
+// A maintenance task: the statement it runs and how it's described to a user
+// who tries to quit meanwhile.
+type maintenanceTask struct {
+	stmt  string
+	label string
+}
+
+var maintenanceTasks = map[string]maintenanceTask{
+	"reindex":    {`REINDEX;`, "Rebuilding database indexes"},
+	"analyze":    {`ANALYZE;`, "Updating database statistics"},
+	"vacuum":     {`VACUUM;`, "Compacting the database"},
+	"checkpoint": {`PRAGMA wal_checkpoint(TRUNCATE);`, "Flushing the database log"},
+}
+
+// maintenanceMu keeps tasks from running over each other.
+var maintenanceMu sync.Mutex
+
+func dbSize(ctx context.Context, db *sql.DB) int64 {
+	var pages, pageSize int64
+	if err := db.QueryRowContext(ctx, `PRAGMA page_count;`).Scan(&pages); err != nil {
+		return 0
+	}
+	if err := db.QueryRowContext(ctx, `PRAGMA page_size;`).Scan(&pageSize); err != nil {
+		return 0
+	}
+	return pages * pageSize
+}
+
+// POST /db/maintenance/{reindex|analyze|vacuum|checkpoint}
+//
+// Runs one task and reports how long it took and the database size before
+// and after. It counts as an in-flight write, so the shell warns before
+// quitting mid-task. Cancelling the request rolls the task back.
+func (h DBHandler) Maintenance(w http.ResponseWriter, r *http.Request) {
+	if !isLocal(r) {
+		http.Error(w, "forbidden", http.StatusForbidden)
+		return
+	}
+	name := strings.TrimPrefix(r.URL.Path, "/db/maintenance/")
+	task, ok := maintenanceTasks[name]
+	if !ok {
+		http.Error(w, "unknown task "+name, http.StatusNotFound)
+		return
+	}
+	if !maintenanceMu.TryLock() {
+		http.Error(w, "maintenance already running", http.StatusConflict)
+		return
+	}
+	defer maintenanceMu.Unlock()
+	defer writes.Begin("maintenance", task.label)()
+
+	ctx := r.Context()
+	before := dbSize(ctx, h.DB)
+	start := time.Now()
+	if _, err := h.DB.ExecContext(ctx, task.stmt); err != nil {
+		logx.Errorf("[db] %s failed: %v", name, err)
+		http.Error(w, err.Error(), http.StatusInternalServerError)
+		return
+	}
+	took := time.Since(start)
+	after := dbSize(ctx, h.DB)
+	log.Printf("[db] %s done in %s (%d -> %d bytes)", name, took.Round(time.Millisecond), before, after)
+	writeJSON(w, map[string]any{
+		"task":       name,
+		"durationMs": took.Milliseconds(),
+		"sizeBefore": before,
+		"sizeAfter":  after,
+	})
+}
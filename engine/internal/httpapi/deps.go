@@ -25,8 +25,11 @@ type Deps struct {
 	DeleteJob func(ctx context.Context, db *sql.DB, id int64) error
 
 	// Scrape entrypoint (inject for testability)
-	RunPollOnce func(db *sql.DB, cfg config.Config, onNewJob func()) (added int, err error)
+	RunPollOnce func(db *sql.DB, cfg config.Config, onNewJob func(id int64)) (added int, err error)
 
 	// DataDir is passed to ApplyHandler for temp file storage
 	DataDir string
+
+	// Version is reported by /health for the shell's compatibility check
+	Version string
 }
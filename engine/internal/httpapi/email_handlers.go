@@ -0,0 +1,240 @@
+package httpapi
+
+import (
+	"bytes"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"text/template"
+
+	"jobhunt-engine/internal/store"
+	"jobhunt-engine/internal/vault"
+)
+
+// EmailDraftHandler renders application and follow-up emails for a job from
+// templates filled in with the job and the applicant profile. The desktop
+// shell turns the draft into a mailto: link or an .eml file.
+//
+// The built-in templates can be overridden per kind in email_templates.json
+// in the data dir: {"follow_up": {"subject": "...", "body": "..."}}.
+
+type EmailDraftHandler struct {
+	DB      *sql.DB
+	DataDir string
+}
+
+type emailTemplate struct {
+	Subject string `json:"subject"`
+	Body    string `json:"body"`
+}
+
+type emailDraft struct {
+	Kind        string       `json:"kind"`
+	Subject     string       `json:"subject"`
+	Body        string       `json:"body"`
+	Attachments []Attachment `json:"attachments"`
+}
+
+// What templates see: the job, the raw applicant profile, and the
+// applicant's full name.
+type emailData struct {
+	Job     store.Job
+	Profile map[string]any
+	Name    string
+}
+
+const emailSignature = `Best regards,
+{{.Name}}{{with .Profile.phone}}
+{{.}}{{end}}{{with .Profile.linkedinURL}}
+{{.}}{{end}}
+`
+
+var defaultEmailTemplates = map[string]emailTemplate{
+	"application": {
+		Subject: "Application for {{.Job.Title}} at {{.Job.Company}}",
+		Body:    `Hello {{.Job.Company}} team,
+
+I'd like to apply for the {{.Job.Title}} role{{with .Job.Location}} ({{.}}){{end}}.` +
+			`{{with .Profile.currentTitle}} I'm currently a {{.}}.{{end}} ` +
+			`My résumé is attached, and I'd welcome the chance to discuss how I could contribute.
+{{with .Job.URL}}
+Posting: {{.}}
+{{end}}
+` + emailSignature,
+	},
+	"follow_up": {
+		Subject: "Following up: {{.Job.Title}} application",
+		Body:    `Hello {{.Job.Company}} team,
+
+I recently applied for the {{.Job.Title}} role and wanted to follow up. ` +
+			`I'm still very interested in the position and would be glad to share anything ` +
+			`that would help your review. I've attached my résumé again for convenience.
+
+Thank you for your time.
+
+` + emailSignature,
+	},
+}
+
+func (h EmailDraftHandler) dataPath(name string) string {
+	dir := h.DataDir
+	if dir == "" {
+		dir = "."
+	}
+	return filepath.Join(dir, name)
+}
+
+func (h EmailDraftHandler) loadTemplate(kind string) (emailTemplate, error) {
+	tpl := defaultEmailTemplates[kind]
+	data, err := vault.ReadFile(h.dataPath("email_templates.json"))
+	if os.IsNotExist(err) {
+		return tpl, nil
+	}
+	if err != nil {
+		return tpl, err
+	}
+	var custom map[string]emailTemplate
+	if err := json.Unmarshal(data, &custom); err != nil {
+		return tpl, errors.New("email_templates.json: " + err.Error())
+	}
+	if c, ok := custom[kind]; ok {
+		if c.Subject != "" {
+			tpl.Subject = c.Subject
+		}
+		if c.Body != "" {
+			tpl.Body = c.Body
+		}
+	}
+	return tpl, nil
+}
+
+func (h EmailDraftHandler) profile() (map[string]any, error) {
+	profile := map[string]any{}
+	data, err := vault.ReadFile(h.dataPath("applicant_profile.json"))
+	if os.IsNotExist(err) {
+		return profile, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	if err := json.Unmarshal(data, &profile); err != nil {
+		return nil, err
+	}
+	return profile, nil
+}
+
+// The most recently added attachment of each kind, in order.
+func (h EmailDraftHandler) attachments(kinds ...string) ([]Attachment, error) {
+	attachmentsMu.Lock()
+	list, err := AttachmentsHandler{DataDir: h.DataDir}.load()
+	attachmentsMu.Unlock()
+	if err != nil {
+		return nil, err
+	}
+	picked := []Attachment{}
+	for _, kind := range kinds {
+		var latest *Attachment
+		for i := range list {
+			if list[i].Kind == kind && (latest == nil || list[i].AddedAt > latest.AddedAt) {
+				latest = &list[i]
+			}
+		}
+		if latest != nil {
+			picked = append(picked, *latest)
+		}
+	}
+	return picked, nil
+}
+
+func renderEmail(name, text string, data emailData) (string, error) {
+	t, err := template.New(name).Parse(text)
+	if err != nil {
+		return "", err
+	}
+	var buf bytes.Buffer
+	if err := t.Execute(&buf, data); err != nil {
+		return "", err
+	}
+	return buf.String(), nil
+}
+
+// Draft handles GET /jobs/{id}/email?kind=application|follow_up
+func (h EmailDraftHandler) Draft(w http.ResponseWriter, r *http.Request) {
+	path := strings.TrimPrefix(r.URL.Path, "/jobs/")
+	path = strings.TrimSuffix(path, "/email")
+	id, err := strconv.ParseInt(strings.TrimSpace(path), 10, 64)
+	if err != nil || id <= 0 {
+		http.Error(w, "invalid id", 400)
+		return
+	}
+	kind := r.URL.Query().Get("kind")
+	if kind == "" {
+		kind = "application"
+	}
+	if _, ok := defaultEmailTemplates[kind]; !ok {
+		http.Error(w, "unknown kind "+strconv.Quote(kind), 400)
+		return
+	}
+
+	job, err := store.GetJob(r.Context(), h.DB, id)
+	if errors.Is(err, sql.ErrNoRows) {
+		http.NotFound(w, r)
+		return
+	}
+	if err != nil {
+		http.Error(w, err.Error(), 500)
+		return
+	}
+	profile, err := h.profile()
+	if errors.Is(err, vault.ErrLocked) {
+		http.Error(w, err.Error(), http.StatusServiceUnavailable)
+		return
+	}
+	if err != nil {
+		http.Error(w, "failed to read profile: "+err.Error(), 500)
+		return
+	}
+	tpl, err := h.loadTemplate(kind)
+	if err != nil {
+		http.Error(w, err.Error(), 500)
+		return
+	}
+
+	first, _ := profile["firstName"].(string)
+	last, _ := profile["lastName"].(string)
+	data := emailData{Job: job, Profile: profile, Name: strings.TrimSpace(first + " " + last)}
+	subject, err := renderEmail("subject", tpl.Subject, data)
+	if err != nil {
+		http.Error(w, "subject template: "+err.Error(), 500)
+		return
+	}
+	body, err := renderEmail("body", tpl.Body, data)
+	if err != nil {
+		http.Error(w, "body template: "+err.Error(), 500)
+		return
+	}
+
+	kinds := []string{"resume"}
+	if kind == "application" {
+		kinds = append(kinds, "cover_letter")
+	}
+	atts, err := h.attachments(kinds...)
+	if err != nil {
+		http.Error(w, "failed to read attachments: "+err.Error(), 500)
+		return
+	}
+
+	// A subject is one line; a template's stray newline would break the header.
+	subject = strings.Join(strings.Fields(subject), " ")
+	writeJSON(w, emailDraft{
+		Kind:        kind,
+		Subject:     subject,
+		Body:        body,
+		Attachments: atts,
+	})
+}
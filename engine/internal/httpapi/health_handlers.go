@@ -3,12 +3,19 @@ package httpapi
 import (
 	"encoding/json"
 	"net/http"
+	"os"
 )
 
-type HealthHandler struct{}
+// HealthHandler answers the desktop shell's readiness probe. The shell picks
+// the port, so a successful /health is all it needs to know we're serving.
+type HealthHandler struct {
+	Version string
+}
 
 func (h HealthHandler) Health(w http.ResponseWriter, r *http.Request) {
 	_ = json.NewEncoder(w).Encode(map[string]any{
-		"ok": true,
+		"ok":      true,
+		"version": h.Version,
+		"pid":     os.Getpid(),
 	})
 }
@@ -0,0 +1,68 @@
+package httpapi
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"path/filepath"
+	"regexp"
+	"strings"
+	"time"
+
+	"jobhunt-engine/internal/events"
+	"jobhunt-engine/internal/writes"
+)
+
+// ImportHandler accepts files dropped onto the desktop window (job
+// descriptions, résumés) and keeps them under <data dir>/imports.
+
+type ImportHandler struct {
+	DataDir string
+	Hub     *events.Hub
+}
+
+const maxImportBytes = 20 << 20
+
+var unsafeImportChars = regexp.MustCompile(`[^A-Za-z0-9._-]+`)
+
+// POST /api/import?name=<file name> — body is the raw file contents
+func (h ImportHandler) Upload(w http.ResponseWriter, r *http.Request) {
+	name := filepath.Base(strings.TrimSpace(r.URL.Query().Get("name")))
+	name = unsafeImportChars.ReplaceAllString(name, "_")
+	if name == "" || name == "." || name == "_" {
+		http.Error(w, "name is required", http.StatusBadRequest)
+		return
+	}
+
+	defer writes.Begin("import", "Importing "+name)()
+
+	dir := filepath.Join(h.DataDir, "imports")
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		http.Error(w, "failed to create imports dir: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	path := filepath.Join(dir, fmt.Sprintf("%d-%s", time.Now().Unix(), name))
+
+	f, err := os.OpenFile(path, os.O_CREATE|os.O_EXCL|os.O_WRONLY, 0o600)
+	if err != nil {
+		http.Error(w, "failed to create file: "+err.Error(), http.StatusInternalServerError)
+		return
+	}
+	n, err := io.Copy(f, http.MaxBytesReader(w, r.Body, maxImportBytes))
+	closeErr := f.Close()
+	if err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		_ = os.Remove(path)
+		http.Error(w, "failed to store file: "+err.Error(), http.StatusBadRequest)
+		return
+	}
+
+	evt, _ := json.Marshal(map[string]any{"type": "file_imported", "name": name, "path": path})
+	h.Hub.Publish(string(evt))
+
+	writeJSON(w, map[string]any{"name": name, "path": path, "size": n})
+}
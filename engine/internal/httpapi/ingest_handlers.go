@@ -0,0 +1,87 @@
+package httpapi
+
+import (
+	"database/sql"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"strings"
+
+	"jobhunt-engine/internal/events"
+	"jobhunt-engine/internal/scrape"
+	"jobhunt-engine/internal/scrape/types"
+)
+
+// IngestHandler adds a single job posting by URL, e.g. one the desktop shell
+// spotted on the clipboard. Details are filled in from whatever the caller
+// knows; the URL is the only required field.
+
+type IngestHandler struct {
+	DB  *sql.DB
+	Hub *events.Hub
+}
+
+type ingestReq struct {
+	URL     string `json:"url"`
+	Title   string `json:"title"`
+	Company string `json:"company"`
+	Source  string `json:"source"`
+}
+
+// companyFromURL pulls the board slug out of Greenhouse / Lever posting URLs.
+func companyFromURL(u *url.URL) string {
+	host := strings.ToLower(u.Hostname())
+	parts := strings.Split(strings.Trim(u.Path, "/"), "/")
+	if len(parts) == 0 || parts[0] == "" {
+		return ""
+	}
+	if strings.HasSuffix(host, "greenhouse.io") || host == "jobs.lever.co" {
+		return parts[0]
+	}
+	return ""
+}
+
+// POST /api/ingest  { "url": "...", "title": "...", "company": "...", "source": "clipboard" }
+func (h IngestHandler) Ingest(w http.ResponseWriter, r *http.Request) {
+	body, err := io.ReadAll(io.LimitReader(r.Body, 64<<10))
+	if err != nil {
+		http.Error(w, "failed to read body", http.StatusBadRequest)
+		return
+	}
+	var req ingestReq
+	if err := json.Unmarshal(body, &req); err != nil {
+		http.Error(w, "invalid json: "+err.Error(), http.StatusBadRequest)
+		return
+	}
+	u, err := url.Parse(strings.TrimSpace(req.URL))
+	if err != nil || (u.Scheme != "http" && u.Scheme != "https") || u.Host == "" {
+		http.Error(w, "url must be an absolute http(s) URL", http.StatusBadRequest)
+		return
+	}
+
+	company := strings.TrimSpace(req.Company)
+	if company == "" {
+		company = companyFromURL(u)
+	}
+	source := strings.TrimSpace(req.Source)
+	if source == "" {
+		source = "manual"
+	}
+
+	id, added, err := scrape.InsertJobIfNew(r.Context(), h.DB, types.JobRow{
+		Company:        company,
+		Title:          strings.TrimSpace(req.Title),
+		URL:            u.String(),
+		SeenFromSource: source,
+	})
+	if err != nil {
+		http.Error(w, err.Error(), http.StatusInternalServerError)
+		return
+	}
+	if added {
+		h.Hub.Publish(`{"type":"job_created","id":` + fmt.Sprint(id) + `}`)
+	}
+	writeJSON(w, map[string]any{"added": added, "id": id})
+}
@@ -3,10 +3,14 @@ package httpapi
 import (
 	"context"
 	"database/sql"
+	"encoding/json"
+	"errors"
 	"fmt"
+	"io"
 	"net/http"
 	"strconv"
 	"strings"
+	"time"
 
 	"jobhunt-engine/internal/events"
 	"jobhunt-engine/internal/store"
@@ -33,6 +37,26 @@ func (h JobsHandler) List(w http.ResponseWriter, r *http.Request) {
 	writeJSON(w, jobs)
 }
 
+// Get handles GET /jobs/{id}
+func (h JobsHandler) Get(w http.ResponseWriter, r *http.Request) {
+	id, err := strconv.ParseInt(strings.TrimPrefix(r.URL.Path, "/jobs/"), 10, 64)
+	if err != nil || id <= 0 {
+		http.Error(w, "invalid id", 400)
+		return
+	}
+
+	job, err := store.GetJob(r.Context(), h.DB, id)
+	if errors.Is(err, sql.ErrNoRows) {
+		http.NotFound(w, r)
+		return
+	}
+	if err != nil {
+		http.Error(w, err.Error(), 500)
+		return
+	}
+	writeJSON(w, job)
+}
+
 // Description handles GET /jobs/{id}/description
 // Returns { "id": 42, "description": "..." }
 func (h JobsHandler) Description(w http.ResponseWriter, r *http.Request) {
@@ -54,6 +78,61 @@ func (h JobsHandler) Description(w http.ResponseWriter, r *http.Request) {
 	writeJSON(w, map[string]any{"id": id, "description": desc})
 }
 
+// SetDeadline handles PUT /jobs/{id}/deadline { "deadline": "2026-05-01" }
+// The deadline is an RFC 3339 time or a date, which means the end of that day
+// in local time; an empty string clears it.
+func (h JobsHandler) SetDeadline(w http.ResponseWriter, r *http.Request) {
+	path := strings.TrimPrefix(r.URL.Path, "/jobs/")
+	path = strings.TrimSuffix(path, "/deadline")
+	id, err := strconv.ParseInt(strings.TrimSpace(path), 10, 64)
+	if err != nil || id <= 0 {
+		http.Error(w, "invalid id", 400)
+		return
+	}
+
+	body, err := io.ReadAll(io.LimitReader(r.Body, 4<<10))
+	if err != nil {
+		http.Error(w, "failed to read body", http.StatusBadRequest)
+		return
+	}
+	var req struct {
+		Deadline string `json:"deadline"`
+	}
+	if err := json.Unmarshal(body, &req); err != nil {
+		http.Error(w, "invalid json: "+err.Error(), http.StatusBadRequest)
+		return
+	}
+
+	var deadline time.Time
+	if s := strings.TrimSpace(req.Deadline); s != "" {
+		if t, err := time.Parse(time.RFC3339, s); err == nil {
+			deadline = t
+		} else if d, err := time.ParseInLocation("2006-01-02", s, time.Local); err == nil {
+			deadline = d.Add(24*time.Hour - time.Second)
+		} else {
+			http.Error(w, "deadline must be an RFC 3339 time or a YYYY-MM-DD date", http.StatusBadRequest)
+			return
+		}
+	}
+
+	err = store.SetJobDeadline(r.Context(), h.DB, id, deadline)
+	if errors.Is(err, sql.ErrNoRows) {
+		http.NotFound(w, r)
+		return
+	}
+	if err != nil {
+		http.Error(w, err.Error(), 500)
+		return
+	}
+
+	value := ""
+	if !deadline.IsZero() {
+		value = deadline.UTC().Format(time.RFC3339)
+	}
+	h.Hub.Publish(`{"type":"job_updated","id":` + fmt.Sprint(id) + `}`)
+	writeJSON(w, map[string]any{"ok": true, "id": id, "deadline": value})
+}
+
 func (h JobsHandler) DeleteByPath(w http.ResponseWriter, r *http.Request) {
 	idStr := strings.TrimPrefix(r.URL.Path, "/jobs/")
 	id, err := strconv.ParseInt(idStr, 10, 64)
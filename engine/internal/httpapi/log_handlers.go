@@ -1,17 +1,23 @@
 package httpapi
 
 import (
+	"context"
 	"encoding/json"
-	"fmt"
 	"io"
-	"log"
+	"log/slog"
 	"net/http"
+	"os"
+	"regexp"
+	"slices"
+	"strconv"
+	"strings"
+	"sync"
+	"sync/atomic"
 	"time"
 )
 
 // LogHandler receives structured log entries from the browser extension and
-// prints them to the engine's stdout so they appear in the cmd console
-// alongside [engine stdout] lines from the Tauri shell.
+// writes them to the engine log as records from their source.
 
 type LogHandler struct{}
 
@@ -35,16 +41,163 @@ func (h LogHandler) Log(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
-	ts := time.Now().Format("15:04:05")
+	lvl, ok := logLevels[strings.ToLower(entry.Level)]
+	if !ok {
+		lvl = logLevels["info"]
+	}
+	target := entry.Source
+	if target == "" {
+		target = "extension"
+	}
+	writeRecord(lvl, target, entry.Message, fields(entry.Message))
+
+	w.WriteHeader(http.StatusNoContent)
+}
+
+// The engine logs through log/slog (see internal/logx) with "[target]
+// message k=v" messages. NewLogger turns each record into one JSON object per
+// line,
+//
+//	{"level":"warn","ts":"...","target":"config","msg":"...","fields":{...}}
+//
+// so the desktop shell can store and filter them without guessing.
+var minLogLevel atomic.Int32
 
-	switch entry.Level {
-	case "warn":
-		log.Printf("[%s] [%s] WARN  %s", ts, entry.Source, entry.Message)
-	case "error":
-		log.Printf("[%s] [%s] ERROR %s", ts, entry.Source, entry.Message)
+var logLevels = map[string]int32{"debug": 0, "info": 1, "warn": 2, "error": 3}
+
+var levelNames = []string{"debug", "info", "warn", "error"}
+
+func slogLevel(l slog.Level) int32 {
+	switch {
+	case l >= slog.LevelError:
+		return logLevels["error"]
+	case l >= slog.LevelWarn:
+		return logLevels["warn"]
+	case l >= slog.LevelInfo:
+		return logLevels["info"]
 	default:
-		fmt.Printf("[%s] [%s] %s\n", ts, entry.Source, entry.Message)
+		return logLevels["debug"]
 	}
+}
 
+var (
+	targetPrefix = regexp.MustCompile(`^\[([^\]\s]+)\]\s*`)
+	fieldPattern = regexp.MustCompile(`(?:^|\s)([A-Za-z_][\w.]*)=("(?:[^"\\]|\\.)*"|\S+)`)
+)
+
+// fields collects the key=value pairs in a message; quoted values are unquoted.
+func fields(msg string) map[string]string {
+	out := map[string]string{}
+	for _, m := range fieldPattern.FindAllStringSubmatch(msg, -1) {
+		v := m[2]
+		if strings.HasPrefix(v, `"`) {
+			if u, err := strconv.Unquote(v); err == nil {
+				v = u
+			}
+		}
+		out[m[1]] = v
+	}
+	return out
+}
+
+type logRecord struct {
+	Level  string            `json:"level"`
+	TS     string            `json:"ts"`
+	Target string            `json:"target"`
+	Msg    string            `json:"msg"`
+	Fields map[string]string `json:"fields"`
+}
+
+var (
+	logMu   sync.Mutex
+	logSink io.Writer = os.Stderr
+)
+
+func writeRecord(lvl int32, target, msg string, f map[string]string) {
+	if lvl < minLogLevel.Load() {
+		return
+	}
+	b, err := json.Marshal(logRecord{
+		Level:  levelNames[lvl],
+		TS:     time.Now().UTC().Format(time.RFC3339Nano),
+		Target: target,
+		Msg:    msg,
+		Fields: f,
+	})
+	if err != nil {
+		return
+	}
+	logMu.Lock()
+	defer logMu.Unlock()
+	logSink.Write(append(b, '\n'))
+}
+
+// recordHandler is the slog.Handler behind NewLogger. A "target" attribute
+// overrides the message's [target] prefix; other attributes join the fields.
+type recordHandler struct {
+	attrs []slog.Attr
+}
+
+func (h recordHandler) Enabled(_ context.Context, l slog.Level) bool {
+	return slogLevel(l) >= minLogLevel.Load()
+}
+
+func (h recordHandler) Handle(_ context.Context, r slog.Record) error {
+	msg := strings.TrimRight(r.Message, "\n")
+	target := "engine"
+	if m := targetPrefix.FindStringSubmatch(msg); m != nil {
+		target = m[1]
+		msg = msg[len(m[0]):]
+	}
+	f := fields(msg)
+	add := func(a slog.Attr) bool {
+		if a.Key == "target" {
+			target = a.Value.String()
+		} else {
+			f[a.Key] = a.Value.String()
+		}
+		return true
+	}
+	for _, a := range h.attrs {
+		add(a)
+	}
+	r.Attrs(add)
+	writeRecord(slogLevel(r.Level), target, msg, f)
+	return nil
+}
+
+func (h recordHandler) WithAttrs(attrs []slog.Attr) slog.Handler {
+	return recordHandler{attrs: append(slices.Clip(h.attrs), attrs...)}
+}
+
+func (h recordHandler) WithGroup(string) slog.Handler { return h }
+
+// NewLogger writes records to w as JSON lines; /log/level sets the lowest
+// level written. Pass it to slog.SetDefault, which also routes the standard
+// log package through it at info.
+func NewLogger(w io.Writer) *slog.Logger {
+	logMu.Lock()
+	logSink = w
+	logMu.Unlock()
+	return slog.New(recordHandler{})
+}
+
+type LogLevelHandler struct{}
+
+// POST /log/level {"level": "debug" | "info" | "warn" | "error"}
+func (h LogLevelHandler) Set(w http.ResponseWriter, r *http.Request) {
+	var body struct {
+		Level string `json:"level"`
+	}
+	if err := json.NewDecoder(io.LimitReader(r.Body, 1<<10)).Decode(&body); err != nil {
+		http.Error(w, "invalid body", http.StatusBadRequest)
+		return
+	}
+	lvl, ok := logLevels[strings.ToLower(body.Level)]
+	if !ok {
+		http.Error(w, "unknown level", http.StatusBadRequest)
+		return
+	}
+	minLogLevel.Store(lvl)
 	w.WriteHeader(http.StatusNoContent)
 }
@@ -1,9 +1,11 @@
 package httpapi
 
 import (
+	"bytes"
 	"database/sql"
 	"net/http"
 	"strings"
+	"time"
 )
 
 type LogosHandler struct {
@@ -37,5 +39,5 @@ func (h LogosHandler) GetByPath(w http.ResponseWriter, r *http.Request) {
 	}
 	w.Header().Set("Content-Type", ct)
 	w.Header().Set("Cache-Control", "public, max-age=604800")
-	_, _ = w.Write(b)
+	http.ServeContent(w, r, key, time.Time{}, bytes.NewReader(b))
 }
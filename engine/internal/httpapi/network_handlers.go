@@ -0,0 +1,53 @@
+package httpapi
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"log"
+	"net/http"
+
+	"jobhunt-engine/internal/events"
+	"jobhunt-engine/internal/netstate"
+)
+
+// NetworkHandler receives connectivity changes from the desktop shell so
+// outgoing requests can wait out an outage instead of failing.
+type NetworkHandler struct {
+	Hub *events.Hub
+}
+
+// GET /control/network
+func (h NetworkHandler) Get(w http.ResponseWriter, r *http.Request) {
+	writeJSON(w, map[string]any{"online": netstate.Online(), "queued": netstate.Queued()})
+}
+
+// POST /control/network {"online": bool}
+func (h NetworkHandler) Set(w http.ResponseWriter, r *http.Request) {
+	body, err := io.ReadAll(io.LimitReader(r.Body, 4<<10))
+	if err != nil {
+		http.Error(w, "failed to read body", http.StatusBadRequest)
+		return
+	}
+	var req struct {
+		Online *bool `json:"online"`
+	}
+	if err := json.Unmarshal(body, &req); err != nil {
+		http.Error(w, "invalid json: "+err.Error(), http.StatusBadRequest)
+		return
+	}
+	if req.Online == nil {
+		http.Error(w, "online is required", http.StatusBadRequest)
+		return
+	}
+
+	was := netstate.Online()
+	netstate.SetOnline(*req.Online)
+	if was != *req.Online {
+		log.Printf("[network] online=%t", *req.Online)
+		if h.Hub != nil {
+			h.Hub.Publish(fmt.Sprintf(`{"type":"network","online":%t}`, *req.Online))
+		}
+	}
+	writeJSON(w, map[string]any{"ok": true, "queued": netstate.Queued()})
+}
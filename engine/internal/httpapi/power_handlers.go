@@ -0,0 +1,89 @@
+package httpapi
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"log"
+	"net/http"
+
+	"jobhunt-engine/internal/events"
+	"jobhunt-engine/internal/poll"
+)
+
+// PowerHandler receives sleep/wake and power source changes from the desktop
+// shell, which can see them when the engine can't.
+type PowerHandler struct {
+	Hub *events.Hub
+}
+
+// POST /control/power {"event": "suspend" | "resume" | "ac" | "battery"}
+func (h PowerHandler) Notify(w http.ResponseWriter, r *http.Request) {
+	body, err := io.ReadAll(io.LimitReader(r.Body, 4<<10))
+	if err != nil {
+		http.Error(w, "failed to read body", http.StatusBadRequest)
+		return
+	}
+	var req struct {
+		Event string `json:"event"`
+	}
+	if err := json.Unmarshal(body, &req); err != nil {
+		http.Error(w, "invalid json: "+err.Error(), http.StatusBadRequest)
+		return
+	}
+
+	switch req.Event {
+	case "suspend":
+		poll.SetSuspended(true)
+	case "resume":
+		poll.SetSuspended(false)
+	case "ac", "battery":
+	default:
+		http.Error(w, "unknown event "+req.Event, http.StatusBadRequest)
+		return
+	}
+	log.Printf("[power] %s", req.Event)
+	if h.Hub != nil {
+		h.Hub.Publish(fmt.Sprintf(`{"type":"power","event":%q}`, req.Event))
+	}
+	writeJSON(w, map[string]any{"ok": true})
+}
+
+// GET /control/activity
+func (h PowerHandler) Activity(w http.ResponseWriter, r *http.Request) {
+	writeJSON(w, map[string]any{"held": poll.Held()})
+}
+
+// POST /control/activity {"held": bool, "idleSecs": n}
+//
+// Sent by the shell when its power and idle settings hold background work,
+// and again each time it rechecks while the hold lasts.
+func (h PowerHandler) SetActivity(w http.ResponseWriter, r *http.Request) {
+	body, err := io.ReadAll(io.LimitReader(r.Body, 4<<10))
+	if err != nil {
+		http.Error(w, "failed to read body", http.StatusBadRequest)
+		return
+	}
+	var req struct {
+		Held     *bool   `json:"held"`
+		IdleSecs *uint64 `json:"idleSecs"`
+	}
+	if err := json.Unmarshal(body, &req); err != nil {
+		http.Error(w, "invalid json: "+err.Error(), http.StatusBadRequest)
+		return
+	}
+	if req.Held == nil {
+		http.Error(w, "held is required", http.StatusBadRequest)
+		return
+	}
+
+	was := poll.Held()
+	poll.SetHeld(*req.Held)
+	if was != *req.Held {
+		log.Printf("[power] background work held=%t", *req.Held)
+		if h.Hub != nil {
+			h.Hub.Publish(fmt.Sprintf(`{"type":"activity","held":%t}`, *req.Held))
+		}
+	}
+	writeJSON(w, map[string]any{"ok": true})
+}
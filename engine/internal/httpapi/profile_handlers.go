@@ -2,10 +2,12 @@ package httpapi
 
 import (
 	"encoding/json"
+	"errors"
 	"io"
 	"net/http"
-	"os"
 	"path/filepath"
+
+	"jobhunt-engine/internal/vault"
 )
 
 // ProfileHandler persists the applicant profile to a JSON file in the data dir.
@@ -26,7 +28,11 @@ func (h ProfileHandler) profilePath() string {
 
 // GET /api/profile — returns the stored profile, or an empty object if none saved yet
 func (h ProfileHandler) Get(w http.ResponseWriter, r *http.Request) {
-	data, err := os.ReadFile(h.profilePath())
+	data, err := vault.ReadFile(h.profilePath())
+	if errors.Is(err, vault.ErrLocked) {
+		http.Error(w, err.Error(), http.StatusServiceUnavailable)
+		return
+	}
 	if err != nil {
 		// No profile saved yet — return empty object so extension can detect this
 		w.Header().Set("Content-Type", "application/json")
@@ -52,7 +58,7 @@ func (h ProfileHandler) Save(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
-	if err := os.WriteFile(h.profilePath(), body, 0o600); err != nil {
+	if err := vault.WriteFile(h.profilePath(), body, 0o600); err != nil {
 		http.Error(w, "failed to save profile: "+err.Error(), http.StatusInternalServerError)
 		return
 	}
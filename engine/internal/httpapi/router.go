@@ -2,29 +2,50 @@ package httpapi
 
 import (
 	"context"
-	"crypto/subtle"
 	"net"
 	"net/http"
+	"net/http/pprof"
 	"strings"
 	"time"
 )
 
-// NewMux returns the raw mux so main() can still attach /shutdown (needs srv+token).
+// NewMux returns the raw mux so main() can still attach /shutdown (needs srv).
+// main() serves it behind Signer.Guard, so no route here checks signatures.
 func NewMux(d Deps) *http.ServeMux {
 	mux := http.NewServeMux()
 
+	// Health — polled by the desktop shell
+	hh := HealthHandler{Version: d.Version}
+	mux.HandleFunc("/health", methodMux(map[string]http.HandlerFunc{
+		http.MethodGet: hh.Health,
+	}))
+
 	// Jobs
 	jh := JobsHandler{DB: d.DB, Hub: d.Hub, DeleteJob: d.DeleteJob}
 	mux.HandleFunc("/jobs", methodMux(map[string]http.HandlerFunc{
 		http.MethodGet: jh.List,
 	}))
-	// /jobs/ catches both /jobs/{id} (DELETE) and /jobs/{id}/description (GET)
+	edh := EmailDraftHandler{DB: d.DB, DataDir: d.DataDir}
+	// /jobs/ catches /jobs/{id} (GET, DELETE), /jobs/{id}/description (GET),
+	// /jobs/{id}/email (GET) and /jobs/{id}/deadline (PUT)
 	mux.HandleFunc("/jobs/", func(w http.ResponseWriter, r *http.Request) {
 		path := r.URL.Path
 		if r.Method == http.MethodGet && strings.HasSuffix(path, "/description") {
 			jh.Description(w, r)
 			return
 		}
+		if r.Method == http.MethodGet && strings.HasSuffix(path, "/email") {
+			edh.Draft(w, r)
+			return
+		}
+		if r.Method == http.MethodPut && strings.HasSuffix(path, "/deadline") {
+			jh.SetDeadline(w, r)
+			return
+		}
+		if r.Method == http.MethodGet {
+			jh.Get(w, r)
+			return
+		}
 		if r.Method == http.MethodDelete {
 			jh.DeleteByPath(w, r)
 			return
@@ -55,6 +76,9 @@ func NewMux(d Deps) *http.ServeMux {
 	mux.HandleFunc("/db/checkpoint", methodMux(map[string]http.HandlerFunc{
 		http.MethodPost: dbh.Checkpoint,
 	}))
+	mux.HandleFunc("/db/maintenance/", methodMux(map[string]http.HandlerFunc{
+		http.MethodPost: dbh.Maintenance,
+	}))
 
 	// Secrets (use cfgVal, NOT a snapshot cfg)
 	sh := SecretsHandler{CfgVal: d.CfgVal}
@@ -95,6 +119,12 @@ func NewMux(d Deps) *http.ServeMux {
 		http.MethodPost: lgh.Log,
 	}))
 
+	// Engine log level — set from the desktop shell's debug console
+	llh := LogLevelHandler{}
+	mux.HandleFunc("/log/level", methodMux(map[string]http.HandlerFunc{
+		http.MethodPost: llh.Set,
+	}))
+
 	// Cover letter save
 	clh := CoverLetterHandler{}
 	mux.HandleFunc("/api/cover-letter/save", methodMux(map[string]http.HandlerFunc{
@@ -108,6 +138,35 @@ func NewMux(d Deps) *http.ServeMux {
 		http.MethodPost: ph.Save,
 	}))
 
+	// Attachments — résumés / cover letters copied into the shell's store.
+	ath := AttachmentsHandler{DataDir: d.DataDir}
+	mux.HandleFunc("/api/attachments", methodMux(map[string]http.HandlerFunc{
+		http.MethodGet:  ath.List,
+		http.MethodPost: ath.Register,
+	}))
+	mux.HandleFunc("/api/attachments/", methodMux(map[string]http.HandlerFunc{
+		http.MethodGet:    ath.GetByPath,
+		http.MethodDelete: ath.DeleteByPath,
+	}))
+
+	// Ingest a single posting by URL
+	igh := IngestHandler{DB: d.DB, Hub: d.Hub}
+	mux.HandleFunc("/api/ingest", methodMux(map[string]http.HandlerFunc{
+		http.MethodPost: igh.Ingest,
+	}))
+
+	// File import — drag-and-drop from the desktop window
+	ih := ImportHandler{DataDir: d.DataDir, Hub: d.Hub}
+	mux.HandleFunc("/api/import", methodMux(map[string]http.HandlerFunc{
+		http.MethodPost: ih.Upload,
+	}))
+
+	// Bulk import — records parsed from LinkedIn / Indeed data exports
+	bih := BulkImportHandler{DB: d.DB, Hub: d.Hub}
+	mux.HandleFunc("/api/import/jobs", methodMux(map[string]http.HandlerFunc{
+		http.MethodPost: bih.Import,
+	}))
+
 	// Apply — two-phase: scrape form fields, then fill with exact selectors
 	ah := ApplyHandler{DB: d.DB, DataDir: d.DataDir}
 	mux.HandleFunc("/api/apply/scrape", methodMux(map[string]http.HandlerFunc{
@@ -138,16 +197,61 @@ func NewMux(d Deps) *http.ServeMux {
 		http.MethodGet: eh.ServeSSE,
 	}))
 
+	// Power — sleep/wake, AC/battery, and background work holds from the desktop shell
+	pwh := PowerHandler{Hub: d.Hub}
+	mux.HandleFunc("/control/power", methodMux(map[string]http.HandlerFunc{
+		http.MethodPost: pwh.Notify,
+	}))
+	mux.HandleFunc("/control/activity", methodMux(map[string]http.HandlerFunc{
+		http.MethodGet:  pwh.Activity,
+		http.MethodPost: pwh.SetActivity,
+	}))
+
+	// Network — connectivity reported by the desktop shell
+	nh := NetworkHandler{Hub: d.Hub}
+	mux.HandleFunc("/control/network", methodMux(map[string]http.HandlerFunc{
+		http.MethodGet:  nh.Get,
+		http.MethodPost: nh.Set,
+	}))
+
+	// Writes — checked by the desktop shell before it quits.
+	wh := WritesHandler{}
+	mux.HandleFunc("/control/writes", methodMux(map[string]http.HandlerFunc{
+		http.MethodGet: wh.List,
+	}))
+
 	// Logos
 	lh := LogosHandler{DB: d.DB}
 	mux.HandleFunc("/logo/", methodMux(map[string]http.HandlerFunc{
 		http.MethodGet: lh.GetByPath,
 	}))
 
+	// Heap profile — captured by the desktop shell when engine memory runs high.
+	mux.HandleFunc("/debug/pprof/heap", methodMux(map[string]http.HandlerFunc{
+		http.MethodGet: pprof.Handler("heap").ServeHTTP,
+	}))
+
 	return mux
 }
 
-func ShutdownHandler(token *string, srv *http.Server) http.HandlerFunc {
+// isLocal reports whether r came from this machine: over loopback TCP, or
+// over the Unix socket or named pipe the desktop shell can serve us on.
+func isLocal(r *http.Request) bool {
+	if addr, ok := r.Context().Value(http.LocalAddrContextKey).(net.Addr); ok {
+		if n := addr.Network(); n == "unix" || n == "pipe" {
+			return true
+		}
+	}
+	host, _, err := net.SplitHostPort(r.RemoteAddr)
+	if err != nil {
+		host = r.RemoteAddr
+	}
+	return host == "127.0.0.1" || host == "::1" || host == "localhost"
+}
+
+// ShutdownHandler is served behind Signer.Guard like the rest of the mux, so
+// only a request signed by the desktop shell gets here.
+func ShutdownHandler(srv *http.Server) http.HandlerFunc {
 	return func(w http.ResponseWriter, r *http.Request) {
 		if r.Method != http.MethodPost {
 			w.WriteHeader(http.StatusMethodNotAllowed)
@@ -155,22 +259,11 @@ func ShutdownHandler(token *string, srv *http.Server) http.HandlerFunc {
 		}
 
 		// Local-only guard (covers typical desktop usage)
-		host, _, err := net.SplitHostPort(r.RemoteAddr)
-		if err != nil {
-			host = r.RemoteAddr
-		}
-		if host != "127.0.0.1" && host != "::1" && host != "localhost" {
+		if !isLocal(r) {
 			http.Error(w, "forbidden", http.StatusForbidden)
 			return
 		}
 
-		// Token guard
-		got := r.Header.Get("X-Shutdown-Token")
-		if got == "" || subtle.ConstantTimeCompare([]byte(got), []byte(*token)) != 1 {
-			http.Error(w, "unauthorized", http.StatusUnauthorized)
-			return
-		}
-
 		// Respond immediately, then shutdown asynchronously
 		w.WriteHeader(http.StatusOK)
 		_, _ = w.Write([]byte("shutting down\n"))
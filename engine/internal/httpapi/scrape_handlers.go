@@ -9,6 +9,7 @@ import (
 
 	"jobhunt-engine/internal/config"
 	"jobhunt-engine/internal/events"
+	"jobhunt-engine/internal/netstate"
 	"jobhunt-engine/internal/scrape/types"
 )
 
@@ -17,7 +18,7 @@ type ScrapeHandler struct {
 	CfgVal       *atomic.Value // config.Config
 	ScrapeStatus *atomic.Value // httpapi.ScrapeStatus
 	Hub          *events.Hub
-	PollOnce     func(db *sql.DB, cfg config.Config, onNewJob func()) (added int, err error)
+	PollOnce     func(db *sql.DB, cfg config.Config, onNewJob func(id int64)) (added int, err error)
 }
 
 func (h ScrapeHandler) Status(w http.ResponseWriter, r *http.Request) {
@@ -32,6 +33,23 @@ func (h ScrapeHandler) Run(w http.ResponseWriter, r *http.Request) {
 		return
 	}
 
+	// Offline, the run waits for the network rather than failing every source.
+	if !netstate.Online() {
+		netstate.Defer("scrape-run", h.start)
+		writeJSON(w, map[string]any{"ok": true, "queued": true})
+		return
+	}
+
+	h.start()
+	writeJSON(w, map[string]any{"ok": true})
+}
+
+func (h ScrapeHandler) start() {
+	st := h.ScrapeStatus.Load().(types.ScrapeStatus)
+	if st.Running {
+		return
+	}
+
 	h.ScrapeStatus.Store(types.ScrapeStatus{
 		LastRunAt: time.Now().Format(time.RFC3339),
 		Running:   true,
@@ -66,8 +84,8 @@ func (h ScrapeHandler) Run(w http.ResponseWriter, r *http.Request) {
 			return
 		}
 
-		added, err := h.PollOnce(h.DB, cfg, func() {
-			h.Hub.Publish(`{"type":"job_created"}`)
+		added, err := h.PollOnce(h.DB, cfg, func(id int64) {
+			h.Hub.Publish(`{"type":"job_created","id":` + fmt.Sprint(id) + `}`)
 		})
 
 		now := time.Now().Format(time.RFC3339)
@@ -84,6 +102,4 @@ func (h ScrapeHandler) Run(w http.ResponseWriter, r *http.Request) {
 		}
 		h.ScrapeStatus.Store(next)
 	}()
-
-	writeJSON(w, map[string]any{"ok": true})
 }
@@ -0,0 +1,116 @@
+package httpapi
+
+import (
+	"bytes"
+	"crypto/hmac"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"strconv"
+	"sync"
+	"time"
+)
+
+// Requests from the desktop shell are signed with a key it generates for each
+// engine session and passes in JOBHUNT_AUTH_KEY. The signature is
+// HMAC-SHA256 over "<timestamp>\n<nonce>\n<METHOD>\n<path?query>\n<hex sha256(body)>",
+// so a captured request can't be altered, and a nonce is accepted only once.
+// Every route but /health needs a signature; see Guard.
+const (
+	SigTimestampHeader = "X-JobHunt-Timestamp"
+	SigNonceHeader     = "X-JobHunt-Nonce"
+	SigHeader          = "X-JobHunt-Signature"
+
+	sigMaxSkew  = 60 * time.Second
+	// The largest body any route takes: a file for /api/import.
+	sigMaxBody  = maxImportBytes
+	sigMaxNonce = 64
+)
+
+type Signer struct {
+	key []byte
+
+	mu sync.Mutex
+	// Nonces seen within the skew window; older ones are rejected by timestamp.
+	seen map[string]time.Time
+}
+
+func NewSigner(key string) *Signer {
+	return &Signer{key: []byte(key), seen: map[string]time.Time{}}
+}
+
+func (s *Signer) mac(ts, nonce, method, path string, body []byte) []byte {
+	sum := sha256.Sum256(body)
+	m := hmac.New(sha256.New, s.key)
+	fmt.Fprintf(m, "%s\n%s\n%s\n%s\n%s", ts, nonce, method, path, hex.EncodeToString(sum[:]))
+	return m.Sum(nil)
+}
+
+// Verify checks r's signature and leaves its body readable for the handler.
+func (s *Signer) Verify(r *http.Request) error {
+	ts := r.Header.Get(SigTimestampHeader)
+	nonce := r.Header.Get(SigNonceHeader)
+	sig, err := hex.DecodeString(r.Header.Get(SigHeader))
+	if ts == "" || nonce == "" || err != nil || len(sig) == 0 {
+		return errors.New("missing or malformed signature")
+	}
+	if len(nonce) > sigMaxNonce {
+		return errors.New("nonce too long")
+	}
+	secs, err := strconv.ParseInt(ts, 10, 64)
+	if err != nil {
+		return errors.New("bad timestamp")
+	}
+	now := time.Now()
+	if d := now.Sub(time.Unix(secs, 0)); d > sigMaxSkew || d < -sigMaxSkew {
+		return errors.New("timestamp outside the allowed window")
+	}
+
+	body, err := io.ReadAll(io.LimitReader(r.Body, sigMaxBody+1))
+	if err != nil {
+		return errors.New("failed to read body")
+	}
+	if len(body) > sigMaxBody {
+		return errors.New("body too large to verify")
+	}
+	r.Body = io.NopCloser(bytes.NewReader(body))
+
+	if !hmac.Equal(sig, s.mac(ts, nonce, r.Method, r.URL.RequestURI(), body)) {
+		return errors.New("bad signature")
+	}
+
+	// Only remember nonces of genuine requests, so forgeries can't fill the table.
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	for n, exp := range s.seen {
+		if now.After(exp) {
+			delete(s.seen, n)
+		}
+	}
+	if _, ok := s.seen[nonce]; ok {
+		return errors.New("replayed request")
+	}
+	s.seen[nonce] = now.Add(2 * sigMaxSkew)
+	return nil
+}
+
+// openPaths answer unsigned requests. The shell polls /health before it talks
+// to a new engine, and it reveals only the version and pid.
+var openPaths = map[string]bool{"/health": true}
+
+// Guard wraps the whole mux so that every route outside openPaths only runs
+// for requests signed by the desktop shell.
+func (s *Signer) Guard(next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if !openPaths[r.URL.Path] {
+			if err := s.Verify(r); err != nil {
+				http.Error(w, "unauthorized: "+err.Error(), http.StatusUnauthorized)
+				return
+			}
+		}
+		next.ServeHTTP(w, r)
+	})
+}
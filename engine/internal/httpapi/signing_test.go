@@ -0,0 +1,164 @@
+package httpapi
+
+import (
+	"encoding/hex"
+	"net/http"
+	"net/http/httptest"
+	"strconv"
+	"strings"
+	"testing"
+	"time"
+)
+
+// The same vector is checked against the desktop shell's signature() in
+// src-tauri/src/proxy.rs; if either side changes the format, both tests fail.
+const (
+	vectorKey   = "test-key"
+	vectorTS    = "1700000000"
+	vectorNonce = "nonce-1"
+	vectorPath  = "/api/jobs?q=a%20b&x=1"
+	vectorBody  = `{"a":1}`
+	vectorMAC   = "a6a5237a3e5faecfe60ffce250fdab65bf6ac2efb2c11c310bc54f8003ac1429"
+)
+
+// ─── helpers ─────────────────────────────────────────────────────────────────
+
+// signed builds a request for target the way the shell sends it: the
+// signature covers the path and query exactly as they appear on the wire.
+func signed(s *Signer, method, target, body, nonce string, at time.Time) *http.Request {
+	var r *http.Request
+	if body == "" {
+		r = httptest.NewRequest(method, target, nil)
+	} else {
+		r = httptest.NewRequest(method, target, strings.NewReader(body))
+	}
+	ts := strconv.FormatInt(at.Unix(), 10)
+	r.Header.Set(SigTimestampHeader, ts)
+	r.Header.Set(SigNonceHeader, nonce)
+	r.Header.Set(SigHeader, hex.EncodeToString(s.mac(ts, nonce, method, target, []byte(body))))
+	return r
+}
+
+// ─── mac ─────────────────────────────────────────────────────────────────────
+
+func TestMacMatchesShellVector(t *testing.T) {
+	s := NewSigner(vectorKey)
+	got := hex.EncodeToString(s.mac(vectorTS, vectorNonce, http.MethodPost, vectorPath, []byte(vectorBody)))
+	if got != vectorMAC {
+		t.Fatalf("mac = %s, want %s", got, vectorMAC)
+	}
+}
+
+// ─── Verify ──────────────────────────────────────────────────────────────────
+
+func TestVerifyAcceptsEscapedPathsAndQueries(t *testing.T) {
+	s := NewSigner(vectorKey)
+	targets := []string{
+		"/health",
+		"/api/jobs?q=a%20b&x=1",
+		"/api/attachments/r%C3%A9sum%C3%A9.pdf?kind=cv",
+		"/api/attachments/a%2Fb.pdf",
+	}
+	for i, target := range targets {
+		r := signed(s, http.MethodGet, target, "", "nonce-"+strconv.Itoa(i), time.Now())
+		if err := s.Verify(r); err != nil {
+			t.Errorf("%s: %v", target, err)
+		}
+	}
+}
+
+func TestVerifyLeavesBodyReadable(t *testing.T) {
+	s := NewSigner(vectorKey)
+	r := signed(s, http.MethodPost, "/api/ingest", vectorBody, "n", time.Now())
+	if err := s.Verify(r); err != nil {
+		t.Fatal(err)
+	}
+	buf := make([]byte, len(vectorBody)+1)
+	n, _ := r.Body.Read(buf)
+	if string(buf[:n]) != vectorBody {
+		t.Fatalf("body = %q, want %q", buf[:n], vectorBody)
+	}
+}
+
+func TestVerifyRejects(t *testing.T) {
+	s := NewSigner(vectorKey)
+	now := time.Now()
+	tests := []struct {
+		name string
+		req  func() *http.Request
+	}{
+		{"unsigned", func() *http.Request {
+			return httptest.NewRequest(http.MethodGet, "/jobs", nil)
+		}},
+		{"other key", func() *http.Request {
+			return signed(NewSigner("other-key"), http.MethodGet, "/jobs", "", "a", now)
+		}},
+		{"altered path", func() *http.Request {
+			r := signed(s, http.MethodGet, "/jobs?limit=1", "", "b", now)
+			r.URL.RawQuery = "limit=2"
+			return r
+		}},
+		{"altered method", func() *http.Request {
+			r := signed(s, http.MethodGet, "/jobs/1", "", "c", now)
+			r.Method = http.MethodDelete
+			return r
+		}},
+		{"altered body", func() *http.Request {
+			r := signed(s, http.MethodPost, "/api/ingest", vectorBody, "d", now)
+			r.Body = httptest.NewRequest(http.MethodPost, "/", strings.NewReader(`{"a":2}`)).Body
+			return r
+		}},
+		{"stale", func() *http.Request {
+			return signed(s, http.MethodGet, "/jobs", "", "e", now.Add(-2*sigMaxSkew))
+		}},
+		{"future", func() *http.Request {
+			return signed(s, http.MethodGet, "/jobs", "", "f", now.Add(2*sigMaxSkew))
+		}},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if err := s.Verify(tt.req()); err == nil {
+				t.Fatal("accepted")
+			}
+		})
+	}
+}
+
+func TestVerifyRejectsReplay(t *testing.T) {
+	s := NewSigner(vectorKey)
+	now := time.Now()
+	if err := s.Verify(signed(s, http.MethodGet, "/jobs", "", "once", now)); err != nil {
+		t.Fatal(err)
+	}
+	if err := s.Verify(signed(s, http.MethodGet, "/jobs", "", "once", now)); err == nil {
+		t.Fatal("replay accepted")
+	}
+}
+
+// ─── Guard ───────────────────────────────────────────────────────────────────
+
+func TestGuardOnlyLeavesHealthOpen(t *testing.T) {
+	s := NewSigner(vectorKey)
+	h := s.Guard(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.WriteHeader(http.StatusNoContent)
+	}))
+	for path, want := range map[string]int{
+		"/health":           http.StatusNoContent,
+		"/events":           http.StatusUnauthorized,
+		"/control/activity": http.StatusUnauthorized,
+		"/control/network":  http.StatusUnauthorized,
+		"/health/../jobs":   http.StatusUnauthorized,
+	} {
+		w := httptest.NewRecorder()
+		h.ServeHTTP(w, httptest.NewRequest(http.MethodGet, path, nil))
+		if w.Code != want {
+			t.Errorf("%s: status %d, want %d", path, w.Code, want)
+		}
+	}
+
+	w := httptest.NewRecorder()
+	h.ServeHTTP(w, signed(s, http.MethodGet, "/events", "", "g", time.Now()))
+	if w.Code != http.StatusNoContent {
+		t.Errorf("signed /events: status %d", w.Code)
+	}
+}
@@ -0,0 +1,16 @@
+package httpapi
+
+import (
+	"net/http"
+
+	"jobhunt-engine/internal/writes"
+)
+
+// WritesHandler reports in-flight writes so the desktop shell can hold off
+// quitting until an import or scrape has committed.
+type WritesHandler struct{}
+
+// GET /control/writes
+func (h WritesHandler) List(w http.ResponseWriter, r *http.Request) {
+	writeJSON(w, map[string]any{"active": writes.Active()})
+}
@@ -0,0 +1,17 @@
+//go:build windows
+
+package localsock
+
+import (
+	"net"
+
+	"github.com/Microsoft/go-winio"
+)
+
+// Full access for the pipe's owner only, and none for network logons so the
+// pipe can't be opened remotely over SMB.
+const ownerOnly = "D:P(D;;GA;;;NU)(A;;GA;;;OW)"
+
+func listen(path string) (net.Listener, error) {
+	return winio.ListenPipe(path, &winio.PipeConfig{SecurityDescriptor: ownerOnly})
+}
@@ -0,0 +1,13 @@
+// Package localsock serves the engine's API on a Unix domain socket, or a
+// named pipe on Windows, for desktop setups that don't want a TCP port open.
+// Only the user running the engine can connect.
+package localsock
+
+import "net"
+
+// Listen opens the socket or pipe at path. A Unix socket is bound inside its
+// parent directory, which is created or narrowed to mode 0700; a socket file
+// left behind by an engine that crashed is replaced.
+func Listen(path string) (net.Listener, error) {
+	return listen(path)
+}
@@ -0,0 +1,26 @@
+// Package logx logs engine messages at an explicit level. Messages keep the
+// "[target] message k=v" shape; the level travels with the record instead of
+// being guessed from the text. Plain log.Printf calls still work and log at
+// info.
+package logx
+
+import (
+	"context"
+	"fmt"
+	"log/slog"
+)
+
+func logf(level slog.Level, format string, args ...any) {
+	ctx := context.Background()
+	if l := slog.Default(); l.Enabled(ctx, level) {
+		l.Log(ctx, level, fmt.Sprintf(format, args...))
+	}
+}
+
+func Debugf(format string, args ...any) { logf(slog.LevelDebug, format, args...) }
+
+func Infof(format string, args ...any) { logf(slog.LevelInfo, format, args...) }
+
+func Warnf(format string, args ...any) { logf(slog.LevelWarn, format, args...) }
+
+func Errorf(format string, args ...any) { logf(slog.LevelError, format, args...) }
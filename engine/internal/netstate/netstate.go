@@ -0,0 +1,72 @@
+// Package netstate tracks whether the desktop shell reports the machine as
+// online, and holds work that needs the network until it is. A standalone
+// engine with no shell to tell it otherwise is always online.
+package netstate
+
+import (
+	"log"
+	"sync"
+)
+
+var (
+	mu      sync.Mutex
+	offline bool
+	// pending runs in queue order once connectivity returns; keys dedupe.
+	pending = map[string]func(){}
+	order   []string
+)
+
+func Online() bool {
+	mu.Lock()
+	defer mu.Unlock()
+	return !offline
+}
+
+// SetOnline records a connectivity change. Going online runs everything
+// queued by Defer while offline.
+func SetOnline(online bool) {
+	mu.Lock()
+	offline = !online
+	if !online {
+		mu.Unlock()
+		return
+	}
+	queued := make([]func(), 0, len(order))
+	for _, key := range order {
+		queued = append(queued, pending[key])
+	}
+	pending = map[string]func(){}
+	order = nil
+	mu.Unlock()
+
+	if len(queued) > 0 {
+		log.Printf("[netstate] back online; flushing %d queued request(s)", len(queued))
+	}
+	for _, fn := range queued {
+		go fn()
+	}
+}
+
+// Defer runs fn now when online. Offline, it queues fn under key, replacing
+// anything already queued under that key, and reports true.
+func Defer(key string, fn func()) (queued bool) {
+	mu.Lock()
+	if !offline {
+		mu.Unlock()
+		go fn()
+		return false
+	}
+	defer mu.Unlock()
+	if _, ok := pending[key]; !ok {
+		order = append(order, key)
+	}
+	pending[key] = fn
+	return true
+}
+
+// Queued is the number of requests waiting for connectivity.
+func Queued() int {
+	mu.Lock()
+	defer mu.Unlock()
+	return len(order)
+}
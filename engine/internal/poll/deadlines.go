@@ -0,0 +1,56 @@
+package poll
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"time"
+
+	"jobhunt-engine/internal/events"
+	"jobhunt-engine/internal/logx"
+	"jobhunt-engine/internal/store"
+)
+
+const (
+	// deadlineWindow is how far ahead of a deadline the reminder goes out.
+	deadlineWindow = 24 * time.Hour
+	// deadlineCheckInterval is how often deadlines are checked.
+	deadlineCheckInterval = 15 * time.Minute
+)
+
+// StartDeadlineWatcher publishes a deadline_approaching event once for each
+// job whose application deadline is within deadlineWindow.
+func StartDeadlineWatcher(db *sql.DB, hub *events.Hub) {
+	go func() {
+		t := time.NewTicker(deadlineCheckInterval)
+		defer t.Stop()
+		for {
+			checkDeadlines(db, hub)
+			<-t.C
+		}
+	}()
+}
+
+func checkDeadlines(db *sql.DB, hub *events.Hub) {
+	ctx, cancel := context.WithTimeout(context.Background(), 30*time.Second)
+	defer cancel()
+
+	due, err := store.DueDeadlines(ctx, db, time.Now().UTC(), deadlineWindow)
+	if err != nil {
+		logx.Errorf("[deadlines] check failed: %v", err)
+		return
+	}
+	for _, j := range due {
+		b, _ := json.Marshal(map[string]any{
+			"type":     "deadline_approaching",
+			"id":       j.ID,
+			"title":    j.Title,
+			"company":  j.Company,
+			"deadline": j.Deadline.Format(time.RFC3339),
+		})
+		hub.Publish(string(b))
+		if err := store.MarkDeadlineNotified(ctx, db, j.ID); err != nil {
+			logx.Errorf("[deadlines] mark job %d: %v", j.ID, err)
+		}
+	}
+}
@@ -4,19 +4,22 @@ import (
 	"context"
 	"database/sql"
 	"jobhunt-engine/internal/config"
+	"jobhunt-engine/internal/logx"
 	"jobhunt-engine/internal/scrape"
-	email_scrape "jobhunt-engine/internal/scrape/email"
 	"jobhunt-engine/internal/scrape/greenhouse"
 	"jobhunt-engine/internal/scrape/lever"
 	"jobhunt-engine/internal/scrape/types"
 	"jobhunt-engine/internal/scrape/util"
+	"jobhunt-engine/internal/writes"
+	email_scrape "jobhunt-engine/internal/scrape/email"
 	"log"
 	"time"
 
 	"golang.org/x/sync/errgroup"
 )
 
-func PollOnce(db *sql.DB, cfg config.Config, onNewJob func()) (added int, err error) {
+func PollOnce(db *sql.DB, cfg config.Config, onNewJob func(id int64)) (added int, err error) {
+	defer writes.Begin("scrape", "Saving new jobs")()
 	parent := context.Background()
 
 	limiter := util.NewHostLimiter(1.0, 2)
@@ -60,7 +63,7 @@ func PollOnce(db *sql.DB, cfg config.Config, onNewJob func()) (added int, err er
 			log.Printf("[%s] Running...", f.Name())
 			res, err := f.Fetch(fctx)
 			if err != nil {
-				log.Printf("[ats:%s] error: %v", f.Name(), err)
+				logx.Errorf("[ats:%s] error: %v", f.Name(), err)
 				return nil
 			}
 			results <- res
@@ -2,21 +2,117 @@ package poll
 
 import (
 	"database/sql"
+	"fmt"
 	"log"
 	"sync/atomic"
 	"time"
 
 	"jobhunt-engine/internal/config"
 	"jobhunt-engine/internal/events"
+	"jobhunt-engine/internal/logx"
+	"jobhunt-engine/internal/netstate"
 	"jobhunt-engine/internal/scrape/types"
 )
 
+const pollInterval = 3 * time.Hour
+
+// suspendMaxAge bounds a suspension whose resume never arrived. It is wall
+// clock time, so it also runs out once the machine has actually slept.
+const suspendMaxAge = 10 * time.Minute
+
+var (
+	// suspended is set while the desktop shell reports the machine asleep.
+	suspended atomic.Bool
+	// suspendedAt is when suspended was set, in Unix nanoseconds.
+	suspendedAt atomic.Int64
+	// held is set while the shell's power settings hold background work.
+	held atomic.Bool
+	// missed is set when a scheduled poll was skipped for sleep, a hold, or no network.
+	missed atomic.Bool
+	// wake has the poller catch up on a missed or overdue run.
+	wake = make(chan struct{}, 1)
+)
+
+// SetSuspended pauses scheduled polls while the machine sleeps. Clearing it
+// runs any poll that came due in the meantime. A suspension lifts on its own
+// after suspendMaxAge, in case the shell's resume is lost.
+func SetSuspended(s bool) {
+	if s {
+		suspendedAt.Store(time.Now().UnixNano())
+	}
+	suspended.Store(s)
+	if !s {
+		Wake()
+	}
+}
+
+// isSuspended reports whether polls are paused for sleep, clearing a
+// suspension that has outlived suspendMaxAge.
+func isSuspended() bool {
+	if !suspended.Load() {
+		return false
+	}
+	since := time.Now().Round(0).Sub(time.Unix(0, suspendedAt.Load()))
+	if since < suspendMaxAge {
+		return true
+	}
+	if suspended.CompareAndSwap(true, false) {
+		logx.Warnf("[power] no resume after %s; resuming polls", since.Round(time.Second))
+	}
+	return false
+}
+
+// SetHeld pauses scheduled polls while the user is on battery or active and
+// has asked for background work to wait. Clearing it runs any poll that came
+// due in the meantime.
+func SetHeld(h bool) {
+	held.Store(h)
+	if !h {
+		Wake()
+	}
+}
+
+// Held reports whether scheduled polls are held.
+func Held() bool {
+	return held.Load()
+}
+
+// Wake runs a poll now if a scheduled one was skipped or is overdue.
+func Wake() {
+	select {
+	case wake <- struct{}{}:
+	default:
+	}
+}
+
 func StartPoller(db *sql.DB, cfgVal *atomic.Value, scrapeStatus *atomic.Value, hub *events.Hub) {
 	go func() {
-		t := time.NewTicker(3 * time.Hour) // Poll every 3 hours
+		t := time.NewTicker(pollInterval)
 		defer t.Stop()
+		lastRun := time.Now()
+
+		for {
+			select {
+			case <-t.C:
+			case <-wake:
+				// Wall-clock check: the ticker's monotonic clock stood still during sleep.
+				if !missed.Load() && time.Since(lastRun).Round(0) < pollInterval {
+					continue
+				}
+				t.Reset(pollInterval)
+			}
+			if isSuspended() || held.Load() {
+				missed.Store(true)
+				continue
+			}
+			if !netstate.Online() {
+				netstate.Defer("poll", Wake)
+				missed.Store(true)
+				continue
+			}
+			missed.Store(false)
+			lastRun = time.Now()
 
-		for range t.C {
 			cfgAny := cfgVal.Load()
 			if cfgAny == nil {
 				continue
@@ -38,9 +134,9 @@ func StartPoller(db *sql.DB, cfgVal *atomic.Value, scrapeStatus *atomic.Value, h
 			st.LastRunAt = time.Now().Format(time.RFC3339)
 			scrapeStatus.Store(st)
 
-			added, err := PollOnce(db, cfg, func() {
+			added, err := PollOnce(db, cfg, func(id int64) {
 				// SSE notify
-				hub.Publish(`{"type":"job_created"}`)
+				hub.Publish(`{"type":"job_created","id":` + fmt.Sprint(id) + `}`)
 			})
 
 			// Update status
@@ -54,7 +150,7 @@ func StartPoller(db *sql.DB, cfgVal *atomic.Value, scrapeStatus *atomic.Value, h
 
 			if err != nil {
 				st.LastError = err.Error()
-				log.Printf("[poll] error: %v", err)
+				logx.Errorf("[poll] error: %v", err)
 			} else {
 				st.LastError = ""
 				st.LastOkAt = time.Now().Format(time.RFC3339)
@@ -7,7 +7,6 @@ import (
 	"errors"
 	"fmt"
 	"io"
-	"log"
 	"net"
 	"net/mail"
 	"os"
@@ -16,6 +15,8 @@ import (
 
 	"github.com/emersion/go-imap/v2"
 	"github.com/emersion/go-imap/v2/imapclient"
+
+	"jobhunt-engine/internal/logx"
 )
 
 // EmailMessage is a minimal representation of an email for scraping.
@@ -230,14 +231,14 @@ func LogoutAndClose(c *imapclient.Client) {
 	// Try LOGOUT, but tolerate common "already closed" cases.
 	if err := c.Logout().Wait(); err != nil {
 		if !isBenignIMAPCloseErr(err) {
-			log.Printf("imap logout: %v", err)
+			logx.Warnf("imap logout: %v", err)
 		}
 	}
 
 	// Always attempt close. Close may also error if already closed.
 	if err := c.Close(); err != nil {
 		if !isBenignIMAPCloseErr(err) {
-			log.Printf("imap close: %v", err)
+			logx.Warnf("imap close: %v", err)
 		}
 	}
 }
@@ -12,6 +12,7 @@ import (
 
 	"jobhunt-engine/internal/config"
 	"jobhunt-engine/internal/domain"
+	"jobhunt-engine/internal/logx"
 	"jobhunt-engine/internal/scrape/types"
 	"jobhunt-engine/internal/scrape/util"
 	"jobhunt-engine/internal/secrets"
@@ -129,7 +130,7 @@ func (e *EmailFetcher) Fetch(ctx context.Context) (types.ScrapeResult, error) {
 	if len(processed) > 0 {
 		if err := MarkSeen(c, processed); err != nil {
 			if !isClosedConnErr(err) {
-				log.Printf("[email] mark seen: %v", err)
+				logx.Warnf("[email] mark seen: %v", err)
 			}
 			// don't fail the whole fetch
 		}
@@ -10,6 +10,7 @@ import (
 	"time"
 
 	"jobhunt-engine/internal/domain"
+	"jobhunt-engine/internal/logx"
 	"jobhunt-engine/internal/scrape/types"
 	"jobhunt-engine/internal/scrape/util"
 
@@ -60,7 +61,7 @@ func (s *Scraper) Fetch(ctx context.Context) (types.ScrapeResult, error) {
 				cancel()
 
 				if err != nil {
-					log.Printf("[ats:greenhouse] company=%q slug=%q err=%v", co.Name, co.Slug, err)
+					logx.Warnf("[ats:greenhouse] company=%q slug=%q err=%v", co.Name, co.Slug, err)
 					continue
 				}
 				if len(jobs) > 0 {
@@ -11,6 +11,7 @@ import (
 	"time"
 
 	"jobhunt-engine/internal/domain"
+	"jobhunt-engine/internal/logx"
 	"jobhunt-engine/internal/scrape/types"
 	"jobhunt-engine/internal/scrape/util"
 
@@ -73,7 +74,7 @@ func (s *Scraper) Fetch(ctx context.Context) (types.ScrapeResult, error) {
 				cancel()
 
 				if err != nil {
-					log.Printf("[ats:lever] company=%q slug=%q err=%v", co.Name, co.Slug, err)
+					logx.Warnf("[ats:lever] company=%q slug=%q err=%v", co.Name, co.Slug, err)
 					continue
 				}
 				if len(jobs) > 0 {
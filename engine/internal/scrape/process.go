@@ -7,19 +7,21 @@ import (
 	"errors"
 	"jobhunt-engine/internal/config"
 	"jobhunt-engine/internal/domain"
+	"jobhunt-engine/internal/logx"
 	"jobhunt-engine/internal/rank"
 	"jobhunt-engine/internal/scrape/greenhouse"
 	"jobhunt-engine/internal/scrape/lever"
 	"jobhunt-engine/internal/scrape/types"
 	"jobhunt-engine/internal/scrape/util"
 	"jobhunt-engine/internal/store"
+	"jobhunt-engine/internal/vault"
 	"log"
 	"net/url"
 	"strings"
 	"time"
 )
 
-func ProcessLeads(ctx context.Context, db *sql.DB, cfg config.Config, leads []domain.JobLead, onNewJob func()) (added int) {
+func ProcessLeads(ctx context.Context, db *sql.DB, cfg config.Config, leads []domain.JobLead, onNewJob func(id int64)) (added int) {
 	scorer := rank.YAMLScorer{Cfg: cfg}
 
 	// Run-local caches (reset every poll)
@@ -29,7 +31,7 @@ func ProcessLeads(ctx context.Context, db *sql.DB, cfg config.Config, leads []do
 	for _, lead := range leads {
 		keep, why := ShouldKeepJob(cfg, lead)
 		if !keep {
-			log.Printf("[%s] skipped (%s) title=%q loc=%q url=%q",
+			logx.Debugf("[%s] skipped (%s) title=%q loc=%q url=%q",
 				lead.FirstSeenSource, why, lead.Title, lead.LocationRaw, lead.URL)
 			continue
 		}
@@ -37,9 +39,9 @@ func ProcessLeads(ctx context.Context, db *sql.DB, cfg config.Config, leads []do
 		j := jobRowFromLead(lead, scorer)
 
 		// Fast path: insert first, no enrichment
-		ok, ierr := InsertJobIfNew(ctx, db, j)
+		id, ok, ierr := InsertJobIfNew(ctx, db, j)
 		if ierr != nil {
-			log.Printf("[process:%s] insert error: %v title=%q url=%q source_id=%q",
+			logx.Errorf("[process:%s] insert error: %v title=%q url=%q source_id=%q",
 				lead.FirstSeenSource, ierr, lead.Title, lead.URL, j.SourceID)
 			continue
 		}
@@ -54,7 +56,7 @@ func ProcessLeads(ctx context.Context, db *sql.DB, cfg config.Config, leads []do
 		if !ok {
 			found, derr := FindCompanyDomainDDG(ctx, j.Company)
 			if derr != nil {
-				log.Printf("[logo] domain lookup err company=%q err=%v", j.Company, derr)
+				logx.Warnf("[logo] domain lookup err company=%q err=%v", j.Company, derr)
 			}
 			dom = found
 			domainCache[j.Company] = dom // cache even if empty
@@ -82,20 +84,22 @@ WHERE source_id = ?
   AND (logo_key = '' OR logo_key IS NULL);`,
 					key, j.SourceID,
 				)
-				log.Printf("[logo] updating company=%q source_id=%q dom=%q key=%q", j.Company, j.SourceID, dom, key)
+				logx.Debugf("[logo] updating company=%q source_id=%q dom=%q key=%q", j.Company, j.SourceID, dom, key)
 			}
 		}
 
 		added++
 		if onNewJob != nil {
-			onNewJob()
+			onNewJob(id)
 		}
 	}
 
 	return added
 }
 
-func InsertJobIfNew(ctx context.Context, db *sql.DB, j types.JobRow) (bool, error) {
+// InsertJobIfNew adds j unless a job with the same source id exists, and
+// returns the new row's id when it did.
+func InsertJobIfNew(ctx context.Context, db *sql.DB, j types.JobRow) (int64, bool, error) {
 	if j.Company == "" {
 		j.Company = "Unknown"
 	}
@@ -109,7 +113,7 @@ func InsertJobIfNew(ctx context.Context, db *sql.DB, j types.JobRow) (bool, erro
 		j.WorkMode = "Unknown"
 	}
 	if j.URL == "" {
-		return false, errors.New("missing url")
+		return 0, false, errors.New("missing url")
 	}
 	if j.ReceivedAt.IsZero() {
 		j.ReceivedAt = time.Now().UTC()
@@ -128,6 +132,10 @@ func InsertJobIfNew(ctx context.Context, db *sql.DB, j types.JobRow) (bool, erro
 	if len(desc) > 65536 {
 		desc = desc[:65536]
 	}
+	desc, err := vault.SealString(desc)
+	if err != nil {
+		return 0, false, err
+	}
 
 	res, err := db.ExecContext(ctx, `
 INSERT OR IGNORE INTO jobs(company, title, location, work_mode, url, score, tags, date, source_id, seen_from_source, logo_key, description)
@@ -146,7 +154,7 @@ VALUES(?,?,?,?,?,?,?,?,?,?,?,?);`,
 		desc,
 	)
 	if err != nil {
-		return false, err
+		return 0, false, err
 	}
 	n, _ := res.RowsAffected()
 	if n == 0 && j.CompanyLogoURL != "" {
@@ -160,7 +168,14 @@ WHERE source_id = ?
 		)
 	}
 
-	return n > 0, nil
+	if n == 0 {
+		return 0, false, nil
+	}
+	id, err := res.LastInsertId()
+	if err != nil {
+		return 0, false, err
+	}
+	return id, true, nil
 }
 
 func jobRowFromLead(lead domain.JobLead, s rank.YAMLScorer) types.JobRow {
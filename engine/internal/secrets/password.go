@@ -4,6 +4,7 @@ import (
 	"errors"
 	"fmt"
 	"jobhunt-engine/internal/config"
+	"os"
 	"strings"
 
 	"github.com/zalando/go-keyring"
@@ -12,8 +13,19 @@ import (
 const (
 	KeyringService = "jobhunt"
 	groqKeyAccount = "jobhunt:groq:api_key"
+
+	// The desktop shell reads these from the OS keychain and passes them in
+	// at spawn time. The IMAP password is stored per account, so its env
+	// copy is only a fallback for when the account's own entry is missing.
+	// Setting or deleting a secret drops the env copy, which is stale from then on.
+	groqKeyEnv      = "JOBHUNT_GROQ_API_KEY"
+	imapPasswordEnv = "JOBHUNT_IMAP_PASSWORD"
 )
 
+func fromEnv(name string) string {
+	return strings.TrimSpace(os.Getenv(name))
+}
+
 // ─── IMAP ────────────────────────────────────────────────────────────────────
 
 func GetIMAPPassword(keyringAccount string) (string, error) {
@@ -23,6 +35,9 @@ func GetIMAPPassword(keyringAccount string) (string, error) {
 			return pw, nil
 		}
 	}
+	if pw := fromEnv(imapPasswordEnv); pw != "" {
+		return pw, nil
+	}
 	return "", errors.New("IMAP password not found (set it in keychain or via env)")
 }
 
@@ -33,13 +48,18 @@ func SetIMAPPassword(keyringAccount string, password string) error {
 	if strings.TrimSpace(password) == "" {
 		return errors.New("password is empty")
 	}
-	return keyring.Set(KeyringService, keyringAccount, password)
+	if err := keyring.Set(KeyringService, keyringAccount, password); err != nil {
+		return err
+	}
+	os.Unsetenv(imapPasswordEnv)
+	return nil
 }
 
 func DeleteIMAPPassword(keyringAccount string) error {
 	if strings.TrimSpace(keyringAccount) == "" {
 		return errors.New("keyring account name is empty")
 	}
+	os.Unsetenv(imapPasswordEnv)
 	return keyring.Delete(KeyringService, keyringAccount)
 }
 
@@ -54,6 +74,9 @@ func IMAPKeyringAccount(cfg config.Config) string {
 // ─── Groq API key ─────────────────────────────────────────────────────────────
 
 func GetGroqAPIKey() (string, error) {
+	if key := fromEnv(groqKeyEnv); key != "" {
+		return key, nil
+	}
 	key, err := keyring.Get(KeyringService, groqKeyAccount)
 	if err != nil {
 		return "", fmt.Errorf("Groq API key not found in keyring: %w", err)
@@ -69,15 +92,23 @@ func SetGroqAPIKey(apiKey string) error {
 	if !strings.HasPrefix(apiKey, "gsk_") {
 		return errors.New("Groq API keys start with 'gsk_'")
 	}
-	return keyring.Set(KeyringService, groqKeyAccount, apiKey)
+	if err := keyring.Set(KeyringService, groqKeyAccount, apiKey); err != nil {
+		return err
+	}
+	os.Unsetenv(groqKeyEnv)
+	return nil
 }
 
 func DeleteGroqAPIKey() error {
+	os.Unsetenv(groqKeyEnv)
 	return keyring.Delete(KeyringService, groqKeyAccount)
 }
 
 // HasGroqAPIKey returns true if a key is stored, without exposing the value.
 func HasGroqAPIKey() bool {
+	if fromEnv(groqKeyEnv) != "" {
+		return true
+	}
 	key, err := keyring.Get(KeyringService, groqKeyAccount)
 	return err == nil && strings.TrimSpace(key) != ""
 }
@@ -0,0 +1,70 @@
+package store
+
+import (
+	"context"
+	"database/sql"
+	"time"
+)
+
+// DeadlineJob is a job whose application deadline is coming up.
+type DeadlineJob struct {
+	ID       int64
+	Title    string
+	Company  string
+	Deadline time.Time
+}
+
+// SetJobDeadline sets or, with the zero time, clears a job's application
+// deadline. Changing it re-arms the reminder. Returns sql.ErrNoRows if there
+// is no job with that id.
+func SetJobDeadline(ctx context.Context, db *sql.DB, id int64, deadline time.Time) error {
+	value := ""
+	if !deadline.IsZero() {
+		value = deadline.UTC().Format(time.RFC3339)
+	}
+	res, err := db.ExecContext(ctx,
+		`UPDATE jobs SET deadline = ?, deadline_notified = 0 WHERE id = ?;`, value, id)
+	if err != nil {
+		return err
+	}
+	if n, _ := res.RowsAffected(); n == 0 {
+		return sql.ErrNoRows
+	}
+	return nil
+}
+
+// DueDeadlines returns the jobs whose deadline falls before now+within and
+// that have not been reminded about yet. Deadlines already past are left out.
+func DueDeadlines(ctx context.Context, db *sql.DB, now time.Time, within time.Duration) ([]DeadlineJob, error) {
+	rows, err := db.QueryContext(ctx, `
+SELECT id, title, company, deadline
+FROM jobs
+WHERE deadline != '' AND deadline_notified = 0;
+`)
+	if err != nil {
+		return nil, err
+	}
+	defer rows.Close()
+
+	var out []DeadlineJob
+	for rows.Next() {
+		var j DeadlineJob
+		var deadline string
+		if err := rows.Scan(&j.ID, &j.Title, &j.Company, &deadline); err != nil {
+			return nil, err
+		}
+		t, err := time.Parse(time.RFC3339, deadline)
+		if err != nil || t.Before(now) || t.After(now.Add(within)) {
+			continue
+		}
+		j.Deadline = t
+		out = append(out, j)
+	}
+	return out, rows.Err()
+}
+
+// MarkDeadlineNotified records that the reminder for a job's deadline went out.
+func MarkDeadlineNotified(ctx context.Context, db *sql.DB, id int64) error {
+	_, err := db.ExecContext(ctx, `UPDATE jobs SET deadline_notified = 1 WHERE id = ?;`, id)
+	return err
+}
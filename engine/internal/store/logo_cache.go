@@ -7,11 +7,12 @@ import (
 	"encoding/hex"
 	"errors"
 	"io"
-	"log"
 	"net/http"
 	"net/url"
 	"strings"
 	"time"
+
+	"jobhunt-engine/internal/logx"
 )
 
 func LogoKeyFromURL(u string) string {
@@ -78,13 +79,13 @@ func CacheLogoFromURL(ctx context.Context, db *sql.DB, raw string) (key string,
 	client := &http.Client{Timeout: 15 * time.Second}
 	resp, err := client.Do(req)
 	if err != nil {
-		log.Printf("[logo-cache] fetch error url=%s err=%v", raw, err)
+		logx.Warnf("[logo-cache] fetch error url=%s err=%v", raw, err)
 		return "", nil
 	}
 	defer resp.Body.Close()
 
 	if resp.StatusCode < 200 || resp.StatusCode > 299 {
-		log.Printf("[logo-cache] non-2xx url=%s status=%s", raw, resp.Status)
+		logx.Warnf("[logo-cache] non-2xx url=%s status=%s", raw, resp.Status)
 		return "", nil
 	}
 
@@ -6,6 +6,8 @@ import (
 	"encoding/json"
 	"fmt"
 	"time"
+
+	"jobhunt-engine/internal/vault"
 )
 
 type Job struct {
@@ -21,6 +23,7 @@ type Job struct {
 	SeenFromSource string   `json:"seenFromSource"`
 	CompanyLogoURL string   `json:"companyLogoURL"`
 	LogoKey        string   `json:"logoKey"`
+	Deadline       string   `json:"deadline,omitempty"`
 }
 
 type ListJobsOpts struct {
@@ -136,6 +139,24 @@ WHERE source_id != '';
 		}
 	}
 
+	// ---- Schema v3: application deadlines ----
+	if v < 3 {
+		if !columnExists(tx, "jobs", "deadline") {
+			if _, err := tx.Exec(`ALTER TABLE jobs ADD COLUMN deadline TEXT NOT NULL DEFAULT '';`); err != nil {
+				return err
+			}
+		}
+		if !columnExists(tx, "jobs", "deadline_notified") {
+			if _, err := tx.Exec(`ALTER TABLE jobs ADD COLUMN deadline_notified INTEGER NOT NULL DEFAULT 0;`); err != nil {
+				return err
+			}
+		}
+
+		if _, err := tx.Exec(`PRAGMA user_version = 3;`); err != nil {
+			return err
+		}
+	}
+
 	return tx.Commit()
 }
 
@@ -198,7 +219,7 @@ func ListJobs(ctx context.Context, db *sql.DB, opts ListJobsOpts) ([]Job, error)
 	}
 
 	query := fmt.Sprintf(`
-SELECT id, company, title, location, work_mode, url, score, tags, date, seen_from_source, logo_key
+SELECT id, company, title, location, work_mode, url, score, tags, date, seen_from_source, logo_key, deadline
 FROM jobs
 %s
 ORDER BY %s %s
@@ -229,6 +250,7 @@ LIMIT ?;
 			&dateStr,
 			&j.SeenFromSource,
 			&j.LogoKey,
+			&j.Deadline,
 		); err != nil {
 			return nil, err
 		}
@@ -248,6 +270,41 @@ LIMIT ?;
 	return out, nil
 }
 
+// GetJob returns a single job, or sql.ErrNoRows if there is none with that id.
+func GetJob(ctx context.Context, db *sql.DB, id int64) (Job, error) {
+	var j Job
+	var tagsJSON, dateStr string
+	err := db.QueryRowContext(ctx, `
+SELECT id, company, title, location, work_mode, url, score, tags, date, seen_from_source, logo_key, deadline
+FROM jobs
+WHERE id = ?
+LIMIT 1;
+`, id).Scan(
+		&j.ID,
+		&j.Company,
+		&j.Title,
+		&j.Location,
+		&j.WorkMode,
+		&j.URL,
+		&j.Score,
+		&tagsJSON,
+		&dateStr,
+		&j.SeenFromSource,
+		&j.LogoKey,
+		&j.Deadline,
+	)
+	if err != nil {
+		return Job{}, err
+	}
+	if j.LogoKey != "" {
+		j.CompanyLogoURL = "/logo/" + j.LogoKey
+	}
+	_ = json.Unmarshal([]byte(tagsJSON), &j.Tags)
+	parsedDate, _ := time.Parse(time.RFC3339, dateStr)
+	j.Date = parsedDate.Format("2006-01-02 15:04:05")
+	return j, nil
+}
+
 // GetJobDescription returns just the description text for a single job.
 // Returns ("", nil) if the job exists but has no description yet.
 func GetJobDescription(ctx context.Context, db *sql.DB, id int64) (string, error) {
@@ -258,7 +315,52 @@ func GetJobDescription(ctx context.Context, db *sql.DB, id int64) (string, error
 	if err == sql.ErrNoRows {
 		return "", nil
 	}
-	return desc, err
+	if err != nil {
+		return "", err
+	}
+	return vault.OpenString(desc)
+}
+
+// ResealDescriptions moves every job description from the previous vault key
+// to the current one, after the user sets, changes or removes the passphrase.
+func ResealDescriptions(ctx context.Context, db *sql.DB, r *vault.Resealer) (int, error) {
+	rows, err := db.QueryContext(ctx, `SELECT id, description FROM jobs WHERE description != '';`)
+	if err != nil {
+		return 0, err
+	}
+	type row struct {
+		id   int64
+		desc string
+	}
+	var all []row
+	for rows.Next() {
+		var x row
+		if err := rows.Scan(&x.id, &x.desc); err != nil {
+			rows.Close()
+			return 0, err
+		}
+		all = append(all, x)
+	}
+	rows.Close()
+	if err := rows.Err(); err != nil {
+		return 0, err
+	}
+
+	tx, err := db.BeginTx(ctx, nil)
+	if err != nil {
+		return 0, err
+	}
+	defer func() { _ = tx.Rollback() }()
+	for _, x := range all {
+		desc, err := r.String(x.desc)
+		if err != nil {
+			return 0, fmt.Errorf("job %d: %w", x.id, err)
+		}
+		if _, err := tx.ExecContext(ctx, `UPDATE jobs SET description = ? WHERE id = ?;`, desc, x.id); err != nil {
+			return 0, err
+		}
+	}
+	return len(all), tx.Commit()
 }
 
 func SeedJob(ctx context.Context, db *sql.DB) (Job, error) {
@@ -0,0 +1,273 @@
+// Package vault encrypts personal data at rest once the user sets a
+// passphrase in the desktop app. The app derives a 256-bit key from the
+// passphrase and hands it over on stdin at spawn; the engine never sees the
+// passphrase and the key never touches argv, env or disk.
+//
+// Sealed values are AES-256-GCM with a random nonce. Values written before a
+// passphrase was set are plaintext and read back unchanged, so turning the
+// vault on doesn't strand existing data; the app restarts the engine with
+// Rekey set to reseal it.
+package vault
+
+import (
+	"bufio"
+	"bytes"
+	"crypto/aes"
+	"crypto/cipher"
+	"crypto/rand"
+	"encoding/base64"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"sync"
+)
+
+// magic prefixes sealed files; textPrefix marks sealed strings in the DB.
+var magic = []byte("JHVAULT1")
+
+const textPrefix = "jhv1:"
+
+var ErrLocked = errors.New("vault is locked")
+
+var (
+	mu   sync.RWMutex
+	aead cipher.AEAD
+)
+
+// Handoff is the single JSON line the app writes to stdin when
+// JOBHUNT_VAULT=stdin. Keys are hex; an empty key means no vault.
+type Handoff struct {
+	Key      string `json:"key"`
+	Previous string `json:"previous"`
+	// Rekey asks for everything sealed under Previous to be resealed under Key.
+	Rekey bool `json:"rekey"`
+}
+
+// ReadHandoff reads the key handoff line from r.
+func ReadHandoff(r io.Reader) (Handoff, error) {
+	line, err := bufio.NewReader(io.LimitReader(r, 4<<10)).ReadString('\n')
+	if err != nil && line == "" {
+		return Handoff{}, fmt.Errorf("read vault key: %w", err)
+	}
+	var h Handoff
+	if err := json.Unmarshal([]byte(strings.TrimSpace(line)), &h); err != nil {
+		return Handoff{}, fmt.Errorf("parse vault key: %w", err)
+	}
+	return h, nil
+}
+
+func newAEAD(hexKey string) (cipher.AEAD, error) {
+	if hexKey == "" {
+		return nil, nil
+	}
+	key, err := hex.DecodeString(hexKey)
+	if err != nil || len(key) != 32 {
+		return nil, errors.New("vault key must be 32 bytes of hex")
+	}
+	block, err := aes.NewCipher(key)
+	if err != nil {
+		return nil, err
+	}
+	return cipher.NewGCM(block)
+}
+
+// SetKey loads the key new values are sealed with; "" turns sealing off.
+func SetKey(hexKey string) error {
+	a, err := newAEAD(hexKey)
+	if err != nil {
+		return err
+	}
+	mu.Lock()
+	aead = a
+	mu.Unlock()
+	return nil
+}
+
+func Enabled() bool {
+	mu.RLock()
+	defer mu.RUnlock()
+	return aead != nil
+}
+
+func seal(a cipher.AEAD, plain []byte) ([]byte, error) {
+	nonce := make([]byte, a.NonceSize())
+	if _, err := rand.Read(nonce); err != nil {
+		return nil, err
+	}
+	out := append([]byte{}, magic...)
+	out = append(out, nonce...)
+	return a.Seal(out, nonce, plain, magic), nil
+}
+
+func open(a cipher.AEAD, data []byte) ([]byte, error) {
+	if !bytes.HasPrefix(data, magic) {
+		return data, nil
+	}
+	if a == nil {
+		return nil, ErrLocked
+	}
+	body := data[len(magic):]
+	if len(body) < a.NonceSize() {
+		return nil, errors.New("sealed value is truncated")
+	}
+	plain, err := a.Open(nil, body[:a.NonceSize()], body[a.NonceSize():], magic)
+	if err != nil {
+		return nil, errors.New("sealed value does not decrypt with this key")
+	}
+	return plain, nil
+}
+
+// Seal encrypts plain under the current key, or returns it as-is without one.
+func Seal(plain []byte) ([]byte, error) {
+	mu.RLock()
+	a := aead
+	mu.RUnlock()
+	if a == nil {
+		return plain, nil
+	}
+	return seal(a, plain)
+}
+
+// Open decrypts a sealed value; plaintext passes through.
+func Open(data []byte) ([]byte, error) {
+	mu.RLock()
+	a := aead
+	mu.RUnlock()
+	return open(a, data)
+}
+
+// SealString is Seal for text columns: sealed values are base64 with a prefix.
+func SealString(s string) (string, error) {
+	if !Enabled() || s == "" {
+		return s, nil
+	}
+	b, err := Seal([]byte(s))
+	if err != nil {
+		return "", err
+	}
+	return textPrefix + base64.StdEncoding.EncodeToString(b), nil
+}
+
+// OpenString reverses SealString; unprefixed text passes through.
+func OpenString(s string) (string, error) {
+	if !strings.HasPrefix(s, textPrefix) {
+		return s, nil
+	}
+	b, err := base64.StdEncoding.DecodeString(s[len(textPrefix):])
+	if err != nil {
+		return "", err
+	}
+	plain, err := Open(b)
+	return string(plain), err
+}
+
+func ReadFile(path string) ([]byte, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, err
+	}
+	return Open(data)
+}
+
+func WriteFile(path string, data []byte, perm os.FileMode) error {
+	sealed, err := Seal(data)
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(path, sealed, perm)
+}
+
+// replaceFile swaps data in for path's contents via a temp file and rename.
+func replaceFile(path string, data []byte) error {
+	tmp := path + ".tmp"
+	if err := os.WriteFile(tmp, data, 0o600); err != nil {
+		return err
+	}
+	return os.Rename(tmp, path)
+}
+
+// SealFile seals a plaintext file in place under the current key. Sealed
+// files, and every file while the vault is off, are left as they are.
+func SealFile(path string) error {
+	if !Enabled() {
+		return nil
+	}
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return err
+	}
+	if bytes.HasPrefix(data, magic) {
+		return nil
+	}
+	sealed, err := Seal(data)
+	if err != nil {
+		return err
+	}
+	return replaceFile(path, sealed)
+}
+
+// Resealer reopens values sealed under a previous key and seals them under
+// the current one (or leaves them plaintext if the vault was turned off).
+// A value already sealed under the current key, left by a reseal that was
+// interrupted and is being retried, is kept as it is.
+type Resealer struct {
+	prev cipher.AEAD
+}
+
+func NewResealer(previousHexKey string) (*Resealer, error) {
+	a, err := newAEAD(previousHexKey)
+	if err != nil {
+		return nil, err
+	}
+	return &Resealer{prev: a}, nil
+}
+
+func (r *Resealer) Bytes(data []byte) ([]byte, error) {
+	plain, err := open(r.prev, data)
+	if err != nil {
+		if _, cerr := Open(data); cerr == nil {
+			return data, nil
+		}
+		return nil, err
+	}
+	return Seal(plain)
+}
+
+func (r *Resealer) String(s string) (string, error) {
+	plain := s
+	if strings.HasPrefix(s, textPrefix) {
+		b, err := base64.StdEncoding.DecodeString(s[len(textPrefix):])
+		if err != nil {
+			return "", err
+		}
+		p, err := open(r.prev, b)
+		if err != nil {
+			if _, cerr := Open(b); cerr == nil {
+				return s, nil
+			}
+			return "", err
+		}
+		plain = string(p)
+	}
+	return SealString(plain)
+}
+
+// File reseals path in place; a missing file is not an error.
+func (r *Resealer) File(path string) error {
+	data, err := os.ReadFile(path)
+	if os.IsNotExist(err) {
+		return nil
+	}
+	if err != nil {
+		return err
+	}
+	out, err := r.Bytes(data)
+	if err != nil {
+		return fmt.Errorf("%s: %w", path, err)
+	}
+	return replaceFile(path, out)
+}
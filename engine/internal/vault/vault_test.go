@@ -0,0 +1,140 @@
+package vault
+
+import (
+	"strings"
+	"testing"
+)
+
+const (
+	keyA = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+	keyB = "1f1e1d1c1b1a191817161514131211100f0e0d0c0b0a09080706050403020100"
+)
+
+// ─── Seal / Open ──────────────────────────────────────────────────────────────
+
+func TestSealOpenRoundTrip(t *testing.T) {
+	if err := SetKey(keyA); err != nil {
+		t.Fatal(err)
+	}
+	defer SetKey("")
+
+	sealed, err := Seal([]byte("desired salary: 150k"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if strings.Contains(string(sealed), "150k") {
+		t.Fatalf("sealed value leaks plaintext: %q", sealed)
+	}
+	plain, err := Open(sealed)
+	if err != nil || string(plain) != "desired salary: 150k" {
+		t.Fatalf("Open = %q, %v", plain, err)
+	}
+}
+
+func TestOpenPassesPlaintextThrough(t *testing.T) {
+	if err := SetKey(keyA); err != nil {
+		t.Fatal(err)
+	}
+	defer SetKey("")
+
+	got, err := OpenString("written before the vault existed")
+	if err != nil || got != "written before the vault existed" {
+		t.Fatalf("OpenString = %q, %v", got, err)
+	}
+}
+
+func TestOpenWithoutKeyIsLocked(t *testing.T) {
+	if err := SetKey(keyA); err != nil {
+		t.Fatal(err)
+	}
+	sealed, err := SealString("notes")
+	if err != nil {
+		t.Fatal(err)
+	}
+	_ = SetKey("")
+
+	if _, err := OpenString(sealed); err != ErrLocked {
+		t.Fatalf("OpenString without key: err = %v, want ErrLocked", err)
+	}
+}
+
+// ─── Resealer ─────────────────────────────────────────────────────────────────
+
+func TestResealChangesKey(t *testing.T) {
+	if err := SetKey(keyA); err != nil {
+		t.Fatal(err)
+	}
+	sealed, err := SealString("notes")
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	if err := SetKey(keyB); err != nil {
+		t.Fatal(err)
+	}
+	defer SetKey("")
+	if _, err := OpenString(sealed); err == nil {
+		t.Fatal("value sealed under the old key opened with the new one")
+	}
+
+	r, err := NewResealer(keyA)
+	if err != nil {
+		t.Fatal(err)
+	}
+	resealed, err := r.String(sealed)
+	if err != nil {
+		t.Fatal(err)
+	}
+	got, err := OpenString(resealed)
+	if err != nil || got != "notes" {
+		t.Fatalf("OpenString after reseal = %q, %v", got, err)
+	}
+}
+
+func TestResealToPlaintext(t *testing.T) {
+	if err := SetKey(keyA); err != nil {
+		t.Fatal(err)
+	}
+	sealed, err := Seal([]byte("profile"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	_ = SetKey("")
+
+	r, err := NewResealer(keyA)
+	if err != nil {
+		t.Fatal(err)
+	}
+	out, err := r.Bytes(sealed)
+	if err != nil || string(out) != "profile" {
+		t.Fatalf("Bytes = %q, %v", out, err)
+	}
+}
+
+func TestResealKeepsValuesAlreadyResealed(t *testing.T) {
+	if err := SetKey(keyA); err != nil {
+		t.Fatal(err)
+	}
+	sealed, err := SealString("notes")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := SetKey(keyB); err != nil {
+		t.Fatal(err)
+	}
+	defer SetKey("")
+
+	r, err := NewResealer(keyA)
+	if err != nil {
+		t.Fatal(err)
+	}
+	once, err := r.String(sealed)
+	if err != nil {
+		t.Fatal(err)
+	}
+	// A retried reseal sees the value under the new key already.
+	twice, err := r.String(once)
+	if err != nil || twice != once {
+		t.Fatalf("second reseal = %q, %v; want it unchanged", twice, err)
+	}
+}
@@ -0,0 +1,53 @@
+// Package writes tracks long-running operations that are writing to the data
+// dir, so the desktop shell can warn before quitting in the middle of one.
+package writes
+
+import (
+	"sort"
+	"sync"
+	"time"
+)
+
+// Op is one in-flight write.
+type Op struct {
+	ID      uint64    `json:"id"`
+	Kind    string    `json:"kind"`  // "import" | "scrape"
+	Label   string    `json:"label"` // human-readable, e.g. "Importing resume.pdf"
+	Started time.Time `json:"started"`
+}
+
+var (
+	mu     sync.Mutex
+	nextID uint64
+	active = map[uint64]Op{}
+)
+
+// Begin records an operation as running; call the returned func when it ends.
+func Begin(kind, label string) (end func()) {
+	mu.Lock()
+	nextID++
+	id := nextID
+	active[id] = Op{ID: id, Kind: kind, Label: label, Started: time.Now()}
+	mu.Unlock()
+
+	var once sync.Once
+	return func() {
+		once.Do(func() {
+			mu.Lock()
+			delete(active, id)
+			mu.Unlock()
+		})
+	}
+}
+
+// Active lists the running operations, oldest first.
+func Active() []Op {
+	mu.Lock()
+	defer mu.Unlock()
+	ops := make([]Op, 0, len(active))
+	for _, op := range active {
+		ops = append(ops, op)
+	}
+	sort.Slice(ops, func(i, j int) bool { return ops[i].ID < ops[j].ID })
+	return ops
+}
@@ -3,27 +3,30 @@
 // background.js — service worker
 // Handles the floating button click, runs: scrape → Groq fill → inject.
 
-const ENGINE = 'http://127.0.0.1:38471';
+// Native messaging host registered by the desktop app. It forwards engine
+// requests, so the extension never needs to know the engine's port.
+const NATIVE_HOST = 'com.jobhunt.native';
 
 // ─── Engine comms ─────────────────────────────────────────────────────────────
 
+async function engineRequest(method, path, body) {
+  const res = await chrome.runtime.sendNativeMessage(NATIVE_HOST, { type: 'engine', method, path, body });
+  if (!res || !res.ok) throw new Error(res?.error || 'JobHunt is not running');
+  return res;
+}
+
 async function engineGet(path) {
-  const res = await fetch(`${ENGINE}${path}`);
-  if (!res.ok) throw new Error(`Engine GET ${path} → ${res.status}`);
-  return res.json();
+  const res = await engineRequest('GET', path);
+  if (res.status >= 400) throw new Error(`Engine GET ${path} → ${res.status}`);
+  return JSON.parse(res.body);
 }
 
 async function enginePost(path, body) {
-  const res = await fetch(`${ENGINE}${path}`, {
-    method:  'POST',
-    headers: { 'Content-Type': 'application/json' },
-    body:    JSON.stringify(body),
-  });
-  if (!res.ok) {
-    const text = await res.text().catch(() => '');
-    throw new Error(`Engine POST ${path} → ${res.status}: ${text}`);
+  const res = await engineRequest('POST', path, body);
+  if (res.status >= 400) {
+    throw new Error(`Engine POST ${path} → ${res.status}: ${res.body || ''}`);
   }
-  return res.json();
+  return JSON.parse(res.body);
 }
 
 // ─── Logging ──────────────────────────────────────────────────────────────────
@@ -37,11 +40,7 @@ async function log(level, message) {
     console.log(`[JobHunt] ${message}`);
   }
   try {
-    await fetch(`${ENGINE}/api/log`, {
-      method:  'POST',
-      headers: { 'Content-Type': 'application/json' },
-      body:    JSON.stringify({ level, source: 'extension', message }),
-    });
+    await engineRequest('POST', '/api/log', { level, source: 'extension', message });
   } catch { /* engine may not be running yet */ }
 }
 
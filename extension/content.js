@@ -7,8 +7,6 @@
 
 'use strict';
 
-const ENGINE = 'http://127.0.0.1:38471';
-
 const COVER_DEBUG_PREFIX = '[JobHunt:cover]';
 
 function coverLog(step, payload) {
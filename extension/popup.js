@@ -1,6 +1,8 @@
 'use strict';
 
-const ENGINE = 'http://127.0.0.1:38471';
+// Native messaging host registered by the desktop app. It also forwards engine
+// requests, so the extension never needs to know the engine's port.
+const NATIVE_HOST = 'com.jobhunt.native';
 
 // ─── State machine ────────────────────────────────────────────────────────────
 
@@ -20,6 +22,7 @@ const jobTitle     = document.getElementById('jobTitle');
 const jobCompany   = document.getElementById('jobCompany');
 const btnScrape    = document.getElementById('btnScrape');
 const btnFill      = document.getElementById('btnFill');
+const btnCapture   = document.getElementById('btnCapture');
 const btnRescrape  = document.getElementById('btnRescrape');
 const btnFillAgain = document.getElementById('btnFillAgain');
 const fieldsList   = document.getElementById('fieldsList');
@@ -42,20 +45,22 @@ function setStatus(color, msg) {
 
 // ─── Engine comms ─────────────────────────────────────────────────────────────
 
+async function engineRequest(method, path, body) {
+  const res = await chrome.runtime.sendNativeMessage(NATIVE_HOST, { type: 'engine', method, path, body });
+  if (!res || !res.ok) throw new Error(res?.error || 'JobHunt is not running');
+  return res;
+}
+
 async function engineGet(path) {
-  const res = await fetch(`${ENGINE}${path}`);
-  if (!res.ok) throw new Error(`${res.status}`);
-  return res.json();
+  const res = await engineRequest('GET', path);
+  if (res.status >= 400) throw new Error(`${res.status}`);
+  return JSON.parse(res.body);
 }
 
 async function enginePost(path, body) {
-  const res = await fetch(`${ENGINE}${path}`, {
-    method: 'POST',
-    headers: { 'Content-Type': 'application/json' },
-    body: JSON.stringify(body),
-  });
-  if (!res.ok) throw new Error(await res.text() || `${res.status}`);
-  return res.json();
+  const res = await engineRequest('POST', path, body);
+  if (res.status >= 400) throw new Error(res.body || `${res.status}`);
+  return JSON.parse(res.body);
 }
 
 // ─── Content script comms ─────────────────────────────────────────────────────
@@ -278,7 +283,7 @@ async function init() {
 
   // Check engine
   try {
-    await fetch(`${ENGINE}/health`);
+    await engineRequest('GET', '/health');
   } catch {
     show('Offline'); setStatus('red', 'Engine offline'); return;
   }
@@ -365,6 +370,25 @@ async function runInject() {
   }
 }
 
+async function sendToDesktop() {
+  btnCapture.disabled = true;
+  setStatus('yellow', 'Sending to JobHunt…');
+  try {
+    const res = await chrome.runtime.sendNativeMessage(NATIVE_HOST, {
+      type:    'capture',
+      url:     currentTab.url,
+      title:   pageInfo?.title || currentTab.title || '',
+      company: pageInfo?.company || '',
+    });
+    if (!res || !res.ok) throw new Error(res?.error || 'no response');
+    setStatus('green', 'Sent to JobHunt');
+  } catch (e) {
+    setStatus('red', 'Could not reach JobHunt: ' + e.message);
+  } finally {
+    btnCapture.disabled = false;
+  }
+}
+
 // ─── Event listeners ──────────────────────────────────────────────────────────
 
 btnScrape.addEventListener('click', runScrapeAndFill);
@@ -379,6 +403,7 @@ btnFillAgain.addEventListener('click', () => {
   }
 });
 btnFill.addEventListener('click', runInject);
+btnCapture.addEventListener('click', sendToDesktop);
 
 // ─── Boot ─────────────────────────────────────────────────────────────────────
 